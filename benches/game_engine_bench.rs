@@ -0,0 +1,151 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, black_box};
+
+use game_engine::camera::Camera;
+use game_engine::camera::orthographic_camera::OrthographicCamera;
+use game_engine::graphics::texture::TextureHandle;
+use game_engine::graphics::transform::Transform;
+use game_engine::input::keyboard::KeyBoard;
+use game_engine::load::load_json;
+use game_engine::loading::Task;
+use game_engine::scenes::Scene;
+use game_engine::scenes::scene_stack::{SceneStack, SceneTransition};
+
+use glam::{Vec2, Vec3};
+use luminance_glfw::GL33Context;
+use specs::{Builder, Join, ReadStorage, World, WorldExt, WriteStorage};
+
+use anyhow::Result;
+
+use std::fs::write;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Moves every entity with a Transform and TextureHandle like a simple gameplay scene would.
+#[derive(Debug)]
+struct BenchScene;
+
+impl Scene<KeyBoard> for BenchScene {
+    fn update(&mut self, ecs: &mut World, _delta: Duration) -> Result<SceneTransition<KeyBoard>> {
+        let (mut transforms, textures): (WriteStorage<Transform>, ReadStorage<TextureHandle>) = ecs.system_data();
+
+        for (transform, _texture) in (&mut transforms, &textures).join() {
+            transform.translation += Vec3::new(1.0, 0.5, 0.0);
+            transform.rotation.z += 0.01;
+        }
+
+        Ok(SceneTransition::NONE)
+    }
+
+    fn draw(&mut self, _ecs: &mut World, _context: &mut GL33Context) -> Result<()> {
+        Ok(())
+    }
+
+    fn interact(&mut self, _ecs: &mut World, _input: &KeyBoard) -> Result<()> {
+        Ok(())
+    }
+
+    fn get_name(&self) -> String {
+        String::from("bench_scene")
+    }
+
+    fn is_finished(&self, _ecs: &mut World) -> Result<bool> {
+        Ok(false)
+    }
+}
+
+/// Entities are placed on a grid derived from their index so every run uses the same World.
+fn create_world(entity_count: usize) -> World {
+    let mut world = World::new();
+    world.register::<Transform>();
+    world.register::<TextureHandle>();
+
+    for i in 0..entity_count {
+        world.create_entity()
+            .with(Transform::from_2d(Vec2::new((i % 100) as f32, (i / 100) as f32), Vec2::ONE, 0.0))
+            .with(TextureHandle::new(format!("texture_{}", i % 8)))
+            .build();
+    }
+
+    world
+}
+
+fn scene_stack_update(c: &mut Criterion) {
+    let mut group = c.benchmark_group("scene_stack_update");
+
+    for entity_count in [100, 1000, 10000].iter() {
+        let mut world = create_world(*entity_count);
+        let mut scene_stack: SceneStack<KeyBoard> = SceneStack::new(vec![Box::new(BenchScene)]);
+
+        group.bench_with_input(BenchmarkId::from_parameter(entity_count), entity_count, |b, _| {
+            b.iter(|| scene_stack.update(&mut world, Duration::from_millis(16)).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+fn task_execute(c: &mut Criterion) {
+    c.bench_function("task_execute_10_serialized", |b| {
+        b.iter(|| {
+            let mut task: Task<u64, u64> = Task::new(Ok);
+
+            for _ in 0..9 {
+                task = task.serialize(Task::new(|(previous, seed): (u64, u64)| Ok(previous.wrapping_mul(31).wrapping_add(seed))));
+            }
+
+            task.execute(black_box(7)).unwrap()
+        })
+    });
+}
+
+fn write_json_file(name: &str, value_count: usize) -> PathBuf {
+    let values: Vec<String> = (0..value_count)
+        .map(|i| format!("{{\"name\": \"value_{}\", \"position\": [{}, {}], \"visible\": {}}}", i, i, i * 2, i % 2 == 0))
+        .collect();
+
+    let json = format!("{{\"load_type_id\": \"bench\", \"actual_value\": {{\"values\": [{}]}}}}", values.join(","));
+
+    let path = std::env::temp_dir().join(name);
+    write(&path, json).expect("Failed to write benchmark JSON file");
+
+    path
+}
+
+fn load_json_files(c: &mut Criterion) {
+    let mut group = c.benchmark_group("load_json");
+
+    for (label, value_count) in [("small", 10), ("large", 10000)].iter() {
+        let path = write_json_file(&format!("game_engine_bench_{}.json", label), *value_count);
+        let path = path.to_str().expect("Temp dir path is not valid UTF-8").to_string();
+
+        group.bench_function(*label, |b| {
+            b.iter(|| load_json(black_box(&path)).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+fn camera_view(c: &mut Criterion) {
+    let mut group = c.benchmark_group("camera_view");
+    let mut camera = OrthographicCamera::new(Vec3::new(0.0, 0.0, 1.0), Vec3::ZERO, Vec3::Y);
+
+    group.bench_function("cached", |b| {
+        b.iter(|| camera.view())
+    });
+
+    group.bench_function("dirty", |b| {
+        let mut x = 0.0;
+
+        b.iter(|| {
+            x += 1.0;
+            camera.set_position(Vec3::new(x, 0.0, 1.0));
+            camera.view()
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, scene_stack_update, task_execute, load_json_files, camera_view);
+criterion_main!(benches);
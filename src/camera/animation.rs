@@ -0,0 +1,75 @@
+use std::ops::{Add, Mul, Sub};
+use std::time::Instant;
+
+use glam::Vec3;
+
+/// Maps the fraction of an animation's duration that has passed, from 0 to 1, to the fraction of
+/// the way from its start value to its target.
+pub type EasingFn = fn(f32) -> f32;
+
+pub fn linear(t: f32) -> f32 {
+    t
+}
+
+pub fn ease_in_quad(t: f32) -> f32 {
+    t * t
+}
+
+pub fn ease_out_quad(t: f32) -> f32 {
+    t * (2.0 - t)
+}
+
+pub fn ease_in_out_quad(t: f32) -> f32 {
+    if t < 0.5 {
+        2.0 * t * t
+    } else {
+        -1.0 + (4.0 - 2.0 * t) * t
+    }
+}
+
+/// Moves a camera value from `start` to `target` over `duration` seconds. It advances by the real
+/// time passed since it was last advanced, so reading the camera several times a frame is fine.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct CameraAnimation<V> {
+    pub start: V,
+    pub target: V,
+    pub duration: f32,
+    pub elapsed: f32,
+    pub easing: EasingFn,
+    last_tick: Instant
+}
+
+pub(crate) type FovAnimation = CameraAnimation<f32>;
+pub(crate) type PositionAnimation = CameraAnimation<Vec3>;
+
+impl<V: Copy + Add<Output = V> + Sub<Output = V> + Mul<f32, Output = V>> CameraAnimation<V> {
+    pub fn new(start: V, target: V, duration: f32, easing: EasingFn) -> Self {
+        Self {
+            start,
+            target,
+            duration,
+            elapsed: 0.0,
+            easing,
+            last_tick: Instant::now()
+        }
+    }
+
+    /// Adds the time since the last advance to `elapsed` and returns the value for the new time.
+    pub fn advance(&mut self) -> V {
+        let now = Instant::now();
+        self.elapsed += (now - self.last_tick).as_secs_f32();
+        self.last_tick = now;
+
+        let t = if self.duration <= 0.0 {
+            1.0
+        } else {
+            (self.elapsed / self.duration).min(1.0)
+        };
+
+        self.start + (self.target - self.start) * (self.easing)(t)
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+}
@@ -1,12 +1,16 @@
 #[allow(non_snake_case)]
 pub mod orthographic_camera;
 pub mod perspective_camera;
+pub mod animation;
 
 use glam::{Mat4, Vec3};
 
 pub trait Camera: Send + Sync {
     fn view(&mut self) -> Mat4;
 
+    /// Maps view space to clip space. Renderers use it with `view` for every draw.
+    fn projection(&self) -> Mat4;
+
     fn position(&self) -> Vec3;
 
     fn set_position(&mut self, new_pos: Vec3);
@@ -22,4 +26,8 @@ pub trait Camera: Send + Sync {
     fn up_vector(&self) -> Vec3;
 
     fn set_up_vector(&mut self, new_vec: Vec3);
+
+    /// Called with the width divided by the height when the primary window is resized. Cameras
+    /// whose projection doesn't depend on the window's shape ignore it.
+    fn set_aspect_ratio(&mut self, _ratio: f32) {}
 }
\ No newline at end of file
@@ -20,9 +20,16 @@ struct CameraValues {
     target: Vec3,
     up_vec: Vec3,
     view: Mat4,
-    change_flag: bool
+    change_flag: bool,
+    left: f32,
+    right: f32,
+    bottom: f32,
+    top: f32,
+    near: f32,
+    far: f32
 }
 
+/// The frustum defaults to the same [-1, 1] cube that clip space covers.
 impl Default for CameraValues {
     fn default() -> Self {
         CameraValues {
@@ -30,11 +37,44 @@ impl Default for CameraValues {
             target: Vec3::ZERO,
             up_vec: Vec3::Y,
             view: Mat4::ZERO,
-            change_flag: true
+            change_flag: true,
+            left: -1.0,
+            right: 1.0,
+            bottom: -1.0,
+            top: 1.0,
+            near: -1.0,
+            far: 1.0
         }
     }
 }
 
+impl OrthographicCamera {
+    #[cfg_attr(feature = "trace", instrument)]
+    pub fn new(position: Vec3, target: Vec3, up_vec: Vec3) -> Self {
+        Self(Arc::new(RwLock::new(
+            CameraValues {
+                position,
+                target,
+                up_vec,
+                ..CameraValues::default()
+            }
+        )))
+    }
+
+    /// Sets the box seen by the camera, in view space. Use it to match the window's size.
+    #[cfg_attr(feature = "trace", instrument)]
+    pub fn set_frustum(&mut self, left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) {
+        let mut vars = self.0.write()
+            .expect("Failed to acquire write lock for camera");
+        vars.left = left;
+        vars.right = right;
+        vars.bottom = bottom;
+        vars.top = top;
+        vars.near = near;
+        vars.far = far;
+    }
+}
+
 impl Camera for OrthographicCamera {
 
     #[cfg_attr(feature = "trace", instrument)]
@@ -57,6 +97,14 @@ impl Camera for OrthographicCamera {
         vars.view
     }
 
+    #[cfg_attr(feature = "trace", instrument)]
+    fn projection(&self) -> Mat4 {
+        let vars = self.0.read()
+            .expect("Failed to acquire read lock for camera");
+
+        Mat4::orthographic_rh(vars.left, vars.right, vars.bottom, vars.top, vars.near, vars.far)
+    }
+
     #[cfg_attr(feature = "trace", instrument)]
     fn position(&self) -> Vec3 {
         let vars = self.0.read()
@@ -123,13 +171,26 @@ pub const ORTHOGRAPHIC_CAMERA_LOAD_ID: &str = "orthographic_camera";
 
 
 #[derive(Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema_gen", derive(schemars::JsonSchema))]
 pub struct OrthographicCameraJSON {
     #[serde(default)]
     position: Option<[f32; 3]>,
     #[serde(default)]
     target: Option<[f32; 3]>,
     #[serde(default)]
-    up_vec: Option<[f32; 3]>
+    up_vec: Option<[f32; 3]>,
+    #[serde(default)]
+    left: Option<f32>,
+    #[serde(default)]
+    right: Option<f32>,
+    #[serde(default)]
+    bottom: Option<f32>,
+    #[serde(default)]
+    top: Option<f32>,
+    #[serde(default)]
+    near: Option<f32>,
+    #[serde(default)]
+    far: Option<f32>
 }
 
 #[derive(Debug, Clone)]
@@ -181,6 +242,12 @@ impl OrthographicCameraLoader {
                             } else {
                                 CameraValues::default().up_vec
                         },
+                        left: json.left.unwrap_or(CameraValues::default().left),
+                        right: json.right.unwrap_or(CameraValues::default().right),
+                        bottom: json.bottom.unwrap_or(CameraValues::default().bottom),
+                        top: json.top.unwrap_or(CameraValues::default().top),
+                        near: json.near.unwrap_or(CameraValues::default().near),
+                        far: json.far.unwrap_or(CameraValues::default().far),
                         ..CameraValues::default()
                     }
                 ))
@@ -194,6 +261,6 @@ pub enum OrthographicCameraErrors {
     #[error("Failed to load Orthographic Camera JSON from file: {path:?}")]
     DeserializeError {
         path: String,
-        source: LoadError
+        source: anyhow::Error
     },
 }
\ No newline at end of file
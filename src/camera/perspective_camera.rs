@@ -1,5 +1,6 @@
 use glam::{Vec3, Mat4};
 use crate::camera::Camera;
+use crate::camera::animation::{EasingFn, FovAnimation, PositionAnimation};
 use std::sync::{RwLock, Arc, PoisonError, RwLockWriteGuard};
 use thiserror::Error;
 use serde::Deserialize;
@@ -20,7 +21,15 @@ struct CameraValues {
     target: Vec3,
     up_vec: Vec3,
     view: Mat4,
-    change_flag: bool
+    change_flag: bool,
+    /// Vertical field of view in radians.
+    fov: f32,
+    /// Width divided by height.
+    aspect_ratio: f32,
+    near: f32,
+    far: f32,
+    fov_animation: Option<FovAnimation>,
+    position_animation: Option<PositionAnimation>
 }
 
 impl Default for CameraValues {
@@ -30,11 +39,92 @@ impl Default for CameraValues {
             target: Vec3::ZERO,
             up_vec: Vec3::Y,
             view: Mat4::ZERO,
-            change_flag: false
+            change_flag: false,
+            fov: DEFAULT_FOV,
+            aspect_ratio: DEFAULT_ASPECT_RATIO,
+            near: DEFAULT_NEAR,
+            far: DEFAULT_FAR,
+            fov_animation: None,
+            position_animation: None
         }
     }
 }
 
+impl CameraValues {
+    /// Moves any running animations forward and clears the finished ones.
+    fn advance_animations(&mut self) {
+        if let Some(animation) = self.fov_animation.as_mut() {
+            self.fov = animation.advance();
+
+            if animation.is_done() {
+                self.fov_animation = None;
+            }
+        }
+
+        if let Some(animation) = self.position_animation.as_mut() {
+            self.position = animation.advance();
+            self.change_flag = true;
+
+            if animation.is_done() {
+                self.position_animation = None;
+            }
+        }
+    }
+}
+
+impl PerspectiveCamera {
+    /// Vertical field of view in radians. Moves any running animation forward.
+    #[cfg_attr(feature = "trace", instrument)]
+    pub fn fov(&self) -> f32 {
+        let mut vars = self.0.write()
+            .expect("Failed to acquire write lock for camera");
+        vars.advance_animations();
+        vars.fov
+    }
+
+    /// Stops any running FOV animation.
+    #[cfg_attr(feature = "trace", instrument)]
+    pub fn set_fov(&mut self, fov: f32) {
+        let mut vars = self.0.write()
+            .expect("Failed to acquire write lock for camera");
+        vars.fov = fov;
+        vars.fov_animation = None;
+    }
+
+    /// Changes the FOV to `target_fov` radians over `duration` seconds, for zooms like a sniper
+    /// scope. The camera animates itself as `view` or `fov` is called, so no system is needed.
+    /// Replaces any running FOV animation, starting from the current FOV.
+    #[cfg_attr(feature = "trace", instrument(skip(easing)))]
+    pub fn animate_fov(&mut self, target_fov: f32, duration: f32, easing: EasingFn) {
+        let mut vars = self.0.write()
+            .expect("Failed to acquire write lock for camera");
+        vars.advance_animations();
+        vars.fov_animation = Some(FovAnimation::new(vars.fov, target_fov, duration, easing));
+    }
+
+    /// Moves the camera to `target_position` over `duration` seconds. Works like `animate_fov`.
+    /// The target is left where it is, so the camera keeps looking at the same point.
+    #[cfg_attr(feature = "trace", instrument(skip(easing)))]
+    pub fn animate_position(&mut self, target_position: Vec3, duration: f32, easing: EasingFn) {
+        let mut vars = self.0.write()
+            .expect("Failed to acquire write lock for camera");
+        vars.advance_animations();
+        vars.position_animation = Some(PositionAnimation::new(vars.position, target_position, duration, easing));
+    }
+
+    pub fn aspect_ratio(&self) -> f32 {
+        let vars = self.0.read()
+            .expect("Failed to acquire read lock for camera");
+        vars.aspect_ratio
+    }
+
+    pub fn is_animating(&self) -> bool {
+        let vars = self.0.read()
+            .expect("Failed to acquire read lock for camera");
+        vars.fov_animation.is_some() || vars.position_animation.is_some()
+    }
+}
+
 impl Camera for PerspectiveCamera {
 
     #[cfg_attr(feature = "trace", instrument)]
@@ -42,6 +132,8 @@ impl Camera for PerspectiveCamera {
         let mut vars = self.0.write()
             .expect("Failed to acquire write lock for camera");
 
+        vars.advance_animations();
+
         if vars.change_flag {
             #[cfg(feature = "trace")]
             debug!("Change flag is set. Recalculating view matrix.");
@@ -57,6 +149,16 @@ impl Camera for PerspectiveCamera {
         vars.view
     }
 
+    /// Moves any running FOV animation forward.
+    #[cfg_attr(feature = "trace", instrument)]
+    fn projection(&self) -> Mat4 {
+        let mut vars = self.0.write()
+            .expect("Failed to acquire write lock for camera");
+        vars.advance_animations();
+
+        Mat4::perspective_rh(vars.fov, vars.aspect_ratio, vars.near, vars.far)
+    }
+
     #[cfg_attr(feature = "trace", instrument)]
     fn position(&self) -> Vec3 {
         let vars = self.0.read()
@@ -69,6 +171,7 @@ impl Camera for PerspectiveCamera {
         let mut vars = self.0.write()
             .expect("Failed to acquire write lock for camera");
         vars.position = new_pos;
+        vars.position_animation = None;
         vars.change_flag = true;
     }
 
@@ -117,19 +220,42 @@ impl Camera for PerspectiveCamera {
         vars.up_vec = new_vec;
         vars.change_flag = true;
     }
+
+    /// The GameLoop sets it when the primary window is resized so the image isn't stretched.
+    #[cfg_attr(feature = "trace", instrument)]
+    fn set_aspect_ratio(&mut self, ratio: f32) {
+        let mut vars = self.0.write()
+            .expect("Failed to acquire write lock for camera");
+        vars.aspect_ratio = ratio;
+    }
 }
 
 pub const PERSPECTIVE_CAMERA_LOAD_ID: &str = "perspective_camera";
+/// 60 degrees.
+pub const DEFAULT_FOV: f32 = std::f32::consts::FRAC_PI_3;
+/// Width divided by height, for a 16:9 window.
+pub const DEFAULT_ASPECT_RATIO: f32 = 16.0 / 9.0;
+pub const DEFAULT_NEAR: f32 = 0.1;
+pub const DEFAULT_FAR: f32 = 1000.0;
 
 
 #[derive(Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema_gen", derive(schemars::JsonSchema))]
 pub struct PerspectiveCameraJSON {
     #[serde(default)]
     position: Option<[f32; 3]>,
     #[serde(default)]
     target: Option<[f32; 3]>,
     #[serde(default)]
-    up_vec: Option<[f32; 3]>
+    up_vec: Option<[f32; 3]>,
+    #[serde(default)]
+    fov_y_radians: Option<f32>,
+    #[serde(default)]
+    aspect_ratio: Option<f32>,
+    #[serde(default)]
+    near: Option<f32>,
+    #[serde(default)]
+    far: Option<f32>
 }
 
 #[derive(Debug, Clone)]
@@ -181,6 +307,10 @@ impl PerspectiveCameraLoader {
                         } else {
                             Default::default()
                         },
+                        fov: json.fov_y_radians.unwrap_or(DEFAULT_FOV),
+                        aspect_ratio: json.aspect_ratio.unwrap_or(DEFAULT_ASPECT_RATIO),
+                        near: json.near.unwrap_or(DEFAULT_NEAR),
+                        far: json.far.unwrap_or(DEFAULT_FAR),
                         ..Default::default()
                     }
                 ))
@@ -194,6 +324,67 @@ pub enum PerspectiveCameraErrors {
     #[error("Failed to load Perspective Camera JSON from file: {path:?}")]
     DeserializeError {
         path: String,
-        source: LoadError
+        source: anyhow::Error
     },
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use glam::Vec4;
+
+    use super::*;
+
+    /// Camera 5 units up the Z axis looking at the origin, seeing 45 degrees to each side.
+    fn camera() -> PerspectiveCamera {
+        let mut camera = PerspectiveCamera(Arc::new(RwLock::new(CameraValues {
+            fov: std::f32::consts::FRAC_PI_2,
+            aspect_ratio: 1.0,
+            near: 1.0,
+            far: 100.0,
+            ..CameraValues::default()
+        })));
+        camera.set_position(Vec3::new(0.0, 0.0, 5.0));
+        camera.set_target(Vec3::ZERO);
+
+        camera
+    }
+
+    /// Normalized device coordinates of `point` seen through `camera`.
+    fn to_ndc(camera: &mut PerspectiveCamera, point: Vec3) -> Vec3 {
+        let clip = camera.projection() * camera.view() * Vec4::new(point.x, point.y, point.z, 1.0);
+
+        Vec3::new(clip.x, clip.y, clip.z) / clip.w
+    }
+
+    #[test]
+    fn target_is_in_the_center_of_clip_space() {
+        let ndc = to_ndc(&mut camera(), Vec3::ZERO);
+
+        assert!(ndc.x.abs() < 1e-5 && ndc.y.abs() < 1e-5);
+        assert!(ndc.z > -1.0 && ndc.z < 1.0);
+    }
+
+    #[test]
+    fn edge_of_the_field_of_view_is_on_the_edge_of_clip_space() {
+        let ndc = to_ndc(&mut camera(), Vec3::new(5.0, 5.0, 0.0));
+
+        assert!((ndc.x - 1.0).abs() < 1e-5);
+        assert!((ndc.y - 1.0).abs() < 1e-5);
+    }
+
+    /// `perspective_rh` maps depth to 0 at the near plane and 1 at the far plane.
+    #[test]
+    fn near_and_far_planes_map_to_the_depth_range() {
+        let mut camera = camera();
+
+        assert!(to_ndc(&mut camera, Vec3::new(0.0, 0.0, 4.0)).z.abs() < 1e-4);
+        assert!((to_ndc(&mut camera, Vec3::new(0.0, 0.0, -95.0)).z - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn projection_uses_the_aspect_ratio() {
+        let mut camera = camera();
+        camera.set_aspect_ratio(2.0);
+
+        assert!((to_ndc(&mut camera, Vec3::new(5.0, 0.0, 0.0)).x - 0.5).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,185 @@
+use serde::{Deserialize, Serialize};
+use specs::{Component, DenseVecStorage, World, Builder};
+use specs::world::LazyBuilder;
+use std::sync::{Arc, RwLock};
+use luminance_glfw::GL33Context;
+use anyhow::{Error, Result};
+use thiserror::Error;
+
+#[cfg(feature = "trace")]
+use tracing::{debug, error, instrument};
+
+use crate::components::ComponentLoader;
+use crate::components::sprite_region::SpriteRegion;
+use crate::load::{JSONLoad, load_deserializable_from_json, serialize_to_json_load, LoadError};
+use crate::components::animated_sprite::AnimatedSpriteLoaderError::{DeserializeError, LoadTypeIDError, InvalidFramesError};
+
+pub const ANIMATED_SPRITE_LOAD_ID: &str = "animated_sprite";
+
+/// Cycles the entity's SpriteRegion through the frames of a sprite sheet row. Frame `n` is the
+/// `n`th region-sized column from the left of the image, on the row the region's `y` picks.
+/// Advanced by the AnimateSprites system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnimatedSprite {
+    pub start_frame: u32,
+    pub end_frame: u32,
+    /// Ticks each frame is shown for.
+    pub frame_pause: u32,
+    /// Ticks the current frame has been shown for.
+    pub frame_pause_counter: u32,
+    /// Set once an animation that doesn't loop has shown its last frame.
+    pub finished: bool
+}
+
+impl Component for AnimatedSprite { type Storage = DenseVecStorage<Self>; }
+crate::register_component!(AnimatedSprite);
+
+impl AnimatedSprite {
+    pub fn new(start_frame: u32, end_frame: u32, frame_pause: u32) -> Self {
+        Self {
+            start_frame,
+            end_frame,
+            frame_pause,
+            frame_pause_counter: 0,
+            finished: false
+        }
+    }
+
+    /// Frame `region` currently shows.
+    pub fn frame(region: &SpriteRegion) -> u32 {
+        region.x / region.width.max(1)
+    }
+
+    /// Moves `region` to the column of `frame`.
+    pub fn set_frame(region: &mut SpriteRegion, frame: u32) {
+        region.x = frame * region.width;
+    }
+
+    /// Starts the animation over from `start_frame`.
+    pub fn restart(&mut self, region: &mut SpriteRegion) {
+        self.frame_pause_counter = 0;
+        self.finished = false;
+        Self::set_frame(region, self.start_frame);
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema_gen", derive(schemars::JsonSchema))]
+pub struct AnimatedSpriteJSON {
+    start_frame: u32,
+    end_frame: u32,
+    frame_pause: u32
+}
+
+#[derive(Debug, Clone)]
+pub struct AnimatedSpriteLoader {
+    json: AnimatedSpriteJSON
+}
+
+impl ComponentLoader for AnimatedSpriteLoader {
+    #[cfg_attr(feature = "trace", instrument)]
+    fn from_json(json: JSONLoad) -> Result<Self> where Self: Sized {
+        Self::validate_schema(&json)?;
+
+        let animated_sprite_json: AnimatedSpriteJSON = load_deserializable_from_json(&json, ANIMATED_SPRITE_LOAD_ID)
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to convert JSONLoad object: ({:?}) into AnimatedSpriteJSON value", json.clone());
+
+                DeserializeError {
+                    source: e,
+                    json: json.clone()
+                }
+            })?;
+
+        Ok(Self { json: animated_sprite_json })
+    }
+
+    #[cfg(feature = "schema_gen")]
+    fn json_schema() -> serde_json::Value where Self: Sized {
+        crate::schema::validation_schema::<AnimatedSpriteJSON>()
+    }
+
+    #[cfg_attr(feature = "trace", instrument(skip(builder, _ecs, _context)))]
+    fn load_component<'a>(&self, builder: LazyBuilder<'a>, _ecs: Arc<RwLock<World>>, _context: Option<Arc<RwLock<GL33Context>>>) -> Result<LazyBuilder<'a>> {
+        if self.json.start_frame > self.json.end_frame || self.json.frame_pause == 0 {
+            #[cfg(feature = "trace")]
+            error!("Animated sprite: {:?} has no frames to show", self.json);
+
+            return Err(Error::new(InvalidFramesError {
+                start_frame: self.json.start_frame,
+                end_frame: self.json.end_frame,
+                frame_pause: self.json.frame_pause
+            }))
+        }
+
+        let animated_sprite = AnimatedSprite::new(self.json.start_frame, self.json.end_frame, self.json.frame_pause);
+
+        #[cfg(feature = "trace")]
+        debug!("Created new animated sprite component: {:?}", animated_sprite);
+
+        Ok(builder.with(animated_sprite))
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn set_value(&mut self, new_value: JSONLoad) -> Result<()> {
+        if new_value.load_type_id == ANIMATED_SPRITE_LOAD_ID {
+            self.json = load_deserializable_from_json(&new_value, ANIMATED_SPRITE_LOAD_ID)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to convert JSONLoad object: ({:?}) into AnimatedSpriteJSON value", new_value.clone());
+
+                    DeserializeError {
+                        source: e,
+                        json: new_value.clone()
+                    }
+                })?;
+
+            Ok(())
+        } else {
+            #[cfg(feature = "trace")]
+            error!("Given load-type ID: ({:?}) does not match expected ID: {:?}", new_value.load_type_id.clone(), ANIMATED_SPRITE_LOAD_ID.to_string());
+
+            Err(Error::new(LoadTypeIDError {
+                actual: new_value.load_type_id,
+                expected: ANIMATED_SPRITE_LOAD_ID.to_string()
+            }))
+        }
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn get_value(&self) -> Result<JSONLoad, LoadError> {
+        serialize_to_json_load(&self.json, ANIMATED_SPRITE_LOAD_ID)
+    }
+
+    fn clone_boxed(&self) -> Box<dyn ComponentLoader> {
+        Box::new(self.clone())
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn get_component_name(&self) -> String {
+        ANIMATED_SPRITE_LOAD_ID.to_string()
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum AnimatedSpriteLoaderError {
+    #[error("Failed to convert JSONLoad object: ({json:?}) to AnimatedSpriteJSON value")]
+    DeserializeError {
+        source: LoadError,
+        json: JSONLoad
+    },
+
+    #[error("Given load-type ID: ({actual}) does not match expected ID: {expected}")]
+    LoadTypeIDError {
+        actual: String,
+        expected: String
+    },
+
+    #[error("Frames: {start_frame}..={end_frame} shown for {frame_pause} ticks each has nothing to show")]
+    InvalidFramesError {
+        start_frame: u32,
+        end_frame: u32,
+        frame_pause: u32
+    }
+}
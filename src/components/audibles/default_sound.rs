@@ -32,6 +32,8 @@ pub struct DefaultSoundLoader {
 
 impl ComponentLoader for DefaultSoundLoader {
     fn from_json(json: JSONLoad) -> Result<Self> where Self: Sized {
+        Self::validate_schema(&json)?;
+
         let component = load_deserializable_from_json(json, DEFAULT_SOUND_LOAD_ID)
             .map_err(|e| {
                 Error::new(e)
@@ -0,0 +1,148 @@
+use serde::{Deserialize, Serialize};
+use specs::{Component, DenseVecStorage, World, Builder};
+use specs::world::LazyBuilder;
+use std::sync::{Arc, RwLock};
+use luminance_glfw::GL33Context;
+use glfw::StandardCursor;
+use anyhow::{Error, Result};
+use thiserror::Error;
+
+#[cfg(feature = "trace")]
+use tracing::{debug, error, instrument};
+
+use crate::components::ComponentLoader;
+use crate::load::{JSONLoad, load_deserializable_from_json, serialize_to_json_load, LoadError};
+use crate::components::cursor_icon::CursorIconLoaderError::{DeserializeError, LoadTypeIDError};
+
+pub const CURSOR_ICON_LOAD_ID: &str = "cursor_icon";
+
+/// The standard cursor shapes GLFW provides.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schema_gen", derive(schemars::JsonSchema))]
+pub enum CursorType {
+    Arrow,
+    IBeam,
+    Crosshair,
+    Hand,
+    HResize,
+    VResize
+}
+
+impl From<CursorType> for StandardCursor {
+    fn from(cursor_type: CursorType) -> Self {
+        match cursor_type {
+            CursorType::Arrow => StandardCursor::Arrow,
+            CursorType::IBeam => StandardCursor::IBeam,
+            CursorType::Crosshair => StandardCursor::Crosshair,
+            CursorType::Hand => StandardCursor::Hand,
+            CursorType::HResize => StandardCursor::HResize,
+            CursorType::VResize => StandardCursor::VResize
+        }
+    }
+}
+
+/// Cursor shown while the entity's Focusable is focused, such as a hand over a button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CursorIcon(pub CursorType);
+
+impl Component for CursorIcon { type Storage = DenseVecStorage<Self>; }
+crate::register_component!(CursorIcon);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema_gen", derive(schemars::JsonSchema))]
+pub struct CursorIconJSON {
+    cursor: CursorType
+}
+
+#[derive(Debug, Clone)]
+pub struct CursorIconLoader {
+    json: CursorIconJSON
+}
+
+impl ComponentLoader for CursorIconLoader {
+    #[cfg_attr(feature = "trace", instrument)]
+    fn from_json(json: JSONLoad) -> Result<Self> where Self: Sized {
+        Self::validate_schema(&json)?;
+
+        let cursor_json: CursorIconJSON = load_deserializable_from_json(&json, CURSOR_ICON_LOAD_ID)
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to convert JSONLoad object: ({:?}) into CursorIconJSON value", json.clone());
+
+                DeserializeError {
+                    source: e,
+                    json: json.clone()
+                }
+            })?;
+
+        Ok(Self { json: cursor_json })
+    }
+
+    #[cfg(feature = "schema_gen")]
+    fn json_schema() -> serde_json::Value where Self: Sized {
+        crate::schema::validation_schema::<CursorIconJSON>()
+    }
+
+    #[cfg_attr(feature = "trace", instrument(skip(builder, _ecs, _context)))]
+    fn load_component<'a>(&self, builder: LazyBuilder<'a>, _ecs: Arc<RwLock<World>>, _context: Option<Arc<RwLock<GL33Context>>>) -> Result<LazyBuilder<'a>> {
+        #[cfg(feature = "trace")]
+        debug!("Created new cursor icon component: {:?}", self.json.cursor);
+
+        Ok(builder.with(CursorIcon(self.json.cursor)))
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn set_value(&mut self, new_value: JSONLoad) -> Result<()> {
+        if new_value.load_type_id == CURSOR_ICON_LOAD_ID {
+            self.json = load_deserializable_from_json(&new_value, CURSOR_ICON_LOAD_ID)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to convert JSONLoad object: ({:?}) into CursorIconJSON value", new_value.clone());
+
+                    DeserializeError {
+                        source: e,
+                        json: new_value.clone()
+                    }
+                })?;
+
+            Ok(())
+        } else {
+            #[cfg(feature = "trace")]
+            error!("Given load-type ID: ({:?}) does not match expected ID: {:?}", new_value.load_type_id.clone(), CURSOR_ICON_LOAD_ID.to_string());
+
+            Err(Error::new(LoadTypeIDError {
+                actual: new_value.load_type_id,
+                expected: CURSOR_ICON_LOAD_ID.to_string()
+            }))
+        }
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn get_value(&self) -> Result<JSONLoad, LoadError> {
+        serialize_to_json_load(&self.json, CURSOR_ICON_LOAD_ID)
+    }
+
+    fn clone_boxed(&self) -> Box<dyn ComponentLoader> {
+        Box::new(self.clone())
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn get_component_name(&self) -> String {
+        CURSOR_ICON_LOAD_ID.to_string()
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum CursorIconLoaderError {
+    #[error("Failed to convert JSONLoad object: ({json:?}) to CursorIconJSON value")]
+    DeserializeError {
+        source: LoadError,
+        json: JSONLoad
+    },
+
+    #[error("Given load-type ID: ({actual}) does not match expected ID: {expected}")]
+    LoadTypeIDError {
+        actual: String,
+        expected: String
+    }
+}
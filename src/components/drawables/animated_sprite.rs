@@ -68,6 +68,8 @@ impl AnimatedSpriteLoader {
 
 impl ComponentLoader for AnimatedSpriteLoader {
     fn from_json(json: JSONLoad) -> Result<Self> where Self: Sized {
+        Self::validate_schema(&json)?;
+
         let sprite_json = load_deserializable_from_json(json, ANIMATED_SPRITE_LOAD_ID)
             .map_err(|e| {
                 Error::new(e)
@@ -47,6 +47,8 @@ pub struct DrawableLoader {
 
 impl ComponentLoader for DrawableLoader {
     fn from_json(json: JSONLoad) -> Result<Self> where Self: Sized {
+        Self::validate_schema(&json)?;
+
         let drawable_json = load_deserializable_from_json(json, DRAWABLE_LOAD_ID)
             .map_err(|e| { Error::new(e) })?;
 
@@ -141,6 +141,8 @@ pub struct ShapesLoader {
 
 impl ComponentLoader for ShapesLoader {
     fn from_json(json: JSONLoad) -> Result<Self> where Self: Sized {
+        Self::validate_schema(&json)?;
+
         let shapes_json = load_deserializable_from_json(json, SHAPES_LOAD_ID)
             .map_err(|e| {
                 Error::new(e)
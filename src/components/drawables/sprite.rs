@@ -61,6 +61,8 @@ impl SpriteLoader {
 
 impl ComponentLoader for SpriteLoader {
     fn from_json(json: JSONLoad) -> Result<Self> where Self: Sized {
+        Self::validate_schema(&json)?;
+
         let sprite_json = load_deserializable_from_json(json, SPRITE_LOAD_ID)
             .map_err(|e| {
                 Error::new(e)
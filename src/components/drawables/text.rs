@@ -98,6 +98,8 @@ pub struct TextLoader {
 
 impl ComponentLoader for TextLoader {
     fn from_json(json: JSONLoad) -> Result<Self> where Self: Sized {
+        Self::validate_schema(&json)?;
+
         let text_json = load_deserializable_from_json(json, TEXT_LOAD_ID)
             .map_err(|e| {
                 Error::new(e)
@@ -0,0 +1,11 @@
+use specs::{Component, DenseVecStorage};
+
+/// A UI element that can hold focus, for example while the mouse is over it. The game's UI code
+/// sets `is_focused`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Focusable {
+    pub is_focused: bool
+}
+
+impl Component for Focusable { type Storage = DenseVecStorage<Self>; }
+crate::register_component!(Focusable);
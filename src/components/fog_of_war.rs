@@ -0,0 +1,191 @@
+use serde::{Deserialize, Serialize};
+use specs::{Component, DenseVecStorage, World, Builder, System, ReadStorage, Write, Join};
+use specs::world::LazyBuilder;
+use std::sync::{Arc, RwLock};
+use luminance_glfw::GL33Context;
+use glam::Vec2;
+use anyhow::{Error, Result};
+use thiserror::Error;
+
+#[cfg(feature = "trace")]
+use tracing::{debug, error, instrument};
+
+use crate::components::ComponentLoader;
+use crate::graphics::transform::Transform;
+use crate::load::{JSONLoad, load_deserializable_from_json, serialize_to_json_load, LoadError};
+use crate::components::fog_of_war::FogOfWarLoaderError::{DeserializeError, LoadTypeIDError};
+
+pub const FOG_OF_WAR_LOAD_ID: &str = "fog_of_war";
+
+/// Reveals every cell of the VisibilityMap within `visibility_radius` world units of the entity's Transform.
+#[derive(Debug, Clone, Copy)]
+pub struct FogOfWar {
+    pub visibility_radius: f32
+}
+
+impl Component for FogOfWar { type Storage = DenseVecStorage<Self>; }
+crate::register_component!(FogOfWar);
+
+/// Grid of explored cells stored row by row, followed by the grid's width and height in cells.
+/// Cell (0, 0) starts at the world origin.
+#[derive(Debug, Clone, Default)]
+pub struct VisibilityMap(pub Vec<bool>, pub u32, pub u32);
+
+impl VisibilityMap {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self(vec![false; (width * height) as usize], width, height)
+    }
+
+    pub fn is_visible(&self, x: u32, y: u32) -> bool {
+        x < self.1 && y < self.2 && self.0[(y * self.1 + x) as usize]
+    }
+
+    pub fn reveal(&mut self, x: u32, y: u32) {
+        if x < self.1 && y < self.2 {
+            self.0[(y * self.1 + x) as usize] = true;
+        }
+    }
+}
+
+/// Marks the cells around every FogOfWar entity as explored. Cells stay explored once revealed.
+#[derive(Debug, Clone, Copy)]
+pub struct FogOfWarSystem {
+    /// Size of a VisibilityMap cell in world units.
+    pub cell_size: f32
+}
+
+impl<'a> System<'a> for FogOfWarSystem {
+    type SystemData = (
+        ReadStorage<'a, FogOfWar>,
+        ReadStorage<'a, Transform>,
+        Write<'a, VisibilityMap>
+    );
+
+    #[cfg_attr(feature = "trace", instrument(skip(self, data)))]
+    fn run(&mut self, data: Self::SystemData) {
+        let (fogs, transforms, mut visibility_map) = data;
+
+        for (fog, transform) in (&fogs, &transforms).join() {
+            let center = transform.translation.truncate() / self.cell_size;
+            let radius = fog.visibility_radius / self.cell_size;
+
+            let min_x = (center.x - radius).floor().max(0.0) as u32;
+            let min_y = (center.y - radius).floor().max(0.0) as u32;
+            let max_x = ((center.x + radius).ceil().max(0.0) as u32).min(visibility_map.1);
+            let max_y = ((center.y + radius).ceil().max(0.0) as u32).min(visibility_map.2);
+
+            for y in min_y..max_y {
+                for x in min_x..max_x {
+                    let cell_center = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+
+                    if cell_center.distance(center) <= radius {
+                        visibility_map.reveal(x, y);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema_gen", derive(schemars::JsonSchema))]
+pub struct FogOfWarJSON {
+    visibility_radius: f32
+}
+
+#[derive(Debug, Clone)]
+pub struct FogOfWarLoader {
+    json: FogOfWarJSON
+}
+
+impl ComponentLoader for FogOfWarLoader {
+    #[cfg_attr(feature = "trace", instrument)]
+    fn from_json(json: JSONLoad) -> Result<Self> where Self: Sized {
+        Self::validate_schema(&json)?;
+
+        let fog_of_war_json: FogOfWarJSON = load_deserializable_from_json(&json, FOG_OF_WAR_LOAD_ID)
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to convert JSONLoad object: ({:?}) into FogOfWarJSON value", json.clone());
+
+                DeserializeError {
+                    source: e,
+                    json: json.clone()
+                }
+            })?;
+
+        Ok(Self { json: fog_of_war_json })
+    }
+
+    #[cfg(feature = "schema_gen")]
+    fn json_schema() -> serde_json::Value where Self: Sized {
+        crate::schema::validation_schema::<FogOfWarJSON>()
+    }
+
+    #[cfg_attr(feature = "trace", instrument(skip(builder, _ecs, _context)))]
+    fn load_component<'a>(&self, builder: LazyBuilder<'a>, _ecs: Arc<RwLock<World>>, _context: Option<Arc<RwLock<GL33Context>>>) -> Result<LazyBuilder<'a>> {
+        let fog_of_war = FogOfWar {
+            visibility_radius: self.json.visibility_radius
+        };
+
+        #[cfg(feature = "trace")]
+        debug!("Created new fog of war component: {:?}", fog_of_war);
+
+        Ok(builder.with(fog_of_war))
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn set_value(&mut self, new_value: JSONLoad) -> Result<()> {
+        if new_value.load_type_id == FOG_OF_WAR_LOAD_ID {
+            self.json = load_deserializable_from_json(&new_value, FOG_OF_WAR_LOAD_ID)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to convert JSONLoad object: ({:?}) into FogOfWarJSON value", new_value.clone());
+
+                    DeserializeError {
+                        source: e,
+                        json: new_value.clone()
+                    }
+                })?;
+
+            Ok(())
+        } else {
+            #[cfg(feature = "trace")]
+            error!("Given load-type ID: ({:?}) does not match expected ID: {:?}", new_value.load_type_id.clone(), FOG_OF_WAR_LOAD_ID.to_string());
+
+            Err(Error::new(LoadTypeIDError {
+                actual: new_value.load_type_id,
+                expected: FOG_OF_WAR_LOAD_ID.to_string()
+            }))
+        }
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn get_value(&self) -> Result<JSONLoad, LoadError> {
+        serialize_to_json_load(&self.json, FOG_OF_WAR_LOAD_ID)
+    }
+
+    fn clone_boxed(&self) -> Box<dyn ComponentLoader> {
+        Box::new(self.clone())
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn get_component_name(&self) -> String {
+        FOG_OF_WAR_LOAD_ID.to_string()
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum FogOfWarLoaderError {
+    #[error("Failed to convert JSONLoad object: ({json:?}) to FogOfWarJSON value")]
+    DeserializeError {
+        source: LoadError,
+        json: JSONLoad
+    },
+
+    #[error("Given load-type ID: ({actual}) does not match expected ID: {expected}")]
+    LoadTypeIDError {
+        actual: String,
+        expected: String
+    }
+}
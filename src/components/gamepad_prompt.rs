@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+use specs::{Component, DenseVecStorage, World, Builder};
+use specs::world::LazyBuilder;
+use std::sync::{Arc, RwLock};
+use luminance_glfw::GL33Context;
+use anyhow::{Error, Result};
+use serde_json::{json, Value};
+use thiserror::Error;
+
+#[cfg(feature = "trace")]
+use tracing::{debug, error, instrument};
+
+use crate::components::ComponentLoader;
+use crate::load::{JSONLoad, load_deserializable_from_json, serialize_to_json_load, LoadError};
+use crate::components::gamepad_prompt::GamepadPromptLoaderError::{DeserializeError, LoadTypeIDError};
+
+pub const GAMEPAD_PROMPT_LOAD_ID: &str = "gamepad_prompt";
+
+/// Shows the icon of the button bound to `action_name` on the connected controller. The
+/// GamepadIconSystem keeps the entity's TextureHandle pointed at the icon.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GamepadPrompt {
+    pub action_name: String
+}
+
+impl Component for GamepadPrompt { type Storage = DenseVecStorage<Self>; }
+crate::register_component!(GamepadPrompt);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema_gen", derive(schemars::JsonSchema))]
+pub struct GamepadPromptJSON {
+    action_name: String
+}
+
+#[derive(Debug, Clone)]
+pub struct GamepadPromptLoader {
+    json: GamepadPromptJSON
+}
+
+impl ComponentLoader for GamepadPromptLoader {
+    #[cfg_attr(feature = "trace", instrument)]
+    fn from_json(json: JSONLoad) -> Result<Self> where Self: Sized {
+        Self::validate_schema(&json)?;
+
+        let prompt_json: GamepadPromptJSON = load_deserializable_from_json(&json, GAMEPAD_PROMPT_LOAD_ID)
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to convert JSONLoad object: ({:?}) into GamepadPromptJSON value", json.clone());
+
+                DeserializeError {
+                    source: e,
+                    json: json.clone()
+                }
+            })?;
+
+        Ok(Self { json: prompt_json })
+    }
+
+    fn json_schema() -> Value where Self: Sized {
+        json!({
+            "type": "object",
+            "properties": {
+                "action_name": { "type": "string", "minLength": 1 }
+            },
+            "required": ["action_name"]
+        })
+    }
+
+    #[cfg_attr(feature = "trace", instrument(skip(builder, _ecs, _context)))]
+    fn load_component<'a>(&self, builder: LazyBuilder<'a>, _ecs: Arc<RwLock<World>>, _context: Option<Arc<RwLock<GL33Context>>>) -> Result<LazyBuilder<'a>> {
+        #[cfg(feature = "trace")]
+        debug!("Created new gamepad prompt component: {:?}", self.json.action_name.clone());
+
+        Ok(builder.with(GamepadPrompt {
+            action_name: self.json.action_name.clone()
+        }))
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn set_value(&mut self, new_value: JSONLoad) -> Result<()> {
+        if new_value.load_type_id == GAMEPAD_PROMPT_LOAD_ID {
+            self.json = load_deserializable_from_json(&new_value, GAMEPAD_PROMPT_LOAD_ID)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to convert JSONLoad object: ({:?}) into GamepadPromptJSON value", new_value.clone());
+
+                    DeserializeError {
+                        source: e,
+                        json: new_value.clone()
+                    }
+                })?;
+
+            Ok(())
+        } else {
+            #[cfg(feature = "trace")]
+            error!("Given load-type ID: ({:?}) does not match expected ID: {:?}", new_value.load_type_id.clone(), GAMEPAD_PROMPT_LOAD_ID.to_string());
+
+            Err(Error::new(LoadTypeIDError {
+                actual: new_value.load_type_id,
+                expected: GAMEPAD_PROMPT_LOAD_ID.to_string()
+            }))
+        }
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn get_value(&self) -> Result<JSONLoad, LoadError> {
+        serialize_to_json_load(&self.json, GAMEPAD_PROMPT_LOAD_ID)
+    }
+
+    fn clone_boxed(&self) -> Box<dyn ComponentLoader> {
+        Box::new(self.clone())
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn get_component_name(&self) -> String {
+        GAMEPAD_PROMPT_LOAD_ID.to_string()
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum GamepadPromptLoaderError {
+    #[error("Failed to convert JSONLoad object: ({json:?}) to GamepadPromptJSON value")]
+    DeserializeError {
+        source: LoadError,
+        json: JSONLoad
+    },
+
+    #[error("Given load-type ID: ({actual}) does not match expected ID: {expected}")]
+    LoadTypeIDError {
+        actual: String,
+        expected: String
+    }
+}
@@ -0,0 +1,231 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use specs::{Component, DenseVecStorage, World, Builder, System, ReadStorage, WriteStorage, Read, Join};
+use specs::world::LazyBuilder;
+use std::sync::{Arc, RwLock};
+use luminance_glfw::GL33Context;
+use glam::Vec2;
+use anyhow::{Error, Result};
+use thiserror::Error;
+
+#[cfg(feature = "trace")]
+use tracing::{debug, error, instrument};
+
+use crate::components::ComponentLoader;
+use crate::graphics::transform::Transform;
+use crate::load::{JSONLoad, load_deserializable_from_json, serialize_to_json_load, LoadError};
+use crate::components::grid_mover::GridMoverLoaderError::{DeserializeError, LoadTypeIDError};
+
+pub const GRID_MOVER_LOAD_ID: &str = "grid_mover";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right
+}
+
+impl Direction {
+    /// Tile offset of one step in this direction. Up is towards positive y.
+    pub fn offset(&self) -> (i32, i32) {
+        match self {
+            Direction::Up => (0, 1),
+            Direction::Down => (0, -1),
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0)
+        }
+    }
+}
+
+/// Moves the entity's Transform from tile to tile. Tile (x, y) is centered on
+/// `(x * grid_size, y * grid_size)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridMover {
+    pub grid_size: f32,
+    pub target_tile: (i32, i32),
+    pub current_tile: (i32, i32),
+    /// World units per second.
+    pub move_speed: f32,
+    /// Direction of the next step, taken once the entity reaches its target tile.
+    pub queued_direction: Option<Direction>
+}
+
+impl Component for GridMover { type Storage = DenseVecStorage<Self>; }
+crate::register_component!(GridMover);
+
+impl GridMover {
+    pub fn new(grid_size: f32, move_speed: f32, tile: (i32, i32)) -> Self {
+        Self {
+            grid_size,
+            target_tile: tile,
+            current_tile: tile,
+            move_speed,
+            queued_direction: None
+        }
+    }
+
+    pub fn tile_position(&self, tile: (i32, i32)) -> Vec2 {
+        Vec2::new(tile.0 as f32, tile.1 as f32) * self.grid_size
+    }
+}
+
+/// Tiles GridMovers can't step onto.
+#[derive(Debug, Clone, Default)]
+pub struct TileCollisionMap(pub HashSet<(i32, i32)>);
+
+impl TileCollisionMap {
+    pub fn is_blocked(&self, tile: (i32, i32)) -> bool {
+        self.0.contains(&tile)
+    }
+}
+
+/// Moves every GridMover towards its target tile by `delta_time` seconds of movement.
+#[derive(Debug, Clone, Copy)]
+pub struct GridMovementSystem {
+    pub delta_time: f32
+}
+
+impl<'a> System<'a> for GridMovementSystem {
+    type SystemData = (
+        WriteStorage<'a, GridMover>,
+        WriteStorage<'a, Transform>,
+        Read<'a, TileCollisionMap>
+    );
+
+    #[cfg_attr(feature = "trace", instrument(skip(self, data)))]
+    fn run(&mut self, data: Self::SystemData) {
+        let (mut movers, mut transforms, collision_map) = data;
+
+        for (mover, transform) in (&mut movers, &mut transforms).join() {
+            let target = mover.tile_position(mover.target_tile).extend(transform.translation.z);
+            let to_target = target - transform.translation;
+            let step = mover.move_speed * self.delta_time;
+
+            if to_target.length() > step {
+                transform.translation += to_target.normalize() * step;
+                continue
+            }
+
+            transform.translation = target;
+            mover.current_tile = mover.target_tile;
+
+            if let Some(direction) = mover.queued_direction.take() {
+                let (dx, dy) = direction.offset();
+                let next_tile = (mover.current_tile.0 + dx, mover.current_tile.1 + dy);
+
+                if collision_map.is_blocked(next_tile) {
+                    #[cfg(feature = "trace")]
+                    debug!("Tile: {:?} is blocked", next_tile);
+                } else {
+                    mover.target_tile = next_tile;
+                }
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema_gen", derive(schemars::JsonSchema))]
+pub struct GridMoverJSON {
+    grid_size: f32,
+    move_speed: f32,
+    #[serde(default)]
+    start_tile: (i32, i32)
+}
+
+#[derive(Debug, Clone)]
+pub struct GridMoverLoader {
+    json: GridMoverJSON
+}
+
+impl ComponentLoader for GridMoverLoader {
+    #[cfg_attr(feature = "trace", instrument)]
+    fn from_json(json: JSONLoad) -> Result<Self> where Self: Sized {
+        Self::validate_schema(&json)?;
+
+        let grid_mover_json: GridMoverJSON = load_deserializable_from_json(&json, GRID_MOVER_LOAD_ID)
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to convert JSONLoad object: ({:?}) into GridMoverJSON value", json.clone());
+
+                DeserializeError {
+                    source: e,
+                    json: json.clone()
+                }
+            })?;
+
+        Ok(Self { json: grid_mover_json })
+    }
+
+    #[cfg(feature = "schema_gen")]
+    fn json_schema() -> serde_json::Value where Self: Sized {
+        crate::schema::validation_schema::<GridMoverJSON>()
+    }
+
+    #[cfg_attr(feature = "trace", instrument(skip(builder, _ecs, _context)))]
+    fn load_component<'a>(&self, builder: LazyBuilder<'a>, _ecs: Arc<RwLock<World>>, _context: Option<Arc<RwLock<GL33Context>>>) -> Result<LazyBuilder<'a>> {
+        let grid_mover = GridMover::new(self.json.grid_size, self.json.move_speed, self.json.start_tile);
+
+        #[cfg(feature = "trace")]
+        debug!("Created new grid mover component: {:?}", grid_mover);
+
+        Ok(builder.with(grid_mover))
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn set_value(&mut self, new_value: JSONLoad) -> Result<()> {
+        if new_value.load_type_id == GRID_MOVER_LOAD_ID {
+            self.json = load_deserializable_from_json(&new_value, GRID_MOVER_LOAD_ID)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to convert JSONLoad object: ({:?}) into GridMoverJSON value", new_value.clone());
+
+                    DeserializeError {
+                        source: e,
+                        json: new_value.clone()
+                    }
+                })?;
+
+            Ok(())
+        } else {
+            #[cfg(feature = "trace")]
+            error!("Given load-type ID: ({:?}) does not match expected ID: {:?}", new_value.load_type_id.clone(), GRID_MOVER_LOAD_ID.to_string());
+
+            Err(Error::new(LoadTypeIDError {
+                actual: new_value.load_type_id,
+                expected: GRID_MOVER_LOAD_ID.to_string()
+            }))
+        }
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn get_value(&self) -> Result<JSONLoad, LoadError> {
+        serialize_to_json_load(&self.json, GRID_MOVER_LOAD_ID)
+    }
+
+    fn clone_boxed(&self) -> Box<dyn ComponentLoader> {
+        Box::new(self.clone())
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn get_component_name(&self) -> String {
+        GRID_MOVER_LOAD_ID.to_string()
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum GridMoverLoaderError {
+    #[error("Failed to convert JSONLoad object: ({json:?}) to GridMoverJSON value")]
+    DeserializeError {
+        source: LoadError,
+        json: JSONLoad
+    },
+
+    #[error("Given load-type ID: ({actual}) does not match expected ID: {expected}")]
+    LoadTypeIDError {
+        actual: String,
+        expected: String
+    }
+}
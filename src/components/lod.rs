@@ -0,0 +1,162 @@
+use serde::{Deserialize, Serialize};
+use specs::{Component, DenseVecStorage, World, Builder};
+use specs::world::LazyBuilder;
+use std::sync::{Arc, RwLock};
+use luminance_glfw::GL33Context;
+use anyhow::{Error, Result};
+use thiserror::Error;
+
+#[cfg(feature = "trace")]
+use tracing::{debug, error, instrument};
+
+use crate::components::ComponentLoader;
+use crate::load::{JSONLoad, load_deserializable_from_json, serialize_to_json_load, LoadError};
+use crate::components::lod::LevelOfDetailLoaderError::{DeserializeError, LoadTypeIDError, NoLevelsError};
+
+pub const LEVEL_OF_DETAIL_LOAD_ID: &str = "level_of_detail";
+
+/// Tess files to draw the entity with at different distances from the camera. Each level is
+/// `(max_distance, tess_path)` and the levels are sorted by distance, nearest first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LevelOfDetail {
+    pub levels: Vec<(f32, String)>
+}
+
+impl Component for LevelOfDetail { type Storage = DenseVecStorage<Self>; }
+crate::register_component!(LevelOfDetail);
+
+impl LevelOfDetail {
+    /// Sorts `levels` by distance.
+    pub fn new(mut levels: Vec<(f32, String)>) -> Self {
+        levels.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+
+        Self { levels }
+    }
+
+    /// Tess path of the nearest level whose `max_distance` covers `distance`. Entities past every
+    /// level use the farthest one.
+    pub fn select(&self, distance: f32) -> Option<&str> {
+        self.levels.iter()
+            .find(|(max_distance, _)| distance <= *max_distance)
+            .or_else(|| self.levels.last())
+            .map(|(_, tess_path)| tess_path.as_str())
+    }
+}
+
+/// Tess path the entity is currently drawn with. Set by the LODSystem.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActiveLOD(pub String);
+
+impl Component for ActiveLOD { type Storage = DenseVecStorage<Self>; }
+crate::register_component!(ActiveLOD);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema_gen", derive(schemars::JsonSchema))]
+pub struct LevelOfDetailJSON {
+    levels: Vec<(f32, String)>
+}
+
+#[derive(Debug, Clone)]
+pub struct LevelOfDetailLoader {
+    json: LevelOfDetailJSON
+}
+
+impl ComponentLoader for LevelOfDetailLoader {
+    #[cfg_attr(feature = "trace", instrument)]
+    fn from_json(json: JSONLoad) -> Result<Self> where Self: Sized {
+        Self::validate_schema(&json)?;
+
+        let lod_json: LevelOfDetailJSON = load_deserializable_from_json(&json, LEVEL_OF_DETAIL_LOAD_ID)
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to convert JSONLoad object: ({:?}) into LevelOfDetailJSON value", json.clone());
+
+                DeserializeError {
+                    source: e,
+                    json: json.clone()
+                }
+            })?;
+
+        Ok(Self { json: lod_json })
+    }
+
+    #[cfg(feature = "schema_gen")]
+    fn json_schema() -> serde_json::Value where Self: Sized {
+        crate::schema::validation_schema::<LevelOfDetailJSON>()
+    }
+
+    #[cfg_attr(feature = "trace", instrument(skip(builder, _ecs, _context)))]
+    fn load_component<'a>(&self, builder: LazyBuilder<'a>, _ecs: Arc<RwLock<World>>, _context: Option<Arc<RwLock<GL33Context>>>) -> Result<LazyBuilder<'a>> {
+        if self.json.levels.is_empty() {
+            #[cfg(feature = "trace")]
+            error!("Level of detail has no levels");
+
+            return Err(Error::new(NoLevelsError))
+        }
+
+        let lod = LevelOfDetail::new(self.json.levels.clone());
+
+        #[cfg(feature = "trace")]
+        debug!("Created new level of detail component: {:?}", lod);
+
+        Ok(builder.with(lod))
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn set_value(&mut self, new_value: JSONLoad) -> Result<()> {
+        if new_value.load_type_id == LEVEL_OF_DETAIL_LOAD_ID {
+            self.json = load_deserializable_from_json(&new_value, LEVEL_OF_DETAIL_LOAD_ID)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to convert JSONLoad object: ({:?}) into LevelOfDetailJSON value", new_value.clone());
+
+                    DeserializeError {
+                        source: e,
+                        json: new_value.clone()
+                    }
+                })?;
+
+            Ok(())
+        } else {
+            #[cfg(feature = "trace")]
+            error!("Given load-type ID: ({:?}) does not match expected ID: {:?}", new_value.load_type_id.clone(), LEVEL_OF_DETAIL_LOAD_ID.to_string());
+
+            Err(Error::new(LoadTypeIDError {
+                actual: new_value.load_type_id,
+                expected: LEVEL_OF_DETAIL_LOAD_ID.to_string()
+            }))
+        }
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn get_value(&self) -> Result<JSONLoad, LoadError> {
+        serialize_to_json_load(&self.json, LEVEL_OF_DETAIL_LOAD_ID)
+    }
+
+    fn clone_boxed(&self) -> Box<dyn ComponentLoader> {
+        Box::new(self.clone())
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn get_component_name(&self) -> String {
+        LEVEL_OF_DETAIL_LOAD_ID.to_string()
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum LevelOfDetailLoaderError {
+    #[error("Failed to convert JSONLoad object: ({json:?}) to LevelOfDetailJSON value")]
+    DeserializeError {
+        source: LoadError,
+        json: JSONLoad
+    },
+
+    #[error("Given load-type ID: ({actual}) does not match expected ID: {expected}")]
+    LoadTypeIDError {
+        actual: String,
+        expected: String
+    },
+
+    #[error("Level of detail needs at least one level")]
+    NoLevelsError
+}
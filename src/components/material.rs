@@ -0,0 +1,310 @@
+use serde::{Deserialize, Serialize};
+use specs::{Component, DenseVecStorage, World, Builder};
+use specs::world::LazyBuilder;
+use std::sync::{Arc, RwLock};
+use luminance_front::shader::{Program, Uniform};
+use luminance_front::pipeline::TextureBinding;
+use luminance_front::texture::Dim2;
+use luminance_front::pixel::Unsigned;
+use luminance_derive::UniformInterface;
+use luminance_glfw::GL33Context;
+use anyhow::{Error, Result};
+use thiserror::Error;
+
+#[cfg(feature = "trace")]
+use tracing::{debug, error, instrument};
+
+use crate::components::ComponentLoader;
+use crate::graphics::shader::ShaderLoader;
+use crate::load::{JSONLoad, load_deserializable_from_json, serialize_to_json_load, LoadError};
+use crate::components::material::MaterialLoaderError::{DeserializeError, LoadTypeIDError, ContextMissing, ShaderLoadError};
+#[cfg(feature = "hot_reload")]
+use std::collections::HashSet;
+#[cfg(feature = "hot_reload")]
+use specs::{Join, WorldExt};
+#[cfg(all(feature = "hot_reload", feature = "trace"))]
+use crate::load::display_chain;
+#[cfg(feature = "hot_reload")]
+use crate::graphics::shader::{watch_program, ShaderLoadError as ShaderError};
+#[cfg(feature = "hot_reload")]
+use crate::components::material::MaterialLoaderError::ShaderWatchError;
+
+pub const MATERIAL_LOAD_ID: &str = "material";
+
+/// The uniforms every sprite shader must declare so the SpriteRenderer can draw with it.
+pub trait MaterialUniformInterface {
+    fn projection(&self) -> &Uniform<[[f32; 4]; 4]>;
+    fn view(&self) -> &Uniform<[[f32; 4]; 4]>;
+    fn model(&self) -> &Uniform<[[f32; 4]; 4]>;
+    fn tex(&self) -> &Uniform<TextureBinding<Dim2, Unsigned>>;
+    /// Set from the World's ColorTint. Shaders may leave it out.
+    fn tint(&self) -> &Uniform<[f32; 4]>;
+    /// Set from the entity's SpriteRegion. Shaders may leave them out.
+    fn uv_min(&self) -> &Uniform<[f32; 2]>;
+    fn uv_max(&self) -> &Uniform<[f32; 2]>;
+}
+
+#[derive(Debug, UniformInterface)]
+pub struct MaterialShaderUniform {
+    /// PROJECTION matrix in MVP
+    projection: Uniform<[[f32; 4]; 4]>,
+    /// VIEW matrix in MVP
+    view: Uniform<[[f32; 4]; 4]>,
+    /// MODEL matrix in MVP
+    model: Uniform<[[f32; 4]; 4]>,
+    /// Texture for the texture.
+    tex: Uniform<TextureBinding<Dim2, Unsigned>>,
+    /// Color the texture is multiplied by.
+    #[uniform(unbound)]
+    tint: Uniform<[f32; 4]>,
+    /// Corners of the part of the texture that is drawn, in UV space.
+    #[uniform(unbound)]
+    uv_min: Uniform<[f32; 2]>,
+    #[uniform(unbound)]
+    uv_max: Uniform<[f32; 2]>,
+}
+
+impl MaterialUniformInterface for MaterialShaderUniform {
+    fn projection(&self) -> &Uniform<[[f32; 4]; 4]> {
+        &self.projection
+    }
+
+    fn view(&self) -> &Uniform<[[f32; 4]; 4]> {
+        &self.view
+    }
+
+    fn model(&self) -> &Uniform<[[f32; 4]; 4]> {
+        &self.model
+    }
+
+    fn tex(&self) -> &Uniform<TextureBinding<Dim2, Unsigned>> {
+        &self.tex
+    }
+
+    fn tint(&self) -> &Uniform<[f32; 4]> {
+        &self.tint
+    }
+
+    fn uv_min(&self) -> &Uniform<[f32; 2]> {
+        &self.uv_min
+    }
+
+    fn uv_max(&self) -> &Uniform<[f32; 2]> {
+        &self.uv_max
+    }
+}
+
+/// Draws the entity's sprite with its own shader instead of the SpriteRenderer's. Entities sharing a
+/// `shader_path` are drawn together.
+#[derive(Clone)]
+pub struct Material {
+    pub shader_path: String,
+    pub program: Option<Arc<RwLock<Program<(), (), MaterialShaderUniform>>>>
+}
+
+unsafe impl Send for Material {}
+unsafe impl Sync for Material {}
+
+impl Component for Material { type Storage = DenseVecStorage<Self>; }
+crate::register_component!(Material);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema_gen", derive(schemars::JsonSchema))]
+pub struct MaterialJSON {
+    shader_path: String
+}
+
+#[derive(Debug, Clone)]
+pub struct MaterialLoader {
+    json: MaterialJSON
+}
+
+impl ComponentLoader for MaterialLoader {
+    #[cfg_attr(feature = "trace", instrument)]
+    fn from_json(json: JSONLoad) -> Result<Self> where Self: Sized {
+        Self::validate_schema(&json)?;
+
+        let material_json: MaterialJSON = load_deserializable_from_json(&json, MATERIAL_LOAD_ID)
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to convert JSONLoad object: ({:?}) into MaterialJSON value", json.clone());
+
+                DeserializeError {
+                    source: e,
+                    json: json.clone()
+                }
+            })?;
+
+        Ok(Self { json: material_json })
+    }
+
+    #[cfg(feature = "schema_gen")]
+    fn json_schema() -> serde_json::Value where Self: Sized {
+        crate::schema::validation_schema::<MaterialJSON>()
+    }
+
+    #[cfg_attr(feature = "trace", instrument(skip(builder, ecs, context)))]
+    fn load_component<'a>(&self, builder: LazyBuilder<'a>, ecs: Arc<RwLock<World>>, context: Option<Arc<RwLock<GL33Context>>>) -> Result<LazyBuilder<'a>> {
+        let context = context
+            .ok_or_else(|| {
+                #[cfg(feature = "trace")]
+                error!("A context is needed to compile the shader for material: {:?}", self.json.shader_path.clone());
+
+                ContextMissing { shader_path: self.json.shader_path.clone() }
+            })?;
+
+        let program = ShaderLoader::new(self.json.shader_path.clone())
+            .load()
+            .execute((ecs.clone(), context))
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to load shader for material from file: {:?}", self.json.shader_path.clone());
+
+                ShaderLoadError {
+                    source: e,
+                    shader_path: self.json.shader_path.clone()
+                }
+            })?;
+
+        // The EntityLoader holds a read lock on the World while components load, so the shader
+        // is watched once the World is maintained.
+        #[cfg(feature = "hot_reload")]
+        {
+            let shader_path = self.json.shader_path.clone();
+            builder.lazy.exec_mut(move |world| {
+                if let Err(_e) = watch_shader(&shader_path, world) {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to watch shader for material: {:?}. Error: {}", shader_path, display_chain(&_e));
+                }
+            });
+        }
+
+        #[cfg(feature = "trace")]
+        debug!("Created new material component with shader: {:?}", self.json.shader_path.clone());
+
+        Ok(builder.with(Material {
+            shader_path: self.json.shader_path.clone(),
+            program: Some(share_program(program))
+        }))
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn set_value(&mut self, new_value: JSONLoad) -> Result<()> {
+        if new_value.load_type_id == MATERIAL_LOAD_ID {
+            self.json = load_deserializable_from_json(&new_value, MATERIAL_LOAD_ID)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to convert JSONLoad object: ({:?}) into MaterialJSON value", new_value.clone());
+
+                    DeserializeError {
+                        source: e,
+                        json: new_value.clone()
+                    }
+                })?;
+
+            Ok(())
+        } else {
+            #[cfg(feature = "trace")]
+            error!("Given load-type ID: ({:?}) does not match expected ID: {:?}", new_value.load_type_id.clone(), MATERIAL_LOAD_ID.to_string());
+
+            Err(Error::new(LoadTypeIDError {
+                actual: new_value.load_type_id,
+                expected: MATERIAL_LOAD_ID.to_string()
+            }))
+        }
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn get_value(&self) -> Result<JSONLoad, LoadError> {
+        serialize_to_json_load(&self.json, MATERIAL_LOAD_ID)
+    }
+
+    fn clone_boxed(&self) -> Box<dyn ComponentLoader> {
+        Box::new(self.clone())
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn get_component_name(&self) -> String {
+        MATERIAL_LOAD_ID.to_string()
+    }
+}
+
+/// Programs are only used on the draw thread, like the Material holding them.
+#[allow(clippy::arc_with_non_send_sync)]
+fn share_program(program: Program<(), (), MaterialShaderUniform>) -> Arc<RwLock<Program<(), (), MaterialShaderUniform>>> {
+    Arc::new(RwLock::new(program))
+}
+
+/// Shader files of the Materials loaded so far, so each one is only watched once.
+#[cfg(feature = "hot_reload")]
+#[derive(Debug, Default)]
+struct WatchedMaterialShaders(HashSet<String>);
+
+/// Gives every Material using the shader file at `shader_path` the rebuilt program when the file
+/// changes, if the shader file sets `watch`.
+#[cfg(feature = "hot_reload")]
+#[cfg_attr(feature = "trace", instrument(skip(world)))]
+#[allow(clippy::result_large_err)]
+fn watch_shader(shader_path: &str, world: &mut World) -> Result<(), MaterialLoaderError> {
+    let first_load = world.entry::<WatchedMaterialShaders>()
+        .or_insert_with(WatchedMaterialShaders::default)
+        .0
+        .insert(shader_path.to_string());
+
+    if !first_load {
+        return Ok(())
+    }
+
+    let path = shader_path.to_string();
+
+    watch_program(shader_path, world, move |world: &World, program: Program<(), (), MaterialShaderUniform>| {
+        let program = share_program(program);
+
+        for material in (&mut world.write_storage::<Material>()).join() {
+            if material.shader_path == path {
+                material.program = Some(program.clone());
+            }
+        }
+    }).map_err(|e| {
+        #[cfg(feature = "trace")]
+        error!("Failed to watch shader for material: {:?}", shader_path);
+
+        ShaderWatchError {
+            source: e,
+            shader_path: shader_path.to_string()
+        }
+    })
+}
+
+#[derive(Error, Debug)]
+pub enum MaterialLoaderError {
+    #[error("Failed to convert JSONLoad object: ({json:?}) to MaterialJSON value")]
+    DeserializeError {
+        source: LoadError,
+        json: JSONLoad
+    },
+
+    #[error("Given load-type ID: ({actual}) does not match expected ID: {expected}")]
+    LoadTypeIDError {
+        actual: String,
+        expected: String
+    },
+
+    #[error("A context is needed to compile the shader for material: {shader_path}")]
+    ContextMissing {
+        shader_path: String
+    },
+
+    #[error("Failed to load shader for material from file: {shader_path}")]
+    ShaderLoadError {
+        source: anyhow::Error,
+        shader_path: String
+    },
+
+    #[cfg(feature = "hot_reload")]
+    #[error("Failed to watch shader for material: {shader_path}")]
+    ShaderWatchError {
+        source: ShaderError,
+        shader_path: String
+    }
+}
@@ -1,24 +1,133 @@
 // pub mod drawables;
 // pub mod audibles;
+pub mod world_text;
+pub mod fog_of_war;
+pub mod material;
+pub mod shadow;
+pub mod typewriter;
+pub mod outline;
+pub mod lod;
+pub mod observer;
+pub mod grid_mover;
+pub mod sprite_region;
+pub mod named_entity;
+pub mod cursor_icon;
+pub mod focusable;
+pub mod persistent_id;
+pub mod gamepad_prompt;
+pub mod pivot;
+pub mod animated_sprite;
+pub mod spatial_audio;
 
-use specs::{World};
+use specs::{Component, World, WorldExt};
 
 use std::fmt::{Debug};
 
 use anyhow::Result;
 
-use crate::load::JSONLoad;
+use crate::load::{JSONLoad, LoadError};
+use crate::load::LoadError::{PatchError, PatchConversionError, PatchedValueError};
 use specs::world::LazyBuilder;
+use serde_json::Value;
 use luminance_glfw::GL33Context;
 use std::sync::{Arc, Mutex, RwLock};
 
 pub const COMPONENTS_DIR: &str = "components/";
 
+/// Registers one component type with a World. Submitted through `register_component!` and
+/// collected from every crate linked into the game.
+pub struct ComponentRegistrar {
+    pub register_fn: fn(&mut World)
+}
+
+inventory::collect!(ComponentRegistrar);
+
+/// Registers every component submitted with `register_component!`.
+pub fn register_all_components(ecs: &mut World) {
+    for registrar in inventory::iter::<ComponentRegistrar> {
+        (registrar.register_fn)(ecs);
+    }
+}
+
+pub fn register<C: Component>(ecs: &mut World) where C::Storage: Default {
+    ecs.register::<C>();
+}
+
+/// Submits a component type to be registered by `register_all_components`. Use it once per
+/// component, at module level, next to the component's definition.
+#[macro_export]
+macro_rules! register_component {
+    ($component:ty) => {
+        $crate::inventory::submit! {
+            $crate::components::ComponentRegistrar {
+                register_fn: $crate::components::register::<$component>
+            }
+        }
+    };
+}
+
 pub trait ComponentLoader: Debug {
     fn from_json(json: JSONLoad) -> Result<Self> where Self: Sized;
+
+    /// JSON Schema that `actual_value` must match before `from_json` deserializes it. Accepts
+    /// any value by default. Most loaders return `schema::validation_schema` of their JSON type,
+    /// which is why the `validate` feature turns on `schema_gen`.
+    fn json_schema() -> Value where Self: Sized {
+        Value::Bool(true)
+    }
+
+    /// Checks `json` against `json_schema` when the `validate` feature is enabled, naming the
+    /// field that is wrong. Call it at the start of `from_json`. Does nothing otherwise.
+    fn validate_schema(json: &JSONLoad) -> Result<(), LoadError> where Self: Sized {
+        #[cfg(feature = "validate")]
+        crate::schema_validation::validate_json(&json.actual_value, &Self::json_schema())?;
+
+        #[cfg(not(feature = "validate"))]
+        let _ = json;
+
+        Ok(())
+    }
+
     fn load_component<'a>(&self, builder: LazyBuilder<'a>, ecs: Arc<RwLock<World>>, context: Option<Arc<RwLock<GL33Context>>>) -> Result<LazyBuilder<'a>>;
     fn set_value(&mut self, new_value: JSONLoad) -> Result<()>;
+    /// The JSON value the component is currently loaded from.
+    fn get_value(&self) -> Result<JSONLoad, LoadError>;
     fn get_component_name(&self) -> String;
+    /// Copies the loader so the same component can be added to several entities without reading
+    /// its file again. Loaders that can't derive Clone can rebuild themselves with
+    /// `Self::from_json(self.get_value()?)`.
+    fn clone_boxed(&self) -> Box<dyn ComponentLoader>;
+
+    /// Changes only the fields named in `patch`. Objects are merged into the current value as a JSON
+    /// merge patch (RFC 7396) and arrays are applied as a list of JSON patch operations (RFC 6902).
+    fn patch_value(&mut self, patch: Value) -> Result<(), LoadError> {
+        let mut json = self.get_value()?;
+
+        if patch.is_array() {
+            let operations = json_patch::from_value(patch.clone())
+                .map_err(|e| PatchConversionError {
+                    value: patch,
+                    source: e
+                })?;
+
+            json_patch::patch(&mut json.actual_value, &operations)
+                .map_err(|e| PatchError { source: e })?;
+        } else {
+            json_patch::merge(&mut json.actual_value, &patch);
+        }
+
+        self.set_value(json)
+            .map_err(|e| PatchedValueError {
+                component: self.get_component_name(),
+                source: e
+            })
+    }
+}
+
+impl Clone for Box<dyn ComponentLoader> {
+    fn clone(&self) -> Self {
+        self.clone_boxed()
+    }
 }
 
 pub trait ComponentMux {
@@ -0,0 +1,128 @@
+use serde::{Deserialize, Serialize};
+use specs::{Component, DenseVecStorage, World, Builder};
+use specs::world::LazyBuilder;
+use std::sync::{Arc, RwLock};
+use luminance_glfw::GL33Context;
+use anyhow::{Error, Result};
+use serde_json::{json, Value};
+use thiserror::Error;
+
+#[cfg(feature = "trace")]
+use tracing::{debug, error, instrument};
+
+use crate::components::ComponentLoader;
+use crate::load::{JSONLoad, load_deserializable_from_json, serialize_to_json_load, LoadError};
+use crate::components::named_entity::NamedEntityLoaderError::{DeserializeError, LoadTypeIDError};
+
+pub const NAMED_ENTITY_LOAD_ID: &str = "named_entity";
+
+/// Name the entity can be found by in the NameRegistry.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NamedEntity(pub String);
+
+impl Component for NamedEntity { type Storage = DenseVecStorage<Self>; }
+crate::register_component!(NamedEntity);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema_gen", derive(schemars::JsonSchema))]
+pub struct NamedEntityJSON {
+    name: String
+}
+
+#[derive(Debug, Clone)]
+pub struct NamedEntityLoader {
+    json: NamedEntityJSON
+}
+
+impl ComponentLoader for NamedEntityLoader {
+    #[cfg_attr(feature = "trace", instrument)]
+    fn from_json(json: JSONLoad) -> Result<Self> where Self: Sized {
+        Self::validate_schema(&json)?;
+
+        let named_json: NamedEntityJSON = load_deserializable_from_json(&json, NAMED_ENTITY_LOAD_ID)
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to convert JSONLoad object: ({:?}) into NamedEntityJSON value", json.clone());
+
+                DeserializeError {
+                    source: e,
+                    json: json.clone()
+                }
+            })?;
+
+        Ok(Self { json: named_json })
+    }
+
+    fn json_schema() -> Value where Self: Sized {
+        json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string", "minLength": 1 }
+            },
+            "required": ["name"]
+        })
+    }
+
+    #[cfg_attr(feature = "trace", instrument(skip(builder, _ecs, _context)))]
+    fn load_component<'a>(&self, builder: LazyBuilder<'a>, _ecs: Arc<RwLock<World>>, _context: Option<Arc<RwLock<GL33Context>>>) -> Result<LazyBuilder<'a>> {
+        #[cfg(feature = "trace")]
+        debug!("Created new named entity component: {:?}", self.json.name.clone());
+
+        Ok(builder.with(NamedEntity(self.json.name.clone())))
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn set_value(&mut self, new_value: JSONLoad) -> Result<()> {
+        if new_value.load_type_id == NAMED_ENTITY_LOAD_ID {
+            self.json = load_deserializable_from_json(&new_value, NAMED_ENTITY_LOAD_ID)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to convert JSONLoad object: ({:?}) into NamedEntityJSON value", new_value.clone());
+
+                    DeserializeError {
+                        source: e,
+                        json: new_value.clone()
+                    }
+                })?;
+
+            Ok(())
+        } else {
+            #[cfg(feature = "trace")]
+            error!("Given load-type ID: ({:?}) does not match expected ID: {:?}", new_value.load_type_id.clone(), NAMED_ENTITY_LOAD_ID.to_string());
+
+            Err(Error::new(LoadTypeIDError {
+                actual: new_value.load_type_id,
+                expected: NAMED_ENTITY_LOAD_ID.to_string()
+            }))
+        }
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn get_value(&self) -> Result<JSONLoad, LoadError> {
+        serialize_to_json_load(&self.json, NAMED_ENTITY_LOAD_ID)
+    }
+
+    fn clone_boxed(&self) -> Box<dyn ComponentLoader> {
+        Box::new(self.clone())
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn get_component_name(&self) -> String {
+        NAMED_ENTITY_LOAD_ID.to_string()
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum NamedEntityLoaderError {
+    #[error("Failed to convert JSONLoad object: ({json:?}) to NamedEntityJSON value")]
+    DeserializeError {
+        source: LoadError,
+        json: JSONLoad
+    },
+
+    #[error("Given load-type ID: ({actual}) does not match expected ID: {expected}")]
+    LoadTypeIDError {
+        actual: String,
+        expected: String
+    }
+}
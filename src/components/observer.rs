@@ -0,0 +1,91 @@
+use std::any::Any;
+use std::marker::PhantomData;
+
+use specs::{Component, DenseVecStorage, Entity, World, WorldExt};
+
+#[cfg(feature = "trace")]
+use tracing::{debug, instrument};
+
+use crate::events::EventQueue;
+
+/// Reacts to events of one type sent to the entity it belongs to.
+pub trait ObserverHandler {
+    /// Events of a different type than the handler was made for are ignored.
+    fn handle(&self, event_raw: &dyn Any, entity: Entity, world: &World);
+}
+
+type ObserverCallback<E> = Box<dyn Fn(E, Entity, &World) + Send + Sync>;
+
+struct CallbackHandler<E: Clone + 'static> {
+    callback: ObserverCallback<E>
+}
+
+impl<E: Clone + 'static> ObserverHandler for CallbackHandler<E> {
+    fn handle(&self, event_raw: &dyn Any, entity: Entity, world: &World) {
+        if let Some(event) = event_raw.downcast_ref::<E>() {
+            (self.callback)(event.clone(), entity, world);
+        }
+    }
+}
+
+/// Callbacks run by the ObserverSystem of each event type. Callbacks can't be loaded from JSON, so
+/// add Observers to entities in code, for example in `GameWrapper::load`.
+///
+/// Callbacks run while the Observer storage is borrowed and must not write to it.
+#[derive(Default)]
+pub struct Observer {
+    handlers: Vec<Box<dyn ObserverHandler + Send + Sync>>
+}
+
+impl Component for Observer { type Storage = DenseVecStorage<Self>; }
+crate::register_component!(Observer);
+
+impl Observer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a callback for events of type `E`.
+    pub fn on<E: Clone + 'static>(mut self, callback: impl Fn(E, Entity, &World) + Send + Sync + 'static) -> Self {
+        self.handlers.push(Box::new(CallbackHandler { callback: Box::new(callback) }));
+
+        self
+    }
+}
+
+/// Sent through an `EventQueue<ObservedEvent<E>>` to deliver `event` to the Observer of `entity`.
+/// Register it with `systems::clear_events::register_event` before running the ObserverSystem.
+#[derive(Debug, Clone)]
+pub struct ObservedEvent<E> {
+    pub entity: Entity,
+    pub event: E
+}
+
+/// Delivers the queued ObservedEvents of type `E` to the Observers of the entities they target.
+/// Events sent by callbacks are delivered if the system runs again in the same frame.
+pub struct ObserverSystem<E> {
+    event: PhantomData<E>
+}
+
+impl<E: Clone + Send + Sync + 'static> ObserverSystem<E> {
+    #[cfg_attr(feature = "trace", instrument(skip(ecs)))]
+    pub fn run(ecs: &World) {
+        let events: Vec<ObservedEvent<E>> = match ecs.try_fetch_mut::<EventQueue<ObservedEvent<E>>>() {
+            Some(mut events) => events.drain().collect(),
+            None => return
+        };
+
+        #[cfg(feature = "trace")]
+        debug!("Delivering {:?} events to observers", events.len());
+
+        let observers = ecs.read_storage::<Observer>();
+
+        for ObservedEvent { entity, event } in events {
+            if let Some(observer) = observers.get(entity) {
+                for handler in observer.handlers.iter() {
+                    handler.handle(&event, entity, ecs);
+                }
+            }
+        }
+    }
+}
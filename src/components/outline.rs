@@ -0,0 +1,137 @@
+use serde::{Deserialize, Serialize};
+use specs::{Component, DenseVecStorage, World, Builder};
+use specs::world::LazyBuilder;
+use std::sync::{Arc, RwLock};
+use luminance_glfw::GL33Context;
+use anyhow::{Error, Result};
+use thiserror::Error;
+
+#[cfg(feature = "trace")]
+use tracing::{debug, error, instrument};
+
+use crate::components::ComponentLoader;
+use crate::load::{JSONLoad, load_deserializable_from_json, serialize_to_json_load, LoadError};
+use crate::components::outline::OutlineLoaderError::{DeserializeError, LoadTypeIDError};
+
+pub const OUTLINE_LOAD_ID: &str = "outline";
+
+/// Draws a ring of `color` around the opaque pixels of the entity's sprite. `thickness` is in
+/// texels of the sprite's texture.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Outline {
+    pub color: [f32; 4],
+    pub thickness: f32
+}
+
+impl Component for Outline { type Storage = DenseVecStorage<Self>; }
+crate::register_component!(Outline);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema_gen", derive(schemars::JsonSchema))]
+pub struct OutlineJSON {
+    color: [f32; 4],
+    #[serde(default = "default_thickness")]
+    thickness: f32
+}
+
+fn default_thickness() -> f32 {
+    1.0
+}
+
+#[derive(Debug, Clone)]
+pub struct OutlineLoader {
+    json: OutlineJSON
+}
+
+impl ComponentLoader for OutlineLoader {
+    #[cfg_attr(feature = "trace", instrument)]
+    fn from_json(json: JSONLoad) -> Result<Self> where Self: Sized {
+        Self::validate_schema(&json)?;
+
+        let outline_json: OutlineJSON = load_deserializable_from_json(&json, OUTLINE_LOAD_ID)
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to convert JSONLoad object: ({:?}) into OutlineJSON value", json.clone());
+
+                DeserializeError {
+                    source: e,
+                    json: json.clone()
+                }
+            })?;
+
+        Ok(Self { json: outline_json })
+    }
+
+    #[cfg(feature = "schema_gen")]
+    fn json_schema() -> serde_json::Value where Self: Sized {
+        crate::schema::validation_schema::<OutlineJSON>()
+    }
+
+    #[cfg_attr(feature = "trace", instrument(skip(builder, _ecs, _context)))]
+    fn load_component<'a>(&self, builder: LazyBuilder<'a>, _ecs: Arc<RwLock<World>>, _context: Option<Arc<RwLock<GL33Context>>>) -> Result<LazyBuilder<'a>> {
+        let outline = Outline {
+            color: self.json.color,
+            thickness: self.json.thickness
+        };
+
+        #[cfg(feature = "trace")]
+        debug!("Created new outline component: {:?}", outline);
+
+        Ok(builder.with(outline))
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn set_value(&mut self, new_value: JSONLoad) -> Result<()> {
+        if new_value.load_type_id == OUTLINE_LOAD_ID {
+            self.json = load_deserializable_from_json(&new_value, OUTLINE_LOAD_ID)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to convert JSONLoad object: ({:?}) into OutlineJSON value", new_value.clone());
+
+                    DeserializeError {
+                        source: e,
+                        json: new_value.clone()
+                    }
+                })?;
+
+            Ok(())
+        } else {
+            #[cfg(feature = "trace")]
+            error!("Given load-type ID: ({:?}) does not match expected ID: {:?}", new_value.load_type_id.clone(), OUTLINE_LOAD_ID.to_string());
+
+            Err(Error::new(LoadTypeIDError {
+                actual: new_value.load_type_id,
+                expected: OUTLINE_LOAD_ID.to_string()
+            }))
+        }
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn get_value(&self) -> Result<JSONLoad, LoadError> {
+        serialize_to_json_load(&self.json, OUTLINE_LOAD_ID)
+    }
+
+    fn clone_boxed(&self) -> Box<dyn ComponentLoader> {
+        Box::new(self.clone())
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn get_component_name(&self) -> String {
+        OUTLINE_LOAD_ID.to_string()
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum OutlineLoaderError {
+    #[error("Failed to convert JSONLoad object: ({json:?}) to OutlineJSON value")]
+    DeserializeError {
+        source: LoadError,
+        json: JSONLoad
+    },
+
+    #[error("Given load-type ID: ({actual}) does not match expected ID: {expected}")]
+    LoadTypeIDError {
+        actual: String,
+        expected: String
+    }
+}
@@ -0,0 +1,202 @@
+use serde::{Deserialize, Serialize};
+use specs::{Component, DenseVecStorage, World, WorldExt, Builder, Entity, Join};
+use std::collections::HashMap;
+use specs::world::LazyBuilder;
+use std::sync::{Arc, RwLock};
+use luminance_glfw::GL33Context;
+use anyhow::{Error, Result};
+use serde_json::{json, Value};
+use thiserror::Error;
+
+#[cfg(feature = "trace")]
+use tracing::{debug, error, instrument};
+
+use crate::components::ComponentLoader;
+use crate::load::{JSONLoad, load_deserializable_from_json, serialize_to_json_load, LoadError};
+use crate::components::persistent_id::PersistentIdLoaderError::{DeserializeError, LoadTypeIDError};
+use crate::components::persistent_id::PersistentIdRegistryError::DuplicateId;
+
+pub const PERSISTENT_ID_LOAD_ID: &str = "persistent_id";
+
+/// ID that stays the same for an entity across game restarts, unlike its `Entity`. Save files
+/// refer to other entities by these IDs.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PersistentId(pub String);
+
+impl Component for PersistentId { type Storage = DenseVecStorage<Self>; }
+crate::register_component!(PersistentId);
+
+/// Finds the entity a PersistentId currently belongs to.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PersistentIdRegistry(pub HashMap<String, Entity>);
+
+impl PersistentIdRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fails if `id` already belongs to another entity.
+    #[cfg_attr(feature = "trace", instrument(skip(self)))]
+    pub fn register(&mut self, id: &str, entity: Entity) -> Result<(), PersistentIdRegistryError> {
+        match self.0.get(id) {
+            Some(existing) if *existing != entity => {
+                #[cfg(feature = "trace")]
+                error!("Persistent ID: {:?} already belongs to entity: {:?}", id, existing);
+
+                Err(DuplicateId {
+                    id: id.to_string(),
+                    existing: *existing,
+                    new_entity: entity
+                })
+            },
+            _ => {
+                #[cfg(feature = "trace")]
+                debug!("Registered persistent ID: {:?} for entity: {:?}", id, entity);
+
+                self.0.insert(id.to_string(), entity);
+
+                Ok(())
+            }
+        }
+    }
+
+    /// Removes `id` if it belongs to `entity`.
+    pub fn unregister(&mut self, id: &str, entity: Entity) {
+        if self.0.get(id) == Some(&entity) {
+            self.0.remove(id);
+        }
+    }
+
+    pub fn get(&self, id: &str) -> Option<Entity> {
+        self.0.get(id).copied()
+    }
+
+    /// Replaces the registry with the PersistentIds of the World's entities. Call it after
+    /// entities are recreated, e.g. from a save file, so IDs point at the new entities.
+    #[cfg_attr(feature = "trace", instrument(skip(ecs)))]
+    pub fn rebuild(ecs: &World) -> Result<Self, PersistentIdRegistryError> {
+        let entities = ecs.entities();
+        let ids = ecs.read_storage::<PersistentId>();
+
+        let mut registry = Self::new();
+        for (entity, id) in (&entities, &ids).join() {
+            registry.register(&id.0, entity)?;
+        }
+
+        Ok(registry)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema_gen", derive(schemars::JsonSchema))]
+pub struct PersistentIdJSON {
+    id: String
+}
+
+#[derive(Debug, Clone)]
+pub struct PersistentIdLoader {
+    json: PersistentIdJSON
+}
+
+impl ComponentLoader for PersistentIdLoader {
+    #[cfg_attr(feature = "trace", instrument)]
+    fn from_json(json: JSONLoad) -> Result<Self> where Self: Sized {
+        Self::validate_schema(&json)?;
+
+        let id_json: PersistentIdJSON = load_deserializable_from_json(&json, PERSISTENT_ID_LOAD_ID)
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to convert JSONLoad object: ({:?}) into PersistentIdJSON value", json.clone());
+
+                DeserializeError {
+                    source: e,
+                    json: json.clone()
+                }
+            })?;
+
+        Ok(Self { json: id_json })
+    }
+
+    fn json_schema() -> Value where Self: Sized {
+        json!({
+            "type": "object",
+            "properties": {
+                "id": { "type": "string", "minLength": 1 }
+            },
+            "required": ["id"]
+        })
+    }
+
+    #[cfg_attr(feature = "trace", instrument(skip(builder, _ecs, _context)))]
+    fn load_component<'a>(&self, builder: LazyBuilder<'a>, _ecs: Arc<RwLock<World>>, _context: Option<Arc<RwLock<GL33Context>>>) -> Result<LazyBuilder<'a>> {
+        #[cfg(feature = "trace")]
+        debug!("Created new persistent id component: {:?}", self.json.id.clone());
+
+        Ok(builder.with(PersistentId(self.json.id.clone())))
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn set_value(&mut self, new_value: JSONLoad) -> Result<()> {
+        if new_value.load_type_id == PERSISTENT_ID_LOAD_ID {
+            self.json = load_deserializable_from_json(&new_value, PERSISTENT_ID_LOAD_ID)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to convert JSONLoad object: ({:?}) into PersistentIdJSON value", new_value.clone());
+
+                    DeserializeError {
+                        source: e,
+                        json: new_value.clone()
+                    }
+                })?;
+
+            Ok(())
+        } else {
+            #[cfg(feature = "trace")]
+            error!("Given load-type ID: ({:?}) does not match expected ID: {:?}", new_value.load_type_id.clone(), PERSISTENT_ID_LOAD_ID.to_string());
+
+            Err(Error::new(LoadTypeIDError {
+                actual: new_value.load_type_id,
+                expected: PERSISTENT_ID_LOAD_ID.to_string()
+            }))
+        }
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn get_value(&self) -> Result<JSONLoad, LoadError> {
+        serialize_to_json_load(&self.json, PERSISTENT_ID_LOAD_ID)
+    }
+
+    fn clone_boxed(&self) -> Box<dyn ComponentLoader> {
+        Box::new(self.clone())
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn get_component_name(&self) -> String {
+        PERSISTENT_ID_LOAD_ID.to_string()
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum PersistentIdLoaderError {
+    #[error("Failed to convert JSONLoad object: ({json:?}) to PersistentIdJSON value")]
+    DeserializeError {
+        source: LoadError,
+        json: JSONLoad
+    },
+
+    #[error("Given load-type ID: ({actual}) does not match expected ID: {expected}")]
+    LoadTypeIDError {
+        actual: String,
+        expected: String
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum PersistentIdRegistryError {
+    #[error("Persistent ID: {id} already belongs to entity: {existing:?}. It can't be given to entity: {new_entity:?}")]
+    DuplicateId {
+        id: String,
+        existing: Entity,
+        new_entity: Entity
+    }
+}
@@ -0,0 +1,140 @@
+use serde::{Deserialize, Serialize};
+use specs::{Component, DenseVecStorage, World, Builder};
+use specs::world::LazyBuilder;
+use std::sync::{Arc, RwLock};
+use luminance_glfw::GL33Context;
+use anyhow::{Error, Result};
+use serde_json::{json, Value};
+use thiserror::Error;
+use glam::Vec2;
+
+#[cfg(feature = "trace")]
+use tracing::{debug, error, instrument};
+
+use crate::components::ComponentLoader;
+use crate::load::{JSONLoad, load_deserializable_from_json, serialize_to_json_load, LoadError};
+use crate::components::pivot::PivotLoaderError::{DeserializeError, LoadTypeIDError};
+
+pub const PIVOT_LOAD_ID: &str = "pivot";
+
+/// Point the SpriteRenderer rotates the entity around, such as the handle of a sword.
+/// `offset` is measured from the sprite's center, after scaling, and stays at the Transform's
+/// translation while the entity rotates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pivot {
+    pub offset: Vec2
+}
+
+impl Component for Pivot { type Storage = DenseVecStorage<Self>; }
+crate::register_component!(Pivot);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema_gen", derive(schemars::JsonSchema))]
+pub struct PivotJSON {
+    offset: [f32; 2]
+}
+
+#[derive(Debug, Clone)]
+pub struct PivotLoader {
+    json: PivotJSON
+}
+
+impl ComponentLoader for PivotLoader {
+    #[cfg_attr(feature = "trace", instrument)]
+    fn from_json(json: JSONLoad) -> Result<Self> where Self: Sized {
+        Self::validate_schema(&json)?;
+
+        let pivot_json: PivotJSON = load_deserializable_from_json(&json, PIVOT_LOAD_ID)
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to convert JSONLoad object: ({:?}) into PivotJSON value", json.clone());
+
+                DeserializeError {
+                    source: e,
+                    json: json.clone()
+                }
+            })?;
+
+        Ok(Self { json: pivot_json })
+    }
+
+    fn json_schema() -> Value where Self: Sized {
+        json!({
+            "type": "object",
+            "properties": {
+                "offset": {
+                    "type": "array",
+                    "items": { "type": "number" },
+                    "minItems": 2,
+                    "maxItems": 2
+                }
+            },
+            "required": ["offset"]
+        })
+    }
+
+    #[cfg_attr(feature = "trace", instrument(skip(builder, _ecs, _context)))]
+    fn load_component<'a>(&self, builder: LazyBuilder<'a>, _ecs: Arc<RwLock<World>>, _context: Option<Arc<RwLock<GL33Context>>>) -> Result<LazyBuilder<'a>> {
+        #[cfg(feature = "trace")]
+        debug!("Created new pivot component: {:?}", self.json.offset);
+
+        Ok(builder.with(Pivot {
+            offset: Vec2::from(self.json.offset)
+        }))
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn set_value(&mut self, new_value: JSONLoad) -> Result<()> {
+        if new_value.load_type_id == PIVOT_LOAD_ID {
+            self.json = load_deserializable_from_json(&new_value, PIVOT_LOAD_ID)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to convert JSONLoad object: ({:?}) into PivotJSON value", new_value.clone());
+
+                    DeserializeError {
+                        source: e,
+                        json: new_value.clone()
+                    }
+                })?;
+
+            Ok(())
+        } else {
+            #[cfg(feature = "trace")]
+            error!("Given load-type ID: ({:?}) does not match expected ID: {:?}", new_value.load_type_id.clone(), PIVOT_LOAD_ID.to_string());
+
+            Err(Error::new(LoadTypeIDError {
+                actual: new_value.load_type_id,
+                expected: PIVOT_LOAD_ID.to_string()
+            }))
+        }
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn get_value(&self) -> Result<JSONLoad, LoadError> {
+        serialize_to_json_load(&self.json, PIVOT_LOAD_ID)
+    }
+
+    fn clone_boxed(&self) -> Box<dyn ComponentLoader> {
+        Box::new(self.clone())
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn get_component_name(&self) -> String {
+        PIVOT_LOAD_ID.to_string()
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum PivotLoaderError {
+    #[error("Failed to convert JSONLoad object: ({json:?}) to PivotJSON value")]
+    DeserializeError {
+        source: LoadError,
+        json: JSONLoad
+    },
+
+    #[error("Given load-type ID: ({actual}) does not match expected ID: {expected}")]
+    LoadTypeIDError {
+        actual: String,
+        expected: String
+    }
+}
@@ -0,0 +1,162 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use specs::{Component, DenseVecStorage, World, Builder};
+use specs::world::LazyBuilder;
+use std::sync::{Arc, RwLock};
+use luminance_glfw::GL33Context;
+use glam::Vec2;
+use anyhow::{Error, Result};
+use thiserror::Error;
+
+#[cfg(feature = "trace")]
+use tracing::{debug, error, instrument};
+
+use crate::components::ComponentLoader;
+use crate::graphics::transform::Transform;
+use crate::load::{JSONLoad, load_deserializable_from_json, serialize_to_json_load, LoadError};
+use crate::components::shadow::ShadowLoaderError::{DeserializeError, LoadTypeIDError};
+
+pub const SHADOW_LOAD_ID: &str = "shadow";
+/// Key in `SceneLoaderJSON::scene_values` that decides whether shadows are drawn before sprites.
+pub const DRAW_SHADOWS_FIRST_KEY: &str = "draw_shadows_first";
+
+/// Draws a darkened copy of the entity's sprite, moved by `offset` and scaled by `scale`.
+#[derive(Debug, Clone, Copy)]
+pub struct Shadow {
+    pub offset: Vec2,
+    pub opacity: f32,
+    pub scale: Vec2
+}
+
+impl Component for Shadow { type Storage = DenseVecStorage<Self>; }
+crate::register_component!(Shadow);
+
+impl Shadow {
+    /// The Transform the shadow of an entity with `transform` is drawn with.
+    pub fn shadow_transform(&self, transform: &Transform) -> Transform {
+        Transform {
+            translation: transform.translation + self.offset.extend(0.0),
+            scale: transform.scale * self.scale.extend(1.0),
+            rotation: transform.rotation
+        }
+    }
+}
+
+/// Reads `draw_shadows_first` from a scene's values. Shadows are drawn first unless the scene says otherwise.
+pub fn draw_shadows_first(scene_values: &Value) -> bool {
+    scene_values.get(DRAW_SHADOWS_FIRST_KEY)
+        .and_then(Value::as_bool)
+        .unwrap_or(true)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema_gen", derive(schemars::JsonSchema))]
+pub struct ShadowJSON {
+    offset: [f32; 2],
+    opacity: f32,
+    #[serde(default = "default_scale")]
+    scale: [f32; 2]
+}
+
+fn default_scale() -> [f32; 2] {
+    [1.0, 1.0]
+}
+
+#[derive(Debug, Clone)]
+pub struct ShadowLoader {
+    json: ShadowJSON
+}
+
+impl ComponentLoader for ShadowLoader {
+    #[cfg_attr(feature = "trace", instrument)]
+    fn from_json(json: JSONLoad) -> Result<Self> where Self: Sized {
+        Self::validate_schema(&json)?;
+
+        let shadow_json: ShadowJSON = load_deserializable_from_json(&json, SHADOW_LOAD_ID)
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to convert JSONLoad object: ({:?}) into ShadowJSON value", json.clone());
+
+                DeserializeError {
+                    source: e,
+                    json: json.clone()
+                }
+            })?;
+
+        Ok(Self { json: shadow_json })
+    }
+
+    #[cfg(feature = "schema_gen")]
+    fn json_schema() -> serde_json::Value where Self: Sized {
+        crate::schema::validation_schema::<ShadowJSON>()
+    }
+
+    #[cfg_attr(feature = "trace", instrument(skip(builder, _ecs, _context)))]
+    fn load_component<'a>(&self, builder: LazyBuilder<'a>, _ecs: Arc<RwLock<World>>, _context: Option<Arc<RwLock<GL33Context>>>) -> Result<LazyBuilder<'a>> {
+        let shadow = Shadow {
+            offset: Vec2::from(self.json.offset),
+            opacity: self.json.opacity,
+            scale: Vec2::from(self.json.scale)
+        };
+
+        #[cfg(feature = "trace")]
+        debug!("Created new shadow component: {:?}", shadow);
+
+        Ok(builder.with(shadow))
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn set_value(&mut self, new_value: JSONLoad) -> Result<()> {
+        if new_value.load_type_id == SHADOW_LOAD_ID {
+            self.json = load_deserializable_from_json(&new_value, SHADOW_LOAD_ID)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to convert JSONLoad object: ({:?}) into ShadowJSON value", new_value.clone());
+
+                    DeserializeError {
+                        source: e,
+                        json: new_value.clone()
+                    }
+                })?;
+
+            Ok(())
+        } else {
+            #[cfg(feature = "trace")]
+            error!("Given load-type ID: ({:?}) does not match expected ID: {:?}", new_value.load_type_id.clone(), SHADOW_LOAD_ID.to_string());
+
+            Err(Error::new(LoadTypeIDError {
+                actual: new_value.load_type_id,
+                expected: SHADOW_LOAD_ID.to_string()
+            }))
+        }
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn get_value(&self) -> Result<JSONLoad, LoadError> {
+        serialize_to_json_load(&self.json, SHADOW_LOAD_ID)
+    }
+
+    fn clone_boxed(&self) -> Box<dyn ComponentLoader> {
+        Box::new(self.clone())
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn get_component_name(&self) -> String {
+        SHADOW_LOAD_ID.to_string()
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ShadowLoaderError {
+    #[error("Failed to convert JSONLoad object: ({json:?}) to ShadowJSON value")]
+    DeserializeError {
+        source: LoadError,
+        json: JSONLoad
+    },
+
+    #[error("Given load-type ID: ({actual}) does not match expected ID: {expected}")]
+    LoadTypeIDError {
+        actual: String,
+        expected: String
+    }
+}
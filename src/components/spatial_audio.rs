@@ -0,0 +1,247 @@
+use serde::{Deserialize, Serialize};
+use specs::{Component, DenseVecStorage, World, Builder, System, ReadStorage, WriteStorage, Read, Write, Join, Entities, Entity};
+use specs::world::LazyBuilder;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use luminance_glfw::GL33Context;
+use kira::instance::InstanceId;
+use anyhow::{Error, Result};
+use thiserror::Error;
+
+#[cfg(feature = "trace")]
+use tracing::{debug, error, instrument};
+
+use crate::camera::Camera;
+use crate::components::ComponentLoader;
+use crate::globals::audio_controller::AudioController;
+use crate::graphics::transform::Transform;
+use crate::load::{JSONLoad, load_deserializable_from_json, serialize_to_json_load, LoadError};
+use crate::components::spatial_audio::SpatialAudioLoaderError::{DeserializeError, LoadTypeIDError, InvalidMaxDistanceError};
+
+pub const SPATIAL_AUDIO_LOAD_ID: &str = "spatial_audio";
+
+/// Plays the sound named `sound_name` from the entity. It is loudest at the camera and fades out
+/// linearly until it is silent `max_distance` away on the x and y axes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpatialAudio {
+    pub sound_name: String,
+    pub max_distance: f32,
+    /// Plays the sound again every time it ends.
+    pub looping: bool,
+    /// Instance of the sound, once the SpatialAudioSystem has started it.
+    pub instance_id: Option<InstanceId>
+}
+
+impl Component for SpatialAudio { type Storage = DenseVecStorage<Self>; }
+crate::register_component!(SpatialAudio);
+
+impl SpatialAudio {
+    pub fn new(sound_name: String, max_distance: f32, looping: bool) -> Self {
+        Self {
+            sound_name,
+            max_distance,
+            looping,
+            instance_id: None
+        }
+    }
+
+    /// Volume of the sound heard `distance` away, from 1 at the source to 0 at `max_distance`.
+    pub fn volume(&self, distance: f32) -> f32 {
+        (1.0 - distance / self.max_distance).max(0.0)
+    }
+}
+
+/// Starts the sound of every SpatialAudio the first time it runs and sets its volume from the
+/// entity's distance to the camera. Stops the sounds it started once their entity is despawned or
+/// loses its SpatialAudio. Does nothing without a camera or an AudioController.
+#[derive(Debug, Clone, Default)]
+pub struct SpatialAudioSystem {
+    playing: HashMap<Entity, InstanceId>
+}
+
+impl<'a> System<'a> for SpatialAudioSystem {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, SpatialAudio>,
+        ReadStorage<'a, Transform>,
+        Read<'a, Option<Box<dyn Camera>>>,
+        Option<Write<'a, AudioController>>
+    );
+
+    #[cfg_attr(feature = "trace", instrument(skip(self, data)))]
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, mut spatial_audios, transforms, camera, audio_controller) = data;
+
+        let (camera_position, mut audio_controller) = match (camera.as_ref(), audio_controller) {
+            (Some(camera), Some(audio_controller)) => (camera.position().truncate(), audio_controller),
+            _ => return
+        };
+
+        for (entity, spatial_audio, transform) in (&entities, &mut spatial_audios, &transforms).join() {
+            let id = match spatial_audio.instance_id {
+                Some(id) => id,
+                None => {
+                    let started = if spatial_audio.looping {
+                        audio_controller.play_looping(&spatial_audio.sound_name)
+                    } else {
+                        audio_controller.play(&spatial_audio.sound_name)
+                    };
+
+                    match started {
+                        Ok(id) => {
+                            self.playing.insert(entity, id);
+
+                            *spatial_audio.instance_id.insert(id)
+                        },
+                        Err(_e) => {
+                            #[cfg(feature = "trace")]
+                            error!("Failed to start spatial sound: {:?}. Error: {}", spatial_audio.sound_name, _e);
+
+                            continue
+                        }
+                    }
+                }
+            };
+
+            let distance = (camera_position - transform.translation.truncate()).length();
+            let volume = spatial_audio.volume(distance);
+
+            if let Err(_e) = audio_controller.set_volume(id, volume as f64) {
+                #[cfg(feature = "trace")]
+                error!("Failed to set volume of spatial sound: {:?}. Error: {}", spatial_audio.sound_name, _e);
+            }
+        }
+
+        self.playing.retain(|entity, id| {
+            if spatial_audios.get(*entity).and_then(|spatial_audio| spatial_audio.instance_id) == Some(*id) {
+                return true
+            }
+
+            #[cfg(feature = "trace")]
+            debug!("Stopping spatial sound: {:?} of removed entity: {:?}", id, entity);
+
+            if let Err(_e) = audio_controller.stop(*id) {
+                #[cfg(feature = "trace")]
+                error!("Failed to stop spatial sound of removed entity: {:?}. Error: {}", entity, _e);
+            }
+
+            false
+        });
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema_gen", derive(schemars::JsonSchema))]
+pub struct SpatialAudioJSON {
+    sound_name: String,
+    max_distance: f32,
+    #[serde(default)]
+    looping: bool
+}
+
+#[derive(Debug, Clone)]
+pub struct SpatialAudioLoader {
+    json: SpatialAudioJSON
+}
+
+impl ComponentLoader for SpatialAudioLoader {
+    #[cfg_attr(feature = "trace", instrument)]
+    fn from_json(json: JSONLoad) -> Result<Self> where Self: Sized {
+        Self::validate_schema(&json)?;
+
+        let spatial_audio_json: SpatialAudioJSON = load_deserializable_from_json(&json, SPATIAL_AUDIO_LOAD_ID)
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to convert JSONLoad object: ({:?}) into SpatialAudioJSON value", json.clone());
+
+                DeserializeError {
+                    source: e,
+                    json: json.clone()
+                }
+            })?;
+
+        Ok(Self { json: spatial_audio_json })
+    }
+
+    #[cfg(feature = "schema_gen")]
+    fn json_schema() -> serde_json::Value where Self: Sized {
+        crate::schema::validation_schema::<SpatialAudioJSON>()
+    }
+
+    #[cfg_attr(feature = "trace", instrument(skip(builder, _ecs, _context)))]
+    fn load_component<'a>(&self, builder: LazyBuilder<'a>, _ecs: Arc<RwLock<World>>, _context: Option<Arc<RwLock<GL33Context>>>) -> Result<LazyBuilder<'a>> {
+        if self.json.max_distance <= 0.0 {
+            #[cfg(feature = "trace")]
+            error!("Spatial audio max distance: {:?} must be greater than 0", self.json.max_distance);
+
+            return Err(Error::new(InvalidMaxDistanceError { max_distance: self.json.max_distance }))
+        }
+
+        let spatial_audio = SpatialAudio::new(self.json.sound_name.clone(), self.json.max_distance, self.json.looping);
+
+        #[cfg(feature = "trace")]
+        debug!("Created new spatial audio component: {:?}", spatial_audio);
+
+        Ok(builder.with(spatial_audio))
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn set_value(&mut self, new_value: JSONLoad) -> Result<()> {
+        if new_value.load_type_id == SPATIAL_AUDIO_LOAD_ID {
+            self.json = load_deserializable_from_json(&new_value, SPATIAL_AUDIO_LOAD_ID)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to convert JSONLoad object: ({:?}) into SpatialAudioJSON value", new_value.clone());
+
+                    DeserializeError {
+                        source: e,
+                        json: new_value.clone()
+                    }
+                })?;
+
+            Ok(())
+        } else {
+            #[cfg(feature = "trace")]
+            error!("Given load-type ID: ({:?}) does not match expected ID: {:?}", new_value.load_type_id.clone(), SPATIAL_AUDIO_LOAD_ID.to_string());
+
+            Err(Error::new(LoadTypeIDError {
+                actual: new_value.load_type_id,
+                expected: SPATIAL_AUDIO_LOAD_ID.to_string()
+            }))
+        }
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn get_value(&self) -> Result<JSONLoad, LoadError> {
+        serialize_to_json_load(&self.json, SPATIAL_AUDIO_LOAD_ID)
+    }
+
+    fn clone_boxed(&self) -> Box<dyn ComponentLoader> {
+        Box::new(self.clone())
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn get_component_name(&self) -> String {
+        SPATIAL_AUDIO_LOAD_ID.to_string()
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum SpatialAudioLoaderError {
+    #[error("Failed to convert JSONLoad object: ({json:?}) to SpatialAudioJSON value")]
+    DeserializeError {
+        source: LoadError,
+        json: JSONLoad
+    },
+
+    #[error("Given load-type ID: ({actual}) does not match expected ID: {expected}")]
+    LoadTypeIDError {
+        actual: String,
+        expected: String
+    },
+
+    #[error("Spatial audio max distance: {max_distance} must be greater than 0")]
+    InvalidMaxDistanceError {
+        max_distance: f32
+    }
+}
@@ -0,0 +1,149 @@
+use serde::{Deserialize, Serialize};
+use specs::{Component, DenseVecStorage, World, Builder};
+use specs::world::LazyBuilder;
+use std::sync::{Arc, RwLock};
+use luminance_glfw::GL33Context;
+use anyhow::{Error, Result};
+use thiserror::Error;
+
+#[cfg(feature = "trace")]
+use tracing::{debug, error, instrument};
+
+use crate::components::ComponentLoader;
+use crate::load::{JSONLoad, load_deserializable_from_json, serialize_to_json_load, LoadError};
+use crate::components::sprite_region::SpriteRegionLoaderError::{DeserializeError, LoadTypeIDError, EmptyRegionError};
+
+pub const SPRITE_REGION_LOAD_ID: &str = "sprite_region";
+
+/// Pixel rectangle of the entity's texture that the SpriteRenderer draws, for sprite sheets that
+/// have no atlas. `x` and `y` are the rectangle's top left corner, counted from the top left of
+/// the image. Entities without one are drawn with the whole texture.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "schema_gen", derive(schemars::JsonSchema))]
+pub struct SpriteRegion {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32
+}
+
+impl Component for SpriteRegion { type Storage = DenseVecStorage<Self>; }
+crate::register_component!(SpriteRegion);
+
+impl SpriteRegion {
+    /// `(uv_min, uv_max)` of the region in a texture of `texture_size` pixels. Textures are
+    /// flipped when they are loaded, so the top of the image is at the top of UV space.
+    pub fn uv_bounds(&self, texture_size: [u32; 2]) -> ([f32; 2], [f32; 2]) {
+        let [tex_w, tex_h] = [texture_size[0].max(1) as f32, texture_size[1].max(1) as f32];
+
+        (
+            [self.x as f32 / tex_w, 1.0 - (self.y + self.height) as f32 / tex_h],
+            [(self.x + self.width) as f32 / tex_w, 1.0 - self.y as f32 / tex_h]
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SpriteRegionLoader {
+    json: SpriteRegion
+}
+
+impl ComponentLoader for SpriteRegionLoader {
+    #[cfg_attr(feature = "trace", instrument)]
+    fn from_json(json: JSONLoad) -> Result<Self> where Self: Sized {
+        Self::validate_schema(&json)?;
+
+        let region: SpriteRegion = load_deserializable_from_json(&json, SPRITE_REGION_LOAD_ID)
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to convert JSONLoad object: ({:?}) into SpriteRegion value", json.clone());
+
+                DeserializeError {
+                    source: e,
+                    json: json.clone()
+                }
+            })?;
+
+        Ok(Self { json: region })
+    }
+
+    #[cfg(feature = "schema_gen")]
+    fn json_schema() -> serde_json::Value where Self: Sized {
+        crate::schema::validation_schema::<SpriteRegion>()
+    }
+
+    #[cfg_attr(feature = "trace", instrument(skip(builder, _ecs, _context)))]
+    fn load_component<'a>(&self, builder: LazyBuilder<'a>, _ecs: Arc<RwLock<World>>, _context: Option<Arc<RwLock<GL33Context>>>) -> Result<LazyBuilder<'a>> {
+        if self.json.width == 0 || self.json.height == 0 {
+            #[cfg(feature = "trace")]
+            error!("Sprite region: {:?} has no area", self.json);
+
+            return Err(Error::new(EmptyRegionError { region: self.json }))
+        }
+
+        #[cfg(feature = "trace")]
+        debug!("Created new sprite region component: {:?}", self.json);
+
+        Ok(builder.with(self.json))
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn set_value(&mut self, new_value: JSONLoad) -> Result<()> {
+        if new_value.load_type_id == SPRITE_REGION_LOAD_ID {
+            self.json = load_deserializable_from_json(&new_value, SPRITE_REGION_LOAD_ID)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to convert JSONLoad object: ({:?}) into SpriteRegion value", new_value.clone());
+
+                    DeserializeError {
+                        source: e,
+                        json: new_value.clone()
+                    }
+                })?;
+
+            Ok(())
+        } else {
+            #[cfg(feature = "trace")]
+            error!("Given load-type ID: ({:?}) does not match expected ID: {:?}", new_value.load_type_id.clone(), SPRITE_REGION_LOAD_ID.to_string());
+
+            Err(Error::new(LoadTypeIDError {
+                actual: new_value.load_type_id,
+                expected: SPRITE_REGION_LOAD_ID.to_string()
+            }))
+        }
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn get_value(&self) -> Result<JSONLoad, LoadError> {
+        serialize_to_json_load(&self.json, SPRITE_REGION_LOAD_ID)
+    }
+
+    fn clone_boxed(&self) -> Box<dyn ComponentLoader> {
+        Box::new(self.clone())
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn get_component_name(&self) -> String {
+        SPRITE_REGION_LOAD_ID.to_string()
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum SpriteRegionLoaderError {
+    #[error("Failed to convert JSONLoad object: ({json:?}) to SpriteRegion value")]
+    DeserializeError {
+        source: LoadError,
+        json: JSONLoad
+    },
+
+    #[error("Given load-type ID: ({actual}) does not match expected ID: {expected}")]
+    LoadTypeIDError {
+        actual: String,
+        expected: String
+    },
+
+    #[error("Sprite region: ({region:?}) must have a width and height")]
+    EmptyRegionError {
+        region: SpriteRegion
+    }
+}
@@ -0,0 +1,203 @@
+use serde::{Deserialize, Serialize};
+use specs::{Component, DenseVecStorage, World, Builder, System, Entities, Entity, ReadStorage, WriteStorage, Write, Join};
+use specs::world::LazyBuilder;
+use std::sync::{Arc, RwLock};
+use luminance_glfw::GL33Context;
+use anyhow::{Error, Result};
+use thiserror::Error;
+
+#[cfg(feature = "trace")]
+use tracing::{debug, error, instrument};
+
+use crate::components::ComponentLoader;
+use crate::components::world_text::WorldText;
+use crate::events::EventQueue;
+use crate::load::{JSONLoad, load_deserializable_from_json, serialize_to_json_load, LoadError};
+use crate::components::typewriter::TypewriterEffectLoaderError::{DeserializeError, LoadTypeIDError};
+
+pub const TYPEWRITER_EFFECT_LOAD_ID: &str = "typewriter_effect";
+
+/// Reveals the entity's WorldText one character at a time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TypewriterEffect {
+    pub chars_per_second: f32,
+    pub accumulated_time: f32,
+    pub current_char: usize
+}
+
+impl Component for TypewriterEffect { type Storage = DenseVecStorage<Self>; }
+crate::register_component!(TypewriterEffect);
+
+impl TypewriterEffect {
+    pub fn new(chars_per_second: f32) -> Self {
+        Self {
+            chars_per_second,
+            accumulated_time: 0.0,
+            current_char: 0
+        }
+    }
+}
+
+/// Drawn by the WorldTextRenderer in place of `WorldText::content` when present.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct RenderedText(pub String);
+
+impl Component for RenderedText { type Storage = DenseVecStorage<Self>; }
+crate::register_component!(RenderedText);
+
+/// Sent through an `EventQueue<TypewriterComplete>` when the entity's text has finished typing
+/// out. Register it with `systems::clear_events::register_event` before running the
+/// TypewriterSystem.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TypewriterComplete {
+    pub entity: Entity
+}
+
+/// Advances every TypewriterEffect by `delta_time` seconds and writes the revealed text to the
+/// entity's RenderedText.
+#[derive(Debug, Clone, Copy)]
+pub struct TypewriterSystem {
+    pub delta_time: f32
+}
+
+impl<'a> System<'a> for TypewriterSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, WorldText>,
+        WriteStorage<'a, TypewriterEffect>,
+        WriteStorage<'a, RenderedText>,
+        Write<'a, EventQueue<TypewriterComplete>>
+    );
+
+    #[cfg_attr(feature = "trace", instrument(skip(self, data)))]
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, texts, mut effects, mut rendered_texts, mut completed) = data;
+
+        for (entity, text, effect) in (&entities, &texts, &mut effects).join() {
+            let char_count = text.content.chars().count();
+            let previous_char = effect.current_char;
+
+            effect.accumulated_time += self.delta_time;
+            effect.current_char = ((effect.chars_per_second * effect.accumulated_time) as usize).min(char_count);
+
+            if effect.current_char == previous_char && rendered_texts.contains(entity) {
+                continue
+            }
+
+            let end = text.content.char_indices()
+                .nth(effect.current_char)
+                .map_or(text.content.len(), |(index, _)| index);
+
+            // Only fails for dead entities and every joined entity is alive.
+            let _ = rendered_texts.insert(entity, RenderedText(text.content[..end].to_string()));
+
+            if effect.current_char == char_count && previous_char < char_count {
+                #[cfg(feature = "trace")]
+                debug!("Typewriter finished for entity: {:?}", entity);
+
+                completed.push(TypewriterComplete { entity });
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema_gen", derive(schemars::JsonSchema))]
+pub struct TypewriterEffectJSON {
+    chars_per_second: f32
+}
+
+#[derive(Debug, Clone)]
+pub struct TypewriterEffectLoader {
+    json: TypewriterEffectJSON
+}
+
+impl ComponentLoader for TypewriterEffectLoader {
+    #[cfg_attr(feature = "trace", instrument)]
+    fn from_json(json: JSONLoad) -> Result<Self> where Self: Sized {
+        Self::validate_schema(&json)?;
+
+        let typewriter_json: TypewriterEffectJSON = load_deserializable_from_json(&json, TYPEWRITER_EFFECT_LOAD_ID)
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to convert JSONLoad object: ({:?}) into TypewriterEffectJSON value", json.clone());
+
+                DeserializeError {
+                    source: e,
+                    json: json.clone()
+                }
+            })?;
+
+        Ok(Self { json: typewriter_json })
+    }
+
+    #[cfg(feature = "schema_gen")]
+    fn json_schema() -> serde_json::Value where Self: Sized {
+        crate::schema::validation_schema::<TypewriterEffectJSON>()
+    }
+
+    #[cfg_attr(feature = "trace", instrument(skip(builder, _ecs, _context)))]
+    fn load_component<'a>(&self, builder: LazyBuilder<'a>, _ecs: Arc<RwLock<World>>, _context: Option<Arc<RwLock<GL33Context>>>) -> Result<LazyBuilder<'a>> {
+        let effect = TypewriterEffect::new(self.json.chars_per_second);
+
+        #[cfg(feature = "trace")]
+        debug!("Created new typewriter effect component: {:?}", effect);
+
+        Ok(builder.with(effect))
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn set_value(&mut self, new_value: JSONLoad) -> Result<()> {
+        if new_value.load_type_id == TYPEWRITER_EFFECT_LOAD_ID {
+            self.json = load_deserializable_from_json(&new_value, TYPEWRITER_EFFECT_LOAD_ID)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to convert JSONLoad object: ({:?}) into TypewriterEffectJSON value", new_value.clone());
+
+                    DeserializeError {
+                        source: e,
+                        json: new_value.clone()
+                    }
+                })?;
+
+            Ok(())
+        } else {
+            #[cfg(feature = "trace")]
+            error!("Given load-type ID: ({:?}) does not match expected ID: {:?}", new_value.load_type_id.clone(), TYPEWRITER_EFFECT_LOAD_ID.to_string());
+
+            Err(Error::new(LoadTypeIDError {
+                actual: new_value.load_type_id,
+                expected: TYPEWRITER_EFFECT_LOAD_ID.to_string()
+            }))
+        }
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn get_value(&self) -> Result<JSONLoad, LoadError> {
+        serialize_to_json_load(&self.json, TYPEWRITER_EFFECT_LOAD_ID)
+    }
+
+    fn clone_boxed(&self) -> Box<dyn ComponentLoader> {
+        Box::new(self.clone())
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn get_component_name(&self) -> String {
+        TYPEWRITER_EFFECT_LOAD_ID.to_string()
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum TypewriterEffectLoaderError {
+    #[error("Failed to convert JSONLoad object: ({json:?}) to TypewriterEffectJSON value")]
+    DeserializeError {
+        source: LoadError,
+        json: JSONLoad
+    },
+
+    #[error("Given load-type ID: ({actual}) does not match expected ID: {expected}")]
+    LoadTypeIDError {
+        actual: String,
+        expected: String
+    }
+}
@@ -0,0 +1,155 @@
+use serde::{Deserialize, Serialize};
+use specs::{Component, DenseVecStorage, World, Builder};
+use specs::world::LazyBuilder;
+use std::sync::{Arc, RwLock};
+use luminance_glfw::GL33Context;
+use anyhow::{Error, Result};
+use thiserror::Error;
+
+#[cfg(feature = "trace")]
+use tracing::{debug, error, instrument};
+
+use crate::components::ComponentLoader;
+use crate::load::{JSONLoad, load_deserializable_from_json, serialize_to_json_load, LoadError};
+use crate::components::world_text::WorldTextLoaderError::{DeserializeError, LoadTypeIDError};
+
+pub const WORLD_TEXT_LOAD_ID: &str = "world_text";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "schema_gen", derive(schemars::JsonSchema))]
+pub enum TextAlignment {
+    #[default]
+    Left,
+    Center,
+    Right
+}
+
+/// Text drawn in world space at the entity's Transform using a font from the FontAtlasDict.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorldText {
+    pub content: String,
+    pub font_handle: String,
+    pub size: f32,
+    pub color: [f32; 4],
+    pub alignment: TextAlignment
+}
+
+impl Component for WorldText { type Storage = DenseVecStorage<Self>; }
+crate::register_component!(WorldText);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema_gen", derive(schemars::JsonSchema))]
+pub struct WorldTextJSON {
+    content: String,
+    font_handle: String,
+    size: f32,
+    #[serde(default = "default_color")]
+    color: [f32; 4],
+    #[serde(default)]
+    alignment: TextAlignment
+}
+
+fn default_color() -> [f32; 4] {
+    [1.0, 1.0, 1.0, 1.0]
+}
+
+#[derive(Debug, Clone)]
+pub struct WorldTextLoader {
+    json: WorldTextJSON
+}
+
+impl ComponentLoader for WorldTextLoader {
+    #[cfg_attr(feature = "trace", instrument)]
+    fn from_json(json: JSONLoad) -> Result<Self> where Self: Sized {
+        Self::validate_schema(&json)?;
+
+        let world_text_json: WorldTextJSON = load_deserializable_from_json(&json, WORLD_TEXT_LOAD_ID)
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to convert JSONLoad object: ({:?}) into WorldTextJSON value", json.clone());
+
+                DeserializeError {
+                    source: e,
+                    json: json.clone()
+                }
+            })?;
+
+        Ok(Self { json: world_text_json })
+    }
+
+    #[cfg(feature = "schema_gen")]
+    fn json_schema() -> serde_json::Value where Self: Sized {
+        crate::schema::validation_schema::<WorldTextJSON>()
+    }
+
+    #[cfg_attr(feature = "trace", instrument(skip(builder, _ecs, _context)))]
+    fn load_component<'a>(&self, builder: LazyBuilder<'a>, _ecs: Arc<RwLock<World>>, _context: Option<Arc<RwLock<GL33Context>>>) -> Result<LazyBuilder<'a>> {
+        let world_text = WorldText {
+            content: self.json.content.clone(),
+            font_handle: self.json.font_handle.clone(),
+            size: self.json.size,
+            color: self.json.color,
+            alignment: self.json.alignment
+        };
+
+        #[cfg(feature = "trace")]
+        debug!("Created new world text component: {:?}", world_text);
+
+        Ok(builder.with(world_text))
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn set_value(&mut self, new_value: JSONLoad) -> Result<()> {
+        if new_value.load_type_id == WORLD_TEXT_LOAD_ID {
+            self.json = load_deserializable_from_json(&new_value, WORLD_TEXT_LOAD_ID)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to convert JSONLoad object: ({:?}) into WorldTextJSON value", new_value.clone());
+
+                    DeserializeError {
+                        source: e,
+                        json: new_value.clone()
+                    }
+                })?;
+
+            Ok(())
+        } else {
+            #[cfg(feature = "trace")]
+            error!("Given load-type ID: ({:?}) does not match expected ID: {:?}", new_value.load_type_id.clone(), WORLD_TEXT_LOAD_ID.to_string());
+
+            Err(Error::new(LoadTypeIDError {
+                actual: new_value.load_type_id,
+                expected: WORLD_TEXT_LOAD_ID.to_string()
+            }))
+        }
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn get_value(&self) -> Result<JSONLoad, LoadError> {
+        serialize_to_json_load(&self.json, WORLD_TEXT_LOAD_ID)
+    }
+
+    fn clone_boxed(&self) -> Box<dyn ComponentLoader> {
+        Box::new(self.clone())
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn get_component_name(&self) -> String {
+        WORLD_TEXT_LOAD_ID.to_string()
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum WorldTextLoaderError {
+    #[error("Failed to convert JSONLoad object: ({json:?}) to WorldTextJSON value")]
+    DeserializeError {
+        source: LoadError,
+        json: JSONLoad
+    },
+
+    #[error("Given load-type ID: ({actual}) does not match expected ID: {expected}")]
+    LoadTypeIDError {
+        actual: String,
+        expected: String
+    }
+}
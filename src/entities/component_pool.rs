@@ -0,0 +1,197 @@
+use std::collections::HashSet;
+use std::marker::PhantomData;
+
+use serde::Deserialize;
+use specs::{Builder, Component, Entity, LazyUpdate, NullStorage, World, WorldExt};
+use thiserror::Error;
+
+#[cfg(feature = "trace")]
+use tracing::{debug, error, instrument};
+
+use crate::entities::component_pool::ComponentPoolError::PoolConfigFileLoadError;
+use crate::load::{load_deserializable_from_file, LoadError};
+use crate::loading::GenTask;
+
+pub const POOL_CONFIG_LOAD_ID: &str = "pool_config";
+
+/// Marks an entity owned by a ComponentPool. The DespawnSystem skips these, so pooled entities
+/// are only deleted by their pool.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PooledEntity;
+
+impl Component for PooledEntity { type Storage = NullStorage<Self>; }
+crate::register_component!(PooledEntity);
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolConfig {
+    /// Entities created up front by `ComponentPool::with_config`.
+    #[serde(default)]
+    pub initial_size: usize,
+    /// Released entities beyond this many inactive ones are deleted instead of kept.
+    pub max_size: usize
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            initial_size: 0,
+            max_size: 256
+        }
+    }
+}
+
+/// Reuses entities holding a `C`, such as projectiles, instead of creating and deleting one each
+/// time. Released entities keep all of their components, with `C` reset to its default, so
+/// systems should treat a default `C` as inactive.
+#[derive(Debug)]
+pub struct ComponentPool<C: Component + Default> {
+    inactive: Vec<Entity>,
+    active: HashSet<Entity>,
+    config: PoolConfig,
+    component: PhantomData<C>
+}
+
+impl<C: Component + Default + Send + Sync> Default for ComponentPool<C> {
+    fn default() -> Self {
+        Self::new(PoolConfig::default())
+    }
+}
+
+impl<C: Component + Default + Send + Sync> ComponentPool<C> {
+    /// Starts empty. `initial_size` is ignored, use `with_config` to fill the pool.
+    pub fn new(config: PoolConfig) -> Self {
+        Self {
+            inactive: Vec::new(),
+            active: HashSet::new(),
+            config,
+            component: PhantomData
+        }
+    }
+
+    /// Creates `config.initial_size` inactive entities right away.
+    #[cfg_attr(feature = "trace", instrument(skip(ecs)))]
+    pub fn with_config(config: PoolConfig, ecs: &mut World) -> Self {
+        let mut pool = Self::new(config);
+
+        pool.inactive = (0..config.initial_size)
+            .map(|_| ecs.create_entity().with(C::default()).with(PooledEntity).build())
+            .collect();
+
+        #[cfg(feature = "trace")]
+        debug!("Created pool with {:?} inactive entities", pool.inactive.len());
+
+        pool
+    }
+
+    /// Reuses an inactive entity, or creates a new one through LazyUpdate if there are none. A new
+    /// entity's components are added at the next `World::maintain`.
+    #[cfg_attr(feature = "trace", instrument(skip(self, ecs)))]
+    pub fn acquire(&mut self, ecs: &World) -> Entity {
+        // Inactive entities deleted by something other than the pool are dropped here.
+        while let Some(entity) = self.inactive.pop() {
+            if ecs.is_alive(entity) {
+                #[cfg(feature = "trace")]
+                debug!("Reusing pooled entity: {:?}", entity);
+
+                self.active.insert(entity);
+
+                return entity
+            }
+        }
+
+        let entity = ecs.fetch::<LazyUpdate>()
+            .create_entity(&ecs.entities())
+            .with(C::default())
+            .with(PooledEntity)
+            .build();
+
+        #[cfg(feature = "trace")]
+        debug!("Pool is empty. Created entity: {:?}", entity);
+
+        self.active.insert(entity);
+
+        entity
+    }
+
+    /// Resets the entity's `C` and returns it to the pool. Entities the pool doesn't own are left
+    /// alone. Once `max_size` entities are inactive, further released entities are deleted.
+    #[cfg_attr(feature = "trace", instrument(skip(self, ecs)))]
+    pub fn release(&mut self, ecs: &World, entity: Entity) {
+        if !self.active.remove(&entity) {
+            #[cfg(feature = "trace")]
+            debug!("Entity: {:?} is not active in this pool", entity);
+
+            return
+        }
+
+        if self.inactive.len() >= self.config.max_size {
+            // Only fails for entities that are already dead.
+            let _ = ecs.entities().delete(entity);
+
+            #[cfg(feature = "trace")]
+            debug!("Pool is full. Deleted entity: {:?}", entity);
+
+            return
+        }
+
+        // Fails for dead entities, which aren't worth keeping anyway.
+        if ecs.write_storage::<C>().insert(entity, C::default()).is_ok() {
+            self.inactive.push(entity);
+        }
+    }
+
+    pub fn is_active(&self, entity: Entity) -> bool {
+        self.active.contains(&entity)
+    }
+
+    pub fn active_count(&self) -> usize {
+        self.active.len()
+    }
+
+    pub fn inactive_count(&self) -> usize {
+        self.inactive.len()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PoolConfigLoader {
+    path: String
+}
+
+impl PoolConfigLoader {
+    #[cfg_attr(feature = "trace", instrument)]
+    pub fn new(file_path: String) -> Self {
+        Self {
+            path: file_path
+        }
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    pub fn load(self) -> GenTask<PoolConfig> {
+        let path = self.path;
+
+        GenTask::new(move |_ecs| {
+            let config: PoolConfig = load_deserializable_from_file(&path, POOL_CONFIG_LOAD_ID)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to deserialize file: ({:?}) into PoolConfig value", path.clone());
+
+                    PoolConfigFileLoadError {
+                        path: path.clone(),
+                        source: e
+                    }
+                })?;
+
+            Ok(config)
+        })
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ComponentPoolError {
+    #[error("Error loading JSON Value for PoolConfigLoader from: {path}")]
+    PoolConfigFileLoadError {
+        path: String,
+        source: anyhow::Error
+    }
+}
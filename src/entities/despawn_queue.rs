@@ -0,0 +1,97 @@
+use specs::{Entity, World, WorldExt};
+use specs::storage::MaskedStorage;
+use specs::error::WrongGeneration;
+
+use std::sync::{Arc, RwLock};
+
+#[cfg(feature="trace")]
+use tracing::{instrument, error, debug};
+
+use crate::entities::EntityError;
+use crate::entities::component_pool::PooledEntity;
+use crate::entities::EntityError::{EntityWorldWriteLockError, EntityDespawnError};
+
+/// Entities waiting to be deleted at the end of the current update. Deleting through the queue
+/// instead of `World::delete_entity` means an entity can be despawned more than once without panicking.
+#[derive(Default, Debug)]
+pub struct DespawnQueue(Vec<Entity>);
+
+impl DespawnQueue {
+    #[cfg_attr(feature="trace", instrument(skip(self)))]
+    pub fn push_back(&mut self, entity: Entity) {
+        if !self.0.contains(&entity) {
+            self.0.push(entity);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+#[cfg_attr(feature="trace", instrument(skip(ecs)))]
+pub fn queue_despawn(entity: Entity, ecs: Arc<RwLock<World>>) -> Result<(), EntityError> {
+    ecs.write()
+        .map_err(|_e| {
+            #[cfg(feature = "trace")]
+            error!("Failed to acquire write lock for World");
+
+            EntityWorldWriteLockError
+        })?
+        .fetch_mut::<DespawnQueue>()
+        .push_back(entity);
+
+    #[cfg(feature = "trace")]
+    debug!("Queued entity: {:?} for despawn", entity);
+
+    Ok(())
+}
+
+/// Deletes every queued entity and maintains the World. Run once at the very end of each update.
+/// Entities owned by a ComponentPool are never deleted here, release them to their pool instead.
+pub struct DespawnSystem;
+
+impl DespawnSystem {
+    #[cfg_attr(feature="trace", instrument(skip(ecs)))]
+    pub fn run(ecs: Arc<RwLock<World>>) -> Result<(), EntityError> {
+        let mut world = ecs.write()
+            .map_err(|_e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to acquire write lock for World");
+
+                EntityWorldWriteLockError
+            })?;
+
+        let queued: Vec<Entity> = world.fetch_mut::<DespawnQueue>().0.drain(..).collect();
+
+        // Entities deleted some other way since they were queued would fail with WrongGeneration.
+        let alive: Vec<Entity> = {
+            let pooled = world.has_value::<MaskedStorage<PooledEntity>>()
+                .then(|| world.read_storage::<PooledEntity>());
+
+            queued.into_iter()
+                .filter(|entity| world.is_alive(*entity))
+                .filter(|entity| !matches!(&pooled, Some(pooled) if pooled.contains(*entity)))
+                .collect()
+        };
+
+        #[cfg(feature = "trace")]
+        debug!("Despawning entities: {:?}", alive);
+
+        world.delete_entities(&alive)
+            .map_err(|e: WrongGeneration| {
+                #[cfg(feature = "trace")]
+                error!("Failed to despawn entities: {:?}", alive);
+
+                EntityDespawnError { source: e }
+            })?;
+
+        world.maintain();
+
+        Ok(())
+    }
+}
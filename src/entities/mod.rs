@@ -2,7 +2,7 @@
 
 use specs::{Builder, Entity, LazyUpdate, World};
 
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
 
 use std::sync::{Arc, RwLock};
 use std::marker::PhantomData;
@@ -16,22 +16,68 @@ use thiserror::Error;
 use anyhow::Result;
 
 #[cfg(feature="trace")]
-use tracing::{instrument, error, debug};
+use tracing::{instrument, error, debug, warn};
+#[cfg(feature="trace")]
+use crate::load::display_chain;
 use specs::world::EntitiesRes;
 use crate::loading::DrawTask;
 use luminance_glfw::GL33Context;
 use std::borrow::BorrowMut;
-use crate::entities::EntityError::{EntityLoaderDeserializeError, EntityWorldWriteLockError, EntityFileLoadError, ComponentMuxError, EntityComponentLoaderError};
+use crate::entities::EntityError::{EntityLoaderDeserializeError, EntityWorldReadLockError, EntityFileLoadError, ComponentMuxError, EntityComponentLoaderError, EntityLoadTimingError, WorldCapacityExceeded};
+use crate::entities::world_limit::reached_limit;
+use crate::loading::timing::{record_entity_load, LoadTimingError};
+use std::time::{Duration, Instant};
+use std::path::Path;
+use glob::glob;
+use specs::rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use crate::loading::GenTask;
+use crate::entities::EntityBatchError::{GlobPatternError, GlobReadError, BatchEntityLoadError, BatchEntityLoadTimingError};
 
 pub mod player;
 pub mod textbox;
+pub mod despawn_queue;
+pub mod world_limit;
+pub mod component_pool;
 
 pub const ENTITIES_DIR: &str = "entities/";
 pub const ENTITY_LOAD_ID: &str = "entity_loader";
 
 #[derive(Deserialize, Debug, Clone)]
 pub(crate) struct EntityLoaderJSON {
-    component_paths: Vec<String>
+    component_paths: Vec<ComponentPathSpec>
+}
+
+/// A component file of an entity. When an optional file can't be read the entity is built
+/// without that component. Written in the entity file as either a path, which is required, or
+/// `{ "path": ..., "required": false }`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ComponentPathSpec {
+    path: String,
+    required: bool
+}
+
+impl<'de> Deserialize<'de> for ComponentPathSpec {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum ComponentPathJSON {
+            Path(String),
+            Spec {
+                path: String,
+                #[serde(default = "default_required")]
+                required: bool
+            }
+        }
+
+        Ok(match ComponentPathJSON::deserialize(deserializer)? {
+            ComponentPathJSON::Path(path) => Self { path, required: true },
+            ComponentPathJSON::Spec { path, required } => Self { path, required }
+        })
+    }
+}
+
+fn default_required() -> bool {
+    true
 }
 
 #[derive(Debug, Clone)]
@@ -57,82 +103,228 @@ impl EntityLoader {
         let file_path = self.entity_file.clone();    // Attempt to not have self in the closure
 
         DrawTask::new(move |(world, context)| {
-            let entity_json: EntityLoaderJSON = load_deserializable_from_file(&file_path, ENTITY_LOAD_ID)
+            let load_start = Instant::now();
+
+            let entity = build_entity::<T>(&file_path, &world, Some(context))?;
+
+            record_entity_load(&world, &file_path, load_start.elapsed())
+                .map_err(|e| EntityLoadTimingError { source: e })?;
+
+            return Ok(entity)
+        })
+    }
+
+    /// Loads every entity file in `dir_path` matching the glob `pattern`, e.g. `"enemy_*.json"`,
+    /// in parallel. The entities are returned in the order of their paths.
+    ///
+    /// Components are loaded without an OpenGL context, so entities with components that need
+    /// one, like materials, have to be loaded with `load_entity` instead. If any entity fails to
+    /// load, the entities already built are deleted and the error of the first failed path is
+    /// returned.
+    #[cfg_attr(feature="trace", instrument)]
+    pub fn load_entity_batch<T: ComponentMux>(dir_path: &str, pattern: &str) -> GenTask<Vec<Entity>> {
+        let full_pattern = Path::new(dir_path).join(pattern).to_string_lossy().into_owned();
+
+        GenTask::new(move |world| {
+            let file_paths = glob(&full_pattern)
                 .map_err(|e| {
                     #[cfg(feature = "trace")]
-                    error!("Failed to load JSON value for Entity from file: {:?}", file_path.clone());
+                    error!("Invalid glob pattern: {:?}. Error: {}", full_pattern.clone(), e);
 
-                    EntityLoaderDeserializeError {
-                        source: e,
-                        file_path: file_path.clone()
+                    GlobPatternError {
+                        pattern: full_pattern.clone(),
+                        source: e
                     }
-                })?;
+                })?
+                .map(|entry| {
+                    entry
+                        .map(|path| path.to_string_lossy().into_owned())
+                        .map_err(|e| {
+                            #[cfg(feature = "trace")]
+                            error!("Failed to read path matching: {:?}. Error: {}", full_pattern.clone(), e);
+
+                            GlobReadError {
+                                pattern: full_pattern.clone(),
+                                source: e
+                            }
+                        })
+                })
+                .collect::<Result<Vec<String>, EntityBatchError>>()?;
 
             #[cfg(feature = "trace")]
-            debug!("Entity JSON value loaded from file: {:?}", file_path.clone());
+            debug!("Loading {:?} entities matching: {:?}", file_paths.len(), full_pattern.clone());
+
+            // Load times are recorded once every entity is built. A thread waiting on the write
+            // lock for the World could otherwise block component loaders reading it on another.
+            let results = file_paths.par_iter()
+                .map(|file_path| {
+                    let load_start = Instant::now();
+
+                    build_entity::<T>(file_path, &world, None)
+                        .map(|entity| (entity, load_start.elapsed()))
+                        .map_err(|e| {
+                            #[cfg(feature = "trace")]
+                            error!("Failed to load entity from: {:?} in batch. Error: {}", file_path.clone(), display_chain(&*e));
+
+                            BatchEntityLoadError {
+                                file_path: file_path.clone(),
+                                source: e
+                            }
+                        })
+                })
+                .collect::<Vec<Result<(Entity, Duration), EntityBatchError>>>();
+
+            let built = results.iter()
+                .filter_map(|result| result.as_ref().ok())
+                .map(|(entity, _)| *entity)
+                .collect::<Vec<Entity>>();
+
+            let loaded = match results.into_iter().collect::<Result<Vec<(Entity, Duration)>, EntityBatchError>>() {
+                Ok(loaded) => loaded,
+                Err(e) => {
+                    delete_entities(&world, &built);
+
+                    return Err(e.into())
+                }
+            };
+
+            let mut entities = Vec::with_capacity(loaded.len());
+            for (file_path, (entity, load_time)) in file_paths.iter().zip(loaded) {
+                if let Err(e) = record_entity_load(&world, file_path, load_time) {
+                    delete_entities(&world, &built);
+
+                    return Err(BatchEntityLoadTimingError {
+                        file_path: file_path.clone(),
+                        source: e
+                    }.into())
+                }
+
+                entities.push(entity);
+            }
 
-            let ecs = world.read()
-                .map_err(|_e| {
-                    #[cfg(feature = "trace")]
-                    debug!("Error acquiring write lock for World");
+            #[cfg(feature = "trace")]
+            debug!("Loaded {:?} entities matching: {:?}", entities.len(), full_pattern.clone());
 
-                    EntityWorldWriteLockError
-                })?;
+            Ok(entities)
+        })
+    }
+}
+
+/// Deletes entities of a batch that failed to load. Their lazily inserted components are dropped
+/// the next time the World is maintained.
+fn delete_entities(world: &Arc<RwLock<World>>, entities: &[Entity]) {
+    let ecs = match world.read() {
+        Ok(ecs) => ecs,
+        Err(_e) => {
+            #[cfg(feature = "trace")]
+            error!("Error acquiring read lock for World. Could not delete {:?} entities of failed batch", entities.len());
+
+            return
+        }
+    };
+
+    let entities_res = ecs.fetch::<EntitiesRes>();
+    for entity in entities {
+        if let Err(_e) = entities_res.delete(*entity) {
+            #[cfg(feature = "trace")]
+            error!("Failed to delete entity: {:?} of failed batch. Error: {}", entity, _e);
+        }
+    }
+}
 
-            let lazy_update = ecs.fetch::<LazyUpdate>();
-            let entities = ecs.fetch::<EntitiesRes>();
+/// Builds the entity described by the file at `file_path` through the World's LazyUpdate.
+/// `context` is passed on to each component loader.
+#[cfg_attr(feature="trace", instrument(skip(world, context)))]
+fn build_entity<T: ComponentMux>(file_path: &str, world: &Arc<RwLock<World>>, context: Option<Arc<RwLock<GL33Context>>>) -> Result<Entity> {
+    let entity_json: EntityLoaderJSON = load_deserializable_from_file(file_path, ENTITY_LOAD_ID)
+        .map_err(|e| {
+            #[cfg(feature = "trace")]
+            error!("Failed to load JSON value for Entity from file: {:?}. Error: {}", file_path, display_chain(&*e));
+
+            EntityLoaderDeserializeError {
+                source: e,
+                file_path: file_path.to_string()
+            }
+        })?;
 
-            let mut builder = lazy_update.create_entity(&entities);
+    #[cfg(feature = "trace")]
+    debug!("Entity JSON value loaded from file: {:?}", file_path);
 
+    let ecs = world.read()
+        .map_err(|_e| {
             #[cfg(feature = "trace")]
-            debug!("Lazy Builder has been created for building Entity");
+            debug!("Error acquiring read lock for World");
 
-            for component_path in entity_json.component_paths {
+            EntityWorldReadLockError
+        })?;
+
+    if let Some(limit) = reached_limit(&ecs) {
+        #[cfg(feature = "trace")]
+        error!("World is at its limit of {:?} entities. Not loading: {:?}", limit, file_path);
+
+        return Err(WorldCapacityExceeded { limit }.into())
+    }
+
+    let lazy_update = ecs.fetch::<LazyUpdate>();
+    let entities = ecs.fetch::<EntitiesRes>();
+
+    let mut builder = lazy_update.create_entity(&entities);
+
+    #[cfg(feature = "trace")]
+    debug!("Lazy Builder has been created for building Entity");
+
+    for ComponentPathSpec { path: component_path, required } in entity_json.component_paths {
+        #[cfg(feature = "trace")]
+        debug!("Loading component from: {:?}", component_path.clone());
+        let json = match load_json(&component_path) {
+            Ok(json) => json,
+            Err(e) if !required && matches!(e.downcast_ref::<LoadError>(), Some(LoadError::ReadError { .. })) => {
                 #[cfg(feature = "trace")]
-                debug!("Loading component from: {:?}", component_path.clone());
-                let json = load_json(&component_path)
-                    .map_err(|e| {
-                        #[cfg(feature = "trace")]
-                        error!("Error occurred while loading component JSON value.");
-
-                        EntityFileLoadError {
-                            file: component_path.clone(),
-                            source: e
-                        }
-                    })?;
-                let loader = T::map_json_to_loader(json.clone())
-                    .map_err(|e| {
-                        #[cfg(feature = "trace")]
-                        error!("Error occurred while mapping JSON value: ({:?}) to Component type", json);
-
-                        ComponentMuxError {
-                            source: e,
-                            component_json: json
-                        }
-                    })?;
-
-                builder = loader.load_component(builder, world.clone(), Some(context.clone()))
-                    .map_err(|e| {
-                        #[cfg(feature = "trace")]
-                        error!("Error occurred while loading component.");
-
-                        EntityComponentLoaderError {
-                            component_path,
-                            source: e
-                        }
-                    })?;
+                warn!("Skipping optional component. Could not read file: {:?}", component_path.clone());
+
+                continue
+            },
+            Err(e) => {
                 #[cfg(feature = "trace")]
-                debug!("Component loaded");
+                error!("Error occurred while loading component JSON value. Error: {}", display_chain(&*e));
+
+                return Err(EntityFileLoadError {
+                    file: component_path.clone(),
+                    source: e
+                }.into())
             }
+        };
+        let loader = T::map_json_to_loader(json.clone())
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Error occurred while mapping JSON value: ({:?}) to Component type. Error: {}", json, display_chain(&*e));
 
-            let entity = builder.build();
+                ComponentMuxError {
+                    source: e,
+                    component_json: json
+                }
+            })?;
 
-            #[cfg(feature = "trace")]
-            debug!("Entity built: {:?}", entity);
+        builder = loader.load_component(builder, world.clone(), context.clone())
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Error occurred while loading component. Error: {}", display_chain(&*e));
 
-            return Ok(entity)
-        })
+                EntityComponentLoaderError {
+                    component_path,
+                    source: e
+                }
+            })?;
+        #[cfg(feature = "trace")]
+        debug!("Component loaded");
     }
+
+    let entity = builder.build();
+
+    #[cfg(feature = "trace")]
+    debug!("Entity built: {:?}", entity);
+
+    Ok(entity)
 }
 
 #[derive(Error, Debug)]
@@ -140,12 +332,12 @@ pub enum EntityError {
     #[error("Error loading JSON Value from {file}")]
     EntityFileLoadError {
         file: String,
-        source: LoadError
+        source: anyhow::Error
     },
     #[error("Error creating EntityLoader JSON from: {file_path}")]
     EntityLoaderDeserializeError {
         file_path: String,
-        source: LoadError
+        source: anyhow::Error
     },
     #[error("Error creating component loader from path: {component_path}")]
     EntityComponentLoaderError {
@@ -154,6 +346,8 @@ pub enum EntityError {
     },
     #[error("Error retrieving write lock for World")]
     EntityWorldWriteLockError,
+    #[error("Error retrieving read lock for World")]
+    EntityWorldReadLockError,
     #[error("Error loading component from Component Loader")]
     EntityLoadComponentError {
         source: anyhow::Error
@@ -162,5 +356,112 @@ pub enum EntityError {
     ComponentMuxError {
         source: anyhow::Error,
         component_json: JSONLoad
+    },
+    #[error("Error despawning queued entities")]
+    EntityDespawnError {
+        source: specs::error::WrongGeneration
+    },
+    #[error("Error recording how long the entity took to load")]
+    EntityLoadTimingError {
+        source: LoadTimingError
+    },
+    #[error("World already holds its limit of {limit} entities")]
+    WorldCapacityExceeded {
+        limit: u32
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum EntityBatchError {
+    #[error("Invalid glob pattern: {pattern}")]
+    GlobPatternError {
+        pattern: String,
+        source: glob::PatternError
+    },
+    #[error("Error reading a path matching: {pattern}")]
+    GlobReadError {
+        pattern: String,
+        source: glob::GlobError
+    },
+    #[error("Error loading entity from: {file_path}")]
+    BatchEntityLoadError {
+        file_path: String,
+        source: anyhow::Error
+    },
+    #[error("Error recording how long the entity from: {file_path} took to load")]
+    BatchEntityLoadTimingError {
+        file_path: String,
+        source: LoadTimingError
+    }
+}
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use specs::{Join, WorldExt};
+
+    use crate::components::ComponentLoader;
+    use crate::test_util::TempDir;
+
+    use super::*;
+
+    /// Entities in these tests have no components, so no component JSON is ever mapped.
+    struct NoComponents;
+
+    impl ComponentMux for NoComponents {
+        fn map_json_to_loader(json: JSONLoad) -> Result<Box<dyn ComponentLoader>> {
+            Err(anyhow::anyhow!("No component loader for: {:?}", json))
+        }
+    }
+
+    fn write_entity(dir: &TempDir, name: &str) {
+        dir.write_load(name, ENTITY_LOAD_ID, json!({"component_paths": []}));
+    }
+
+    fn world() -> Arc<RwLock<World>> {
+        Arc::new(RwLock::new(World::new()))
     }
-}
\ No newline at end of file
+
+    fn entity_count(world: &Arc<RwLock<World>>) -> usize {
+        let mut world = world.write().unwrap();
+        world.maintain();
+
+        let count = (&world.entities()).join().count();
+
+        count
+    }
+
+    #[test]
+    fn batch_loads_every_matching_entity() {
+        let dir = TempDir::new("entity_batch_loads");
+        write_entity(&dir, "enemy_a.json");
+        write_entity(&dir, "enemy_b.json");
+        write_entity(&dir, "player.json");
+        let world = world();
+
+        let entities = EntityLoader::load_entity_batch::<NoComponents>(&dir.root(), "enemy_*.json")
+            .execute(world.clone())
+            .unwrap();
+
+        assert_eq!(entities.len(), 2);
+        assert_eq!(entity_count(&world), 2);
+    }
+
+    #[test]
+    fn failed_batch_deletes_built_entities() {
+        let dir = TempDir::new("entity_batch_fails");
+        write_entity(&dir, "enemy_a.json");
+        write_entity(&dir, "enemy_c.json");
+        dir.write("enemy_b.json", "not json");
+        let world = world();
+
+        let result = EntityLoader::load_entity_batch::<NoComponents>(&dir.root(), "enemy_*.json")
+            .execute(world.clone());
+
+        let error = result.unwrap_err();
+        match error.downcast_ref::<EntityBatchError>() {
+            Some(BatchEntityLoadError { file_path, .. }) => assert!(file_path.ends_with("enemy_b.json")),
+            other => panic!("Expected BatchEntityLoadError, got: {:?}", other)
+        }
+        assert_eq!(entity_count(&world), 0);
+    }
+}
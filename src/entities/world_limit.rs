@@ -0,0 +1,68 @@
+use specs::{Entities, Join, Read, System, World, WorldExt, Write};
+
+#[cfg(feature="trace")]
+use tracing::{instrument, warn};
+
+use crate::events::EventQueue;
+
+/// Share of `max_entities` at which the WorldLimitSystem starts warning.
+pub const CAPACITY_WARNING_RATIO: f32 = 0.9;
+
+/// Caps the number of living entities. Without this resource, or with `max_entities` unset, the
+/// World can grow without limit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WorldConfig {
+    pub max_entities: Option<u32>
+}
+
+/// Sent through an `EventQueue<WorldCapacityWarning>` by the WorldLimitSystem. Register it with
+/// `systems::clear_events::register_event` before running the system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WorldCapacityWarning {
+    pub current: u32,
+    pub max: u32
+}
+
+/// Counts the living entities and queues a WorldCapacityWarning once the count reaches
+/// `CAPACITY_WARNING_RATIO` of `WorldConfig::max_entities`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WorldLimitSystem;
+
+impl<'a> System<'a> for WorldLimitSystem {
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, WorldConfig>,
+        Write<'a, EventQueue<WorldCapacityWarning>>
+    );
+
+    #[cfg_attr(feature="trace", instrument(skip(self, data)))]
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, config, mut warnings) = data;
+
+        let max = match config.max_entities {
+            Some(max) => max,
+            None => return
+        };
+
+        let current = (&entities).join().count() as u32;
+
+        if current as f32 >= max as f32 * CAPACITY_WARNING_RATIO {
+            #[cfg(feature = "trace")]
+            warn!("World holds {:?} of at most {:?} entities", current, max);
+
+            warnings.push(WorldCapacityWarning { current, max });
+        }
+    }
+}
+
+/// The limit the World has reached, if any.
+pub fn reached_limit(ecs: &World) -> Option<u32> {
+    let max = ecs.try_fetch::<WorldConfig>()?.max_entities?;
+    let current = (&ecs.entities()).join().count() as u32;
+
+    if current >= max {
+        Some(max)
+    } else {
+        None
+    }
+}
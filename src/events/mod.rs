@@ -0,0 +1,42 @@
+/// Events of one type sent between systems during a frame, such as `WindowResized`. Senders push
+/// and readers iterate or drain. Register the event type with
+/// `systems::clear_events::register_event` in `GameWrapper::register_components`. That inserts
+/// the queue, which must be in the World before any system reads or writes it, and empties it
+/// at the start of every frame.
+#[derive(Debug)]
+pub struct EventQueue<E: Send + Sync>(Vec<E>);
+
+// Implemented by hand so `E` doesn't need to implement Default.
+impl<E: Send + Sync> Default for EventQueue<E> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+impl<E: Send + Sync> EventQueue<E> {
+    pub fn push(&mut self, event: E) {
+        self.0.push(event);
+    }
+
+    /// Takes every queued event, oldest first.
+    pub fn drain(&mut self) -> impl Iterator<Item = E> + '_ {
+        self.0.drain(..)
+    }
+
+    /// Reads the queued events, oldest first, leaving them for other readers.
+    pub fn iter(&self) -> impl Iterator<Item = &E> {
+        self.0.iter()
+    }
+
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
@@ -18,20 +18,44 @@ use luminance_glfw::GL33Context;
 use anyhow::Result;
 use std::borrow::BorrowMut;
 use std::ops::DerefMut;
+use std::time::Duration;
 use crate::load::{LOAD_PATH, JSON_FILE};
 use crate::scenes::SCENES_DIR;
+use crate::scenes::registry::SceneRegistry;
+use crate::game_loop::WindowConfig;
+use crate::graphics::render_graph::SWAP_BUFFERS_NODE;
+use crate::components::register_all_components;
+#[cfg(all(feature = "dev_server", unix))]
+use crate::game_loop::hot_swap::{HotSwapCommand, load_hot_swap_scene};
+#[cfg(feature = "debug_overlay")]
+use crate::scenes::debug_overlay::{DebugOverlay, DebugOverlayFont, toggle_requested};
 
 pub const GAME_FILE_ID: &str = "game";
 
 pub trait GameWrapper<T: Input + Debug> {
-    fn register_components(ecs: &mut World);
+    /// Registers every component submitted with `register_component!`, including the engine's own.
+    /// Override it to register components by hand. Event types the game sends between systems are
+    /// registered here too, with `systems::clear_events::register_event`.
+    fn register_components(ecs: &mut World) {
+        register_all_components(ecs);
+    }
+    /// Registered scenes can be loaded with `SceneStackLoader::from_registry` using the
+    /// `Arc<SceneRegistry<T>>` in the World.
+    fn register_scenes(registry: &mut SceneRegistry<T>);
     // Allow user to pre-fill World with global values here
     fn load() -> DrawTask<SceneStack<T>>;
+    /// Size, title and mode of the primary window the GameLoop opens.
+    fn window_config() -> WindowConfig {
+        WindowConfig::default()
+    }
     // fn load_scene_stack(ecs: Arc<RwLock<World>>, window: &Window) -> Task<SceneStack<T>>;
 }
 
 pub struct Game<T: GameWrapper<U>, U: 'static + Input + Debug> {
     scene_stack: SceneStack<U>,
+    /// Drawn on top of the scenes while open. Opened and closed with the DebugOverlayKey.
+    #[cfg(feature = "debug_overlay")]
+    debug_overlay: Option<DebugOverlay>,
     phantom_wrapper: PhantomData<T>,
 }
 
@@ -53,6 +77,20 @@ impl<T: GameWrapper<U>, U: Input + Debug> Game<T,U> {
         #[cfg(feature="trace")]
         debug!("Components registered");
 
+        let mut scene_registry = SceneRegistry::default();
+        T::register_scenes(&mut scene_registry);
+
+        ecs.write()
+            .map_err(|_e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to acquire write lock for World");
+
+                WorldWriteLockError
+            })?
+            .insert(Arc::new(scene_registry));
+        #[cfg(feature="trace")]
+        debug!("Scenes registered");
+
         let scene_stack = T::load()
             .execute((ecs.clone(), context))
             .map_err(|e| { GameWrapperLoadError { source: e } })?;
@@ -69,6 +107,8 @@ impl<T: GameWrapper<U>, U: Input + Debug> Game<T,U> {
         debug!("EXIT: MyGame::load");
         Ok(Game {
             scene_stack,
+            #[cfg(feature = "debug_overlay")]
+            debug_overlay: None,
             phantom_wrapper: PhantomData,
         })
     }
@@ -78,13 +118,24 @@ impl<T: GameWrapper<U>, U: Input + Debug> Game<T,U> {
         #[cfg(feature="trace")]
         debug!("ENTER: MyGame::draw");
 
-         self.scene_stack.draw(ecs, context)
-             .map_err(|e| {
-                 #[cfg(feature="trace")]
-                 error!("ERROR: Game failed during draw function: {:?}", e);
+        #[cfg(feature = "debug_overlay")]
+        let debug_overlay = &mut self.debug_overlay;
+
+        // The debug overlay is drawn before a render graph swaps the buffers.
+        self.scene_stack.draw_with_overlay(ecs, context, |_ecs: &World, _context: &mut GL33Context| {
+            #[cfg(feature = "debug_overlay")]
+            if let Some(debug_overlay) = debug_overlay.as_mut() {
+                debug_overlay.draw(_ecs, _context)?;
+            }
+
+            Ok(())
+        })
+            .map_err(|e| {
+                #[cfg(feature="trace")]
+                error!("ERROR: Game failed during draw function: {:?}", e);
 
-                 GameDrawError { source: e }
-             })?;
+                GameDrawError { source: e }
+            })?;
 
         #[cfg(feature="trace")]
         debug!("EXIT: MyGame::draw");
@@ -110,11 +161,11 @@ impl<T: GameWrapper<U>, U: Input + Debug> Game<T,U> {
     }
 
     #[cfg_attr(feature="trace", instrument(skip(self, ecs)))]
-    pub(crate) fn update(&mut self, ecs: &mut World) -> Result<(), GameError> {
+    pub(crate) fn update(&mut self, ecs: &mut World, delta: Duration) -> Result<(), GameError> {
         #[cfg(feature="trace")]
         debug!("ENTER: MyGame::update");
 
-        self.scene_stack.update(ecs)
+        self.scene_stack.update(ecs, delta)
             .map_err(|e| {
                 #[cfg(feature="trace")]
                 error!("ERROR: Game failed during update function: {:?}", e);
@@ -122,11 +173,79 @@ impl<T: GameWrapper<U>, U: Input + Debug> Game<T,U> {
                 GameUpdateError { source: e }
             })?;
 
+        #[cfg(feature = "debug_overlay")]
+        self.update_debug_overlay(ecs, delta);
+
         #[cfg(feature="trace")]
         trace!("EXIT: MyGame::update");
         Ok(())
     }
 
+    /// Opens or closes the debug overlay when the DebugOverlayKey was pressed, and updates it while
+    /// it is open. The SceneStack is left alone, so the game keeps running beneath the overlay.
+    #[cfg(feature = "debug_overlay")]
+    fn update_debug_overlay(&mut self, ecs: &World, delta: Duration) {
+        if toggle_requested(ecs) {
+            self.debug_overlay = match self.debug_overlay.take() {
+                Some(_) => None,
+                None => {
+                    let debug_overlay = ecs.try_fetch::<DebugOverlayFont>()
+                        .map(|font| DebugOverlay::new(font.name.clone(), font.size));
+
+                    #[cfg(feature="trace")]
+                    if debug_overlay.is_none() {
+                        error!("The World has no DebugOverlayFont. The debug overlay wasn't opened");
+                    }
+
+                    debug_overlay
+                }
+            };
+        }
+
+        if let Some(debug_overlay) = self.debug_overlay.as_mut() {
+            debug_overlay.update(ecs, delta);
+        }
+    }
+
+    /// Replaces every scene with the one `command` loads. The scenes are kept if loading fails.
+    #[cfg(all(feature = "dev_server", unix))]
+    #[cfg_attr(feature="trace", instrument(skip(self, ecs, context)))]
+    pub(crate) fn hot_swap(&mut self, command: &HotSwapCommand, ecs: Arc<RwLock<World>>, context: Arc<RwLock<GL33Context>>) {
+        match load_hot_swap_scene(command, ecs, context) {
+            Ok((scene, texture_scope)) => {
+                #[cfg(feature="trace")]
+                debug!("Hot swapped scene stack to: {:?}", scene.get_name());
+
+                if let Err(_e) = self.scene_stack.reset_to(scene, texture_scope) {
+                    #[cfg(feature="trace")]
+                    error!("Hot swapped scenes failed to extend the render graph. Error: {:?}", _e);
+                }
+            },
+            Err(_e) => {
+                #[cfg(feature="trace")]
+                error!("Hot swap failed. Keeping current scenes. Error: {:?}", _e);
+            }
+        }
+    }
+
+    /// Loads the scene a `SceneTransition::PRELOAD` is waiting on. A scene that fails to load before
+    /// COMMIT_PRELOAD is kept as its error for COMMIT_PRELOAD to report.
+    #[cfg_attr(feature="trace", instrument(skip(self, ecs, context)))]
+    pub(crate) fn load_preload(&mut self, ecs: Arc<RwLock<World>>, context: Arc<RwLock<GL33Context>>) -> Result<(), GameError> {
+        self.scene_stack.load_preload(ecs, context)
+            .map_err(|e| {
+                #[cfg(feature="trace")]
+                error!("ERROR: Game failed to push preloaded scene: {:?}", e);
+
+                GameUpdateError { source: e }
+            })
+    }
+
+    /// Whether the SceneStack's render graph puts the frame on screen itself.
+    pub(crate) fn swaps_buffers(&self) -> bool {
+        matches!(self.scene_stack.render_graph(), Some(render_graph) if render_graph.contains(SWAP_BUFFERS_NODE))
+    }
+
     #[cfg_attr(feature="trace", instrument(skip(self, ecs)))]
     pub(crate) fn is_finished(&self, ecs: &mut World) -> bool {
         #[cfg(feature = "trace")]
@@ -0,0 +1,173 @@
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::fs::{remove_file, symlink_metadata};
+use std::io::{BufRead, BufReader};
+use std::os::unix::fs::FileTypeExt;
+use std::os::unix::net::UnixListener;
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread::{self, JoinHandle};
+
+use luminance_glfw::GL33Context;
+use serde::{Deserialize, Serialize};
+use specs::World;
+use thiserror::Error;
+
+#[cfg(feature = "trace")]
+use tracing::{debug, error, instrument, warn};
+
+use crate::game_loop::hot_swap::HotSwapError::{SocketBindError, WorldReadLockError, SceneRegistryMissing, SceneJSONLoadError, SceneFactoryError, SceneLoadError};
+use crate::globals::texture_dict::{self, SceneTextureScope};
+use crate::input::Input;
+use crate::load::{load_json, JSONLoad};
+use crate::scenes::Scene;
+use crate::scenes::registry::{SceneRegistry, SceneRegistryError};
+
+pub const DEFAULT_HOT_SWAP_SOCKET: &str = "/tmp/game_engine_hotswap.sock";
+
+/// Sent to the hot swap socket as one line of JSON, e.g.
+/// `{"scene_type_id": "level", "scene_json_path": "assets/JSON/scenes/level_1.json"}`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct HotSwapCommand {
+    /// Scene type registered in the SceneRegistry. Used instead of the file's `load_type_id`.
+    pub scene_type_id: String,
+    pub scene_json_path: String
+}
+
+/// Commands received by the hot swap listener that the GameLoop hasn't applied yet.
+#[derive(Debug, Clone, Default)]
+pub struct HotSwapQueue(Arc<Mutex<VecDeque<HotSwapCommand>>>);
+
+impl HotSwapQueue {
+    pub fn push(&self, command: HotSwapCommand) {
+        if let Ok(mut queue) = self.0.lock() {
+            queue.push_back(command);
+        }
+    }
+
+    /// Takes every queued command, oldest first.
+    pub fn drain(&self) -> Vec<HotSwapCommand> {
+        self.0.lock()
+            .map(|mut queue| queue.drain(..).collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Listens on the UNIX socket at `socket_path` on its own thread and queues every command it
+/// receives. A socket left behind by an earlier run is replaced.
+#[cfg_attr(feature = "trace", instrument(skip(queue)))]
+pub fn spawn_hot_swap_listener(socket_path: &str, queue: HotSwapQueue) -> Result<JoinHandle<()>, HotSwapError> {
+    if matches!(symlink_metadata(socket_path), Ok(metadata) if metadata.file_type().is_socket()) {
+        // Binding fails below if this didn't work.
+        let _ = remove_file(socket_path);
+    }
+
+    let listener = UnixListener::bind(socket_path)
+        .map_err(|e| {
+            #[cfg(feature = "trace")]
+            error!("Failed to bind hot swap socket: {:?}", socket_path);
+
+            SocketBindError {
+                path: socket_path.to_string(),
+                source: e
+            }
+        })?;
+
+    #[cfg(feature = "trace")]
+    debug!("Listening for hot swap commands on: {:?}", socket_path);
+
+    Ok(thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            for line in BufReader::new(stream).lines().map_while(Result::ok) {
+                match serde_json::from_str::<HotSwapCommand>(&line) {
+                    Ok(command) => queue.push(command),
+                    Err(_e) => {
+                        #[cfg(feature = "trace")]
+                        warn!("Ignoring invalid hot swap command: {:?}. Error: {:?}", line, _e);
+                    }
+                }
+            }
+        }
+    }))
+}
+
+/// Loads the scene a command asks for with the World's SceneRegistry. The scene file is read from
+/// disk again, so changes to it are picked up.
+#[cfg_attr(feature = "trace", instrument(skip(ecs, context)))]
+pub fn load_hot_swap_scene<T: 'static + Input + Debug>(command: &HotSwapCommand, ecs: Arc<RwLock<World>>, context: Arc<RwLock<GL33Context>>) -> Result<(Box<dyn Scene<T>>, SceneTextureScope), HotSwapError> {
+    let registry = ecs.read()
+        .map_err(|_e| {
+            #[cfg(feature = "trace")]
+            error!("Failed to acquire read lock for World");
+
+            WorldReadLockError
+        })?
+        .try_fetch::<Arc<SceneRegistry<T>>>()
+        .map(|registry| Arc::clone(&registry))
+        .ok_or(SceneRegistryMissing)?;
+
+    let scene_json = load_json(&command.scene_json_path)
+        .map_err(|e| {
+            #[cfg(feature = "trace")]
+            error!("Failed to load scene file: {:?}", command.scene_json_path.clone());
+
+            SceneJSONLoadError {
+                path: command.scene_json_path.clone(),
+                source: e
+            }
+        })?;
+
+    let scene_loader = registry.create(JSONLoad {
+        load_type_id: command.scene_type_id.clone(),
+        actual_value: scene_json.actual_value
+    }).map_err(|e| SceneFactoryError { source: e })?;
+
+    texture_dict::begin_scope(&ecs);
+    let scene = scene_loader.load_scene()
+        .execute((ecs.clone(), context));
+    let texture_scope = texture_dict::end_scope(&ecs);
+
+    let scene = scene
+        .map_err(|e| {
+            #[cfg(feature = "trace")]
+            error!("Failed to load hot swapped scene: {:?}", command.scene_json_path.clone());
+
+            SceneLoadError {
+                path: command.scene_json_path.clone(),
+                source: e
+            }
+        })?;
+
+    Ok((scene, texture_scope))
+}
+
+#[derive(Error, Debug)]
+pub enum HotSwapError {
+    #[error("Failed to bind hot swap socket: {path}")]
+    SocketBindError {
+        path: String,
+        source: std::io::Error
+    },
+
+    #[error("Failed to acquire read lock for World")]
+    WorldReadLockError,
+
+    #[error("No SceneRegistry was found in the World")]
+    SceneRegistryMissing,
+
+    #[error("Failed to load scene file: {path}")]
+    SceneJSONLoadError {
+        path: String,
+        source: anyhow::Error
+    },
+
+    #[error("Failed to create a scene loader for the hot swapped scene")]
+    SceneFactoryError {
+        source: SceneRegistryError
+    },
+
+    #[error("Failed to load hot swapped scene: {path}")]
+    SceneLoadError {
+        path: String,
+        source: anyhow::Error
+    }
+}
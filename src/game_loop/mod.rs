@@ -2,23 +2,192 @@
 use tracing::{instrument, debug, error};
 
 use crate::game::{GameWrapper, Game, GameError};
-use luminance_glfw::{GlfwSurface, GlfwSurfaceError};
+use luminance_glfw::{GlfwSurface, GlfwSurfaceError, GL33Context};
 use std::process::exit;
-use glfw::{WindowEvent, Key, Action, Context as _};
+use glfw::{WindowEvent, WindowMode, Key, Action, Context as _};
 use crate::input::Input;
 use std::fmt::Debug;
 use std::marker::PhantomData;
 use specs::{World, WorldExt};
 use luminance_windowing::WindowOpt;
 use std::sync::{Arc, Mutex, RwLock};
+use std::sync::mpsc::Receiver;
 use std::ops::DerefMut;
 use thiserror::Error;
-use crate::game_loop::GameLoopError::{SurfaceCreationError, ContextWriteLockError, WorldWriteLockError, GameLoadFailure, GameInteractError, GameUpdateError, GameDrawError};
+use crate::game_loop::GameLoopError::{SurfaceCreationError, ContextWriteLockError, WorldWriteLockError, WorldReadLockError, GameLoadFailure, GameInteractError, GameUpdateError, GameDrawError, WindowRegistryError, DespawnError, WindowIconError, FullscreenToggleFailed, InvalidFps, InvalidThreadCount, ThreadPoolError};
+#[cfg(feature = "trace")]
+use crate::game_loop::GameLoopError::LogConfigError;
+#[cfg(all(feature = "dev_server", unix))]
+use crate::game_loop::GameLoopError::HotSwapListenerError;
+#[cfg(feature = "hot_reload")]
+use crate::game_loop::GameLoopError::HotReloadPollError;
+#[cfg(feature = "validate_manifest")]
+use crate::game_loop::GameLoopError::InvalidAssetManifest;
+use crate::graphics::window_registry::{WindowRegistry, WindowError};
+use crate::graphics::{set_window_icon, IconError};
+use crate::entities::EntityError;
+use crate::graphics::dynamic_resolution::DynamicResolutionSystem;
+use crate::graphics::interpolation::RenderAlpha;
+use std::time::{Duration, Instant};
+use std::thread::sleep;
+use specs::rayon::{ThreadPoolBuilder, ThreadPoolBuildError};
+use crate::entities::despawn_queue::{DespawnQueue, DespawnSystem};
+use crate::events::EventQueue;
+use crate::systems::clear_events::{EventClearers, register_event};
+use crate::camera::Camera;
+#[cfg(feature = "trace")]
+use crate::log::{LogConfig, LogInitError};
+use crate::loading::Pool;
+#[cfg(feature = "trace_recording")]
+use crate::loading::trace_recorder::flush_trace_recorder;
+#[cfg(feature = "hot_reload")]
+use crate::hot_reload::{self, HotReloadWatcher, HotReloadError};
+#[cfg(feature = "validate_manifest")]
+use crate::loading::manifest::{AssetManifest, DEFAULT_ASSET_MANIFEST};
+#[cfg(feature = "validate_manifest")]
+use crate::load::display_chain;
+#[cfg(feature = "debug_overlay")]
+use crate::scenes::debug_overlay::{DebugOverlayKey, DebugOverlayToggled};
+#[cfg(all(feature = "dev_server", unix))]
+use crate::game_loop::hot_swap::{HotSwapError, HotSwapQueue, spawn_hot_swap_listener, DEFAULT_HOT_SWAP_SOCKET};
+
+#[cfg(all(feature = "dev_server", unix))]
+pub mod hot_swap;
+
+/// Longest time step passed to `Scene::update`, so a long hitch doesn't make the next update
+/// simulate a huge jump.
+pub const DEFAULT_MAX_DELTA: Duration = Duration::from_millis(100);
+/// Frames per second the GameLoop is capped at unless `GameLoop::fps` sets another.
+pub const DEFAULT_FPS: u64 = 60;
+
+#[derive(Debug, Clone)]
+pub struct WindowConfig {
+    pub width: u32,
+    pub height: u32,
+    pub title: String,
+    pub resizable: bool,
+    pub fullscreen: bool
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        Self {
+            width: 960,
+            height: 540,
+            title: "Game".to_string(),
+            resizable: true,
+            fullscreen: false
+        }
+    }
+}
+
+/// Asks the GameLoop to switch the primary window to fullscreen (`true`) or windowed (`false`)
+/// once the current update is done. Set by `SceneTransition::SET_FULLSCREEN`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FullscreenRequest(pub Option<bool>);
+
+/// Sent through an `EventQueue<WindowResized>` in the World when the primary window changes size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WindowResized {
+    pub width: u32,
+    pub height: u32
+}
+
+/// Key that toggles the primary window between windowed and fullscreen. F11 unless the World
+/// holds another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FullscreenKey(pub Key);
+
+impl Default for FullscreenKey {
+    fn default() -> Self {
+        Self(Key::F11)
+    }
+}
+
+pub fn is_fullscreen(context: &GL33Context) -> bool {
+    context.window.with_window_mode(|mode| matches!(mode, WindowMode::FullScreen(_)))
+}
+
+/// Fullscreen uses the primary monitor at its current video mode. Windowed uses the size in
+/// `windowed`, centered on the primary monitor.
+#[cfg_attr(feature = "trace", instrument(skip(context)))]
+pub fn set_fullscreen(context: &mut GL33Context, fullscreen: bool, windowed: &WindowConfig) -> Result<(), GameLoopError> {
+    let mut glfw = context.window.glfw.clone();
+    let window = &mut context.window;
+
+    glfw.with_primary_monitor_mut(|_, monitor| {
+        let (monitor, video_mode) = match monitor.and_then(|monitor| Some((monitor, monitor.get_video_mode()?))) {
+            Some(found) => found,
+            None => {
+                #[cfg(feature = "trace")]
+                error!("Failed to query the primary monitor's video mode");
+
+                return Err(FullscreenToggleFailed)
+            }
+        };
+
+        if fullscreen {
+            window.set_monitor(WindowMode::FullScreen(monitor), 0, 0, video_mode.width, video_mode.height, Some(video_mode.refresh_rate));
+        } else {
+            let x = video_mode.width.saturating_sub(windowed.width) / 2;
+            let y = video_mode.height.saturating_sub(windowed.height) / 2;
+
+            window.set_monitor(WindowMode::Windowed, x as i32, y as i32, windowed.width, windowed.height, None);
+        }
+
+        #[cfg(feature = "trace")]
+        debug!("Set fullscreen: {:?}", fullscreen);
+
+        Ok(())
+    })
+}
+
+/// Resizes the primary window. The back buffer is sized from the window's framebuffer each
+/// frame, so it follows.
+pub fn resize(context: &mut GL33Context, width: u32, height: u32) {
+    context.window.set_size(width as i32, height as i32);
+}
+
+pub fn toggle_fullscreen(context: &mut GL33Context, windowed: &WindowConfig) -> Result<(), GameLoopError> {
+    let fullscreen = !is_fullscreen(context);
+
+    set_fullscreen(context, fullscreen, windowed)
+}
+
+/// Size the window returns to when leaving fullscreen.
+fn windowed_config(ecs: &World) -> WindowConfig {
+    ecs.try_fetch::<WindowConfig>()
+        .map(|config| WindowConfig::clone(&config))
+        .unwrap_or_default()
+}
+
+/// Time step for an update at `now` when the last one was at `last_update`, capped at `max_delta`.
+fn frame_delta(last_update: Instant, now: Instant, max_delta: Duration) -> Duration {
+    now.duration_since(last_update).min(max_delta)
+}
+
+/// RenderAlpha for a draw at `now` in the frame that started at `frame_start`: the part of the
+/// frame period left over after the update.
+fn render_alpha(frame_start: Instant, now: Instant, frame_time: Duration) -> RenderAlpha {
+    let remaining = frame_time.saturating_sub(now.duration_since(frame_start));
+
+    RenderAlpha(remaining.as_secs_f32() / frame_time.as_secs_f32())
+}
 
 #[derive(Debug)]
 pub struct GameLoop<T: GameWrapper<U>, U: Input + Debug> {
     wrapper: PhantomData<T>,
-    input: PhantomData<U>
+    input: PhantomData<U>,
+    window_icon: Option<String>,
+    max_delta: Duration,
+    fps: u64,
+    num_threads: Option<usize>,
+    #[cfg(feature = "trace")]
+    log_config: Option<LogConfig>,
+    #[cfg(feature = "validate_manifest")]
+    asset_manifest: String,
+    #[cfg(all(feature = "dev_server", unix))]
+    hot_swap_socket: String
 }
 
 impl<T: GameWrapper<U>, U: Input + Debug + 'static> GameLoop<T,U> {
@@ -26,27 +195,147 @@ impl<T: GameWrapper<U>, U: Input + Debug + 'static> GameLoop<T,U> {
     pub fn new() -> Self {
         Self {
             wrapper: PhantomData,
-            input: PhantomData
+            input: PhantomData,
+            window_icon: None,
+            max_delta: DEFAULT_MAX_DELTA,
+            fps: DEFAULT_FPS,
+            num_threads: None,
+            #[cfg(feature = "trace")]
+            log_config: None,
+            #[cfg(feature = "validate_manifest")]
+            asset_manifest: DEFAULT_ASSET_MANIFEST.to_string(),
+            #[cfg(all(feature = "dev_server", unix))]
+            hot_swap_socket: DEFAULT_HOT_SWAP_SOCKET.to_string()
         }
     }
 
+    /// Sets the primary window's icon to the image at `path` once the window is created.
+    pub fn window_icon(mut self, path: impl Into<String>) -> Self {
+        self.window_icon = Some(path.into());
+
+        self
+    }
+
+    /// Caps the time since the last update that scenes are given. Defaults to `DEFAULT_MAX_DELTA`.
+    pub fn max_delta(mut self, max_delta: Duration) -> Self {
+        self.max_delta = max_delta;
+
+        self
+    }
+
+    /// Caps how many frames are run each second. Defaults to `DEFAULT_FPS`. Vsync can keep the
+    /// frame rate below the cap.
+    pub fn fps(mut self, fps: u64) -> Self {
+        self.fps = fps;
+
+        self
+    }
+
+    /// Number of threads in the pool specs systems and `Task::parallel` run on, and in the World's
+    /// `Pool`. Defaults to one per CPU core.
+    pub fn num_threads(mut self, num_threads: usize) -> Self {
+        self.num_threads = Some(num_threads);
+
+        self
+    }
+
+    /// Path of the UNIX socket hot swap commands are read from. Defaults to
+    /// `DEFAULT_HOT_SWAP_SOCKET`.
+    #[cfg(all(feature = "dev_server", unix))]
+    pub fn hot_swap_socket(mut self, path: impl Into<String>) -> Self {
+        self.hot_swap_socket = path.into();
+
+        self
+    }
+
+    /// Path of the AssetManifest checked at the start of `run`. Defaults to
+    /// `DEFAULT_ASSET_MANIFEST`.
+    #[cfg(feature = "validate_manifest")]
+    pub fn asset_manifest(mut self, path: impl Into<String>) -> Self {
+        self.asset_manifest = path.into();
+
+        self
+    }
+
+    /// Installs the config's subscriber at the start of `run`.
+    #[cfg(feature = "trace")]
+    pub fn log_config(mut self, config: LogConfig) -> Self {
+        self.log_config = Some(config);
+
+        self
+    }
+
+    /// Opens the primary window with `GameWrapper::window_config` and runs the game until it
+    /// finishes.
     #[cfg_attr(feature = "trace", instrument(skip(self)))]
-    pub fn run(self, options: WindowOpt, name: String) -> Result<(), GameLoopError> {
+    pub fn run(self) -> Result<(), GameLoopError> {
+        #[cfg(feature = "trace")]
+        if let Some(config) = &self.log_config {
+            config.init().map_err(|e| LogConfigError { source: e })?;
+        }
+
         #[cfg(feature = "trace")]
         debug!("ENTER: GameLoop::run");
 
-        let surface = GlfwSurface::new_gl33(name, options)
+        #[cfg(feature = "validate_manifest")]
+        if let Err(errors) = AssetManifest::validate(&self.asset_manifest) {
+            for error in errors.iter() {
+                eprintln!("ERROR: {}", display_chain(error));
+            }
+
+            return Err(InvalidAssetManifest {
+                path: self.asset_manifest.clone(),
+                error_count: errors.len()
+            })
+        }
+
+        if self.fps == 0 {
+            #[cfg(feature = "trace")]
+            error!("GameLoop fps must be greater than 0");
+
+            return Err(InvalidFps { fps: self.fps })
+        }
+
+        if let Some(num_threads) = self.num_threads {
+            if num_threads == 0 {
+                #[cfg(feature = "trace")]
+                error!("GameLoop num_threads must be at least 1");
+
+                return Err(InvalidThreadCount { num_threads })
+            }
+
+            ThreadPoolBuilder::new()
+                .num_threads(num_threads)
+                .build_global()
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to build the global thread pool with {:?} threads", num_threads);
+
+                    ThreadPoolError { source: e }
+                })?;
+        }
+
+        let window_config = T::window_config();
+
+        let mut surface = GlfwSurface::new_gl33(&window_config.title, WindowOpt::default())
             .map_err(|e| {
                 #[cfg(feature = "trace")]
                 error!("An error occurred while creating the GlfwSurface");
 
                 SurfaceCreationError { source: e }
             })?;
+        // The surface opens a default sized window, which is then resized or made fullscreen.
+        surface.context.window.set_resizable(window_config.resizable);
+        if window_config.fullscreen {
+            set_fullscreen(&mut surface.context, true, &window_config)?;
+        } else {
+            resize(&mut surface.context, window_config.width, window_config.height);
+        }
 
         #[cfg(feature = "trace")]
         debug!("GlfwSurface created. Calling main loop");
 
-        self.main_loop(surface)?;
+        self.main_loop(surface, window_config)?;
 
         #[cfg(feature = "trace")]
         debug!("EXIT: Returned from main loop. Exiting GameLoop::run");
@@ -55,7 +344,7 @@ impl<T: GameWrapper<U>, U: Input + Debug + 'static> GameLoop<T,U> {
     }
 
     #[cfg_attr(feature = "trace", instrument(skip(self, surface)))]
-    fn main_loop(&self, surface: GlfwSurface) -> Result<(), GameLoopError> {
+    fn main_loop(&self, surface: GlfwSurface, window_config: WindowConfig) -> Result<(), GameLoopError> {
         #[cfg(feature = "trace")]
         debug!("ENTER: GameLoop::main_loop");
 
@@ -63,6 +352,16 @@ impl<T: GameWrapper<U>, U: Input + Debug + 'static> GameLoop<T,U> {
         #[cfg(feature = "trace")]
         debug!("Context created");
 
+        if let Some(icon_path) = &self.window_icon {
+            set_window_icon(&context, icon_path)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to set window icon: {:?}", icon_path);
+
+                    WindowIconError { source: e }
+                })?;
+        }
+
         let events = surface.events_rx;
         #[cfg(feature = "trace")]
         debug!("Events receiver created from surface");
@@ -70,7 +369,7 @@ impl<T: GameWrapper<U>, U: Input + Debug + 'static> GameLoop<T,U> {
         // May need to call this inside the loop to get a new buffer ever frame.
         // let back_buffer = ctxt.back_buffer().expect("back buffer");
 
-        let mut input = U::new();
+        let input = U::new();
         #[cfg(feature = "trace")]
         debug!("Input Created: {:?}", input);
 
@@ -78,6 +377,97 @@ impl<T: GameWrapper<U>, U: Input + Debug + 'static> GameLoop<T,U> {
         #[cfg(feature = "trace")]
         debug!("World created");
 
+        ecs.write()
+            .map_err(|_e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to acquire write lock for World");
+
+                WorldWriteLockError
+            })?
+            .insert(WindowRegistry::new(context.clone()));
+        #[cfg(feature = "trace")]
+        debug!("WindowRegistry inserted into World");
+
+        ecs.write()
+            .map_err(|_e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to acquire write lock for World");
+
+                WorldWriteLockError
+            })?
+            .insert(DespawnQueue::default());
+        #[cfg(feature = "trace")]
+        debug!("DespawnQueue inserted into World");
+
+        let pool: Pool = ThreadPoolBuilder::new()
+            .num_threads(self.num_threads.unwrap_or(0))
+            .build()
+            .map(Arc::new)
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to build the World's thread pool with {:?} threads", self.num_threads);
+
+                ThreadPoolError { source: e }
+            })?;
+
+        ecs.write()
+            .map_err(|_e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to acquire write lock for World");
+
+                WorldWriteLockError
+            })?
+            .insert(pool);
+        #[cfg(feature = "trace")]
+        debug!("Pool inserted into World");
+
+        register_event::<WindowResized>(ecs.write()
+            .map_err(|_e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to acquire write lock for World");
+
+                WorldWriteLockError
+            })?
+            .deref_mut()
+        );
+
+        #[cfg(feature = "debug_overlay")]
+        register_event::<DebugOverlayToggled>(ecs.write()
+            .map_err(|_e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to acquire write lock for World");
+
+                WorldWriteLockError
+            })?
+            .deref_mut()
+        );
+
+        #[cfg(feature = "hot_reload")]
+        {
+            ecs.write()
+                .map_err(|_e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to acquire write lock for World");
+
+                    WorldWriteLockError
+                })?
+                .insert(HotReloadWatcher::new());
+            #[cfg(feature = "trace")]
+            debug!("HotReloadWatcher inserted into World");
+        }
+
+        // The window returns to this size when leaving fullscreen.
+        ecs.write()
+            .map_err(|_e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to acquire write lock for World");
+
+                WorldWriteLockError
+            })?
+            .insert(window_config);
+        #[cfg(feature = "trace")]
+        debug!("WindowConfig inserted into World");
+
         let mut game: Game<T,U> = Game::load(ecs.clone(), context.clone())
             .map_err(|e| {
                 #[cfg(feature = "trace")]
@@ -88,9 +478,69 @@ impl<T: GameWrapper<U>, U: Input + Debug + 'static> GameLoop<T,U> {
         #[cfg(feature = "trace")]
         debug!("Game loaded");
 
+        #[cfg(all(feature = "dev_server", unix))]
+        let hot_swap_queue = HotSwapQueue::default();
+        #[cfg(all(feature = "dev_server", unix))]
+        spawn_hot_swap_listener(&self.hot_swap_socket, hot_swap_queue.clone())
+            .map_err(|e| HotSwapListenerError { source: e })?;
+
         #[cfg(feature = "trace")]
         debug!("Setup complete. Entering game loop.");
+        let result = self.run_frames(&ecs, &context, &mut game, &events, input,
+            #[cfg(all(feature = "dev_server", unix))] &hot_swap_queue);
+
+        // The frames leading up to an error are the ones worth replaying.
+        #[cfg(feature = "trace_recording")]
+        if result.is_err() {
+            if let Ok(world) = ecs.read() {
+                flush_trace_recorder(&world);
+            }
+        }
+
+        result
+    }
+
+    /// Runs one iteration of the loop per frame until the game finishes or the primary window is
+    /// closed.
+    fn run_frames(
+        &self,
+        ecs: &Arc<RwLock<World>>,
+        context: &Arc<RwLock<GL33Context>>,
+        game: &mut Game<T,U>,
+        events: &Receiver<(f64, WindowEvent)>,
+        mut input: U,
+        #[cfg(all(feature = "dev_server", unix))] hot_swap_queue: &HotSwapQueue
+    ) -> Result<(), GameLoopError> {
+        let mut last_update = Instant::now();
+        let frame_time = Duration::from_nanos(1_000_000_000 / self.fps);
+
         loop {
+            let frame_start = Instant::now();
+            // Time since the previous frame started, which is one frame period at the fps cap.
+            let delta = frame_delta(last_update, frame_start, self.max_delta);
+            last_update = frame_start;
+
+            EventClearers::run(&*ecs.read()
+                .map_err(|_e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to acquire read lock for World");
+
+                    WorldReadLockError
+                })?
+            );
+
+            // Reload files watched with `hot_reload::watch` that changed since the last frame.
+            #[cfg(feature = "hot_reload")]
+            hot_reload::poll(&ecs)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to poll the HotReloadWatcher: {:?}", e);
+
+                    HotReloadPollError { source: e }
+                })?;
+
+            input.poll();
+
             // handle events
             context.write()
                 .map_err(|_e| {
@@ -103,9 +553,101 @@ impl<T: GameWrapper<U>, U: Input + Debug + 'static> GameLoop<T,U> {
                 .glfw
                 .poll_events();
             #[cfg(feature = "trace")]
-            debug!("Polled events from context. Now processing them.");
+            debug!("Polled events from context. Processing events from additional windows.");
+
+            let window_events = ecs.read()
+                .map_err(|_e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to acquire read lock for World");
+
+                    WorldReadLockError
+                })?
+                .fetch::<WindowRegistry>()
+                .flush_events()
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to flush events from additional windows: {:?}", e);
+
+                    WindowRegistryError { source: e }
+                })?;
+
+            for (name, event) in window_events {
+                match event {
+                    WindowEvent::Close => {
+                        #[cfg(feature = "trace")]
+                        debug!("Closing window: {:?}", name);
+
+                        ecs.write()
+                            .map_err(|_e| {
+                                #[cfg(feature = "trace")]
+                                error!("Failed to acquire write lock for World");
+
+                                WorldWriteLockError
+                            })?
+                            .fetch_mut::<WindowRegistry>()
+                            .remove_window(&name);
+                    },
+                    WindowEvent::Key(..)
+                    | WindowEvent::Focus(_)
+                    | WindowEvent::CursorEnter(..)
+                    | WindowEvent::MouseButton(..)
+                    | WindowEvent::CursorPos(..)
+                    | WindowEvent::Scroll(..)
+                    | WindowEvent::CharModifiers(..)
+                    | WindowEvent::Char(_) => {
+                        #[cfg(feature = "trace")]
+                        debug!("Processing input event from window: {:?}", name);
+
+                        input.update(event);
+                        game.interact(ecs
+                          .write()
+                          .map_err(|_e| {
+                              #[cfg(feature = "trace")]
+                              error!("Failed to acquire the write lock for World");
+
+                              WorldWriteLockError
+                          })?
+                          .deref_mut(), &input
+                        ).map_err(|e| {
+                            #[cfg(feature = "trace")]
+                            error!("Error occurred while running Game::interact: {:?}", e);
+
+                            GameInteractError { source: e }
+                        })?;
+                    },
+                    _ => ()
+                }
+            }
+
+            #[cfg(feature = "trace")]
+            debug!("Processing events from primary window.");
+
+            let (fullscreen_key, windowed) = {
+                let world = ecs.read()
+                    .map_err(|_e| {
+                        #[cfg(feature = "trace")]
+                        error!("Failed to acquire read lock for World");
+
+                        WorldReadLockError
+                    })?;
+
+                (world.try_fetch::<FullscreenKey>().map(|key| *key).unwrap_or_default().0, windowed_config(&world))
+            };
+
+            #[cfg(feature = "debug_overlay")]
+            let debug_overlay_key = ecs.read()
+                .map_err(|_e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to acquire read lock for World");
+
+                    WorldReadLockError
+                })?
+                .try_fetch::<DebugOverlayKey>()
+                .map(|key| *key)
+                .unwrap_or_default()
+                .0;
 
-            for (_thing, event) in glfw::flush_messages(&events) {
+            for (_thing, event) in glfw::flush_messages(events) {
                 #[cfg(feature = "trace")]
                 debug!("Processing event: {:?}", event);
 
@@ -118,6 +660,60 @@ impl<T: GameWrapper<U>, U: Input + Debug + 'static> GameLoop<T,U> {
 
                         return Ok(())
                     },
+                    WindowEvent::Size(width, height) => {
+                        #[cfg(feature = "trace")]
+                        debug!("Primary window resized to: {:?}x{:?}", width, height);
+
+                        let world = ecs.write()
+                            .map_err(|_e| {
+                                #[cfg(feature = "trace")]
+                                error!("Failed to acquire write lock for World");
+
+                                WorldWriteLockError
+                            })?;
+
+                        // A minimized window has a height of 0.
+                        if height > 0 {
+                            if let Some(mut camera) = world.try_fetch_mut::<Option<Box<dyn Camera>>>() {
+                                if let Some(camera) = camera.as_mut() {
+                                    camera.set_aspect_ratio(width as f32 / height as f32);
+                                }
+                            }
+                        }
+
+                        world.fetch_mut::<EventQueue<WindowResized>>()
+                            .push(WindowResized {
+                                width: width as u32,
+                                height: height as u32
+                            });
+                    },
+                    #[cfg(feature = "debug_overlay")]
+                    WindowEvent::Key(key, _, Action::Press, _) if key == debug_overlay_key => {
+                        #[cfg(feature = "trace")]
+                        debug!("Debug overlay key pressed");
+
+                        ecs.write()
+                            .map_err(|_e| {
+                                #[cfg(feature = "trace")]
+                                error!("Failed to acquire write lock for World");
+
+                                WorldWriteLockError
+                            })?
+                            .fetch_mut::<EventQueue<DebugOverlayToggled>>()
+                            .push(DebugOverlayToggled);
+                    },
+                    WindowEvent::Key(key, _, Action::Press, _) if key == fullscreen_key => {
+                        toggle_fullscreen(context
+                            .write()
+                            .map_err(|_e| {
+                                #[cfg(feature = "trace")]
+                                error!("Failed to acquire write lock for context");
+
+                                ContextWriteLockError
+                            })?
+                            .deref_mut(), &windowed
+                        )?;
+                    },
                     WindowEvent::Key(..)
                     | WindowEvent::Focus(_)
                     | WindowEvent::CursorEnter(..)
@@ -148,56 +744,127 @@ impl<T: GameWrapper<U>, U: Input + Debug + 'static> GameLoop<T,U> {
                     },
                     _ => ()
                 }
+            }
 
+            #[cfg(feature = "trace")]
+            debug!("Events processed. Now updating game");
+
+            #[cfg(all(feature = "dev_server", unix))]
+            for command in hot_swap_queue.drain() {
+                game.hot_swap(&command, ecs.clone(), context.clone());
+            }
+
+            // Update
+            game.update(ecs
+                .write()
+                .map_err(|_e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to acquire write lock for World");
+
+                    WorldWriteLockError
+                })?
+                .deref_mut(), delta
+            ).map_err(|e| {
                 #[cfg(feature = "trace")]
-                debug!("Events processed. Now updating game");
+                error!("Error occurred while running Game::update");
 
-                // Update
-                game.update(ecs
+                GameUpdateError { source: e }
+            })?;
+
+            let fullscreen_request = ecs.write()
+                .map_err(|_e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to acquire write lock for World");
+
+                    WorldWriteLockError
+                })?
+                .entry::<FullscreenRequest>()
+                .or_insert_with(FullscreenRequest::default)
+                .0
+                .take();
+
+            if let Some(fullscreen) = fullscreen_request {
+                set_fullscreen(context
                     .write()
                     .map_err(|_e| {
                         #[cfg(feature = "trace")]
-                        error!("Failed to acquire write lock for World");
+                        error!("Failed to acquire write lock for context");
 
-                        WorldWriteLockError
+                        ContextWriteLockError
                     })?
-                    .deref_mut()
-                ).map_err(|e| {
+                    .deref_mut(), fullscreen, &windowed
+                )?;
+            }
+
+            // Loaded outside of the update so the loader can lock the World.
+            game.load_preload(ecs.clone(), context.clone())
+                .map_err(|e| {
                     #[cfg(feature = "trace")]
-                    error!("Error occurred while running Game::update");
+                    error!("Error occurred while loading the preloaded scene");
 
                     GameUpdateError { source: e }
                 })?;
 
-                #[cfg(feature = "trace")]
-                debug!("Game updated. Now calling Game::draw");
-
-                // Draw
-                game.draw(ecs
-                              .write()
-                              .map_err(|_e| {
-                                  #[cfg(feature = "trace")]
-                                  error!("Failed to acquire write lock for World");
-
-                                  WorldWriteLockError
-                              })?
-                              .deref_mut(),
-                          context
-                              .write()
-                              .map_err(|_e| {
-                                  #[cfg(feature = "trace")]
-                                  error!("Failed to acquire write lock for Context");
-
-                                  ContextWriteLockError
-                              })?
-                              .deref_mut()
-                ).map_err(|e| {
+            DespawnSystem::run(ecs.clone())
+                .map_err(|e| {
                     #[cfg(feature = "trace")]
-                    error!("Error occurred while running Game::draw");
+                    error!("Failed to despawn queued entities: {:?}", e);
 
-                    GameDrawError { source: e }
+                    DespawnError { source: e }
                 })?;
 
+            #[cfg(feature = "trace")]
+            debug!("Game updated. Now calling Game::draw");
+
+            // Draw
+            let draw_start = Instant::now();
+            ecs.write()
+                .map_err(|_e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to acquire write lock for World");
+
+                    WorldWriteLockError
+                })?
+                .insert(render_alpha(frame_start, draw_start, frame_time));
+
+            game.draw(ecs
+                          .write()
+                          .map_err(|_e| {
+                              #[cfg(feature = "trace")]
+                              error!("Failed to acquire write lock for World");
+
+                              WorldWriteLockError
+                          })?
+                          .deref_mut(),
+                      context
+                          .write()
+                          .map_err(|_e| {
+                              #[cfg(feature = "trace")]
+                              error!("Failed to acquire write lock for Context");
+
+                              ContextWriteLockError
+                          })?
+                          .deref_mut()
+            ).map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Error occurred while running Game::draw");
+
+                GameDrawError { source: e }
+            })?;
+
+            DynamicResolutionSystem::run(ecs
+                .write()
+                .map_err(|_e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to acquire write lock for World");
+
+                    WorldWriteLockError
+                })?
+                .deref_mut(), draw_start.elapsed()
+            );
+
+            // A render graph with a swap_buffers node has already put the frame on screen.
+            if !game.swaps_buffers() {
                 #[cfg(feature = "trace")]
                 debug!("Rendering complete. Swapping buffers to put new graphics on screen.");
 
@@ -210,27 +877,32 @@ impl<T: GameWrapper<U>, U: Input + Debug + 'static> GameLoop<T,U> {
                     })?
                     .window
                     .swap_buffers();
+            }
 
-                // Exit if finished
-                if game.is_finished(ecs.write()
-                    .map_err(|_e| {
-                        #[cfg(feature = "trace")]
-                        error!("Failed to acquire write lock for World");
-
-                        WorldWriteLockError
-                    })?
-                    .deref_mut())
-                {
+            // Exit if finished
+            if game.is_finished(ecs.write()
+                .map_err(|_e| {
                     #[cfg(feature = "trace")]
-                    debug!("EXIT: GameLoop::main_loop. Game returned that it has finished. Ending game loop.");
-
-                    return Ok(())
-                }
+                    error!("Failed to acquire write lock for World");
 
-                // Clear the input before next frame
-                input.clear();
+                    WorldWriteLockError
+                })?
+                .deref_mut())
+            {
                 #[cfg(feature = "trace")]
-                debug!("Input cleared. Completed iteration of loop.")
+                debug!("EXIT: GameLoop::run_frames. Game returned that it has finished. Ending game loop.");
+
+                return Ok(())
+            }
+
+            // Clear the input before next frame
+            input.clear();
+            #[cfg(feature = "trace")]
+            debug!("Input cleared. Completed iteration of loop.");
+
+            // Wait out the rest of the frame so the loop runs at most `fps` times a second.
+            if let Some(remaining) = frame_time.checked_sub(frame_start.elapsed()) {
+                sleep(remaining);
             }
         }
     }
@@ -257,5 +929,77 @@ pub enum GameLoopError {
     #[error("Error occurred while running Game::draw")]
     GameDrawError { source: GameError },
     #[error("Error occurred while running Game::is_finished")]
-    GameIsFinishedError { source: GameError }
-}
\ No newline at end of file
+    GameIsFinishedError { source: GameError },
+    #[error("Error occurred while handling additional windows")]
+    WindowRegistryError { source: WindowError },
+    #[error("Error occurred while despawning queued entities")]
+    DespawnError { source: EntityError },
+    #[error("Failed to set the window icon")]
+    WindowIconError { source: IconError },
+    #[error("Failed to switch fullscreen because the primary monitor could not be queried")]
+    FullscreenToggleFailed,
+    #[error("GameLoop fps: {fps} must be greater than 0")]
+    InvalidFps { fps: u64 },
+    #[error("GameLoop num_threads: {num_threads} must be at least 1")]
+    InvalidThreadCount { num_threads: usize },
+    #[error("Failed to build the global thread pool")]
+    ThreadPoolError { source: ThreadPoolBuildError },
+    #[cfg(all(feature = "dev_server", unix))]
+    #[error("Failed to start listening for hot swap commands")]
+    HotSwapListenerError { source: HotSwapError },
+    #[cfg(feature = "validate_manifest")]
+    #[error("Asset manifest: {path} has {error_count} errors")]
+    InvalidAssetManifest { path: String, error_count: usize },
+    #[cfg(feature = "hot_reload")]
+    #[error("Failed to poll watched files for changes")]
+    HotReloadPollError { source: HotReloadError },
+    #[cfg(feature = "trace")]
+    #[error("Failed to apply the log config")]
+    LogConfigError { source: LogInitError }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread::sleep;
+
+    use super::*;
+
+    fn frame_period() -> Duration {
+        Duration::from_secs(1) / DEFAULT_FPS as u32
+    }
+
+    #[test]
+    fn render_alpha_is_the_part_of_the_frame_left_after_the_update() {
+        let frame_start = Instant::now();
+        let frame_time = Duration::from_secs(1);
+
+        assert_eq!(render_alpha(frame_start, frame_start, frame_time).0, 1.0);
+        assert_eq!(render_alpha(frame_start, frame_start + frame_time / 4, frame_time).0, 0.75);
+        assert_eq!(render_alpha(frame_start, frame_start + frame_time * 2, frame_time).0, 0.0);
+    }
+
+    #[test]
+    fn delta_is_the_time_since_the_last_update() {
+        let last_update = Instant::now();
+
+        assert_eq!(frame_delta(last_update, last_update + frame_period(), DEFAULT_MAX_DELTA), frame_period());
+    }
+
+    #[test]
+    fn delta_is_about_one_frame_at_the_target_fps() {
+        let last_update = Instant::now();
+        sleep(frame_period());
+
+        let delta = frame_delta(last_update, Instant::now(), DEFAULT_MAX_DELTA);
+
+        assert!(delta >= frame_period());
+        assert!(delta < frame_period() * 3);
+    }
+
+    #[test]
+    fn delta_is_capped_at_max_delta_after_a_hitch() {
+        let last_update = Instant::now();
+
+        assert_eq!(frame_delta(last_update, last_update + Duration::from_secs(2), DEFAULT_MAX_DELTA), DEFAULT_MAX_DELTA);
+    }
+}
@@ -0,0 +1,116 @@
+#[cfg(feature="trace")]
+use tracing::{instrument, error, debug};
+
+use std::fs::write;
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use thiserror::Error;
+
+use crate::globals::accessibility::AccessibilityError::{ConfigLoadError, SerializeError, FileWriteError};
+use crate::load::{load_deserializable_from_file, LoadError};
+
+pub const ACCESSIBILITY_CONFIG_LOAD_ID: &str = "accessibility_config";
+
+pub const HIGH_CONTRAST_TEXT_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+pub const HIGH_CONTRAST_CLEAR_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+
+/// Player accessibility preferences. Text renderers multiply text sizes by `font_scale` and, when
+/// `high_contrast` is set, draw all text in white.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct AccessibilityConfig {
+    pub font_scale: f32,
+    pub high_contrast: bool
+}
+
+impl Default for AccessibilityConfig {
+    fn default() -> Self {
+        Self {
+            font_scale: 1.0,
+            high_contrast: false
+        }
+    }
+}
+
+impl AccessibilityConfig {
+    pub fn text_color(&self, color: [f32; 4]) -> [f32; 4] {
+        if self.high_contrast {
+            HIGH_CONTRAST_TEXT_COLOR
+        } else {
+            color
+        }
+    }
+
+    /// Scenes pass the color they would normally clear the back buffer with.
+    pub fn clear_color(&self, color: [f32; 4]) -> [f32; 4] {
+        if self.high_contrast {
+            HIGH_CONTRAST_CLEAR_COLOR
+        } else {
+            color
+        }
+    }
+
+    #[cfg_attr(feature="trace", instrument)]
+    pub fn load_from_file(file_path: &str) -> Result<Self, AccessibilityError> {
+        load_deserializable_from_file(file_path, ACCESSIBILITY_CONFIG_LOAD_ID)
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to load AccessibilityConfig from file: {:?}", file_path);
+
+                ConfigLoadError {
+                    path: file_path.to_string(),
+                    source: e
+                }
+            })
+    }
+
+    #[cfg_attr(feature="trace", instrument)]
+    pub fn save_to_file(&self, file_path: &str) -> Result<(), AccessibilityError> {
+        let json = serde_json::to_string_pretty(&json!({
+            "load_type_id": ACCESSIBILITY_CONFIG_LOAD_ID,
+            "actual_value": self
+        })).map_err(|e| {
+            #[cfg(feature = "trace")]
+            error!("Failed to serialize AccessibilityConfig: {:?}", self);
+
+            SerializeError { source: e }
+        })?;
+
+        write(file_path, json)
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to write AccessibilityConfig to file: {:?}", file_path);
+
+                FileWriteError {
+                    path: file_path.to_string(),
+                    source: e
+                }
+            })?;
+
+        #[cfg(feature = "trace")]
+        debug!("Saved AccessibilityConfig to file: {:?}", file_path);
+
+        Ok(())
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum AccessibilityError {
+    #[error("Failed to load AccessibilityConfig from file: {path}")]
+    ConfigLoadError {
+        path: String,
+        source: anyhow::Error
+    },
+
+    #[error("Failed to serialize AccessibilityConfig")]
+    SerializeError {
+        source: serde_json::Error
+    },
+
+    #[error("Failed to write AccessibilityConfig to file: {path}")]
+    FileWriteError {
+        path: String,
+        source: std::io::Error
+    }
+}
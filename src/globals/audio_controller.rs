@@ -1,23 +1,24 @@
 #[cfg(feature="trace")]
-use tracing::{instrument, trace, error};
+use tracing::{instrument, debug, error};
 
 use std::collections::HashMap;
-use std::io::ErrorKind;
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, RwLock, RwLockWriteGuard};
 
 use kira::sound::SoundId;
 use kira::manager::{AudioManager, AudioManagerSettings};
 use kira::AudioError;
+use kira::instance::{InstanceId, InstanceSettings, StopInstanceSettings};
 use kira::playable::PlayableSettings;
 
-use coffee::load::Task;
-
 use serde::Deserialize;
 
 use thiserror::Error;
 
-use crate::load::{load_deserializable_from_file, LoadError};
+use crate::load::load_deserializable_from_file;
+use crate::loading::GenTask;
+use crate::globals::audio_playlist::{Playlist, PlaylistEntry};
 use crate::globals::audio_controller::AudioControllerError::{FileLoadError, ManagerError, LoadSoundError};
+use crate::globals::audio_controller::AudioControllerPlaybackError::{ManagerWriteLockError, UnknownSoundError, PlayError, StopError, VolumeError};
 
 pub const AUDIO_CONTROLLER_LOAD_ID: &str = "audio_controller";
 pub const AUDIO_DIR: &str = "audio/";
@@ -25,6 +26,7 @@ pub const AUDIO_DIR: &str = "audio/";
 #[derive(Default, Debug)]
 pub struct AudioDict(pub HashMap<String, SoundId>);
 
+/// The sounds the game has loaded, by name, and the AudioManager that plays them.
 pub struct AudioController {
     pub audio_lib: AudioDict,
     pub audio_manager: Arc<RwLock<AudioManager>>
@@ -35,14 +37,106 @@ unsafe impl Sync for AudioController {}
 
 impl Default for AudioController {
     fn default() -> Self {
-        return AudioController {
+        AudioController {
             audio_lib: AudioDict(HashMap::new()),
-            audio_manager: Arc::new(RwLock::new(AudioManager::new(AudioManagerSettings::default())
-                .expect("Failed to create default AudioManager with default settings")))
+            audio_manager: share_manager(AudioManager::new(AudioManagerSettings::default())
+                .expect("Failed to create default AudioManager with default settings"))
         }
     }
 }
 
+// AudioManager isn't Send or Sync, which AudioController asserts for it so it can be a resource.
+#[allow(clippy::arc_with_non_send_sync)]
+fn share_manager(audio_manager: AudioManager) -> Arc<RwLock<AudioManager>> {
+    Arc::new(RwLock::new(audio_manager))
+}
+
+impl AudioController {
+    /// Plays the sound named `name` once.
+    #[cfg_attr(feature="trace", instrument(skip(self)))]
+    pub fn play(&mut self, name: &str) -> Result<InstanceId, AudioControllerPlaybackError> {
+        self.play_with_settings(name, InstanceSettings::new())
+    }
+
+    /// Plays the sound named `name` from the start again every time it ends, until it is stopped.
+    #[cfg_attr(feature="trace", instrument(skip(self)))]
+    pub fn play_looping(&mut self, name: &str) -> Result<InstanceId, AudioControllerPlaybackError> {
+        self.play_with_settings(name, InstanceSettings::new().loop_start(0.0))
+    }
+
+    #[cfg_attr(feature="trace", instrument(skip(self)))]
+    pub fn stop(&mut self, id: InstanceId) -> Result<(), AudioControllerPlaybackError> {
+        self.write_manager()?
+            .stop_instance(id, StopInstanceSettings::new())
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to stop sound instance: {:?}", id);
+
+                StopError { source: e }
+            })
+    }
+
+    /// Creates a Playlist of sounds from this controller. Insert it into the World for the
+    /// PlaylistSystem to play it.
+    #[cfg_attr(feature="trace", instrument(skip(self)))]
+    pub fn create_playlist(&self, entries: Vec<PlaylistEntry>, repeat: bool) -> Playlist {
+        Playlist::new(entries, repeat)
+    }
+
+    /// Sets the volume of a playing instance. 1.0 is the sound's own volume.
+    #[cfg_attr(feature="trace", instrument(skip(self)))]
+    pub fn set_volume(&mut self, id: InstanceId, volume: f64) -> Result<(), AudioControllerPlaybackError> {
+        self.write_manager()?
+            .set_instance_volume(id, volume)
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to set volume of sound instance: {:?} to: {:?}", id, volume);
+
+                VolumeError {
+                    volume,
+                    source: e
+                }
+            })
+    }
+
+    fn play_with_settings(&mut self, name: &str, settings: InstanceSettings) -> Result<InstanceId, AudioControllerPlaybackError> {
+        let sound_id = *self.audio_lib.0.get(name)
+            .ok_or_else(|| {
+                #[cfg(feature = "trace")]
+                error!("No sound named: {:?} has been loaded", name);
+
+                UnknownSoundError { sound_name: name.to_string() }
+            })?;
+
+        let instance = self.write_manager()?
+            .play(sound_id, settings)
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to play sound: {:?}", name);
+
+                PlayError {
+                    sound_name: name.to_string(),
+                    source: e
+                }
+            })?;
+
+        #[cfg(feature = "trace")]
+        debug!("Playing sound: {:?}", name);
+
+        Ok(instance)
+    }
+
+    fn write_manager(&self) -> Result<RwLockWriteGuard<'_, AudioManager>, AudioControllerPlaybackError> {
+        self.audio_manager.write()
+            .map_err(|_e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to acquire write lock for AudioManager");
+
+                ManagerWriteLockError
+            })
+    }
+}
+
 #[derive(Deserialize, Debug)]
 pub struct AudioControllerLoader {
     path: String
@@ -56,69 +150,63 @@ struct AudioControllerJSON {
 impl AudioControllerLoader {
     #[cfg_attr(feature="trace", instrument)]
     pub fn new(file_path: String) -> Self {
-        #[cfg(feature="trace")]
-        trace!("ENTER: AudioControllerLoader::new");
-        let new = Self {
+        Self {
             path: file_path
-        };
-        #[cfg(feature="trace")]
-        trace!("EXIT: AudioControllerLoader::new");
-        return new
+        }
     }
 
-    #[cfg_attr(feature="trace", instrument(skip(self, _ecs, _window)))]
-    pub fn load(self, settings: AudioManagerSettings) -> Task<AudioController> {
-        #[cfg(feature="trace")]
-        trace!("ENTER: AudioControllerLoader::load");
-        Task::new(|| {
-            let audio_controller_json: AudioControllerJSON =
-                load_deserializable_from_file(self.path.as_str(), AUDIO_CONTROLLER_LOAD_ID)
-                    .map_err(|e| {
-                        let error: coffee::Error = FileLoadError {
-                            path: self.path,
-                            var_name: stringify!(self.path).to_string(),
-                            source: e
-                        }.into();
+    /// Creates an AudioManager with `settings` and loads every sound in the file into it.
+    #[cfg_attr(feature="trace", instrument)]
+    pub fn load(self, settings: AudioManagerSettings) -> GenTask<AudioController> {
+        let path = self.path;
 
-                        return error
-                    })?;
+        GenTask::new(move |_ecs| {
+            let audio_controller_json: AudioControllerJSON = load_deserializable_from_file(&path, AUDIO_CONTROLLER_LOAD_ID)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to deserialize file: ({:?}) into AudioController JSON value", path.clone());
 
-            #[cfg(feature="trace")]
-            trace!("AudioControllerJSON: {:#?} successfully loaded from: {:#?}", audio_controller_json, self.path);
+                    FileLoadError {
+                        path: path.clone(),
+                        source: e
+                    }
+                })?;
 
             let mut audio_manager = AudioManager::new(settings.clone())
                 .map_err(|e| {
-                    let error: coffee::Error = ManagerError {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to create AudioManager with settings: {:?}", settings.clone());
+
+                    ManagerError {
                         settings,
                         source: e
-                    }.into();
-
-                    return error
+                    }
                 })?;
             let mut audio_dict = HashMap::new();
 
             for (audio_name, audio_path) in audio_controller_json.sounds {
-                #[cfg(feature="trace")]
-                trace!("Adding {:#?} at {:#?} to AudioDict", audio_name.clone(), audio_path.clone());
+                #[cfg(feature = "trace")]
+                debug!("Adding {:?} at {:?} to AudioDict", audio_name.clone(), audio_path.clone());
+
                 let audio = audio_manager.load_sound(audio_path.clone(), PlayableSettings::new())
                     .map_err(|e| {
-                        let error: coffee::Error = LoadSoundError {
+                        #[cfg(feature = "trace")]
+                        error!("Failed to load sound: {:?} from: {:?}", audio_name.clone(), audio_path.clone());
+
+                        LoadSoundError {
                             sound_name: audio_name.clone(),
                             sound_path: audio_path,
-                            settings: PlayableSettings::new()
-                        }.into();
-
-                        return error
+                            settings: PlayableSettings::new(),
+                            source: e
+                        }
                     })?;
 
                 audio_dict.insert(audio_name, audio);
             }
 
-            #[cfg(feature="trace")]
-            trace!("EXIT: AudioControllerLoader::load");
-            return Ok(AudioController {
+            Ok(AudioController {
                 audio_lib: AudioDict(audio_dict),
-                audio_manager: Arc::new(RwLock::new(audio_manager))
+                audio_manager: share_manager(audio_manager)
             })
         })
     }
@@ -126,11 +214,10 @@ impl AudioControllerLoader {
 
 #[derive(Error, Debug)]
 pub enum AudioControllerError {
-    #[error("Error loading JSON Value for AudioControllerLoader from: {var_name} = {path}")]
+    #[error("Error loading JSON Value for AudioControllerLoader from: {path}")]
     FileLoadError {
         path: String,
-        var_name: String,
-        source: LoadError
+        source: anyhow::Error
     },
     #[error("Error creating AudioManager with settings: {settings:#?}")]
     ManagerError {
@@ -141,17 +228,35 @@ pub enum AudioControllerError {
     LoadSoundError {
         sound_name: String,
         sound_path: String,
-        settings: PlayableSettings
+        settings: PlayableSettings,
+        source: AudioError
     }
 }
 
-impl Into<coffee::Error> for AudioControllerError {
-    fn into(self) -> coffee::Error {
-        coffee::Error::IO(
-            std::io::Error::new(
-                ErrorKind::InvalidData,
-                format!("{:#?}", self)
-            )
-        )
+#[derive(Error, Debug)]
+pub enum AudioControllerPlaybackError {
+    #[error("Failed to acquire write lock for AudioManager")]
+    ManagerWriteLockError,
+
+    #[error("No sound named: {sound_name} has been loaded")]
+    UnknownSoundError {
+        sound_name: String
+    },
+
+    #[error("Failed to play sound: {sound_name}")]
+    PlayError {
+        sound_name: String,
+        source: AudioError
+    },
+
+    #[error("Failed to stop sound instance")]
+    StopError {
+        source: AudioError
+    },
+
+    #[error("Failed to set volume of sound instance to: {volume}")]
+    VolumeError {
+        volume: f64,
+        source: AudioError
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,246 @@
+#[cfg(feature="trace")]
+use tracing::{instrument, error, debug};
+
+use std::collections::HashMap;
+
+use kira::AudioError;
+use kira::instance::{InstanceId, InstanceSettings, StopInstanceSettings};
+use kira::manager::AudioManager;
+use kira::sound::SoundId;
+
+use serde::Deserialize;
+
+use thiserror::Error;
+
+use crate::globals::audio_mixer::AudioMixerError::{MixerFileLoadError, UnknownLayerError, UnknownSoundError, PlayError, VolumeError, StopError};
+use crate::load::{load_deserializable_from_file, LoadError};
+use crate::loading::GenTask;
+
+pub const AUDIO_MIXER_LOAD_ID: &str = "audio_mixer";
+
+pub const MUSIC_LAYER: &str = "music";
+pub const SFX_LAYER: &str = "sfx";
+pub const AMBIENT_LAYER: &str = "ambient";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioLayer {
+    pub volume: f32,
+    /// Instances played on the layer since it was last stopped. Some may have already finished.
+    pub active_instances: Vec<InstanceId>
+}
+
+impl AudioLayer {
+    pub fn new(volume: f32) -> Self {
+        Self {
+            volume,
+            active_instances: Vec::new()
+        }
+    }
+}
+
+/// Groups sounds into named layers, such as music and sound effects, whose volumes are set
+/// independently. The `music`, `sfx` and `ambient` layers always exist at full volume unless
+/// the mixer file changes them.
+#[derive(Debug, Clone)]
+pub struct AudioMixer {
+    pub layers: HashMap<String, AudioLayer>
+}
+
+impl Default for AudioMixer {
+    fn default() -> Self {
+        let layers = [MUSIC_LAYER, SFX_LAYER, AMBIENT_LAYER].iter()
+            .map(|name| (name.to_string(), AudioLayer::new(1.0)))
+            .collect();
+
+        Self { layers }
+    }
+}
+
+impl AudioMixer {
+    /// Plays the sound named `sound` at the layer's volume.
+    #[cfg_attr(feature="trace", instrument(skip(self, manager, sounds)))]
+    pub fn play_on_layer(&mut self, layer: &str, sound: &str, manager: &mut AudioManager, sounds: &HashMap<String, SoundId>) -> Result<InstanceId, AudioMixerError> {
+        let audio_layer = self.layers.get_mut(layer)
+            .ok_or_else(|| {
+                #[cfg(feature = "trace")]
+                error!("No audio layer named: {:?}", layer);
+
+                UnknownLayerError { layer: layer.to_string() }
+            })?;
+
+        let sound_id = *sounds.get(sound)
+            .ok_or_else(|| {
+                #[cfg(feature = "trace")]
+                error!("No sound named: {:?} has been loaded", sound);
+
+                UnknownSoundError { sound_name: sound.to_string() }
+            })?;
+
+        let instance = manager.play(sound_id, InstanceSettings::new().volume(audio_layer.volume as f64))
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to play sound: {:?} on layer: {:?}", sound, layer);
+
+                PlayError {
+                    sound_name: sound.to_string(),
+                    source: e
+                }
+            })?;
+
+        #[cfg(feature = "trace")]
+        debug!("Playing sound: {:?} on layer: {:?}", sound, layer);
+
+        audio_layer.active_instances.push(instance);
+
+        Ok(instance)
+    }
+
+    /// Changes the volume of the layer and of every instance playing on it.
+    #[cfg_attr(feature="trace", instrument(skip(self, manager)))]
+    pub fn set_layer_volume(&mut self, layer: &str, volume: f32, manager: &mut AudioManager) -> Result<(), AudioMixerError> {
+        let audio_layer = self.layers.get_mut(layer)
+            .ok_or_else(|| {
+                #[cfg(feature = "trace")]
+                error!("No audio layer named: {:?}", layer);
+
+                UnknownLayerError { layer: layer.to_string() }
+            })?;
+
+        audio_layer.volume = volume;
+
+        for instance in audio_layer.active_instances.iter() {
+            manager.set_instance_volume(*instance, volume as f64)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to set volume of layer: {:?}", layer);
+
+                    VolumeError {
+                        layer: layer.to_string(),
+                        source: e
+                    }
+                })?;
+        }
+
+        Ok(())
+    }
+
+    /// Stops every instance playing on the layer and stops tracking them.
+    #[cfg_attr(feature="trace", instrument(skip(self, manager)))]
+    pub fn stop_layer(&mut self, layer: &str, manager: &mut AudioManager) -> Result<(), AudioMixerError> {
+        let audio_layer = self.layers.get_mut(layer)
+            .ok_or_else(|| {
+                #[cfg(feature = "trace")]
+                error!("No audio layer named: {:?}", layer);
+
+                UnknownLayerError { layer: layer.to_string() }
+            })?;
+
+        for instance in audio_layer.active_instances.drain(..) {
+            manager.stop_instance(instance, StopInstanceSettings::new())
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to stop layer: {:?}", layer);
+
+                    StopError {
+                        layer: layer.to_string(),
+                        source: e
+                    }
+                })?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct AudioLayerJSON {
+    name: String,
+    #[serde(default = "default_volume")]
+    volume: f32
+}
+
+fn default_volume() -> f32 {
+    1.0
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct AudioMixerJSON {
+    layers: Vec<AudioLayerJSON>
+}
+
+#[derive(Debug, Clone)]
+pub struct AudioMixerLoader {
+    path: String
+}
+
+impl AudioMixerLoader {
+    #[cfg_attr(feature="trace", instrument)]
+    pub fn new(file_path: String) -> Self {
+        Self {
+            path: file_path
+        }
+    }
+
+    /// Layers in the file are added to the default layers, replacing any with the same name.
+    #[cfg_attr(feature="trace", instrument)]
+    pub fn load(self) -> GenTask<AudioMixer> {
+        let path = self.path;
+
+        GenTask::new(move |_ecs| {
+            let json: AudioMixerJSON = load_deserializable_from_file(&path, AUDIO_MIXER_LOAD_ID)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to deserialize file: ({:?}) into AudioMixer JSON value", path.clone());
+
+                    MixerFileLoadError {
+                        path: path.clone(),
+                        source: e
+                    }
+                })?;
+
+            let mut mixer = AudioMixer::default();
+            for layer in json.layers {
+                mixer.layers.insert(layer.name, AudioLayer::new(layer.volume));
+            }
+
+            Ok(mixer)
+        })
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum AudioMixerError {
+    #[error("Error loading JSON Value for AudioMixerLoader from: {path}")]
+    MixerFileLoadError {
+        path: String,
+        source: anyhow::Error
+    },
+
+    #[error("No audio layer named: {layer}")]
+    UnknownLayerError {
+        layer: String
+    },
+
+    #[error("No sound named: {sound_name} has been loaded")]
+    UnknownSoundError {
+        sound_name: String
+    },
+
+    #[error("Failed to play sound: {sound_name}")]
+    PlayError {
+        sound_name: String,
+        source: AudioError
+    },
+
+    #[error("Failed to set volume of layer: {layer}")]
+    VolumeError {
+        layer: String,
+        source: AudioError
+    },
+
+    #[error("Failed to stop layer: {layer}")]
+    StopError {
+        layer: String,
+        source: AudioError
+    }
+}
@@ -0,0 +1,284 @@
+#[cfg(feature="trace")]
+use tracing::{instrument, error, debug};
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use kira::AudioError;
+use kira::instance::{InstanceId, InstanceSettings, StopInstanceSettings};
+use kira::manager::AudioManager;
+use kira::parameter::Tween;
+use kira::sound::SoundId;
+
+use serde::Deserialize;
+
+use specs::{System, Read, Write};
+
+use thiserror::Error;
+
+use crate::globals::audio_playlist::PlaylistError::{PlaylistFileLoadError, UnknownSoundError, PlayError, StopError};
+use crate::globals::audio_controller::AudioController;
+use crate::scenes::pause::{PauseState, PauseAudioWithGame};
+use crate::load::{load_deserializable_from_file, LoadError};
+use crate::loading::GenTask;
+
+pub const PLAYLIST_LOAD_ID: &str = "playlist";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaylistEntry {
+    pub sound_name: String,
+    pub fade_in: Duration,
+    pub fade_out: Duration
+}
+
+#[derive(Debug, Clone)]
+struct CurrentTrack {
+    entry: PlaylistEntry,
+    instance: InstanceId,
+    sound: SoundId,
+    started: Instant
+}
+
+/// An ordered list of sounds played one after the other. When `repeat` is set, finished entries are
+/// put back at the end of the queue so the playlist loops forever. Insert it into the World as a
+/// resource for the PlaylistSystem to play it.
+#[derive(Debug, Clone, Default)]
+pub struct Playlist {
+    pub tracks: VecDeque<PlaylistEntry>,
+    pub repeat: bool,
+    current: Option<CurrentTrack>
+}
+
+impl Playlist {
+    #[cfg_attr(feature="trace", instrument)]
+    pub fn new(entries: Vec<PlaylistEntry>, repeat: bool) -> Self {
+        Self {
+            tracks: VecDeque::from(entries),
+            repeat,
+            current: None
+        }
+    }
+
+    pub fn current_track(&self) -> Option<&PlaylistEntry> {
+        self.current.as_ref().map(|current| &current.entry)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.current.is_none() && self.tracks.is_empty()
+    }
+
+    /// Polls the current track and starts the next entry once the current one begins fading out.
+    /// Call once per frame with the AudioManager the sounds were loaded into.
+    #[cfg_attr(feature="trace", instrument(skip(self, manager, sounds)))]
+    pub fn update(&mut self, manager: &mut AudioManager, sounds: &HashMap<String, SoundId>) -> Result<(), PlaylistError> {
+        if let Some(current) = &self.current {
+            let length = Duration::from_secs_f64(current.sound.duration());
+
+            if current.started.elapsed() + current.entry.fade_out < length {
+                return Ok(())
+            }
+
+            #[cfg(feature = "trace")]
+            debug!("Track: {:?} is ending. Fading out over {:?}", current.entry.sound_name.clone(), current.entry.fade_out);
+
+            manager.stop_instance(
+                current.instance,
+                StopInstanceSettings::new().fade_tween(fade_tween(current.entry.fade_out))
+            ).map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to stop track: {:?}", current.entry.sound_name.clone());
+
+                StopError {
+                    sound_name: current.entry.sound_name.clone(),
+                    source: e
+                }
+            })?;
+
+            self.current = None;
+        }
+
+        let entry = match self.tracks.pop_front() {
+            Some(entry) => entry,
+            None => return Ok(())
+        };
+
+        if self.repeat {
+            self.tracks.push_back(entry.clone());
+        }
+
+        let sound = *sounds.get(&entry.sound_name)
+            .ok_or_else(|| {
+                #[cfg(feature = "trace")]
+                error!("No sound named: {:?} has been loaded", entry.sound_name.clone());
+
+                UnknownSoundError { sound_name: entry.sound_name.clone() }
+            })?;
+
+        let mut settings = InstanceSettings::new();
+        if let Some(tween) = fade_tween(entry.fade_in) {
+            settings = settings.fade_in_tween(tween);
+        }
+
+        let instance = manager.play(sound, settings)
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to play track: {:?}", entry.sound_name.clone());
+
+                PlayError {
+                    sound_name: entry.sound_name.clone(),
+                    source: e
+                }
+            })?;
+
+        #[cfg(feature = "trace")]
+        debug!("Started track: {:?}", entry.sound_name.clone());
+
+        self.current = Some(CurrentTrack {
+            entry,
+            instance,
+            sound,
+            started: Instant::now()
+        });
+
+        Ok(())
+    }
+}
+
+/// Calls `Playlist::update` on the Playlist resource every frame with the AudioController's
+/// sounds. Does nothing without a Playlist or an AudioController, or while the game is paused
+/// with `PauseAudioWithGame` set.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PlaylistSystem;
+
+impl<'a> System<'a> for PlaylistSystem {
+    type SystemData = (
+        Option<Write<'a, Playlist>>,
+        Option<Read<'a, AudioController>>,
+        Option<Read<'a, PauseState>>,
+        Option<Read<'a, PauseAudioWithGame>>
+    );
+
+    #[cfg_attr(feature = "trace", instrument(skip(self, data)))]
+    fn run(&mut self, data: Self::SystemData) {
+        let (playlist, audio_controller, pause_state, pause_audio) = data;
+
+        let (mut playlist, audio_controller) = match (playlist, audio_controller) {
+            (Some(playlist), Some(audio_controller)) => (playlist, audio_controller),
+            _ => return
+        };
+
+        if matches!((pause_state.as_deref(), pause_audio.as_deref()), (Some(PauseState(true)), Some(PauseAudioWithGame(true)))) {
+            return
+        }
+
+        let mut audio_manager = match audio_controller.audio_manager.write() {
+            Ok(audio_manager) => audio_manager,
+            Err(_e) => {
+                #[cfg(feature = "trace")]
+                error!("Failed to acquire write lock for AudioManager");
+
+                return
+            }
+        };
+
+        if let Err(_e) = playlist.update(&mut audio_manager, &audio_controller.audio_lib.0) {
+            #[cfg(feature = "trace")]
+            error!("Failed to advance playlist. Error: {}", _e);
+        }
+    }
+}
+
+fn fade_tween(duration: Duration) -> Option<Tween> {
+    if duration.as_secs_f64() > 0.0 {
+        Some(Tween::linear(duration.as_secs_f64()))
+    } else {
+        None
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct PlaylistEntryJSON {
+    sound_name: String,
+    /// Seconds
+    #[serde(default)]
+    fade_in: f64,
+    /// Seconds
+    #[serde(default)]
+    fade_out: f64
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct PlaylistJSON {
+    tracks: Vec<PlaylistEntryJSON>,
+    #[serde(default)]
+    repeat: bool
+}
+
+#[derive(Debug, Clone)]
+pub struct PlaylistLoader {
+    path: String
+}
+
+impl PlaylistLoader {
+    #[cfg_attr(feature="trace", instrument)]
+    pub fn new(file_path: String) -> Self {
+        Self {
+            path: file_path
+        }
+    }
+
+    #[cfg_attr(feature="trace", instrument)]
+    pub fn load(self) -> GenTask<Playlist> {
+        let path = self.path;
+
+        GenTask::new(move |_ecs| {
+            let json: PlaylistJSON = load_deserializable_from_file(&path, PLAYLIST_LOAD_ID)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to deserialize file: ({:?}) into Playlist JSON value", path.clone());
+
+                    PlaylistFileLoadError {
+                        path: path.clone(),
+                        source: e
+                    }
+                })?;
+
+            let entries = json.tracks
+                .into_iter()
+                .map(|track| PlaylistEntry {
+                    sound_name: track.sound_name,
+                    fade_in: Duration::from_secs_f64(track.fade_in.max(0.0)),
+                    fade_out: Duration::from_secs_f64(track.fade_out.max(0.0))
+                })
+                .collect();
+
+            Ok(Playlist::new(entries, json.repeat))
+        })
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum PlaylistError {
+    #[error("Error loading JSON Value for PlaylistLoader from: {path}")]
+    PlaylistFileLoadError {
+        path: String,
+        source: anyhow::Error
+    },
+
+    #[error("No sound named: {sound_name} has been loaded")]
+    UnknownSoundError {
+        sound_name: String
+    },
+
+    #[error("Failed to play track: {sound_name}")]
+    PlayError {
+        sound_name: String,
+        source: AudioError
+    },
+
+    #[error("Failed to stop track: {sound_name}")]
+    StopError {
+        sound_name: String,
+        source: AudioError
+    }
+}
@@ -0,0 +1,98 @@
+#[cfg(feature="trace")]
+use tracing::{instrument, error, debug};
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use thiserror::Error;
+
+use crate::globals::collision_matrix::CollisionMatrixError::CollisionMatrixFileLoadError;
+use crate::load::{load_deserializable_from_file, LoadError};
+use crate::loading::GenTask;
+
+pub const COLLISION_MATRIX_LOAD_ID: &str = "collision_matrix";
+
+/// Which collision layers collide with which. Pairs are stored as `(layer_a, layer_b)` with
+/// `layer_a <= layer_b`, so the order a pair is given in doesn't matter. Pairs that were never
+/// set collide.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CollisionMatrix(pub HashMap<(u32, u32), bool>);
+
+impl CollisionMatrix {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether `layer_a` and `layer_b` collide, in both orders.
+    pub fn symmetric_set(&mut self, layer_a: u32, layer_b: u32, enabled: bool) {
+        self.0.insert(Self::key(layer_a, layer_b), enabled);
+    }
+
+    pub fn should_collide(&self, a_layer: u32, b_layer: u32) -> bool {
+        self.0.get(&Self::key(a_layer, b_layer))
+            .copied()
+            .unwrap_or(true)
+    }
+
+    fn key(layer_a: u32, layer_b: u32) -> (u32, u32) {
+        (layer_a.min(layer_b), layer_a.max(layer_b))
+    }
+}
+
+/// `matrix` holds `[layer_a, layer_b, enabled]` entries.
+#[derive(Deserialize, Debug, Clone)]
+pub struct CollisionMatrixJSON {
+    matrix: Vec<(u32, u32, bool)>
+}
+
+#[derive(Debug, Clone)]
+pub struct CollisionMatrixLoader {
+    path: String
+}
+
+impl CollisionMatrixLoader {
+    #[cfg_attr(feature = "trace", instrument)]
+    pub fn new(file_path: String) -> Self {
+        Self {
+            path: file_path
+        }
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    pub fn load(self) -> GenTask<CollisionMatrix> {
+        let path = self.path;
+
+        GenTask::new(move |_ecs| {
+            let json: CollisionMatrixJSON = load_deserializable_from_file(&path, COLLISION_MATRIX_LOAD_ID)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to deserialize file: ({:?}) into CollisionMatrixJSON value", path.clone());
+
+                    CollisionMatrixFileLoadError {
+                        path: path.clone(),
+                        source: e
+                    }
+                })?;
+
+            let mut matrix = CollisionMatrix::new();
+            for (layer_a, layer_b, enabled) in json.matrix {
+                matrix.symmetric_set(layer_a, layer_b, enabled);
+            }
+
+            #[cfg(feature = "trace")]
+            debug!("Loaded collision matrix: {:?}", matrix);
+
+            Ok(matrix)
+        })
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum CollisionMatrixError {
+    #[error("Error loading JSON Value for CollisionMatrixLoader from: {path}")]
+    CollisionMatrixFileLoadError {
+        path: String,
+        source: anyhow::Error
+    }
+}
@@ -0,0 +1,300 @@
+#[cfg(feature="trace")]
+use tracing::{instrument, error, debug};
+
+use std::collections::HashMap;
+use std::fs::read;
+use std::ops::DerefMut;
+
+use ab_glyph::{Font, FontVec, ScaleFont, InvalidFont};
+use luminance_front::pixel::RGBA8UI;
+use luminance_front::texture::{Texture, Sampler, Wrap, MinFilter, MagFilter, GenMipmaps, Dim2, TextureError};
+use luminance_front::depth_test::DepthComparison;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::globals::font_atlas_dict::FontAtlasDictError::{FontAtlasDictFileLoadError, FontFileReadError, InvalidFontError, TextureCreationError, ContextWriteLockError, WorldReadLockError};
+use crate::globals::texture_dict::TextureDict;
+use crate::graphics::texture::TextureHandle;
+use crate::load::{load_deserializable_from_file, LoadError};
+use crate::loading::DrawTask;
+
+pub const FONT_ATLAS_DICT_LOAD_ID: &str = "font_atlas_dict";
+pub const FONTS_DIR: &str = "fonts/";
+
+const ATLAS_WIDTH: u32 = 512;
+const ATLAS_PADDING: u32 = 1;
+const FIRST_CHAR: char = ' ';
+const LAST_CHAR: char = '~';
+
+/// Placement of a single glyph inside of a FontAtlas. All sizes are in pixels at the atlas' font size.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct GlyphMetrics {
+    pub uv_min: [f32; 2],
+    pub uv_max: [f32; 2],
+    pub size: [f32; 2],
+    /// Offset from the pen position on the baseline to the bottom-left corner of the glyph.
+    pub bearing: [f32; 2],
+    pub advance: f32
+}
+
+/// A font rasterized into a texture stored in the TextureDict.
+#[derive(Debug, Clone)]
+pub struct FontAtlas {
+    pub texture_handle: TextureHandle,
+    pub font_size: f32,
+    pub line_height: f32,
+    pub glyphs: HashMap<char, GlyphMetrics>
+}
+
+#[derive(Default, Debug)]
+pub struct FontAtlasDict(pub HashMap<String, FontAtlas>);
+
+impl FontAtlasDict {
+    pub fn get(&self, font_handle: &str) -> Option<&FontAtlas> {
+        self.0.get(font_handle)
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct FontAtlasJSON {
+    path: String,
+    size: f32
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct FontAtlasDictJSON {
+    fonts: HashMap<String, FontAtlasJSON>
+}
+
+#[derive(Debug, Clone)]
+pub struct FontAtlasDictLoader {
+    path: String
+}
+
+impl FontAtlasDictLoader {
+    const SAMPLER: Sampler = Sampler {
+        wrap_r: Wrap::ClampToEdge,
+        wrap_s: Wrap::ClampToEdge,
+        wrap_t: Wrap::ClampToEdge,
+        // The atlas is an integer texture, which can't be filtered linearly.
+        min_filter: MinFilter::Nearest,
+        mag_filter: MagFilter::Nearest,
+        depth_comparison: Some(DepthComparison::Less)
+    };
+
+    #[cfg_attr(feature="trace", instrument)]
+    pub fn new(file_path: String) -> Self {
+        Self {
+            path: file_path
+        }
+    }
+
+    /// Rasterizes every font listed in the file and stores each atlas texture in the World's TextureDict
+    /// under the font's name.
+    #[cfg_attr(feature="trace", instrument)]
+    pub fn load(self) -> DrawTask<FontAtlasDict> {
+        let path = self.path;
+
+        DrawTask::new(move |(ecs, context)| {
+            let json: FontAtlasDictJSON = load_deserializable_from_file(&path, FONT_ATLAS_DICT_LOAD_ID)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to deserialize file: ({:?}) into FontAtlasDict JSON value", path.clone());
+
+                    FontAtlasDictFileLoadError {
+                        path: path.clone(),
+                        source: e
+                    }
+                })?;
+
+            let world = ecs.read()
+                .map_err(|_e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to acquire read lock for World");
+
+                    WorldReadLockError
+                })?;
+
+            let mut texture_dict = world.fetch_mut::<TextureDict>();
+
+            let mut ctx = context.write()
+                .map_err(|_e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to acquire write lock for Context");
+
+                    ContextWriteLockError
+                })?;
+
+            let mut font_atlas_dict = HashMap::new();
+
+            for (font_name, font_json) in json.fonts {
+                #[cfg(feature = "trace")]
+                debug!("Building atlas for font: {:?} from: {:?}", font_name.clone(), font_json.path.clone());
+
+                let bytes = read(&font_json.path)
+                    .map_err(|e| {
+                        #[cfg(feature = "trace")]
+                        error!("Failed to read font file: {:?}", font_json.path.clone());
+
+                        FontFileReadError {
+                            path: font_json.path.clone(),
+                            source: e
+                        }
+                    })?;
+
+                let font = FontVec::try_from_vec(bytes)
+                    .map_err(|e| {
+                        #[cfg(feature = "trace")]
+                        error!("Failed to parse font file: {:?}", font_json.path.clone());
+
+                        InvalidFontError {
+                            path: font_json.path.clone(),
+                            source: e
+                        }
+                    })?;
+
+                let (pixels, dimensions, glyphs) = rasterize_atlas(&font, font_json.size);
+                #[cfg(feature = "trace")]
+                debug!("Rasterized {:?} glyphs into atlas of size: {:?}", glyphs.len(), dimensions);
+
+                let texture = Texture::new_raw(
+                    ctx.deref_mut(),
+                    dimensions,
+                    0,
+                    Self::SAMPLER,
+                    GenMipmaps::No,
+                    &pixels
+                ).map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to create texture for font atlas: {:?}", font_name.clone());
+
+                    TextureCreationError {
+                        font_name: font_name.clone(),
+                        source: e
+                    }
+                })?;
+
+                let texture_handle = TextureHandle::new(font_name.clone());
+                texture_dict.insert(&texture_handle, texture);
+
+                font_atlas_dict.insert(font_name, FontAtlas {
+                    texture_handle,
+                    font_size: font_json.size,
+                    line_height: font.as_scaled(font_json.size).height(),
+                    glyphs
+                });
+            }
+
+            Ok(FontAtlasDict(font_atlas_dict))
+        })
+    }
+}
+
+/// Packs the printable ASCII glyphs of `font` into rows of an RGBA8 image. Glyphs are written white with
+/// their coverage in the alpha channel. Rows are stored bottom-up to match OpenGL's texture coordinates.
+/// The image is `ATLAS_WIDTH` wide, or as wide as the widest glyph at large font sizes.
+fn rasterize_atlas(font: &FontVec, size: f32) -> (Vec<u8>, [u32; 2], HashMap<char, GlyphMetrics>) {
+    let scaled_font = font.as_scaled(size);
+
+    let mut outlines = Vec::new();
+    let mut glyphs = HashMap::new();
+
+    for character in FIRST_CHAR..=LAST_CHAR {
+        let glyph_id = scaled_font.glyph_id(character);
+        let advance = scaled_font.h_advance(glyph_id);
+
+        match scaled_font.outline_glyph(glyph_id.with_scale(size)) {
+            Some(outlined) => outlines.push((character, advance, outlined)),
+            // Whitespace has nothing to draw but still moves the pen.
+            None => {
+                glyphs.insert(character, GlyphMetrics { advance, ..GlyphMetrics::default() });
+            }
+        }
+    }
+
+    let widest_glyph = outlines.iter()
+        .map(|(_, _, outlined)| outlined.px_bounds().width().ceil() as u32)
+        .max()
+        .unwrap_or(0);
+    let atlas_width = ATLAS_WIDTH.max(widest_glyph + 2 * ATLAS_PADDING);
+
+    let mut placements = Vec::new();
+    let (mut cursor_x, mut cursor_y, mut row_height) = (ATLAS_PADDING, ATLAS_PADDING, 0);
+
+    for (character, advance, outlined) in outlines {
+        let bounds = outlined.px_bounds();
+        let (width, height) = (bounds.width().ceil() as u32, bounds.height().ceil() as u32);
+
+        if cursor_x + width + ATLAS_PADDING > atlas_width {
+            cursor_x = ATLAS_PADDING;
+            cursor_y += row_height + ATLAS_PADDING;
+            row_height = 0;
+        }
+
+        glyphs.insert(character, GlyphMetrics {
+            size: [width as f32, height as f32],
+            bearing: [bounds.min.x, -bounds.max.y],
+            advance,
+            ..GlyphMetrics::default()
+        });
+        placements.push((character, outlined, cursor_x, cursor_y, width, height));
+
+        cursor_x += width + ATLAS_PADDING;
+        row_height = row_height.max(height);
+    }
+
+    let atlas_height = (cursor_y + row_height + ATLAS_PADDING).next_power_of_two();
+    let mut pixels = vec![0u8; (atlas_width * atlas_height * 4) as usize];
+
+    for (character, outlined, x, y, width, height) in placements {
+        outlined.draw(|glyph_x, glyph_y, coverage| {
+            // Glyphs rasterize top-down but the atlas is stored bottom-up.
+            let atlas_x = x + glyph_x;
+            let atlas_y = y + (height - 1 - glyph_y);
+            let index = ((atlas_y * atlas_width + atlas_x) * 4) as usize;
+
+            pixels[index..index + 3].copy_from_slice(&[255, 255, 255]);
+            pixels[index + 3] = (coverage * 255.0) as u8;
+        });
+
+        if let Some(metrics) = glyphs.get_mut(&character) {
+            metrics.uv_min = [x as f32 / atlas_width as f32, y as f32 / atlas_height as f32];
+            metrics.uv_max = [(x + width) as f32 / atlas_width as f32, (y + height) as f32 / atlas_height as f32];
+        }
+    }
+
+    (pixels, [atlas_width, atlas_height], glyphs)
+}
+
+#[derive(Error, Debug)]
+pub enum FontAtlasDictError {
+    #[error("Error loading JSON Value for FontAtlasDictLoader from: {path}")]
+    FontAtlasDictFileLoadError {
+        path: String,
+        source: anyhow::Error
+    },
+
+    #[error("Failed to read font file: {path}")]
+    FontFileReadError {
+        path: String,
+        source: std::io::Error
+    },
+
+    #[error("File at {path} is not a valid font")]
+    InvalidFontError {
+        path: String,
+        source: InvalidFont
+    },
+
+    #[error("Failed to create atlas texture for font: {font_name}")]
+    TextureCreationError {
+        font_name: String,
+        source: TextureError
+    },
+
+    #[error("Failed to acquire read lock for World")]
+    WorldReadLockError,
+
+    #[error("Failed to acquire write lock for Context")]
+    ContextWriteLockError
+}
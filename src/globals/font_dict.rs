@@ -24,10 +24,6 @@ pub struct FontDict(pub HashMap<String, Font>);
 
 pub const FONTS_DIR: &str = "fonts/";
 
-const FONT_VEC_SIZE: usize = 4;
-const FONT_FILE_SIZE: usize = 60_000;
-static mut FONT_BYTES: [[u8; FONT_FILE_SIZE]; FONT_VEC_SIZE] = [[0; FONT_FILE_SIZE]; FONT_VEC_SIZE];
-
 unsafe impl Send for FontDict {}
 unsafe impl Sync for FontDict {}
 
@@ -101,7 +97,7 @@ impl FontDictLoader {
         #[cfg(feature="trace")]
         trace!("Value: {} successfully transformed into FontDictLoaderJSON", json_value.actual_value.clone());
 
-        for (index, (font_name, font_path)) in fonts.fonts.into_iter().enumerate() {
+        for (font_name, font_path) in fonts.fonts {
             let font = map_err_return!(
                 read(font_path.clone()),
                 |e| { build_task_error(
@@ -115,29 +111,13 @@ impl FontDictLoader {
             #[cfg(feature="trace")]
             trace!("Font: {} successfully loaded from: {}", font_name.clone(), font_path);
 
-            if font.len() <= FONT_FILE_SIZE {
-                unsafe {
-                    for (i, byte) in font.iter().enumerate() {
-                        FONT_BYTES[index][i] = *byte
-                    }
-                };
-            } else {
-                let error = FontDictFontSizeError {
-                    font_size: font.len(),
-                    font_name: font_name.clone(),
-                    font_path: font_path.clone()
-                };
-                #[cfg(feature="trace")]
-                error!("ERROR: Could not load font: {:#?}", error);
-
-                return build_task_error(
-                    error,
-                    ErrorKind::InvalidData
-                )
-            }
+            // Font::load_from_bytes needs bytes that live as long as the game. Fonts are loaded
+            // once and kept for the rest of the game, so leaking them costs nothing extra and
+            // leaves no limit on how many fonts there are or how large they can be.
+            let font_bytes: &'static [u8] = Box::leak(font.into_boxed_slice());
 
             font_task = (
-                Font::load_from_bytes(unsafe { &FONT_BYTES[index] }),
+                Font::load_from_bytes(font_bytes),
                 font_task
             )
                 .join()
@@ -174,8 +154,9 @@ pub enum FontDictError {
         path: String,
         source: std::io::Error
     },
-    #[error("Error: {font_name} at {font_path} has size {font_size} greater than {} byte limit")]
-    FontDictFontSizeError {
+    #[deprecated(note = "Fonts are no longer copied into fixed size buffers, so there is no size limit")]
+    #[error("Error: {font_name} at {font_path} has size {font_size} greater than the font byte limit")]
+    FontBytesExceededStaticCapacity {
         font_size: usize,
         font_name: String,
         font_path: String
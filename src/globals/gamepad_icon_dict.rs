@@ -0,0 +1,84 @@
+#[cfg(feature="trace")]
+use tracing::{instrument, error, debug};
+
+use std::collections::HashMap;
+
+use glfw::GamepadButton;
+use serde::Deserialize;
+
+use thiserror::Error;
+
+use crate::globals::gamepad_icon_dict::GamepadIconDictError::GamepadIconDictFileLoadError;
+use crate::input::gamepad::{ControllerType, GamepadButtonDef};
+use crate::load::{load_deserializable_from_file, LoadError};
+use crate::loading::GenTask;
+
+pub const GAMEPAD_ICON_DICT_LOAD_ID: &str = "gamepad_icon_dict";
+
+/// Name of the icon texture for each button of each controller type, e.g. `"xbox_a"` or
+/// `"ps_cross"`. The textures themselves are loaded into the TextureDict under these names.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GamepadIconDict(pub HashMap<(ControllerType, GamepadButton), String>);
+
+impl GamepadIconDict {
+    pub fn get(&self, controller_type: ControllerType, button: GamepadButton) -> Option<&String> {
+        self.0.get(&(controller_type, button))
+    }
+}
+
+/// `icons` holds `[controller_type, button, texture_name]` entries.
+#[derive(Deserialize, Debug, Clone)]
+pub struct GamepadIconDictJSON {
+    icons: Vec<(ControllerType, GamepadButtonDef, String)>
+}
+
+#[derive(Debug, Clone)]
+pub struct GamepadIconDictLoader {
+    path: String
+}
+
+impl GamepadIconDictLoader {
+    #[cfg_attr(feature = "trace", instrument)]
+    pub fn new(file_path: String) -> Self {
+        Self {
+            path: file_path
+        }
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    pub fn load(self) -> GenTask<GamepadIconDict> {
+        let path = self.path;
+
+        GenTask::new(move |_ecs| {
+            let json: GamepadIconDictJSON = load_deserializable_from_file(&path, GAMEPAD_ICON_DICT_LOAD_ID)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to deserialize file: ({:?}) into GamepadIconDictJSON value", path.clone());
+
+                    GamepadIconDictFileLoadError {
+                        path: path.clone(),
+                        source: e
+                    }
+                })?;
+
+            let dict = GamepadIconDict(json.icons
+                .into_iter()
+                .map(|(controller_type, button, texture_name)| ((controller_type, GamepadButton::from(button)), texture_name))
+                .collect());
+
+            #[cfg(feature = "trace")]
+            debug!("Loaded gamepad icon dict: {:?}", dict);
+
+            Ok(dict)
+        })
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum GamepadIconDictError {
+    #[error("Error loading JSON Value for GamepadIconDictLoader from: {path}")]
+    GamepadIconDictFileLoadError {
+        path: String,
+        source: anyhow::Error
+    }
+}
@@ -1,3 +1,14 @@
-// pub mod audio_controller;
+pub mod audio_controller;
 pub mod texture_dict;
-// pub mod font_dict;
\ No newline at end of file
+pub mod font_atlas_dict;
+pub mod audio_playlist;
+pub mod audio_mixer;
+pub mod sound_variation;
+pub mod accessibility;
+pub mod name_registry;
+pub mod collision_matrix;
+pub mod gamepad_icon_dict;
+pub mod resource_key;
+// pub mod font_dict;
+pub mod rng;
+
@@ -0,0 +1,166 @@
+#[cfg(feature="trace")]
+use tracing::{instrument, error, debug, warn};
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use specs::Entity;
+
+use thiserror::Error;
+
+use crate::globals::name_registry::NameRegistryError::{ConfigLoadError, DuplicateName};
+use crate::load::{load_deserializable_from_file, LoadError};
+
+pub const NAME_REGISTRY_CONFIG_LOAD_ID: &str = "name_registry_config";
+
+/// Settings for the NameRegistrySystem. Insert it into the World before the system runs.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NameRegistryConfig {
+    /// Makes registering a name that belongs to another entity an error instead of a warning.
+    #[serde(default)]
+    pub strict_names: bool
+}
+
+impl NameRegistryConfig {
+    #[cfg_attr(feature="trace", instrument)]
+    pub fn load_from_file(file_path: &str) -> Result<Self, NameRegistryError> {
+        load_deserializable_from_file(file_path, NAME_REGISTRY_CONFIG_LOAD_ID)
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to load NameRegistryConfig from file: {:?}", file_path);
+
+                ConfigLoadError {
+                    path: file_path.to_string(),
+                    source: e
+                }
+            })
+    }
+}
+
+/// Looks up entities by the name in their NamedEntity component. Filled by the
+/// NameRegistrySystem.
+#[derive(Debug, Clone, Default)]
+pub struct NameRegistry {
+    names: HashMap<String, Entity>,
+    /// When set, `register` refuses names that already belong to another entity. Otherwise the
+    /// name is moved to the new entity with a warning.
+    pub strict_names: bool
+}
+
+impl NameRegistry {
+    pub fn new(strict_names: bool) -> Self {
+        Self {
+            names: HashMap::new(),
+            strict_names
+        }
+    }
+
+    #[cfg_attr(feature="trace", instrument(skip(self)))]
+    pub fn register(&mut self, name: &str, entity: Entity) -> Result<(), NameRegistryError> {
+        match self.names.get(name) {
+            Some(existing) if *existing != entity && self.strict_names => {
+                #[cfg(feature = "trace")]
+                error!("Name: {:?} already belongs to entity: {:?}", name, existing);
+
+                return Err(DuplicateName {
+                    name: name.to_string(),
+                    existing: *existing,
+                    new_entity: entity
+                })
+            },
+            Some(_existing) if *_existing != entity => {
+                #[cfg(feature = "trace")]
+                warn!("Name: {:?} moved from entity: {:?} to entity: {:?}", name, _existing, entity);
+            },
+            _ => {
+                #[cfg(feature = "trace")]
+                debug!("Registered name: {:?} for entity: {:?}", name, entity);
+            }
+        }
+
+        self.names.insert(name.to_string(), entity);
+
+        Ok(())
+    }
+
+    /// Removes `name` if it belongs to `entity`.
+    pub fn unregister(&mut self, name: &str, entity: Entity) {
+        if self.names.get(name) == Some(&entity) {
+            self.names.remove(name);
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<Entity> {
+        self.names.get(name).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum NameRegistryError {
+    #[error("Name: {name} already belongs to entity: {existing:?}. It can't be given to entity: {new_entity:?}")]
+    DuplicateName {
+        name: String,
+        existing: Entity,
+        new_entity: Entity
+    },
+
+    #[error("Failed to load NameRegistryConfig from file: {path}")]
+    ConfigLoadError {
+        path: String,
+        source: anyhow::Error
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use specs::{Builder, World, WorldExt};
+
+    use super::*;
+
+    fn two_entities() -> (Entity, Entity) {
+        let mut world = World::new();
+
+        (world.create_entity().build(), world.create_entity().build())
+    }
+
+    #[test]
+    fn strict_register_rejects_a_name_owned_by_another_entity() {
+        let (first, second) = two_entities();
+        let mut registry = NameRegistry::new(true);
+        registry.register("player", first).unwrap();
+
+        let result = registry.register("player", second);
+
+        assert!(matches!(result, Err(DuplicateName { existing, new_entity, .. }) if existing == first && new_entity == second));
+        assert_eq!(registry.get("player"), Some(first));
+    }
+
+    #[test]
+    fn non_strict_register_moves_the_name_to_the_new_entity() {
+        let (first, second) = two_entities();
+        let mut registry = NameRegistry::new(false);
+        registry.register("player", first).unwrap();
+
+        registry.register("player", second).unwrap();
+
+        assert_eq!(registry.get("player"), Some(second));
+        assert_eq!(registry.len(), 1);
+    }
+
+    #[test]
+    fn strict_register_accepts_the_same_entity_twice() {
+        let (first, _) = two_entities();
+        let mut registry = NameRegistry::new(true);
+        registry.register("player", first).unwrap();
+
+        assert!(registry.register("player", first).is_ok());
+    }
+}
@@ -0,0 +1,98 @@
+use std::borrow::{Borrow, Cow};
+use std::fmt::{Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+
+/// Name of a `T` in one of the global dicts, e.g. a texture in the TextureDict. The type parameter
+/// keeps a key for one kind of resource from being used to look up another. Names known at
+/// compile time can be written with `resource_key!`.
+pub struct ResourceKey<T> {
+    name: Cow<'static, str>,
+    resource: PhantomData<fn() -> T>
+}
+
+impl<T> ResourceKey<T> {
+    pub fn new(name: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            name: name.into(),
+            resource: PhantomData
+        }
+    }
+
+    pub const fn from_static(name: &'static str) -> Self {
+        Self {
+            name: Cow::Borrowed(name),
+            resource: PhantomData
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.name
+    }
+}
+
+// Implemented by hand so `T` doesn't need to implement any of these.
+impl<T> Clone for ResourceKey<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.name.clone())
+    }
+}
+
+impl<T> Debug for ResourceKey<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ResourceKey")
+            .field(&self.name)
+            .finish()
+    }
+}
+
+impl<T> Display for ResourceKey<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+impl<T> PartialEq for ResourceKey<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl<T> Eq for ResourceKey<T> {}
+
+impl<T> Hash for ResourceKey<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+/// Lets dicts keyed by ResourceKey be searched with a `&str`.
+impl<T> Borrow<str> for ResourceKey<T> {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<T> From<String> for ResourceKey<T> {
+    fn from(name: String) -> Self {
+        Self::new(name)
+    }
+}
+
+impl<T> From<&'static str> for ResourceKey<T> {
+    fn from(name: &'static str) -> Self {
+        Self::from_static(name)
+    }
+}
+
+/// Builds a ResourceKey from a string literal or an identifier, e.g. `resource_key!(player)` or
+/// `resource_key!("player")`.
+#[macro_export]
+macro_rules! resource_key {
+    ($name:literal) => {
+        $crate::globals::resource_key::ResourceKey::from_static($name)
+    };
+    ($name:ident) => {
+        $crate::globals::resource_key::ResourceKey::from_static(stringify!($name))
+    };
+}
@@ -0,0 +1,115 @@
+#[cfg(feature="trace")]
+use tracing::{instrument, error, debug};
+
+use rand::{Rng, SeedableRng, thread_rng};
+use rand::rngs::SmallRng;
+use rand::distributions::Distribution;
+
+use serde::{Deserialize, Serialize};
+
+use thiserror::Error;
+
+use crate::globals::rng::GameRngError::{RngFileLoadError, WorldWriteError};
+use crate::load::{load_deserializable_from_file, LoadError};
+use crate::loading::GenTask;
+
+pub const GAME_RNG_LOAD_ID: &str = "game_rng";
+
+/// Seed the GameRng was created from. Kept as its own resource so it can be shown or saved
+/// without handing out the generator itself.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Seed(pub u64);
+
+/// Random number generator shared by all systems. Two GameRngs built from the same seed produce
+/// the same sequence of values.
+#[derive(Debug, Clone)]
+pub struct GameRng(SmallRng);
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self::new(thread_rng().gen())
+    }
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        Self(SmallRng::seed_from_u64(seed))
+    }
+
+    /// Value in the range [0, 1).
+    pub fn next_f32(&mut self) -> f32 {
+        self.0.gen()
+    }
+
+    /// Value in the range [lo, hi). Panics if `lo >= hi`.
+    pub fn next_range(&mut self, lo: i32, hi: i32) -> i32 {
+        self.0.gen_range(lo..hi)
+    }
+
+    pub fn sample<T: Distribution<V>, V>(&mut self, dist: T) -> V {
+        dist.sample(&mut self.0)
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct GameRngJSON {
+    seed: u64
+}
+
+pub struct GameRngLoader {
+    path: String
+}
+
+impl GameRngLoader {
+    #[cfg_attr(feature="trace", instrument)]
+    pub fn new(file_path: String) -> Self {
+        Self {
+            path: file_path
+        }
+    }
+
+    /// Also inserts the Seed read from the file into the World.
+    #[cfg_attr(feature="trace", instrument(skip(self)))]
+    pub fn load(self) -> GenTask<GameRng> {
+        let path = self.path;
+
+        GenTask::new(move |ecs| {
+            let json: GameRngJSON = load_deserializable_from_file(&path, GAME_RNG_LOAD_ID)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to deserialize file: ({:?}) into GameRng JSON value", path.clone());
+
+                    RngFileLoadError {
+                        path: path.clone(),
+                        source: e
+                    }
+                })?;
+
+            ecs.write()
+                .map_err(|_e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to acquire write lock for World");
+
+                    WorldWriteError
+                })?
+                .insert(Seed(json.seed));
+
+            #[cfg(feature = "trace")]
+            debug!("Created GameRng with seed: {:?}", json.seed);
+
+            Ok(GameRng::new(json.seed))
+        })
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum GameRngError {
+    #[error("Failed to load GameRng from file: {path}")]
+    RngFileLoadError {
+        path: String,
+        source: anyhow::Error
+    },
+
+    #[error("Failed to acquire write lock for World")]
+    WorldWriteError
+}
@@ -0,0 +1,197 @@
+#[cfg(feature="trace")]
+use tracing::{instrument, error, debug};
+
+use std::collections::HashMap;
+
+use kira::AudioError;
+use kira::instance::{InstanceId, InstanceSettings};
+use kira::manager::AudioManager;
+use kira::sound::SoundId;
+
+use serde::Deserialize;
+
+use thiserror::Error;
+
+use crate::globals::rng::GameRng;
+use crate::globals::sound_variation::SoundVariationError::{VariationFileLoadError, EmptyVariationError, UnknownGroupError, UnknownSoundError, PlayError};
+use crate::load::{load_deserializable_from_file, LoadError};
+use crate::loading::GenTask;
+
+pub const SOUND_VARIATION_LOAD_ID: &str = "sound_variation";
+
+/// How a SoundVariation picks the next sound from its list.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VariationStrategy {
+    Random,
+    /// Plays the sounds in order and starts again from the first after the last.
+    Sequential,
+    /// Random, but never the same sound twice in a row.
+    RandomNonRepeat
+}
+
+/// A group of interchangeable sounds, such as footsteps, that are played in turn so the same
+/// sound isn't heard every time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoundVariation {
+    pub sounds: Vec<String>,
+    pub strategy: VariationStrategy,
+    last_index: Option<usize>
+}
+
+impl SoundVariation {
+    pub fn new(sounds: Vec<String>, strategy: VariationStrategy) -> Self {
+        Self {
+            sounds,
+            strategy,
+            last_index: None
+        }
+    }
+
+    /// Name of the sound to play next. Panics if `sounds` is empty.
+    pub fn next(&mut self, rng: &mut GameRng) -> &str {
+        let count = self.sounds.len();
+
+        let index = match (self.strategy, self.last_index) {
+            (VariationStrategy::Sequential, Some(last)) => (last + 1) % count,
+            (VariationStrategy::Sequential, None) => 0,
+            (VariationStrategy::RandomNonRepeat, Some(last)) if count > 1 => {
+                // Pick from every index but the last one by skipping over it.
+                let index = rng.next_range(0, count as i32 - 1) as usize;
+                if index >= last { index + 1 } else { index }
+            },
+            _ => rng.next_range(0, count as i32) as usize
+        };
+
+        self.last_index = Some(index);
+
+        &self.sounds[index]
+    }
+}
+
+/// Variation groups by name.
+#[derive(Debug, Clone, Default)]
+pub struct SoundVariationDict(pub HashMap<String, SoundVariation>);
+
+impl SoundVariationDict {
+    /// Plays the next sound of the variation group named `group`.
+    #[cfg_attr(feature="trace", instrument(skip(self, rng, manager, sounds)))]
+    pub fn play_variation(&mut self, group: &str, rng: &mut GameRng, manager: &mut AudioManager, sounds: &HashMap<String, SoundId>) -> Result<InstanceId, SoundVariationError> {
+        let variation = self.0.get_mut(group)
+            .ok_or_else(|| {
+                #[cfg(feature = "trace")]
+                error!("No sound variation group named: {:?}", group);
+
+                UnknownGroupError { group: group.to_string() }
+            })?;
+
+        if variation.sounds.is_empty() {
+            #[cfg(feature = "trace")]
+            error!("Sound variation group: {:?} has no sounds", group);
+
+            return Err(EmptyVariationError { group: group.to_string() })
+        }
+
+        let sound = variation.next(rng);
+
+        let sound_id = *sounds.get(sound)
+            .ok_or_else(|| {
+                #[cfg(feature = "trace")]
+                error!("No sound named: {:?} has been loaded", sound);
+
+                UnknownSoundError { sound_name: sound.to_string() }
+            })?;
+
+        let instance = manager.play(sound_id, InstanceSettings::default())
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to play sound: {:?} from group: {:?}", sound, group);
+
+                PlayError {
+                    sound_name: sound.to_string(),
+                    source: e
+                }
+            })?;
+
+        #[cfg(feature = "trace")]
+        debug!("Playing sound: {:?} from group: {:?}", sound, group);
+
+        Ok(instance)
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct SoundVariationJSON {
+    sounds: Vec<String>,
+    strategy: VariationStrategy
+}
+
+#[derive(Debug, Clone)]
+pub struct SoundVariationLoader {
+    path: String
+}
+
+impl SoundVariationLoader {
+    #[cfg_attr(feature="trace", instrument)]
+    pub fn new(file_path: String) -> Self {
+        Self {
+            path: file_path
+        }
+    }
+
+    #[cfg_attr(feature="trace", instrument)]
+    pub fn load(self) -> GenTask<SoundVariation> {
+        let path = self.path;
+
+        GenTask::new(move |_ecs| {
+            let json: SoundVariationJSON = load_deserializable_from_file(&path, SOUND_VARIATION_LOAD_ID)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to deserialize file: ({:?}) into SoundVariation JSON value", path.clone());
+
+                    VariationFileLoadError {
+                        path: path.clone(),
+                        source: e
+                    }
+                })?;
+
+            if json.sounds.is_empty() {
+                #[cfg(feature = "trace")]
+                error!("Sound variation file: {:?} has no sounds", path.clone());
+
+                return Err(EmptyVariationError { group: path }.into())
+            }
+
+            Ok(SoundVariation::new(json.sounds, json.strategy))
+        })
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum SoundVariationError {
+    #[error("Error loading JSON Value for SoundVariationLoader from: {path}")]
+    VariationFileLoadError {
+        path: String,
+        source: anyhow::Error
+    },
+
+    #[error("Sound variation: {group} has no sounds")]
+    EmptyVariationError {
+        group: String
+    },
+
+    #[error("No sound variation group named: {group}")]
+    UnknownGroupError {
+        group: String
+    },
+
+    #[error("No sound named: {sound_name} has been loaded")]
+    UnknownSoundError {
+        sound_name: String
+    },
+
+    #[error("Failed to play sound: {sound_name}")]
+    PlayError {
+        sound_name: String,
+        source: AudioError
+    }
+}
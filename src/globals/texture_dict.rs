@@ -1,7 +1,8 @@
 #[cfg(feature="trace")]
 use tracing::{instrument, trace, error, debug};
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, RwLock};
 
 use crate::load::{LoadError, load_deserializable_from_file};
 
@@ -18,21 +19,193 @@ use anyhow::Result;
 use image::io::Reader;
 use luminance_front::depth_test::DepthComparison;
 use crate::graphics::texture::TextureHandle;
-use crate::globals::texture_dict::TextureDictError::{PathConversionFailed, RGB8ConversionFailed, WorldWriteLockError, TextureDictFileLoadError};
+use crate::globals::resource_key::ResourceKey;
+use crate::globals::texture_dict::TextureDictError::{PathConversionFailed, RGB8ConversionFailed, WorldWriteLockError, TextureDictFileLoadError, ContextWriteLockError, ImageOpenError, ImageDecodeError, TextureCreationError};
 use luminance::pixel::RGB8UI;
 use specs::World;
 use std::borrow::BorrowMut;
 use std::ops::DerefMut;
 use image::Pixels;
+#[cfg(feature = "hot_reload")]
+use crate::hot_reload::{self, HotReloadError};
+#[cfg(feature = "hot_reload")]
+use crate::globals::texture_dict::TextureDictError::HotReloadFailed;
 
 pub const TEXTURE_DICT_LOAD_ID: &str = "texture_dict";
 
+pub type TextureKey = ResourceKey<Texture<Dim2, RGBA8UI>>;
+
 #[derive(Default)]
-pub struct TextureDict(HashMap<String, Texture<Dim2, RGBA8UI>>);
+pub struct TextureDict(HashMap<TextureKey, Texture<Dim2, RGBA8UI>>, TextureDictStats, TextureRefCounts);
+
+/// Textures whose `SceneTextureScope` was dropped, waiting for `TextureDict::remove_released`.
+type ReleasedTextures = Arc<Mutex<Vec<TextureKey>>>;
+
+/// How many SceneTextureScopes hold each texture. A texture is only removed once none do, so a
+/// texture shared by two scenes stays loaded until both are gone. Textures loaded outside a scope
+/// have no count and are never released.
+#[derive(Debug, Default)]
+struct TextureRefCounts {
+    counts: HashMap<TextureKey, usize>,
+    /// Textures loaded or found in the dict since `begin_scope`.
+    recording: Option<HashSet<TextureKey>>,
+    released: ReleasedTextures
+}
+
+impl TextureRefCounts {
+    /// Records a texture first loaded in the current scope.
+    fn record(&mut self, key: &TextureKey) {
+        if let Some(recording) = self.recording.as_mut() {
+            recording.insert(key.clone());
+        }
+    }
+
+    /// Records a texture found already loaded, only if a scope holds it. Textures loaded outside
+    /// a scope, like the ones of the GameWrapper or a font atlas, stay uncounted.
+    fn record_hit(&mut self, key: &TextureKey) {
+        if self.counts.contains_key(key) {
+            self.record(key);
+        }
+    }
+
+    /// Adds `key` to `textures` with the value `load` returns, unless it is there already.
+    /// Returns whether it was.
+    fn load_or_get<V, E>(&mut self, textures: &mut HashMap<TextureKey, V>, key: TextureKey, load: impl FnOnce() -> Result<V, E>) -> Result<bool, E> {
+        if textures.contains_key(&key) {
+            self.record_hit(&key);
+            return Ok(true)
+        }
+
+        let value = load()?;
+        self.record(&key);
+        textures.insert(key, value);
+
+        Ok(false)
+    }
+
+    fn insert<V>(&mut self, textures: &mut HashMap<TextureKey, V>, key: TextureKey, value: V) -> Option<V> {
+        if textures.contains_key(&key) {
+            self.record_hit(&key);
+        } else {
+            self.record(&key);
+        }
+
+        textures.insert(key, value)
+    }
+
+    /// Removes the textures `take_unused` returns from `textures`.
+    fn remove_unused<V>(&mut self, textures: &mut HashMap<TextureKey, V>) {
+        for key in self.take_unused() {
+            #[cfg(feature = "trace")]
+            debug!("Removing released texture: {:?}", key);
+
+            textures.remove(&key);
+        }
+    }
+
+    fn begin_scope(&mut self) {
+        self.recording = Some(HashSet::new());
+    }
+
+    /// Scope holding every texture recorded since `begin_scope`. Each one is counted once more.
+    fn end_scope(&mut self) -> SceneTextureScope {
+        let keys = self.recording.take().unwrap_or_default();
+        for key in keys.iter() {
+            *self.counts.entry(key.clone()).or_insert(0) += 1;
+        }
+
+        SceneTextureScope {
+            handles: keys.into_iter().map(TextureHandle::new).collect(),
+            released: self.released.clone()
+        }
+    }
+
+    /// Counts down the textures of every scope dropped since the last call. Returns the ones no
+    /// scope holds anymore.
+    fn take_unused(&mut self) -> Vec<TextureKey> {
+        let released = match self.released.lock() {
+            Ok(mut released) => std::mem::take(&mut *released),
+            Err(_e) => {
+                #[cfg(feature = "trace")]
+                error!("Failed to acquire lock for released textures");
+
+                return Vec::new()
+            }
+        };
+
+        let counts = &mut self.counts;
+        released.into_iter()
+            .filter(|key| match counts.get_mut(key) {
+                Some(count) if *count > 1 => {
+                    *count -= 1;
+                    false
+                },
+                Some(_) => {
+                    counts.remove(key);
+                    true
+                },
+                None => false
+            })
+            .collect()
+    }
+
+    fn clear(&mut self) {
+        self.counts.clear();
+
+        if let Ok(mut released) = self.released.lock() {
+            released.clear();
+        }
+    }
+}
+
+/// How often `TextureDict::load_or_get` found a texture already loaded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TextureDictStats {
+    pub cache_hits: u64,
+    pub cache_misses: u64
+}
 
 unsafe impl Send for TextureDict {}
 unsafe impl Sync for TextureDict {}
 
+/// Textures a scene loaded or found in the TextureDict while it loaded, from `begin_scope` to
+/// `end_scope`. They are released when the scope is dropped, which the SceneStack does when the
+/// scene leaves the stack. The TextureDict is in the World, so dropping the scope only queues the
+/// textures. The next call to `TextureDict::remove_released`, which has to be made on the draw
+/// thread, frees the ones no other scope holds.
+#[derive(Debug, Default)]
+pub struct SceneTextureScope {
+    handles: Vec<TextureHandle>,
+    released: ReleasedTextures
+}
+
+impl SceneTextureScope {
+    pub fn handles(&self) -> &[TextureHandle] {
+        &self.handles
+    }
+
+    /// Drops the scope without releasing its textures, so they stay loaded for the rest of the game.
+    pub fn keep(mut self) {
+        self.handles.clear();
+    }
+}
+
+impl Drop for SceneTextureScope {
+    fn drop(&mut self) {
+        if self.handles.is_empty() {
+            return
+        }
+
+        match self.released.lock() {
+            Ok(mut released) => released.extend(self.handles.drain(..).map(|handle| handle.handle)),
+            Err(_e) => {
+                #[cfg(feature = "trace")]
+                error!("Failed to acquire lock for released textures. Textures: {:?} stay loaded", self.handles);
+            }
+        }
+    }
+}
+
 pub const IMAGES_DIR: &str = "images/";
 
 #[derive(Deserialize, Debug, Clone)]
@@ -46,15 +219,6 @@ struct TextureDictJSON {
 }
 
 impl TextureDictLoader {
-    const SAMPLER: Sampler = Sampler {
-        wrap_r: Wrap::ClampToEdge,
-        wrap_s: Wrap::ClampToEdge,
-        wrap_t: Wrap::ClampToEdge,
-        min_filter: MinFilter::Nearest,
-        mag_filter: MagFilter::Nearest,
-        depth_comparison: Some(DepthComparison::Less)
-    };
-
     #[cfg_attr(feature="trace", instrument)]
     pub fn new(file_path: String) -> Self {
         Self {
@@ -62,101 +226,279 @@ impl TextureDictLoader {
         }
     }
 
+    /// Adds the textures in the file to the World's TextureDict, if it has one, so textures loaded
+    /// by earlier scenes are not uploaded again.
     #[cfg_attr(feature="trace", instrument)]
     pub fn load(self) -> DrawTask<TextureDict> {
         let path = self.path.clone();
 
-        DrawTask::new(move |(_, context)| {
-            let json: TextureDictJSON = load_deserializable_from_file(&path, TEXTURE_DICT_LOAD_ID)
-                .map_err(|e| {
-                    #[cfg(feature = "trace")]
-                    error!("Failed to deserialize file: ({:?}) into TextureDict JSON value", path.clone());
-
-                    TextureDictFileLoadError {
-                        path: path.clone(),
-                        source: e
-                    }
-                })?;
+        DrawTask::new(move |(ecs, context)| {
+            let json = load_texture_dict_json(&path)?;
 
             #[cfg(feature="trace")]
             trace!("ImageDictJSON: ({:#?}) successfully loaded from: {:#?}", json.clone(), path.clone());
 
-            let mut texture_dict = HashMap::new();
-
-            let mut ctx = context.write()
+            let mut texture_dict = ecs.write()
                 .map_err(|_e| {
                     #[cfg(feature = "trace")]
                     error!("Failed to acquire write lock for World");
 
                     WorldWriteLockError
+                })?
+                .remove::<TextureDict>()
+                .unwrap_or_default();
+
+            let mut ctx = context.write()
+                .map_err(|_e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to acquire write lock for context");
+
+                    ContextWriteLockError
                 })?;
 
             for (image_name, image_path) in json.textures {
                 #[cfg(feature="trace")]
-                debug!("Adding {:#?} at {:#?} to new TextureDict", image_name.clone(), image_path.clone());
+                debug!("Adding {:#?} at {:#?} to TextureDict", image_name.clone(), image_path.clone());
 
-                let dynamic_image = Reader::open(image_path.clone())?
-                    .decode()?;
-                let rgb_image = dynamic_image
-                    .into_rgba8();
+                texture_dict.load_or_get(&image_name, &image_path, ctx.deref_mut())?;
+            }
 
-                #[cfg(feature = "trace")]
-                debug!("Loaded image from file: ({:?}). Converted to rgb_image", image_path.clone());
-
-                let rgb_image_rev: Vec<u8> = rgb_image.rows()
-                    // Reverse the contents of each row a.k.a mirror it
-                    // and get rid of the Rev iter layer using flat_map instead of map
-                    .flat_map(|row| {
-                        row.rev()
-                    })
-                    // Reverse all the rows a.k.a flip upside down
-                    .rev()
-                    // Flat_map expects an iter as the return value and automatically flattens it
-                    // so we can use it as another way to convert a vec of pixels into the raw bytes
-                    .flat_map(|pixel| {
-                        pixel.0
-                    })
-                    .collect();
+            #[cfg(feature = "trace")]
+            debug!("Loaded and returning TextureDict. Keys: {:?}", texture_dict.0.keys());
 
-                #[cfg(feature = "trace")]
-                debug!("Image reversed for texture and converted into raw bytes.");
+            Ok(texture_dict)
+        })
+    }
+}
 
-                let (x, y) = rgb_image.dimensions();
-                #[cfg(feature = "trace")]
-                debug!("Image dimensions: ({:?}, {:?})", x, y);
-
-                let texture = Texture::new_raw(
-                    ctx.deref_mut(),
-                    [x, y],
-                    0,
-                    Self::SAMPLER,
-                    GenMipmaps::Yes,
-                    &rgb_image_rev
-                ).map_err(|e| {
-                    #[cfg(feature = "trace")]
-                    error!("Failed to create texture from image. Name: ({:?}). Path: {:?}", image_name.clone(), image_path.clone());
+fn load_texture_dict_json(path: &str) -> Result<TextureDictJSON, TextureDictError> {
+    load_deserializable_from_file(path, TEXTURE_DICT_LOAD_ID)
+        .map_err(|e| {
+            #[cfg(feature = "trace")]
+            error!("Failed to deserialize file: ({:?}) into TextureDict JSON value", path);
 
-                    return e
-                })?;
+            TextureDictFileLoadError {
+                path: path.to_string(),
+                source: e
+            }
+        })
+}
 
-                #[cfg(feature = "trace")]
-                debug!("Texture created.");
+/// Reloads every texture in the texture dict file at `path` when the file, or one of the images
+/// it lists when `watch` is called, changes. The textures are replaced in the World's TextureDict.
+/// A failed reload keeps the textures already loaded.
+#[cfg(feature = "hot_reload")]
+#[cfg_attr(feature = "trace", instrument(skip(ecs)))]
+pub fn watch(path: &str, ecs: &Arc<RwLock<World>>) -> Result<(), TextureDictError> {
+    let json = load_texture_dict_json(path)?;
+
+    let watched_paths = std::iter::once(path.to_string())
+        .chain(json.textures.values().cloned());
 
-                texture_dict.insert(image_name, texture);
+    for watched_path in watched_paths {
+        let dict_path = path.to_string();
 
+        hot_reload::watch(&watched_path, ecs, Box::new(move |ecs| {
+            if let Err(_e) = reload_texture_dict(&dict_path, &ecs) {
                 #[cfg(feature = "trace")]
-                debug!("Texture inserted into texture_dict");
+                error!("Failed to reload textures from: {:?}. Keeping the loaded textures. Error: {:?}", dict_path, _e);
             }
+        })).map_err(|e| HotReloadFailed {
+            path: watched_path.clone(),
+            source: e
+        })?;
+    }
+
+    Ok(())
+}
 
+#[cfg(feature = "hot_reload")]
+#[cfg_attr(feature = "trace", instrument(skip(ecs)))]
+fn reload_texture_dict(path: &str, ecs: &Arc<RwLock<World>>) -> Result<(), TextureDictError> {
+    let json = load_texture_dict_json(path)?;
+
+    let context = hot_reload::primary_context(ecs)
+        .map_err(|e| HotReloadFailed {
+            path: path.to_string(),
+            source: e
+        })?;
+
+    let world = ecs.write()
+        .map_err(|_e| {
             #[cfg(feature = "trace")]
-            debug!("Loaded and returning TextureDict. Keys: {:?}", texture_dict.keys());
+            error!("Failed to acquire write lock for World");
 
-            return Ok(TextureDict(texture_dict))
-        })
+            WorldWriteLockError
+        })?;
+
+    let mut texture_dict = match world.try_fetch_mut::<TextureDict>() {
+        Some(texture_dict) => texture_dict,
+        None => return Ok(())
+    };
+
+    let mut ctx = context.write()
+        .map_err(|_e| {
+            #[cfg(feature = "trace")]
+            error!("Failed to acquire write lock for context");
+
+            ContextWriteLockError
+        })?;
+
+    for (image_name, image_path) in json.textures {
+        #[cfg(feature = "trace")]
+        debug!("Reloading {:?} from {:?}", image_name, image_path);
+
+        texture_dict.reload(&image_name, &image_path, ctx.deref_mut())?;
+    }
+
+    Ok(())
+}
+
+/// Starts a scope on the World's TextureDict, adding an empty dict if it has none so the textures
+/// of a TextureDictLoader are recorded as well. Call `end_scope` once the scene is loaded.
+#[cfg_attr(feature = "trace", instrument(skip(ecs)))]
+pub fn begin_scope(ecs: &Arc<RwLock<World>>) {
+    match ecs.write() {
+        Ok(mut world) => world.entry::<TextureDict>()
+            .or_insert_with(TextureDict::default)
+            .begin_scope(),
+        Err(_e) => {
+            #[cfg(feature = "trace")]
+            error!("Failed to acquire write lock for World. Textures of the scene won't be released");
+        }
     }
 }
 
+/// Ends the scope `begin_scope` started on the World's TextureDict.
+#[cfg_attr(feature = "trace", instrument(skip(ecs)))]
+pub fn end_scope(ecs: &Arc<RwLock<World>>) -> SceneTextureScope {
+    ecs.read()
+        .ok()
+        .and_then(|world| world.try_fetch_mut::<TextureDict>()
+            .map(|mut texture_dict| texture_dict.end_scope()))
+        .unwrap_or_default()
+}
+
 impl TextureDict {
+    /// Uploads the image at `path` as texture `name` unless a texture with that name is already loaded.
+    #[cfg_attr(feature = "trace", instrument(skip(self, ctx)))]
+    pub fn load_or_get(&mut self, name: &str, path: &str, ctx: &mut GL33Context) -> Result<(), TextureDictError> {
+        let key = TextureKey::new(name.to_string());
+        let loaded = self.2.load_or_get(&mut self.0, key, || Self::upload(name, path, ctx))?;
+
+        if loaded {
+            #[cfg(feature = "trace")]
+            debug!("Texture: {:?} is already loaded", name);
+
+            self.1.cache_hits += 1;
+        } else {
+            self.1.cache_misses += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Uploads the image at `path` as texture `name`, replacing the texture already loaded under
+    /// that name.
+    #[cfg_attr(feature = "trace", instrument(skip(self, ctx)))]
+    pub fn reload(&mut self, name: &str, path: &str, ctx: &mut GL33Context) -> Result<(), TextureDictError> {
+        let texture = Self::upload(name, path, ctx)?;
+        self.0.insert(TextureKey::new(name.to_string()), texture);
+
+        Ok(())
+    }
+
+    fn upload(name: &str, path: &str, ctx: &mut GL33Context) -> Result<Texture<Dim2, RGBA8UI>, TextureDictError> {
+        let rgb_image = Reader::open(path)
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to open image file at path: {:?}", path);
+
+                ImageOpenError {
+                    path: path.to_string(),
+                    source: e
+                }
+            })?
+            .decode()
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to decode image at path: {:?}", path);
+
+                ImageDecodeError {
+                    path: path.to_string(),
+                    source: e
+                }
+            })?
+            .into_rgba8();
+
+        #[cfg(feature = "trace")]
+        debug!("Loaded image from file: ({:?}). Converted to rgb_image", path);
+
+        let rgb_image_rev: Vec<u8> = rgb_image.rows()
+            // Reverse the contents of each row a.k.a mirror it
+            // and get rid of the Rev iter layer using flat_map instead of map
+            .flat_map(|row| {
+                row.rev()
+            })
+            // Reverse all the rows a.k.a flip upside down
+            .rev()
+            // Flat_map expects an iter as the return value and automatically flattens it
+            // so we can use it as another way to convert a vec of pixels into the raw bytes
+            .flat_map(|pixel| {
+                pixel.0
+            })
+            .collect();
+
+        let (x, y) = rgb_image.dimensions();
+        #[cfg(feature = "trace")]
+        debug!("Image dimensions: ({:?}, {:?})", x, y);
+
+        Texture::new_raw(
+            ctx,
+            [x, y],
+            0,
+            TextureHandle::SAMPLER,
+            GenMipmaps::No,
+            &rgb_image_rev
+        ).map_err(|e| {
+            #[cfg(feature = "trace")]
+            error!("Failed to create texture from image. Name: ({:?}). Path: {:?}", name, path);
+
+            TextureCreationError {
+                name: name.to_string(),
+                source: e
+            }
+        })
+    }
+
+    pub fn stats(&self) -> TextureDictStats {
+        self.1
+    }
+
+    /// Names of the loaded textures.
+    pub fn keys(&self) -> impl Iterator<Item = &TextureKey> {
+        self.0.keys()
+    }
+
+    /// Starts recording the textures `load_or_get` and `insert` are called with for the scope
+    /// `end_scope` returns. Ones already loaded are only recorded if another scope holds them.
+    pub fn begin_scope(&mut self) {
+        self.2.begin_scope();
+    }
+
+    /// Scope holding every texture recorded since `begin_scope`.
+    pub fn end_scope(&mut self) -> SceneTextureScope {
+        self.2.end_scope()
+    }
+
+    /// Releases the textures of every SceneTextureScope dropped since the last call and removes
+    /// the ones no scope holds anymore. Frees them on the GPU, so it must be called on the draw
+    /// thread.
+    #[cfg_attr(feature = "trace", instrument(skip(self)))]
+    pub fn remove_released(&mut self) {
+        self.2.remove_unused(&mut self.0);
+    }
+
     #[cfg_attr(feature = "trace", instrument(skip(self)))]
     pub fn contains_key(&self, key: &TextureHandle) -> bool {
         self.0.contains_key(&key.handle)
@@ -174,7 +516,19 @@ impl TextureDict {
 
     #[cfg_attr(feature = "trace", instrument(skip(self, value)))]
     pub fn insert(&mut self, key: &TextureHandle, value: Texture<Dim2,RGBA8UI>) -> Option<Texture<Dim2,RGBA8UI>> {
-        self.0.insert(key.handle.clone(), value)
+        self.2.insert(&mut self.0, key.handle.clone(), value)
+    }
+
+    #[cfg_attr(feature = "trace", instrument(skip(self)))]
+    pub fn remove(&mut self, key: &TextureHandle) -> Option<Texture<Dim2, RGBA8UI>> {
+        self.0.remove(&key.handle)
+    }
+
+    /// Removes every texture. The counts of the SceneTextureScopes holding them are forgotten too.
+    #[cfg_attr(feature = "trace", instrument(skip(self)))]
+    pub fn clear(&mut self) {
+        self.0.clear();
+        self.2.clear();
     }
 }
 
@@ -183,7 +537,7 @@ pub enum TextureDictError {
     #[error("Error loading JSON Value for ImageDictLoader from: {path}")]
     TextureDictFileLoadError {
         path: String,
-        source: LoadError
+        source: anyhow::Error
     },
 
     #[error("Could not convert Path ref to str")]
@@ -195,5 +549,151 @@ pub enum TextureDictError {
         image_path: String
     },
     #[error("Failed to acquire write lock for World")]
-    WorldWriteLockError
-}
\ No newline at end of file
+    WorldWriteLockError,
+
+    #[error("Failed to acquire write lock for context")]
+    ContextWriteLockError,
+
+    #[error("Failed to open image file at path: {path}")]
+    ImageOpenError {
+        path: String,
+        source: std::io::Error
+    },
+
+    #[error("Failed to decode image at path: {path}")]
+    ImageDecodeError {
+        path: String,
+        source: image::ImageError
+    },
+
+    #[error("Failed to create texture: {name}")]
+    TextureCreationError {
+        name: String,
+        source: luminance_front::texture::TextureError
+    },
+
+    #[cfg(feature = "hot_reload")]
+    #[error("Hot reload of: {path} failed")]
+    HotReloadFailed {
+        path: String,
+        source: HotReloadError
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(name: &str) -> TextureKey {
+        TextureKey::new(name.to_string())
+    }
+
+    /// Scope recording `names`, as if a scene loaded or found them.
+    fn scope(counts: &mut TextureRefCounts, names: &[&str]) -> SceneTextureScope {
+        counts.begin_scope();
+        for name in names {
+            counts.record(&key(name));
+        }
+
+        counts.end_scope()
+    }
+
+    #[test]
+    fn texture_is_unused_once_its_only_scope_is_dropped() {
+        let mut counts = TextureRefCounts::default();
+        let level = scope(&mut counts, &["player", "grass"]);
+
+        assert!(counts.take_unused().is_empty());
+
+        drop(level);
+        let mut unused = counts.take_unused();
+        unused.sort_by_key(|key| key.to_string());
+
+        assert_eq!(unused, vec![key("grass"), key("player")]);
+    }
+
+    #[test]
+    fn shared_texture_stays_until_every_scope_is_dropped() {
+        let mut counts = TextureRefCounts::default();
+        let level = scope(&mut counts, &["player", "grass"]);
+        // Found in the dict already, like a cache hit in load_or_get.
+        let menu = scope(&mut counts, &["player"]);
+
+        drop(level);
+        assert_eq!(counts.take_unused(), vec![key("grass")]);
+
+        drop(menu);
+        assert_eq!(counts.take_unused(), vec![key("player")]);
+    }
+
+    #[test]
+    fn new_scope_taken_before_the_old_one_is_dropped_keeps_its_textures() {
+        let mut counts = TextureRefCounts::default();
+        let old_scene = scope(&mut counts, &["player", "grass"]);
+        let new_scene = scope(&mut counts, &["player"]);
+
+        drop(old_scene);
+
+        assert_eq!(counts.take_unused(), vec![key("grass")]);
+        assert_eq!(new_scene.handles().len(), 1);
+    }
+
+    #[test]
+    fn textures_recorded_outside_a_scope_are_never_released() {
+        let mut counts = TextureRefCounts::default();
+        counts.record(&key("font_atlas"));
+
+        drop(scope(&mut counts, &[]));
+
+        assert!(counts.take_unused().is_empty());
+    }
+
+    #[test]
+    fn texture_loaded_outside_a_scope_stays_after_a_scope_reusing_it_is_dropped() {
+        let mut counts = TextureRefCounts::default();
+        let mut textures = HashMap::new();
+        counts.load_or_get(&mut textures, key("font_atlas"), || Ok::<_, ()>(())).unwrap();
+
+        counts.begin_scope();
+        let hit = counts.load_or_get(&mut textures, key("font_atlas"), || Ok::<_, ()>(())).unwrap();
+        counts.load_or_get(&mut textures, key("grass"), || Ok::<_, ()>(())).unwrap();
+        let level = counts.end_scope();
+
+        assert!(hit);
+
+        drop(level);
+        counts.remove_unused(&mut textures);
+
+        assert!(textures.contains_key(&key("font_atlas")));
+        assert!(!textures.contains_key(&key("grass")));
+    }
+
+    #[test]
+    fn texture_loaded_in_a_scope_is_counted_when_another_scope_reuses_it() {
+        let mut counts = TextureRefCounts::default();
+        let mut textures = HashMap::new();
+
+        counts.begin_scope();
+        counts.insert(&mut textures, key("player"), ());
+        let level = counts.end_scope();
+
+        counts.begin_scope();
+        counts.insert(&mut textures, key("player"), ());
+        let menu = counts.end_scope();
+
+        drop(level);
+        counts.remove_unused(&mut textures);
+        assert!(textures.contains_key(&key("player")));
+
+        drop(menu);
+        counts.remove_unused(&mut textures);
+        assert!(!textures.contains_key(&key("player")));
+    }
+
+    #[test]
+    fn kept_scope_never_releases_its_textures() {
+        let mut counts = TextureRefCounts::default();
+        scope(&mut counts, &["title"]).keep();
+
+        assert!(counts.take_unused().is_empty());
+    }
+}
@@ -0,0 +1,26 @@
+// Model matrix of the instance, one column per attribute.
+in vec4 model_col0;
+in vec4 model_col1;
+in vec4 model_col2;
+in vec4 model_col3;
+// Part of the texture drawn on the quad as (min.x, min.y, max.x, max.y) in UV space.
+in vec4 uv_bounds;
+
+uniform mat4 projection;
+uniform mat4 view;
+
+out vec2 v_uv;
+
+const vec2[4] QUAD_POS = vec2[](
+  vec2(-1., -1.),
+  vec2( 1., -1.),
+  vec2( 1.,  1.),
+  vec2(-1.,  1.)
+);
+
+void main() {
+  vec2 p = QUAD_POS[gl_VertexID];
+  mat4 model = mat4(model_col0, model_col1, model_col2, model_col3);
+  gl_Position = projection * view * model * vec4(p, 0., 1.);
+  v_uv = mix(uv_bounds.xy, uv_bounds.zw, p * .5 + .5);
+}
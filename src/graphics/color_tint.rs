@@ -0,0 +1,10 @@
+/// Color the SpriteRenderer multiplies every sprite by. Sprite shaders apply it through their
+/// `tint` uniform. The SceneStack lowers the alpha while fading between scenes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorTint(pub [f32; 4]);
+
+impl Default for ColorTint {
+    fn default() -> Self {
+        Self([1.0, 1.0, 1.0, 1.0])
+    }
+}
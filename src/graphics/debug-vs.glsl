@@ -0,0 +1,16 @@
+uniform mat4 projection;
+uniform mat4 view;
+uniform mat4 model;
+
+// Corners of the sprite quad, ending on the first one again to close the line strip.
+const vec2[5] OUTLINE_POS = vec2[](
+  vec2(-1., -1.),
+  vec2( 1., -1.),
+  vec2( 1.,  1.),
+  vec2(-1.,  1.),
+  vec2(-1., -1.)
+);
+
+void main() {
+  gl_Position = projection * view * model * vec4(OUTLINE_POS[gl_VertexID], 0., 1.);
+}
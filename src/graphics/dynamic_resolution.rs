@@ -0,0 +1,68 @@
+use std::time::Duration;
+
+use specs::{World, WorldExt};
+
+#[cfg(feature = "trace")]
+use tracing::{debug, instrument};
+
+/// Amount the scale drops when a frame goes over budget.
+pub const SCALE_DECREASE: f32 = 0.1;
+/// Amount the scale rises when a frame finishes well under budget.
+pub const SCALE_INCREASE: f32 = 0.05;
+/// Fraction of the budget a frame must stay under to count as well under budget.
+pub const HEADROOM: f32 = 0.75;
+
+/// Internal render resolution as a fraction of the back buffer's, adjusted after every draw to keep the
+/// draw time within `target_frame_budget`. The `sprites` node of `RenderGraph::with_default_nodes`
+/// draws at this scale. Scenes drawn without a render graph always draw at full resolution.
+#[derive(Debug, Clone, Copy)]
+pub struct DynamicResolution {
+    pub current_scale: f32,
+    pub min_scale: f32,
+    pub max_scale: f32,
+    pub target_frame_budget: Duration
+}
+
+impl Default for DynamicResolution {
+    fn default() -> Self {
+        Self {
+            current_scale: 1.0,
+            min_scale: 0.5,
+            max_scale: 1.0,
+            target_frame_budget: Duration::from_secs_f64(1.0 / 60.0)
+        }
+    }
+}
+
+impl DynamicResolution {
+    /// Returns true if `current_scale` changed.
+    #[cfg_attr(feature = "trace", instrument)]
+    pub fn adjust(&mut self, draw_time: Duration) -> bool {
+        let previous = self.current_scale;
+
+        if draw_time > self.target_frame_budget {
+            self.current_scale = (self.current_scale - SCALE_DECREASE).max(self.min_scale);
+        } else if draw_time.as_secs_f32() < self.target_frame_budget.as_secs_f32() * HEADROOM {
+            self.current_scale = (self.current_scale + SCALE_INCREASE).min(self.max_scale);
+        }
+
+        #[cfg(feature = "trace")]
+        debug!("Draw took {:?}. Render scale went from {:?} to {:?}", draw_time, previous, self.current_scale);
+
+        (self.current_scale - previous).abs() > f32::EPSILON
+    }
+}
+
+/// Runs after the draw call with the time it took. Does nothing unless the World has a DynamicResolution.
+pub struct DynamicResolutionSystem;
+
+impl DynamicResolutionSystem {
+    #[cfg_attr(feature = "trace", instrument(skip(ecs)))]
+    pub fn run(ecs: &mut World, draw_time: Duration) -> bool {
+        if !ecs.has_value::<DynamicResolution>() {
+            return false
+        }
+
+        ecs.fetch_mut::<DynamicResolution>().adjust(draw_time)
+    }
+}
@@ -0,0 +1,14 @@
+uniform mat4 projection;
+uniform mat4 view;
+uniform mat4 model;
+
+const vec2[4] QUAD_POS = vec2[](
+  vec2(-1., -1.),
+  vec2( 1., -1.),
+  vec2( 1.,  1.),
+  vec2(-1.,  1.)
+);
+
+void main() {
+  gl_Position = projection * view * model * vec4(QUAD_POS[gl_VertexID], 0., 1.);
+}
\ No newline at end of file
@@ -0,0 +1,130 @@
+use specs::{Component, VecStorage, DenseVecStorage, System, Entities, ReadStorage, WriteStorage, Read, Join};
+
+#[cfg(feature = "trace")]
+use tracing::{debug, instrument};
+
+use crate::graphics::transform::Transform;
+
+/// The entity's Transform as it was before the latest update. Only entities with this component
+/// are interpolated.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PreviousTransform(pub Transform);
+
+impl Component for PreviousTransform { type Storage = VecStorage<Self>; }
+crate::register_component!(PreviousTransform);
+
+/// Transform the SpriteRenderer draws the entity with in place of its Transform.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RenderTransform(pub Transform);
+
+impl Component for RenderTransform { type Storage = VecStorage<Self>; }
+crate::register_component!(RenderTransform);
+
+/// How far the draw is between the previous update and the latest one, from 0 to 1. The GameLoop
+/// sets it before each draw to the part of the frame period left over after the update.
+#[derive(Debug, Clone, Copy)]
+pub struct RenderAlpha(pub f32);
+
+impl Default for RenderAlpha {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// Eases the entity's Transform towards `target`. Each second it covers `speed` times the
+/// distance left, so it slows down as it arrives.
+#[derive(Debug, Clone, Copy)]
+pub struct TransformInterpolator {
+    pub target: Transform,
+    pub speed: f32
+}
+
+impl Component for TransformInterpolator { type Storage = DenseVecStorage<Self>; }
+crate::register_component!(TransformInterpolator);
+
+impl TransformInterpolator {
+    pub fn new(target: Transform, speed: f32) -> Self {
+        Self {
+            target,
+            speed
+        }
+    }
+}
+
+/// Moves every Transform with a TransformInterpolator towards its target by `delta_time` seconds.
+#[derive(Debug, Clone, Copy)]
+pub struct InterpolateTransforms {
+    pub delta_time: f32
+}
+
+impl<'a> System<'a> for InterpolateTransforms {
+    type SystemData = (
+        ReadStorage<'a, TransformInterpolator>,
+        WriteStorage<'a, Transform>
+    );
+
+    #[cfg_attr(feature = "trace", instrument(skip(self, data)))]
+    fn run(&mut self, data: Self::SystemData) {
+        let (interpolators, mut transforms) = data;
+
+        for (interpolator, transform) in (&interpolators, &mut transforms).join() {
+            // Large steps would overshoot the target.
+            let t = (self.delta_time * interpolator.speed).clamp(0.0, 1.0);
+
+            *transform = transform.lerp(&interpolator.target, t)
+                .slerp_rotation(&interpolator.target, t);
+        }
+    }
+}
+
+/// Saves every interpolated entity's Transform. Run it before the systems that move entities.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PreviousTransformSystem;
+
+impl<'a> System<'a> for PreviousTransformSystem {
+    type SystemData = (
+        ReadStorage<'a, Transform>,
+        WriteStorage<'a, PreviousTransform>
+    );
+
+    #[cfg_attr(feature = "trace", instrument(skip(self, data)))]
+    fn run(&mut self, data: Self::SystemData) {
+        let (transforms, mut previous_transforms) = data;
+
+        for (transform, previous) in (&transforms, &mut previous_transforms).join() {
+            previous.0 = *transform;
+        }
+    }
+}
+
+/// Blends each entity's PreviousTransform and Transform by the RenderAlpha into its RenderTransform.
+/// Run it at the start of each draw.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TransformInterpolationSystem;
+
+impl<'a> System<'a> for TransformInterpolationSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, Transform>,
+        ReadStorage<'a, PreviousTransform>,
+        WriteStorage<'a, RenderTransform>,
+        Read<'a, RenderAlpha>
+    );
+
+    #[cfg_attr(feature = "trace", instrument(skip(self, data)))]
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, transforms, previous_transforms, mut render_transforms, alpha) = data;
+        let alpha = alpha.0.clamp(0.0, 1.0);
+
+        #[cfg(feature = "trace")]
+        debug!("Interpolating transforms with alpha: {:?}", alpha);
+
+        for (entity, transform, previous) in (&entities, &transforms, &previous_transforms).join() {
+            let render_transform = previous.0.lerp(transform, alpha)
+                .slerp_rotation(transform, alpha);
+
+            // Only fails for dead entities and every joined entity is alive.
+            let _ = render_transforms.insert(entity, RenderTransform(render_transform));
+        }
+    }
+}
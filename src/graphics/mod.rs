@@ -1,15 +1,141 @@
+#[cfg(feature = "trace")]
+use tracing::{debug, error, instrument};
+
 use anyhow::Result;
 use luminance_glfw::GL33Context;
+use image::ImageError;
+use image::io::Reader;
+use std::sync::{Arc, RwLock};
+use thiserror::Error;
+
+use crate::graphics::IconError::{ContextWriteLockError, ReaderFailedToOpen, DecodeError};
+use crate::components::cursor_icon::CursorType;
 
 pub mod texture;
 pub mod render;
 pub mod transform;
 pub mod shader;
 pub mod tess;
+pub mod window_registry;
+pub mod dynamic_resolution;
+pub mod interpolation;
+pub mod color_tint;
+pub mod render_graph;
 
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
 pub struct Handle(pub String);
 
+/// Window icons need GLFW 3.2 or later.
+const ICON_MIN_GLFW_VERSION: (u64, u64) = (3, 2);
+
+/// Sets the icon of the context's window to the image at `icon_path`. Does nothing if the GLFW
+/// library is too old to support window icons.
+#[cfg_attr(feature = "trace", instrument(skip(context)))]
+pub fn set_window_icon(context: &Arc<RwLock<GL33Context>>, icon_path: &str) -> Result<(), IconError> {
+    let version = glfw::get_version();
+    if (version.major, version.minor) < ICON_MIN_GLFW_VERSION {
+        #[cfg(feature = "trace")]
+        debug!("GLFW version: {:?} does not support window icons. Skipping.", version);
+
+        return Ok(())
+    }
+
+    let image = Reader::open(icon_path)
+        .map_err(|e| {
+            #[cfg(feature = "trace")]
+            error!("Failed to open icon file at path: {:?}", icon_path);
+
+            ReaderFailedToOpen {
+                path: icon_path.to_string(),
+                source: e
+            }
+        })?
+        .decode()
+        .map_err(|e| {
+            #[cfg(feature = "trace")]
+            error!("Failed to decode icon file at path: {:?}", icon_path);
+
+            DecodeError {
+                path: icon_path.to_string(),
+                source: e
+            }
+        })?
+        .into_rgba8();
+
+    let (width, height) = image.dimensions();
+    let pixels = image.pixels()
+        .map(|pixel| u32::from_le_bytes(pixel.0))
+        .collect();
+
+    context.write()
+        .map_err(|_e| {
+            #[cfg(feature = "trace")]
+            error!("Failed to acquire write lock for context");
+
+            ContextWriteLockError
+        })?
+        .window
+        .set_icon_from_pixels(vec![glfw::PixelImage { width, height, pixels }]);
+
+    Ok(())
+}
+
+/// Shows the standard `cursor_type` cursor while the mouse is over the context's window.
+#[cfg_attr(feature = "trace", instrument(skip(context)))]
+pub fn set_cursor(context: &Arc<RwLock<GL33Context>>, cursor_type: CursorType) -> Result<(), CursorError> {
+    context.write()
+        .map_err(|_e| {
+            #[cfg(feature = "trace")]
+            error!("Failed to acquire write lock for context");
+
+            CursorError::ContextWriteLockError
+        })?
+        .window
+        .set_cursor(Some(glfw::Cursor::standard(cursor_type.into())));
+
+    Ok(())
+}
+
+/// Goes back to the system's default cursor.
+#[cfg_attr(feature = "trace", instrument(skip(context)))]
+pub fn restore_default_cursor(context: &Arc<RwLock<GL33Context>>) -> Result<(), CursorError> {
+    context.write()
+        .map_err(|_e| {
+            #[cfg(feature = "trace")]
+            error!("Failed to acquire write lock for context");
+
+            CursorError::ContextWriteLockError
+        })?
+        .window
+        .set_cursor(None);
+
+    Ok(())
+}
+
+#[derive(Error, Debug)]
+pub enum CursorError {
+    #[error("Failed to acquire write lock for context")]
+    ContextWriteLockError
+}
+
+#[derive(Error, Debug)]
+pub enum IconError {
+    #[error("Failed to acquire write lock for context")]
+    ContextWriteLockError,
+
+    #[error("Failed to open icon file at path: {path}")]
+    ReaderFailedToOpen {
+        path: String,
+        source: std::io::Error
+    },
+
+    #[error("Failed to decode icon file at path: {path}")]
+    DecodeError {
+        path: String,
+        source: ImageError
+    }
+}
+
 // pub(crate) fn draw(context: &mut GL33Context) -> Result<()> {
 //     let back_buffer = context.back_buffer()?;
 // }
\ No newline at end of file
@@ -0,0 +1,39 @@
+in vec2 v_uv;
+out vec4 frag;
+
+uniform usampler2D tex;
+uniform vec4 color;
+uniform float thickness;
+uniform vec2 grow;
+
+float alpha_at(vec2 uv) {
+    if (any(lessThan(uv, vec2(0.))) || any(greaterThan(uv, vec2(1.)))) {
+        return 0.;
+    }
+
+    return float(texture(tex, uv).a) / 255.;
+}
+
+void main() {
+    // The quad is drawn larger than the sprite so the ring has room outside of it.
+    vec2 uv = (v_uv - .5) * grow + .5;
+
+    if (alpha_at(uv) > 0.) {
+        discard;
+    }
+
+    vec2 texel = thickness / vec2(textureSize(tex, 0));
+    float neighbor = 0.;
+
+    for (int x = -1; x <= 1; x++) {
+        for (int y = -1; y <= 1; y++) {
+            neighbor = max(neighbor, alpha_at(uv + vec2(x, y) * texel));
+        }
+    }
+
+    if (neighbor == 0.) {
+        discard;
+    }
+
+    frag = color;
+}
@@ -0,0 +1,9 @@
+in vec2 position;
+in vec2 uv;
+
+out vec2 v_uv;
+
+void main() {
+  gl_Position = vec4(position, 0., 1.);
+  v_uv = uv;
+}
\ No newline at end of file
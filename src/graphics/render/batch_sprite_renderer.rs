@@ -0,0 +1,399 @@
+use luminance_front::{
+    render_state::RenderState,
+    tess::{Tess, TessView, Mode, Interleaved, TessError, TessMapError},
+    shader::{Uniform, Program, ProgramError},
+    pipeline::{TextureBinding, Pipeline},
+    shading_gate::ShadingGate,
+    texture::Dim2,
+    pixel::Unsigned,
+    context::GraphicsContext,
+};
+use luminance_derive::{Semantics, Vertex, UniformInterface};
+use luminance_glfw::GL33Context;
+
+use specs::{World, Join};
+
+use glam::Mat4;
+
+use std::ops::DerefMut;
+
+use thiserror::Error;
+
+#[cfg(feature = "trace")]
+use tracing::{debug, error, instrument, warn};
+
+use crate::globals::texture_dict::TextureKey;
+use crate::graphics::color_tint::ColorTint;
+use crate::graphics::render::{Renderer, ShaderTypes};
+use crate::graphics::render::batch_sprite_renderer::BatchSpriteRendererError::{ContextWriteError, RenderStateLoadError, ShaderProgramBuildError, TessBuildError, ZeroBatchSize};
+use crate::graphics::render::deserializations::{RenderStateDef, RENDER_STATE_LOAD_ID};
+use crate::graphics::render::sprite_renderer::{default_sprite_render_state, SpriteData, SpriteRenderError};
+use crate::graphics::render::sprite_renderer::SpriteRenderError::{CameraDNE, FailedToBind, InstanceBufferError, RenderGateError, TessRenderError};
+use crate::graphics::texture::TextureHandle;
+use crate::load::load_deserializable_from_file;
+use crate::loading::DrawTask;
+
+const VS: &str = include_str!("../batch-sprite-vs.glsl");
+const FS: &str = include_str!("../texture-fs.glsl");
+
+/// Sprites drawn by each instanced draw call unless `BatchSpriteRenderer::new` is given another size.
+pub const DEFAULT_BATCH_SIZE: usize = 1024;
+
+#[derive(Copy, Clone, Debug, Semantics)]
+pub enum SpriteInstanceSemantics {
+    #[sem(name = "model_col0", repr = "[f32; 4]", wrapper = "ModelCol0")]
+    ModelCol0,
+    #[sem(name = "model_col1", repr = "[f32; 4]", wrapper = "ModelCol1")]
+    ModelCol1,
+    #[sem(name = "model_col2", repr = "[f32; 4]", wrapper = "ModelCol2")]
+    ModelCol2,
+    #[sem(name = "model_col3", repr = "[f32; 4]", wrapper = "ModelCol3")]
+    ModelCol3,
+    #[sem(name = "uv_bounds", repr = "[f32; 4]", wrapper = "UVBounds")]
+    UVBounds
+}
+
+/// Model matrix and texture region of one sprite in a batch.
+#[derive(Copy, Clone, Debug, Vertex)]
+#[vertex(sem = "SpriteInstanceSemantics", instanced = "true")]
+pub struct SpriteInstance {
+    pub model_col0: ModelCol0,
+    pub model_col1: ModelCol1,
+    pub model_col2: ModelCol2,
+    pub model_col3: ModelCol3,
+    pub uv_bounds: UVBounds
+}
+
+impl SpriteInstance {
+    pub fn from_model(model: Mat4, uv_min: [f32; 2], uv_max: [f32; 2]) -> Self {
+        let [col0, col1, col2, col3] = model.to_cols_array_2d();
+
+        Self {
+            model_col0: ModelCol0::new(col0),
+            model_col1: ModelCol1::new(col1),
+            model_col2: ModelCol2::new(col2),
+            model_col3: ModelCol3::new(col3),
+            uv_bounds: UVBounds::new([uv_min[0], uv_min[1], uv_max[0], uv_max[1]])
+        }
+    }
+}
+
+#[derive(Debug, UniformInterface)]
+pub struct BatchSpriteShaderUniform {
+    /// PROJECTION matrix in MVP
+    projection: Uniform<[[f32; 4]; 4]>,
+    /// VIEW matrix in MVP
+    view: Uniform<[[f32; 4]; 4]>,
+    /// Texture shared by every sprite in the batch.
+    tex: Uniform<TextureBinding<Dim2, Unsigned>>,
+    /// Color the texture is multiplied by.
+    #[uniform(unbound)]
+    tint: Uniform<[f32; 4]>,
+}
+
+/// Draws sprites in layer order, with one instanced draw call for each run of sprites sharing a
+/// texture instead of one draw call per sprite. Sprites whose Material has a compiled shader are
+/// skipped, which is logged whenever their number changes, so draw those with a SpriteRenderer.
+/// Every sprite is drawn on a quad even if it has an ActiveLOD. Layers that mix textures take more
+/// draw calls, one per change of texture.
+pub struct BatchSpriteRenderer {
+    pub render_state: RenderState,
+    /// Attributeless quad holding `batch_size` instances, refilled for every draw call.
+    tess: Tess<(), (), SpriteInstance, Interleaved>,
+    shader: Program<SpriteInstanceSemantics, (), BatchSpriteShaderUniform>,
+    batch_size: usize,
+    /// Sprites with a material shader skipped in the last frame.
+    skipped_materials: usize
+}
+
+impl ShaderTypes for BatchSpriteRenderer {
+    type Semantics = SpriteInstanceSemantics;
+    type ReturnValue = ();
+    type UniformInterface = BatchSpriteShaderUniform;
+}
+
+impl BatchSpriteRenderer {
+    /// `batch_size` is the most sprites one draw call can draw. Textures used by more sprites are
+    /// drawn with several calls.
+    #[cfg_attr(feature = "trace", instrument(skip(context)))]
+    pub fn new(context: &mut GL33Context, render_state: RenderState, batch_size: usize) -> Result<Self, BatchSpriteRendererError> {
+        if batch_size == 0 {
+            #[cfg(feature = "trace")]
+            error!("BatchSpriteRenderer batch size must be at least 1");
+
+            return Err(ZeroBatchSize)
+        }
+
+        let empty = SpriteInstance::from_model(Mat4::ZERO, [0.0, 0.0], [0.0, 0.0]);
+        let tess = context
+            .new_tess()
+            .set_render_vertex_nb(4)
+            .set_mode(Mode::TriangleFan)
+            .set_instances(vec![empty; batch_size])
+            .build()
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to build instanced quad for batching.");
+
+                TessBuildError {
+                    source: e
+                }
+            })?;
+
+        let shader = context
+            .new_shader_program()
+            .from_strings(VS, None, None, FS)
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to build batch sprite shader program.");
+
+                ShaderProgramBuildError {
+                    source: e
+                }
+            })?
+            .ignore_warnings();
+
+        Ok(Self {
+            render_state,
+            tess,
+            shader,
+            batch_size,
+            skipped_materials: 0
+        })
+    }
+
+    /// Uses `default_sprite_render_state` and `DEFAULT_BATCH_SIZE`.
+    pub fn load_default() -> DrawTask<BatchSpriteRenderer> {
+        DrawTask::new(|(_ecs, context)| {
+            let mut context = context.write()
+                .map_err(|_e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to acquire write lock for context");
+
+                    ContextWriteError
+                })?;
+
+            Ok(BatchSpriteRenderer::new(&mut context, default_sprite_render_state(), DEFAULT_BATCH_SIZE)?)
+        })
+    }
+}
+
+impl Renderer for BatchSpriteRenderer {
+    type S = Self;
+
+    /// `path` is a render state file. The renderer draws `DEFAULT_BATCH_SIZE` sprites per call.
+    #[cfg_attr(feature = "trace", instrument)]
+    fn load(path: String) -> DrawTask<Self> {
+        DrawTask::new(move |(_ecs, context)| {
+            let render_state: RenderStateDef = load_deserializable_from_file(&path, RENDER_STATE_LOAD_ID)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to deserialize Render State from file: {:?}", path.clone());
+
+                    RenderStateLoadError {
+                        path: path.clone(),
+                        source: e
+                    }
+                })?;
+
+            let mut context = context.write()
+                .map_err(|_e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to acquire write lock for context");
+
+                    ContextWriteError
+                })?;
+
+            Ok(BatchSpriteRenderer::new(&mut context, RenderState::from(render_state), DEFAULT_BATCH_SIZE)?)
+        })
+    }
+
+    #[cfg_attr(feature = "trace", instrument(skip(self, pipeline, shd_gate, world)))]
+    fn render(
+        &mut self,
+        pipeline: &Pipeline,
+        shd_gate: &mut ShadingGate,
+        world: &World,
+    ) -> Result<(), SpriteRenderError> {
+        let (textures, transforms, render_transforms, materials, culled, _active_lods, regions, pivots, mut texture_dict, mut camera): SpriteData = world.system_data();
+
+        let camera = camera.as_mut().ok_or(CameraDNE)?;
+        let (view, projection) = (camera.view(), camera.projection());
+        let tint = world.try_fetch::<ColorTint>().map(|tint| *tint).unwrap_or_default();
+
+        let mut sprites = Vec::new();
+        let mut skipped_materials = 0;
+        for (tex_handle, transform, render_transform, material, region, pivot, _) in (&textures, &transforms, render_transforms.maybe(), materials.maybe(), regions.maybe(), pivots.maybe(), !&culled).join() {
+            // Materials without a compiled program are drawn with the default shader, as the
+            // SpriteRenderer does.
+            if material.is_some_and(|material| material.program.is_some()) {
+                skipped_materials += 1;
+                continue
+            }
+
+            let texture = match texture_dict.get(tex_handle) {
+                Some(texture) => texture,
+                None => continue
+            };
+
+            let transform = render_transform.map_or(transform, |render_transform| &render_transform.0);
+            let (uv_min, uv_max) = region.map_or(([0.0, 0.0], [1.0, 1.0]), |region| region.uv_bounds(texture.size()));
+
+            sprites.push((
+                transform.layer(),
+                tex_handle.key().clone(),
+                SpriteInstance::from_model(transform.to_model(pivot.map(|pivot| pivot.offset)), uv_min, uv_max)
+            ));
+        }
+        let batches = batch_in_layer_order(sprites);
+
+        if skipped_materials != self.skipped_materials {
+            #[cfg(feature = "trace")]
+            warn!("BatchSpriteRenderer skipped {:?} sprites with a material shader. Draw them with a SpriteRenderer", skipped_materials);
+
+            self.skipped_materials = skipped_materials;
+        }
+
+        #[cfg(feature = "trace")]
+        debug!("Drawing {:?} sprite batches", batches.len());
+
+        let tess = &mut self.tess;
+        let render_state = &self.render_state;
+        let batch_size = self.batch_size;
+
+        shd_gate.shade(&mut self.shader, |mut iface, uni, mut rdr_gate| {
+            iface.set(&uni.projection, projection.to_cols_array_2d());
+            iface.set(&uni.view, view.to_cols_array_2d());
+            iface.set(&uni.tint, tint.0);
+
+            for (key, instances) in batches.iter() {
+                let tex_handle = TextureHandle::new(key.clone());
+                let texture = match texture_dict.get_mut(&tex_handle) {
+                    Some(texture) => texture,
+                    None => continue
+                };
+
+                let bound_tex = pipeline.bind_texture(texture)
+                    .map_err(|e| {
+                        #[cfg(feature = "trace")]
+                        error!("Failed to bind texture to pipeline.");
+
+                        FailedToBind {
+                            texture: tex_handle.clone(),
+                            source: e
+                        }
+                    })?;
+                iface.set(&uni.tex, bound_tex.binding());
+
+                for chunk in instances.chunks(batch_size) {
+                    tess.instances_mut()
+                        .map_err(|e| {
+                            #[cfg(feature = "trace")]
+                            error!("Failed to map the instance buffer.");
+
+                            InstanceBufferError {
+                                source: e
+                            }
+                        })?
+                        .deref_mut()[..chunk.len()]
+                        .copy_from_slice(chunk);
+
+                    let tess = &*tess;
+                    rdr_gate.render(render_state, |mut tess_gate| {
+                        tess_gate.render(TessView::inst_whole(tess, chunk.len()))
+                            .map_err(|e| {
+                                #[cfg(feature = "trace")]
+                                error!("Failed to call render on tess gate.");
+
+                                TessRenderError {
+                                    source: e
+                                }
+                            })
+                    })
+                        .map_err(|e| {
+                            #[cfg(feature = "trace")]
+                            error!("Failed to call render on render gate.");
+
+                            RenderGateError {
+                                source: Box::new(e)
+                            }
+                        })?;
+                }
+            }
+
+            Ok(())
+        })
+    }
+
+    fn supports_batching(&self) -> bool {
+        true
+    }
+}
+
+/// Sorts `sprites` by layer and groups each run of consecutive sprites with the same texture into
+/// one batch. The depth test always passes, so batches have to be drawn in this order. The sort is
+/// stable, so sprites on the same layer keep their order.
+fn batch_in_layer_order<T>(mut sprites: Vec<(f32, TextureKey, T)>) -> Vec<(TextureKey, Vec<T>)> {
+    sprites.sort_by(|(a, ..), (b, ..)| a.total_cmp(b));
+
+    let mut batches: Vec<(TextureKey, Vec<T>)> = Vec::new();
+    for (_, key, sprite) in sprites {
+        match batches.last_mut() {
+            Some((last_key, batch)) if *last_key == key => batch.push(sprite),
+            _ => batches.push((key, vec![sprite]))
+        }
+    }
+
+    batches
+}
+
+#[derive(Error, Debug)]
+pub enum BatchSpriteRendererError {
+    #[error("Failed to acquire write lock for context")]
+    ContextWriteError,
+
+    #[error("BatchSpriteRenderer batch size must be at least 1")]
+    ZeroBatchSize,
+
+    #[error("Failed to build the instanced quad")]
+    TessBuildError {
+        source: TessError
+    },
+
+    #[error("Failed to build the batch sprite shader program")]
+    ShaderProgramBuildError {
+        source: ProgramError
+    },
+
+    #[error("Failed to deserialize Render State from file: {path}")]
+    RenderStateLoadError {
+        path: String,
+        source: anyhow::Error
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(name: &str) -> TextureKey {
+        TextureKey::new(name.to_string())
+    }
+
+    #[test]
+    fn batches_follow_layer_order_across_textures() {
+        let sprites = vec![
+            (5.0, key("tree"), "tree on 5"),
+            (0.0, key("grass"), "grass on 0"),
+            (0.0, key("grass"), "more grass on 0"),
+            (5.0, key("grass"), "grass on 5"),
+            (1.0, key("tree"), "tree on 1"),
+        ];
+
+        assert_eq!(batch_in_layer_order(sprites), vec![
+            (key("grass"), vec!["grass on 0", "more grass on 0"]),
+            (key("tree"), vec!["tree on 1", "tree on 5"]),
+            (key("grass"), vec!["grass on 5"]),
+        ]);
+    }
+}
@@ -0,0 +1,211 @@
+use luminance_front::{
+    render_state::RenderState,
+    tess::{Tess, Mode, TessError},
+    shader::{Uniform, Program, ProgramError},
+    pipeline::{TextureBinding, PipelineError, PipelineState, Viewport},
+    framebuffer::{Framebuffer, FramebufferError},
+    texture::Dim2,
+    pixel::{NormRGBA8UI, NormUnsigned},
+    scissor::ScissorRegion,
+    context::GraphicsContext,
+};
+use luminance_derive::{Semantics, Vertex, UniformInterface};
+use luminance_glfw::GL33Context;
+
+use thiserror::Error;
+
+#[cfg(feature = "trace")]
+use tracing::{debug, error, instrument};
+
+use crate::graphics::render::blit::BlitError::{BackBufferError, ContextWriteError, ShaderProgramBuildError, TessBuildError, TessRenderError, PipelineRenderError};
+use crate::loading::DrawTask;
+
+const VS: &str = include_str!("../passthrough-vs.glsl");
+const FS: &str = include_str!("../passthrough-fs.glsl");
+
+#[derive(Copy, Clone, Debug, Semantics)]
+pub enum PosUvSemantics {
+    #[sem(name = "position", repr = "[f32; 2]", wrapper = "BlitPosition")]
+    Position,
+    #[sem(name = "uv", repr = "[f32; 2]", wrapper = "BlitUV")]
+    UV
+}
+
+#[derive(Copy, Clone, Debug, Vertex)]
+#[vertex(sem = "PosUvSemantics")]
+pub struct PosUvVertex {
+    pub position: BlitPosition,
+    pub uv: BlitUV
+}
+
+#[derive(Debug, UniformInterface)]
+pub struct PassthroughShaderUniform {
+    /// Color attachment of the framebuffer being copied.
+    tex: Uniform<TextureBinding<Dim2, NormUnsigned>>,
+}
+
+/// Corners of clip space with UVs covering the whole source texture.
+const FULLSCREEN_QUAD: [PosUvVertex; 4] = [
+    PosUvVertex { position: BlitPosition::new([-1.0, -1.0]), uv: BlitUV::new([0.0, 0.0]) },
+    PosUvVertex { position: BlitPosition::new([ 1.0, -1.0]), uv: BlitUV::new([1.0, 0.0]) },
+    PosUvVertex { position: BlitPosition::new([ 1.0,  1.0]), uv: BlitUV::new([1.0, 1.0]) },
+    PosUvVertex { position: BlitPosition::new([-1.0,  1.0]), uv: BlitUV::new([0.0, 1.0]) },
+];
+
+/// Copies the color attachment of a framebuffer into a region of the back buffer. Keep one around
+/// when blitting every frame, since building it compiles the passthrough shader. The attachment is
+/// normalized, unlike loaded textures, because the sprite shaders write float colors.
+pub struct Blitter {
+    tess: Tess<PosUvVertex>,
+    shader: Program<PosUvSemantics, (), PassthroughShaderUniform>
+}
+
+impl Blitter {
+    #[cfg_attr(feature = "trace", instrument(skip(context)))]
+    pub fn new(context: &mut GL33Context) -> Result<Self, BlitError> {
+        let tess = context
+            .new_tess()
+            .set_vertices(FULLSCREEN_QUAD.to_vec())
+            .set_mode(Mode::TriangleFan)
+            .build()
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to build fullscreen quad for blitting.");
+
+                TessBuildError {
+                    source: e
+                }
+            })?;
+
+        let shader = context
+            .new_shader_program()
+            .from_strings(VS, None, None, FS)
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to build passthrough shader program.");
+
+                ShaderProgramBuildError {
+                    source: e
+                }
+            })?
+            .ignore_warnings();
+
+        Ok(Self {
+            tess,
+            shader
+        })
+    }
+
+    pub fn load_default() -> DrawTask<Blitter> {
+        DrawTask::new(|(_ecs, context)| {
+            let mut context = context.write()
+                .map_err(|_e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to acquire write lock for context");
+
+                    ContextWriteError
+                })?;
+
+            Ok(Blitter::new(&mut context)?)
+        })
+    }
+
+    /// Stretches `source` over `target_region` of the back buffer. The rest of the back buffer is
+    /// left as it is.
+    #[cfg_attr(feature = "trace", instrument(skip(self, context, source)))]
+    pub fn blit(&mut self, context: &mut GL33Context, source: &mut Framebuffer<Dim2, NormRGBA8UI, ()>, target_region: ScissorRegion) -> Result<(), BlitError> {
+        let back_buffer = context.back_buffer()
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to get back buffer.");
+
+                BackBufferError {
+                    source: e
+                }
+            })?;
+
+        let pipeline_state = PipelineState::default()
+            .enable_clear_color(false)
+            .enable_clear_depth(false)
+            .set_viewport(Viewport::Specific {
+                x: target_region.x,
+                y: target_region.y,
+                width: target_region.width,
+                height: target_region.height
+            });
+        let render_state = RenderState::default()
+            .set_depth_test(None)
+            .set_scissor(target_region);
+
+        let tess = &self.tess;
+        let shader = &mut self.shader;
+
+        context.new_pipeline_gate().pipeline(&back_buffer, &pipeline_state, |pipeline, mut shd_gate| {
+            let bound_tex = pipeline.bind_texture(source.color_slot())?;
+
+            shd_gate.shade(shader, |mut iface, uni, mut rdr_gate| {
+                iface.set(&uni.tex, bound_tex.binding());
+
+                rdr_gate.render(&render_state, |mut tess_gate| {
+                    tess_gate.render(tess)
+                        .map_err(|e| {
+                            #[cfg(feature = "trace")]
+                            error!("Failed to render fullscreen quad.");
+
+                            TessRenderError {
+                                source: e
+                            }
+                        })
+                })
+            })
+        }).into_result()?;
+
+        #[cfg(feature = "trace")]
+        debug!("Blitted framebuffer into region: {:?}", target_region);
+
+        Ok(())
+    }
+}
+
+/// Copies the color attachment of `source` into `target_region` of the back buffer. Builds a new
+/// Blitter on every call, so use `Blitter::blit` directly for per-frame compositing.
+pub fn blit_framebuffer(ctx: &mut GL33Context, source: &mut Framebuffer<Dim2, NormRGBA8UI, ()>, target_region: ScissorRegion) -> Result<(), BlitError> {
+    Blitter::new(ctx)?.blit(ctx, source, target_region)
+}
+
+#[derive(Error, Debug)]
+pub enum BlitError {
+    #[error("Failed to acquire write lock for context")]
+    ContextWriteError,
+
+    #[error("Failed to get the back buffer")]
+    BackBufferError {
+        source: FramebufferError
+    },
+
+    #[error("Failed to build the passthrough shader program")]
+    ShaderProgramBuildError {
+        source: ProgramError
+    },
+
+    #[error("Failed to build the fullscreen quad")]
+    TessBuildError {
+        source: TessError
+    },
+
+    #[error("An error occurred while rendering the fullscreen quad")]
+    TessRenderError {
+        source: TessError
+    },
+
+    #[error("An error occurred in the pipeline")]
+    PipelineRenderError {
+        source: PipelineError
+    }
+}
+
+impl From<PipelineError> for BlitError {
+    fn from(e: PipelineError) -> Self {
+        PipelineRenderError { source: e }
+    }
+}
@@ -0,0 +1,433 @@
+use std::collections::HashMap;
+
+use luminance_front::{
+    render_state::RenderState,
+    tess::{Tess, Mode, TessError, Interleaved},
+    shader::{Uniform, Program},
+    pipeline::Pipeline,
+    shading_gate::ShadingGate,
+    context::GraphicsContext,
+};
+use luminance_derive::UniformInterface;
+use luminance_glfw::GL33Context;
+
+use glam::{Mat4, Quat, Vec3};
+use serde::Deserialize;
+use specs::{World, Write, Join, ReadStorage, Read};
+use thiserror::Error;
+
+#[cfg(feature = "trace")]
+use tracing::{debug, error, instrument};
+
+use crate::camera::Camera;
+use crate::components::world_text::{WorldText, TextAlignment};
+use crate::globals::font_atlas_dict::FontAtlasDict;
+use crate::globals::texture_dict::TextureDict;
+use crate::graphics::texture::TextureHandle;
+use crate::graphics::transform::Transform;
+use crate::graphics::render::{Renderer, ShaderTypes};
+use crate::graphics::render::sprite_renderer::{default_sprite_render_state, SpriteRenderError};
+use crate::graphics::render::sprite_renderer::SpriteRenderError::{FailedToBind, TessRenderError, RenderGateError, CameraDNE};
+use crate::graphics::render::text_renderer::{layout_text, TextSemantics, TextShaderUniform, TextVertex};
+use crate::graphics::render::debug_renderer::DebugRendererLoadError::{DeserializeError, ContextWriteError, ShaderProgramBuildError, TessBuildError};
+use crate::graphics::render::debug_renderer::DebugRendererPrepareError::LabelTessBuildError;
+use crate::load::load_deserializable_from_file;
+use crate::loading::DrawTask;
+
+const VS: &str = include_str!("../debug-vs.glsl");
+const FS: &str = include_str!("../debug-fs.glsl");
+const TEXT_VS: &str = include_str!("../text-vs.glsl");
+const TEXT_FS: &str = include_str!("../text-fs.glsl");
+
+pub const DEBUG_RENDERER_LOAD_ID: &str = "debug_renderer";
+
+const WHITE: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+const DEFAULT_LABEL_SIZE: f32 = 12.0;
+/// Gap between the top of a box and the baseline of its label.
+const LABEL_MARGIN: f32 = 2.0;
+
+#[derive(Debug, UniformInterface)]
+pub struct DebugShaderUniform {
+    /// PROJECTION matrix in MVP
+    projection: Uniform<[[f32; 4]; 4]>,
+    /// VIEW matrix in MVP
+    view: Uniform<[[f32; 4]; 4]>,
+    /// MODEL matrix in MVP
+    model: Uniform<[[f32; 4]; 4]>,
+    /// Color of the lines.
+    color: Uniform<[f32; 4]>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct DebugRendererJSON {
+    /// Font in the FontAtlasDict the labels are written in. Without one, no labels are drawn.
+    #[serde(default)]
+    font_handle: Option<String>,
+    #[serde(default = "default_label_size")]
+    label_size: f32
+}
+
+fn default_label_size() -> f32 {
+    DEFAULT_LABEL_SIZE
+}
+
+pub struct DebugRendererLoader {
+    pub path: String
+}
+
+impl DebugRendererLoader {
+    pub fn new(path: String) -> Self {
+        Self {
+            path
+        }
+    }
+
+    pub fn load(&self) -> DrawTask<DebugRenderer> {
+        let path = self.path.clone();
+
+        DrawTask::new(move |(_ecs, context)| {
+            #[cfg(feature = "trace")]
+            debug!("Loading Debug Renderer from file: {:?}", path.clone());
+
+            let json: DebugRendererJSON = load_deserializable_from_file(&path, DEBUG_RENDERER_LOAD_ID)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to load deserializable from file: {:?}", path.clone());
+
+                    DeserializeError {
+                        source: e,
+                        path: path.clone()
+                    }
+                })?;
+
+            let mut context = context.write()
+                .map_err(|_e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to acquire write lock for context");
+
+                    ContextWriteError
+                })?;
+
+            Ok(DebugRenderer::new(&mut context, json.font_handle, json.label_size)?)
+        })
+    }
+
+    /// Draws the boxes without labels.
+    pub fn load_default() -> DrawTask<DebugRenderer> {
+        DrawTask::new(|(_ecs, context)| {
+            let mut context = context.write()
+                .map_err(|_e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to acquire write lock for context");
+
+                    ContextWriteError
+                })?;
+
+            Ok(DebugRenderer::new(&mut context, None, DEFAULT_LABEL_SIZE)?)
+        })
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum DebugRendererLoadError {
+    #[error("Failed to deserialize file: {path:?}")]
+    DeserializeError {
+        source: anyhow::Error,
+        path: String
+    },
+
+    #[error("Failed to acquire write lock for context")]
+    ContextWriteError,
+
+    #[error("Failed to build the debug shader programs")]
+    ShaderProgramBuildError {
+        source: luminance_front::shader::ProgramError
+    },
+
+    #[error("Failed to build the debug box tess")]
+    TessBuildError {
+        source: TessError
+    }
+}
+
+/// Draws a white box the size of each entity's Transform, on top of everything drawn before it,
+/// and writes the name of the entity's TextureHandle above the box. Labels need a font, and like
+/// the WorldTextRenderer, `prepare` must be called before `render` to build them.
+pub struct DebugRenderer {
+    pub render_state: RenderState,
+    pub shader: Program<(), (), DebugShaderUniform>,
+    pub text_shader: Program<TextSemantics, (), TextShaderUniform>,
+    /// Line strip around the sprite quad.
+    pub tess: Tess<(), (), (), Interleaved>,
+    pub font_handle: Option<String>,
+    pub label_size: f32,
+    /// Glyph quads of each texture name, or None if the name has no visible glyphs.
+    labels: HashMap<String, Option<Tess<TextVertex>>>
+}
+
+impl DebugRenderer {
+    pub fn new(context: &mut GL33Context, font_handle: Option<String>, label_size: f32) -> Result<Self, DebugRendererLoadError> {
+        let build_error = |e| {
+            #[cfg(feature = "trace")]
+            error!("Failed to build debug shader program.");
+
+            ShaderProgramBuildError {
+                source: e
+            }
+        };
+
+        let shader = context
+            .new_shader_program()
+            .from_strings(VS, None, None, FS)
+            .map_err(build_error)?
+            .ignore_warnings();
+
+        let text_shader = context
+            .new_shader_program()
+            .from_strings(TEXT_VS, None, None, TEXT_FS)
+            .map_err(build_error)?
+            .ignore_warnings();
+
+        let tess = context
+            .new_tess()
+            .set_render_vertex_nb(5)
+            .set_mode(Mode::LineStrip)
+            .build()
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to build debug box tess.");
+
+                TessBuildError {
+                    source: e
+                }
+            })?;
+
+        Ok(Self {
+            render_state: default_sprite_render_state(),
+            shader,
+            text_shader,
+            tess,
+            font_handle,
+            label_size,
+            labels: HashMap::new()
+        })
+    }
+
+    /// Lays out the labels of texture names that don't have one yet.
+    #[cfg_attr(feature = "trace", instrument(skip(self, world, context)))]
+    pub fn prepare(&mut self, world: &World, context: &mut GL33Context) -> Result<(), DebugRendererPrepareError> {
+        let font_handle = match &self.font_handle {
+            Some(font_handle) => font_handle,
+            None => return Ok(())
+        };
+
+        let (textures, transforms, font_atlas_dict): (ReadStorage<TextureHandle>, ReadStorage<Transform>, Option<Read<FontAtlasDict>>) = world.system_data();
+
+        let atlas = match font_atlas_dict.as_ref().and_then(|font_atlas_dict| font_atlas_dict.get(font_handle)) {
+            Some(atlas) => atlas,
+            None => {
+                #[cfg(feature = "trace")]
+                debug!("No font atlas found for debug labels. Font: {:?}", font_handle);
+
+                return Ok(())
+            }
+        };
+
+        for (tex_handle, _) in (&textures, &transforms).join() {
+            let name = tex_handle.key().as_str();
+            if self.labels.contains_key(name) {
+                continue
+            }
+
+            let label = WorldText {
+                content: name.to_string(),
+                font_handle: font_handle.clone(),
+                size: self.label_size,
+                color: WHITE,
+                alignment: TextAlignment::Left
+            };
+
+            let vertices = layout_text(&label, atlas);
+            let tess = if vertices.is_empty() {
+                None
+            } else {
+                Some(
+                    context
+                        .new_tess()
+                        .set_vertices(vertices)
+                        .set_mode(Mode::Triangle)
+                        .build()
+                        .map_err(|e| {
+                            #[cfg(feature = "trace")]
+                            error!("Failed to build tess for debug label: {:?}", name);
+
+                            LabelTessBuildError {
+                                label: name.to_string(),
+                                source: e
+                            }
+                        })?
+                )
+            };
+
+            self.labels.insert(name.to_string(), tess);
+        }
+
+        Ok(())
+    }
+
+    fn render_boxes(&mut self, shd_gate: &mut ShadingGate, world: &World) -> Result<(), SpriteRenderError> {
+        let tess = &self.tess;
+        let render_state = &self.render_state;
+
+        shd_gate.shade(&mut self.shader, |mut iface, uni, mut rdr_gate| {
+            let (transforms, mut camera): (ReadStorage<Transform>, Write<Option<Box<dyn Camera>>>) = world.system_data();
+
+            let camera = camera.as_mut().ok_or(CameraDNE)?;
+
+            iface.set(&uni.projection, camera.projection().to_cols_array_2d());
+            iface.set(&uni.view, camera.view().to_cols_array_2d());
+            iface.set(&uni.color, WHITE);
+
+            for transform in transforms.join() {
+                iface.set(&uni.model, transform.to_model(None).to_cols_array_2d());
+
+                rdr_gate.render(render_state, |mut tess_gate| {
+                    tess_gate.render(tess)
+                        .map_err(|e| {
+                            #[cfg(feature = "trace")]
+                            error!("Failed to call render on tess gate.");
+
+                            TessRenderError {
+                                source: e
+                            }
+                        })
+                })
+                    .map_err(|e| {
+                        #[cfg(feature = "trace")]
+                        error!("Failed to call render on render gate.");
+
+                        RenderGateError {
+                            source: Box::new(e)
+                        }
+                    })?;
+            }
+
+            Ok(())
+        })
+    }
+
+    fn render_labels(&mut self, pipeline: &Pipeline, shd_gate: &mut ShadingGate, world: &World) -> Result<(), SpriteRenderError> {
+        let font_handle = match &self.font_handle {
+            Some(font_handle) => font_handle,
+            None => return Ok(())
+        };
+        let labels = &self.labels;
+        let render_state = &self.render_state;
+        let label_size = self.label_size;
+
+        shd_gate.shade(&mut self.text_shader, |mut iface, uni, mut rdr_gate| {
+            let (textures, transforms, font_atlas_dict, mut texture_dict, mut camera): (ReadStorage<TextureHandle>, ReadStorage<Transform>, Option<Read<FontAtlasDict>>, Write<TextureDict>, Write<Option<Box<dyn Camera>>>) = world.system_data();
+
+            let atlas = match font_atlas_dict.as_ref().and_then(|font_atlas_dict| font_atlas_dict.get(font_handle)) {
+                Some(atlas) => atlas,
+                None => return Ok(())
+            };
+
+            let camera = camera.as_mut().ok_or(CameraDNE)?;
+
+            iface.set(&uni.projection, camera.projection().to_cols_array_2d());
+            iface.set(&uni.view, camera.view().to_cols_array_2d());
+            iface.set(&uni.color, WHITE);
+
+            let texture = match texture_dict.get_mut(&atlas.texture_handle) {
+                Some(texture) => texture,
+                None => return Ok(())
+            };
+
+            let bound_tex = pipeline.bind_texture(texture)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to bind font atlas to pipeline.");
+
+                    FailedToBind {
+                        texture: atlas.texture_handle.clone(),
+                        source: e
+                    }
+                })?;
+            iface.set(&uni.tex, bound_tex.binding());
+
+            let scale = label_size / atlas.font_size;
+
+            for (tex_handle, transform) in (&textures, &transforms).join() {
+                let tess = match labels.get(tex_handle.key().as_str()) {
+                    Some(Some(tess)) => tess,
+                    _ => continue
+                };
+
+                // Written from the top left corner of the box.
+                let position = transform.translation + Vec3::new(-transform.scale.x, transform.scale.y + LABEL_MARGIN, 0.0);
+                let model = Mat4::from_scale_rotation_translation(Vec3::new(scale, scale, 1.0), Quat::IDENTITY, position);
+                iface.set(&uni.model, model.to_cols_array_2d());
+
+                rdr_gate.render(render_state, |mut tess_gate| {
+                    tess_gate.render(tess)
+                        .map_err(|e| {
+                            #[cfg(feature = "trace")]
+                            error!("Failed to call render on tess gate.");
+
+                            TessRenderError {
+                                source: e
+                            }
+                        })
+                })
+                    .map_err(|e| {
+                        #[cfg(feature = "trace")]
+                        error!("Failed to call render on render gate.");
+
+                        RenderGateError {
+                            source: Box::new(e)
+                        }
+                    })?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum DebugRendererPrepareError {
+    #[error("Failed to build glyph tess for debug label: {label:?}")]
+    LabelTessBuildError {
+        label: String,
+        source: TessError
+    }
+}
+
+impl ShaderTypes for DebugRenderer {
+    type Semantics = ();
+    type ReturnValue = ();
+    type UniformInterface = DebugShaderUniform;
+}
+
+impl Renderer for DebugRenderer {
+    type S = Self;
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn load(path: String) -> DrawTask<Self> {
+        let loader = DebugRendererLoader::new(path);
+
+        loader.load()
+    }
+
+    #[cfg_attr(feature = "trace", instrument(skip(self, pipeline, shd_gate, world)))]
+    fn render(
+        &mut self,
+        pipeline: &Pipeline,
+        shd_gate: &mut ShadingGate,
+        world: &World,
+    ) -> Result<(), SpriteRenderError> {
+        self.render_boxes(shd_gate, world)?;
+        self.render_labels(pipeline, shd_gate, world)
+    }
+}
@@ -0,0 +1,243 @@
+use luminance_front::{
+    render_state::RenderState,
+    tess::{Tess, Interleaved},
+    shader::{Uniform, Program},
+    pipeline::Pipeline,
+    shading_gate::ShadingGate,
+    context::GraphicsContext,
+};
+use luminance_derive::UniformInterface;
+
+use glam::{Mat4, Quat, Vec3};
+use serde::Deserialize;
+use specs::{World, Write, Read, WorldExt};
+use thiserror::Error;
+
+#[cfg(feature = "trace")]
+use tracing::{debug, error, instrument};
+
+use crate::camera::Camera;
+use crate::components::fog_of_war::VisibilityMap;
+use crate::graphics::tess::TessLoader;
+use crate::graphics::render::{Renderer, ShaderTypes};
+use crate::graphics::render::sprite_renderer::{default_sprite_render_state, SpriteRenderError};
+use crate::graphics::render::sprite_renderer::SpriteRenderError::{TessRenderError, RenderGateError, CameraDNE};
+use crate::graphics::render::fog_renderer::FogRendererLoadError::{DeserializeError, TessLoadError, ContextWriteError, ShaderProgramBuildError, WorldWriteError};
+use crate::load::{load_deserializable_from_file, LoadError};
+use crate::loading::DrawTask;
+
+const VS: &str = include_str!("../fog-vs.glsl");
+const FS: &str = include_str!("../fog-fs.glsl");
+
+pub const FOG_RENDERER_LOAD_ID: &str = "fog_renderer";
+
+#[derive(Debug, UniformInterface)]
+pub struct FogShaderUniform {
+    /// PROJECTION matrix in MVP
+    projection: Uniform<[[f32; 4]; 4]>,
+    /// VIEW matrix in MVP
+    view: Uniform<[[f32; 4]; 4]>,
+    /// MODEL matrix in MVP
+    model: Uniform<[[f32; 4]; 4]>,
+    /// Color of the fog overlay.
+    color: Uniform<[f32; 4]>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema_gen", derive(schemars::JsonSchema))]
+pub struct FogRendererJSON {
+    overlay_color: [f32; 4],
+    grid_width: u32,
+    grid_height: u32,
+    cell_size: f32
+}
+
+pub struct FogRendererLoader {
+    pub path: String
+}
+
+impl FogRendererLoader {
+    pub fn new(path: String) -> Self {
+        Self {
+            path
+        }
+    }
+
+    /// Also inserts an empty VisibilityMap with the grid resolution from the file into the World.
+    pub fn load(&self) -> DrawTask<FogRenderer> {
+        let path = self.path.clone();
+
+        DrawTask::new(move |(ecs, context)| {
+            #[cfg(feature = "trace")]
+            debug!("Loading Fog Renderer from file: {:?}", path.clone());
+
+            let json: FogRendererJSON = load_deserializable_from_file(&path, FOG_RENDERER_LOAD_ID)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to load deserializable from file: {:?}", path.clone());
+
+                    DeserializeError {
+                        source: e,
+                        path: path.clone()
+                    }
+                })?;
+
+            let tess = TessLoader::load_default()
+                .execute((ecs.clone(), context.clone()))
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to load default Tess for fog overlay");
+
+                    TessLoadError { source: e }
+                })?;
+
+            let shader = context.write()
+                .map_err(|_e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to acquire write lock for context");
+
+                    ContextWriteError
+                })?
+                .new_shader_program()
+                .from_strings(VS, None, None, FS)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to build fog shader program.");
+
+                    ShaderProgramBuildError { source: e }
+                })?
+                .ignore_warnings();
+
+            ecs.write()
+                .map_err(|_e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to acquire write lock for World");
+
+                    WorldWriteError
+                })?
+                .insert(VisibilityMap::new(json.grid_width, json.grid_height));
+            #[cfg(feature = "trace")]
+            debug!("Inserted VisibilityMap of size: {:?}x{:?}", json.grid_width, json.grid_height);
+
+            Ok(FogRenderer {
+                render_state: default_sprite_render_state(),
+                tess,
+                shader,
+                overlay_color: json.overlay_color,
+                cell_size: json.cell_size
+            })
+        })
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum FogRendererLoadError {
+    #[error("Failed to deserialize file: {path:?}")]
+    DeserializeError {
+        source: anyhow::Error,
+        path: String
+    },
+
+    #[error("Failed to load default Tess for fog overlay")]
+    TessLoadError {
+        source: anyhow::Error
+    },
+
+    #[error("Failed to acquire write lock for context")]
+    ContextWriteError,
+
+    #[error("Failed to acquire write lock for World")]
+    WorldWriteError,
+
+    #[error("Failed to build the fog shader program")]
+    ShaderProgramBuildError {
+        source: luminance_front::shader::ProgramError
+    }
+}
+
+/// Covers every unexplored cell of the VisibilityMap with a quad of the overlay color.
+/// `cell_size` must match the one given to the FogOfWarSystem.
+pub struct FogRenderer {
+    pub render_state: RenderState,
+    pub tess: Tess<(),(),(),Interleaved>,
+    pub shader: Program<(), (), FogShaderUniform>,
+    pub overlay_color: [f32; 4],
+    pub cell_size: f32
+}
+
+impl ShaderTypes for FogRenderer {
+    type Semantics = ();
+    type ReturnValue = ();
+    type UniformInterface = FogShaderUniform;
+}
+
+impl Renderer for FogRenderer {
+    type S = Self;
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn load(path: String) -> DrawTask<Self> {
+        let loader = FogRendererLoader::new(path);
+
+        loader.load()
+    }
+
+    #[cfg_attr(feature = "trace", instrument(skip(self, _pipeline, shd_gate, world)))]
+    fn render(
+        &mut self,
+        _pipeline: &Pipeline,
+        shd_gate: &mut ShadingGate,
+        world: &World,
+    ) -> Result<(), SpriteRenderError> {
+        let shader = &mut self.shader;
+        let tess = &self.tess;
+        let render_state = &self.render_state;
+        let overlay_color = self.overlay_color;
+        let cell_size = self.cell_size;
+
+        shd_gate.shade(shader, |mut iface, uni, mut rdr_gate| {
+            let (visibility_map, mut camera): (Read<VisibilityMap>, Write<Option<Box<dyn Camera>>>) = world.system_data();
+
+            let camera = camera.as_mut().ok_or(CameraDNE)?;
+
+            iface.set(&uni.projection, camera.projection().to_cols_array_2d());
+            iface.set(&uni.view, camera.view().to_cols_array_2d());
+            iface.set(&uni.color, overlay_color);
+
+            let half_cell = Vec3::new(cell_size / 2.0, cell_size / 2.0, 1.0);
+
+            for y in 0..visibility_map.2 {
+                for x in 0..visibility_map.1 {
+                    if visibility_map.is_visible(x, y) {
+                        continue
+                    }
+
+                    let center = Vec3::new((x as f32 + 0.5) * cell_size, (y as f32 + 0.5) * cell_size, 0.0);
+                    let model = Mat4::from_scale_rotation_translation(half_cell, Quat::IDENTITY, center);
+                    iface.set(&uni.model, model.to_cols_array_2d());
+
+                    rdr_gate.render(render_state, |mut tess_gate| {
+                        tess_gate.render(tess)
+                            .map_err(|e| {
+                                #[cfg(feature = "trace")]
+                                error!("Failed to call render on tess gate.");
+
+                                TessRenderError {
+                                    source: e
+                                }
+                            })
+                    })
+                        .map_err(|e| {
+                            #[cfg(feature = "trace")]
+                            error!("Failed to call render on render gate.");
+
+                            RenderGateError {
+                                source: Box::new(e)
+                            }
+                        })?;
+                }
+            }
+
+            Ok(())
+        })
+    }
+}
@@ -8,20 +8,41 @@ use luminance_front::render_state::RenderState;
 use luminance_front::pipeline::Pipeline;
 use luminance_front::shading_gate::ShadingGate;
 use glam::Mat4;
-use specs::World;
+use specs::{World, Write};
 use crate::graphics::render::sprite_renderer::SpriteRenderError;
 use luminance_front::context::GraphicsContext;
 use crate::loading::DrawTask;
+use luminance_glfw::GL33Context;
+use crate::camera::Camera;
 
 pub mod sprite_renderer;
+pub mod batch_sprite_renderer;
+pub mod text_renderer;
+pub mod fog_renderer;
+pub mod shadow_renderer;
+pub mod outline_renderer;
+#[cfg(feature = "debug_rendering")]
+pub mod debug_renderer;
+pub mod blit;
 pub(crate) mod deserializations;
 
+pub use blit::{blit_framebuffer, BlitError};
+
+/// The World's camera, as renderers fetch it with `World::system_data`.
+pub(crate) type CameraData<'a> = Write<'a, Option<Box<dyn Camera>>>;
+
 pub trait ShaderTypes {
     type Semantics: Semantics;
     type ReturnValue;
     type UniformInterface;
 }
 
+/// Draws a whole frame on its own, from the back buffer down. Scenes that want their own rendering
+/// return one from `Scene::get_renderer`.
+pub trait RenderPipeline {
+    fn render_frame(&mut self, ecs: &mut World, context: &mut GL33Context) -> anyhow::Result<()>;
+}
+
 pub trait Renderer {
     type S: ShaderTypes;
 
@@ -31,7 +52,11 @@ pub trait Renderer {
         &mut self,
         pipeline: &Pipeline,
         shd_gate: &mut ShadingGate,
-        proj_matrix: &Mat4,
         world: &World,
     ) -> Result<<<Self as Renderer>::S as ShaderTypes>::ReturnValue, SpriteRenderError>;
+
+    /// Whether `render` draws all the sprites sharing a texture with a single draw call.
+    fn supports_batching(&self) -> bool {
+        false
+    }
 }
\ No newline at end of file
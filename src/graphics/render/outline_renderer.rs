@@ -0,0 +1,296 @@
+use luminance_front::{
+    render_state::RenderState,
+    tess::{Tess, Interleaved},
+    shader::{Uniform, Program},
+    pipeline::{TextureBinding, Pipeline},
+    texture::Dim2,
+    shading_gate::ShadingGate,
+    pixel::Unsigned,
+    context::GraphicsContext,
+};
+use luminance_derive::UniformInterface;
+
+use glam::{Mat4, Vec2};
+use serde::Deserialize;
+use specs::{World, Write, Join, ReadStorage};
+use thiserror::Error;
+
+#[cfg(feature = "trace")]
+use tracing::{debug, error, instrument};
+
+use crate::camera::Camera;
+use crate::components::outline::Outline;
+use crate::globals::texture_dict::TextureDict;
+use crate::graphics::texture::TextureHandle;
+use crate::graphics::transform::Transform;
+use crate::graphics::shader::ShaderLoader;
+use crate::graphics::tess::TessLoader;
+use crate::graphics::render::{Renderer, ShaderTypes, CameraData};
+use crate::graphics::render::deserializations::{RenderStateDef, RENDER_STATE_LOAD_ID};
+use crate::graphics::render::sprite_renderer::SpriteRenderError;
+use crate::graphics::render::shadow_renderer::default_shadow_render_state;
+use crate::graphics::render::sprite_renderer::SpriteRenderError::{FailedToBind, TessRenderError, RenderGateError, CameraDNE};
+use crate::graphics::render::outline_renderer::OutlineRendererLoadError::{DeserializeError, TessLoadError, ShaderLoadError, ContextWriteError, ShaderProgramBuildError};
+use crate::load::{load_deserializable_from_file, LoadError};
+use crate::loading::DrawTask;
+
+const VS: &str = include_str!("../texture-vs.glsl");
+const FS: &str = include_str!("../outline-fs.glsl");
+
+pub const OUTLINE_RENDERER_LOAD_ID: &str = "outline_renderer";
+
+#[derive(Debug, UniformInterface)]
+pub struct OutlineShaderUniform {
+    /// PROJECTION matrix in MVP
+    projection: Uniform<[[f32; 4]; 4]>,
+    /// VIEW matrix in MVP
+    view: Uniform<[[f32; 4]; 4]>,
+    /// MODEL matrix in MVP
+    model: Uniform<[[f32; 4]; 4]>,
+    /// Sprite being outlined.
+    tex: Uniform<TextureBinding<Dim2, Unsigned>>,
+    /// Color of the outline.
+    color: Uniform<[f32; 4]>,
+    /// Width of the outline in texels.
+    thickness: Uniform<f32>,
+    /// How much larger the quad is than the sprite.
+    grow: Uniform<[f32; 2]>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema_gen", derive(schemars::JsonSchema))]
+pub struct OutlineRendererJSON {
+    render_state_path: String,
+    shader_path: String
+}
+
+pub struct OutlineRendererLoader {
+    pub path: String
+}
+
+impl OutlineRendererLoader {
+    pub fn new(path: String) -> Self {
+        Self {
+            path
+        }
+    }
+
+    pub fn load(&self) -> DrawTask<OutlineRenderer> {
+        let path = self.path.clone();
+
+        DrawTask::new(move |(ecs, context)| {
+            #[cfg(feature = "trace")]
+            debug!("Loading Outline Renderer from file: {:?}", path.clone());
+
+            let json: OutlineRendererJSON = load_deserializable_from_file(&path, OUTLINE_RENDERER_LOAD_ID)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to load deserializable from file: {:?}", path.clone());
+
+                    DeserializeError {
+                        source: e,
+                        path: path.clone()
+                    }
+                })?;
+
+            let render_state: RenderStateDef = load_deserializable_from_file(&json.render_state_path, RENDER_STATE_LOAD_ID)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to deserialize Render State from file: {:?}", json.render_state_path.clone());
+
+                    DeserializeError {
+                        source: e,
+                        path: json.render_state_path.clone()
+                    }
+                })?;
+
+            let tess = TessLoader::load_default()
+                .execute((ecs.clone(), context.clone()))
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to load default Tess for outlines");
+
+                    TessLoadError { source: e }
+                })?;
+
+            let shader = ShaderLoader::new(json.shader_path.clone())
+                .load()
+                .execute((ecs, context))
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to load shader from file: {:?}", json.shader_path);
+
+                    ShaderLoadError {
+                        source: e,
+                        path: json.shader_path.clone()
+                    }
+                })?;
+
+            Ok(OutlineRenderer {
+                render_state: RenderState::from(render_state),
+                tess,
+                shader
+            })
+        })
+    }
+
+    pub fn load_default() -> DrawTask<OutlineRenderer> {
+        TessLoader::load_default()
+            .map(|tess, (_ecs, context)| {
+                let shader = context.write()
+                    .map_err(|_e| {
+                        #[cfg(feature = "trace")]
+                        error!("Failed to acquire write lock for context");
+
+                        ContextWriteError
+                    })?
+                    .new_shader_program()
+                    .from_strings(VS, None, None, FS)
+                    .map_err(|e| {
+                        #[cfg(feature = "trace")]
+                        error!("Failed to build default outline shader program.");
+
+                        ShaderProgramBuildError { source: e }
+                    })?
+                    .ignore_warnings();
+
+                Ok(OutlineRenderer {
+                    render_state: default_shadow_render_state(),
+                    tess,
+                    shader
+                })
+            })
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum OutlineRendererLoadError {
+    #[error("Failed to deserialize file: {path:?}")]
+    DeserializeError {
+        source: anyhow::Error,
+        path: String
+    },
+
+    #[error("Failed to load default Tess for outlines")]
+    TessLoadError {
+        source: anyhow::Error
+    },
+
+    #[error("Failed to load Shader from file: {path}")]
+    ShaderLoadError {
+        source: anyhow::Error,
+        path: String
+    },
+
+    #[error("Failed to acquire write lock for context")]
+    ContextWriteError,
+
+    #[error("Failed to build the default outline shader program")]
+    ShaderProgramBuildError {
+        source: luminance_front::shader::ProgramError
+    }
+}
+
+/// Draws the Outline of every entity that also has a TextureHandle and Transform. The sprite's quad is
+/// enlarged by `thickness` texels and the shader colors every transparent pixel next to an opaque one.
+/// Render it before the SpriteRenderer so the sprite is drawn inside its outline.
+pub struct OutlineRenderer {
+    pub render_state: RenderState,
+    pub tess: Tess<(),(),(),Interleaved>,
+    pub shader: Program<(), (), OutlineShaderUniform>,
+}
+
+impl ShaderTypes for OutlineRenderer {
+    type Semantics = ();
+    type ReturnValue = ();
+    type UniformInterface = OutlineShaderUniform;
+}
+
+impl Renderer for OutlineRenderer {
+    type S = Self;
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn load(path: String) -> DrawTask<Self> {
+        let loader = OutlineRendererLoader::new(path);
+
+        loader.load()
+    }
+
+    #[cfg_attr(feature = "trace", instrument(skip(self, pipeline, shd_gate, world)))]
+    fn render(
+        &mut self,
+        pipeline: &Pipeline,
+        shd_gate: &mut ShadingGate,
+        world: &World,
+    ) -> Result<(), SpriteRenderError> {
+        let shader = &mut self.shader;
+        let tess = &self.tess;
+        let render_state = &self.render_state;
+
+        shd_gate.shade(shader, |mut iface, uni, mut rdr_gate| {
+            let (outlines, textures, transforms, mut texture_dict, mut camera): (ReadStorage<Outline>, ReadStorage<TextureHandle>, ReadStorage<Transform>, Write<TextureDict>, CameraData) = world.system_data();
+
+            let camera = camera.as_mut().ok_or(CameraDNE)?;
+
+            iface.set(&uni.projection, camera.projection().to_cols_array_2d());
+            iface.set(&uni.view, camera.view().to_cols_array_2d());
+
+            for (outline, tex_handle, transform) in (&outlines, &textures, &transforms).join() {
+                #[cfg(feature = "trace")]
+                debug!("Rendering outline: ({:?}) of texture: {:?}", outline, tex_handle.clone());
+
+                if let Some(texture) = texture_dict.get_mut(tex_handle) {
+                    let [width, height] = texture.size();
+                    let grow = Vec2::new(
+                        1.0 + 2.0 * outline.thickness / width as f32,
+                        1.0 + 2.0 * outline.thickness / height as f32
+                    );
+
+                    let bound_tex = pipeline.bind_texture(texture)
+                        .map_err(|e| {
+                            #[cfg(feature = "trace")]
+                            error!("Failed to bind texture to pipeline.");
+
+                            FailedToBind {
+                                texture: tex_handle.clone(),
+                                source: e
+                            }
+                        })?;
+
+                    let model = Transform {
+                        scale: transform.scale * grow.extend(1.0),
+                        ..*transform
+                    }.to_model(None);
+
+                    iface.set(&uni.tex, bound_tex.binding());
+                    iface.set(&uni.model, model.to_cols_array_2d());
+                    iface.set(&uni.color, outline.color);
+                    iface.set(&uni.thickness, outline.thickness);
+                    iface.set(&uni.grow, grow.to_array());
+
+                    rdr_gate.render(render_state, |mut tess_gate| {
+                        tess_gate.render(tess)
+                            .map_err(|e| {
+                                #[cfg(feature = "trace")]
+                                error!("Failed to call render on tess gate.");
+
+                                TessRenderError {
+                                    source: e
+                                }
+                            })
+                    })
+                        .map_err(|e| {
+                            #[cfg(feature = "trace")]
+                            error!("Failed to call render on render gate.");
+
+                            RenderGateError {
+                                source: Box::new(e)
+                            }
+                        })?;
+                }
+            }
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,284 @@
+use luminance_front::{
+    render_state::RenderState,
+    tess::{Tess, Interleaved},
+    shader::{Uniform, Program},
+    pipeline::{TextureBinding, Pipeline},
+    texture::Dim2,
+    shading_gate::ShadingGate,
+    pixel::Unsigned,
+    depth_test::DepthWrite,
+    context::GraphicsContext,
+};
+use luminance_derive::UniformInterface;
+
+use glam::Mat4;
+use serde::Deserialize;
+use specs::{World, Write, Join, ReadStorage};
+use thiserror::Error;
+
+#[cfg(feature = "trace")]
+use tracing::{debug, error, instrument};
+
+use crate::camera::Camera;
+use crate::components::shadow::Shadow;
+use crate::globals::texture_dict::TextureDict;
+use crate::graphics::texture::TextureHandle;
+use crate::graphics::transform::Transform;
+use crate::graphics::shader::ShaderLoader;
+use crate::graphics::tess::TessLoader;
+use crate::graphics::render::{Renderer, ShaderTypes, CameraData};
+use crate::graphics::render::deserializations::{RenderStateDef, RENDER_STATE_LOAD_ID};
+use crate::graphics::render::sprite_renderer::{default_sprite_render_state, SpriteRenderError};
+use crate::graphics::render::sprite_renderer::SpriteRenderError::{FailedToBind, TessRenderError, RenderGateError, CameraDNE};
+use crate::graphics::render::shadow_renderer::ShadowRendererLoadError::{DeserializeError, TessLoadError, ShaderLoadError, ContextWriteError, ShaderProgramBuildError};
+use crate::load::{load_deserializable_from_file, LoadError};
+use crate::loading::DrawTask;
+
+const VS: &str = include_str!("../texture-vs.glsl");
+const FS: &str = include_str!("../shadow-fs.glsl");
+
+pub const SHADOW_RENDERER_LOAD_ID: &str = "shadow_renderer";
+
+/// Same as the sprite render state but shadows never write depth so sprites drawn afterwards cover them.
+pub fn default_shadow_render_state() -> RenderState {
+    default_sprite_render_state()
+        .set_depth_write(DepthWrite::Off)
+}
+
+#[derive(Debug, UniformInterface)]
+pub struct ShadowShaderUniform {
+    /// PROJECTION matrix in MVP
+    projection: Uniform<[[f32; 4]; 4]>,
+    /// VIEW matrix in MVP
+    view: Uniform<[[f32; 4]; 4]>,
+    /// MODEL matrix in MVP
+    model: Uniform<[[f32; 4]; 4]>,
+    /// Sprite the shadow is cast by.
+    tex: Uniform<TextureBinding<Dim2, Unsigned>>,
+    /// Opacity of the shadow.
+    opacity: Uniform<f32>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema_gen", derive(schemars::JsonSchema))]
+pub struct ShadowRendererJSON {
+    render_state_path: String,
+    shader_path: String
+}
+
+pub struct ShadowRendererLoader {
+    pub path: String
+}
+
+impl ShadowRendererLoader {
+    pub fn new(path: String) -> Self {
+        Self {
+            path
+        }
+    }
+
+    pub fn load(&self) -> DrawTask<ShadowRenderer> {
+        let path = self.path.clone();
+
+        DrawTask::new(move |(ecs, context)| {
+            #[cfg(feature = "trace")]
+            debug!("Loading Shadow Renderer from file: {:?}", path.clone());
+
+            let json: ShadowRendererJSON = load_deserializable_from_file(&path, SHADOW_RENDERER_LOAD_ID)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to load deserializable from file: {:?}", path.clone());
+
+                    DeserializeError {
+                        source: e,
+                        path: path.clone()
+                    }
+                })?;
+
+            let render_state: RenderStateDef = load_deserializable_from_file(&json.render_state_path, RENDER_STATE_LOAD_ID)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to deserialize Render State from file: {:?}", json.render_state_path.clone());
+
+                    DeserializeError {
+                        source: e,
+                        path: json.render_state_path.clone()
+                    }
+                })?;
+
+            let tess = TessLoader::load_default()
+                .execute((ecs.clone(), context.clone()))
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to load default Tess for shadows");
+
+                    TessLoadError { source: e }
+                })?;
+
+            let shader = ShaderLoader::new(json.shader_path.clone())
+                .load()
+                .execute((ecs, context))
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to load shader from file: {:?}", json.shader_path);
+
+                    ShaderLoadError {
+                        source: e,
+                        path: json.shader_path.clone()
+                    }
+                })?;
+
+            Ok(ShadowRenderer {
+                render_state: RenderState::from(render_state),
+                tess,
+                shader
+            })
+        })
+    }
+
+    pub fn load_default() -> DrawTask<ShadowRenderer> {
+        TessLoader::load_default()
+            .map(|tess, (_ecs, context)| {
+                let shader = context.write()
+                    .map_err(|_e| {
+                        #[cfg(feature = "trace")]
+                        error!("Failed to acquire write lock for context");
+
+                        ContextWriteError
+                    })?
+                    .new_shader_program()
+                    .from_strings(VS, None, None, FS)
+                    .map_err(|e| {
+                        #[cfg(feature = "trace")]
+                        error!("Failed to build default shadow shader program.");
+
+                        ShaderProgramBuildError { source: e }
+                    })?
+                    .ignore_warnings();
+
+                Ok(ShadowRenderer {
+                    render_state: default_shadow_render_state(),
+                    tess,
+                    shader
+                })
+            })
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ShadowRendererLoadError {
+    #[error("Failed to deserialize file: {path:?}")]
+    DeserializeError {
+        source: anyhow::Error,
+        path: String
+    },
+
+    #[error("Failed to load default Tess for shadows")]
+    TessLoadError {
+        source: anyhow::Error
+    },
+
+    #[error("Failed to load Shader from file: {path}")]
+    ShaderLoadError {
+        source: anyhow::Error,
+        path: String
+    },
+
+    #[error("Failed to acquire write lock for context")]
+    ContextWriteError,
+
+    #[error("Failed to build the default shadow shader program")]
+    ShaderProgramBuildError {
+        source: luminance_front::shader::ProgramError
+    }
+}
+
+/// Draws the Shadow of every entity that also has a TextureHandle and Transform. Render it before the
+/// SpriteRenderer so sprites are drawn on top of their shadows.
+pub struct ShadowRenderer {
+    pub render_state: RenderState,
+    pub tess: Tess<(),(),(),Interleaved>,
+    pub shader: Program<(), (), ShadowShaderUniform>,
+}
+
+impl ShaderTypes for ShadowRenderer {
+    type Semantics = ();
+    type ReturnValue = ();
+    type UniformInterface = ShadowShaderUniform;
+}
+
+impl Renderer for ShadowRenderer {
+    type S = Self;
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn load(path: String) -> DrawTask<Self> {
+        let loader = ShadowRendererLoader::new(path);
+
+        loader.load()
+    }
+
+    #[cfg_attr(feature = "trace", instrument(skip(self, pipeline, shd_gate, world)))]
+    fn render(
+        &mut self,
+        pipeline: &Pipeline,
+        shd_gate: &mut ShadingGate,
+        world: &World,
+    ) -> Result<(), SpriteRenderError> {
+        let shader = &mut self.shader;
+        let tess = &self.tess;
+        let render_state = &self.render_state;
+
+        shd_gate.shade(shader, |mut iface, uni, mut rdr_gate| {
+            let (shadows, textures, transforms, mut texture_dict, mut camera): (ReadStorage<Shadow>, ReadStorage<TextureHandle>, ReadStorage<Transform>, Write<TextureDict>, CameraData) = world.system_data();
+
+            let camera = camera.as_mut().ok_or(CameraDNE)?;
+
+            iface.set(&uni.projection, camera.projection().to_cols_array_2d());
+            iface.set(&uni.view, camera.view().to_cols_array_2d());
+
+            for (shadow, tex_handle, transform) in (&shadows, &textures, &transforms).join() {
+                #[cfg(feature = "trace")]
+                debug!("Rendering shadow: ({:?}) of texture: {:?}", shadow, tex_handle.clone());
+
+                if let Some(texture) = texture_dict.get_mut(tex_handle) {
+                    let bound_tex = pipeline.bind_texture(texture)
+                        .map_err(|e| {
+                            #[cfg(feature = "trace")]
+                            error!("Failed to bind texture to pipeline.");
+
+                            FailedToBind {
+                                texture: tex_handle.clone(),
+                                source: e
+                            }
+                        })?;
+
+                    iface.set(&uni.tex, bound_tex.binding());
+                    iface.set(&uni.model, shadow.shadow_transform(transform).to_model(None).to_cols_array_2d());
+                    iface.set(&uni.opacity, shadow.opacity);
+
+                    rdr_gate.render(render_state, |mut tess_gate| {
+                        tess_gate.render(tess)
+                            .map_err(|e| {
+                                #[cfg(feature = "trace")]
+                                error!("Failed to call render on tess gate.");
+
+                                TessRenderError {
+                                    source: e
+                                }
+                            })
+                    })
+                        .map_err(|e| {
+                            #[cfg(feature = "trace")]
+                            error!("Failed to call render on render gate.");
+
+                            RenderGateError {
+                                source: Box::new(e)
+                            }
+                        })?;
+                }
+            }
+
+            Ok(())
+        })
+    }
+}
@@ -6,8 +6,10 @@ use luminance_front::{
     pipeline::{
         TextureBinding,
         Pipeline,
-        PipelineError
+        PipelineError,
+        PipelineState
     },
+    framebuffer::FramebufferError,
     texture::Dim2,
     blending::{
         Blending,
@@ -29,10 +31,16 @@ use specs::{World, Write, Join, WriteStorage, ReadStorage, Read};
 use crate::graphics::texture::TextureHandle;
 use crate::graphics::transform::Transform;
 use crate::globals::texture_dict::TextureDict;
-use crate::graphics::render::sprite_renderer::SpriteRenderError::{FailedToBind, TessRenderError, RenderGateError, CameraDNE};
+use crate::graphics::render::sprite_renderer::SpriteRenderError::{FailedToBind, TessRenderError, RenderGateError, CameraDNE, MaterialLockError, BackBufferError};
+use crate::components::material::{Material, MaterialUniformInterface};
+use crate::systems::visibility::Culled;
+use crate::graphics::interpolation::RenderTransform;
+use luminance_front::shader::ProgramInterface;
+use luminance_front::render_gate::RenderGate;
+use std::collections::HashMap;
 
 use thiserror::Error;
-use luminance_front::tess::{Interleaved, TessError, Deinterleaved, DeinterleavedData};
+use luminance_front::tess::{Interleaved, TessError, TessMapError, Deinterleaved, DeinterleavedData};
 use std::ops::DerefMut;
 use luminance_glfw::GL33Context;
 use glam::Mat4;
@@ -47,18 +55,24 @@ use crate::loading::DrawTask;
 use luminance_front::vertex::Semantics;
 use luminance::tess::{TessVertexData, TessIndex};
 use crate::load::{load_deserializable_from_file, LoadError};
-use crate::graphics::tess::TessLoader;
+use crate::graphics::tess::{TessLoader, TessCache, TessType, LoadableTessIndex};
+use crate::components::lod::ActiveLOD;
+use crate::components::sprite_region::SpriteRegion;
+use crate::components::pivot::Pivot;
+use crate::graphics::color_tint::ColorTint;
 use luminance::blending::BlendingMode;
 use luminance::depth_test::{DepthComparison, DepthWrite};
 use luminance::face_culling::FaceCulling;
 use luminance::scissor::ScissorRegion;
-use crate::graphics::render::sprite_renderer::SpriteRendererLoadError::{DeserializeError, TessLoadError, ShaderLoadError};
+use crate::graphics::render::sprite_renderer::SpriteRendererLoadError::{DeserializeError, TessLoadError, ShaderLoadError, TessTypeMismatchError};
 use crate::graphics::shader::ShaderLoader;
-use crate::graphics::render::{Renderer, ShaderTypes};
+use crate::graphics::render::{Renderer, RenderPipeline, ShaderTypes, CameraData, BlitError};
 use std::marker::PhantomData;
 use crate::graphics::render::deserializations::{RenderStateDef, RENDER_STATE_LOAD_ID};
 use crate::camera::Camera;
 use std::sync::{Arc, RwLock};
+#[cfg(feature = "debug_rendering")]
+use crate::graphics::render::debug_renderer::DebugRenderer;
 
 #[cfg_attr(feature = "trace", instrument)]
 pub fn default_sprite_render_state() -> RenderState {
@@ -88,6 +102,44 @@ pub struct DefaultSpriteShaderUniform {
     model: Uniform<[[f32; 4]; 4]>,
     /// Texture for the texture.
     tex: Uniform<TextureBinding<Dim2, Unsigned>>,
+    /// Color the texture is multiplied by.
+    #[uniform(unbound)]
+    tint: Uniform<[f32; 4]>,
+    /// Corners of the part of the texture that is drawn, in UV space.
+    #[uniform(unbound)]
+    uv_min: Uniform<[f32; 2]>,
+    #[uniform(unbound)]
+    uv_max: Uniform<[f32; 2]>,
+}
+
+impl MaterialUniformInterface for DefaultSpriteShaderUniform {
+    fn projection(&self) -> &Uniform<[[f32; 4]; 4]> {
+        &self.projection
+    }
+
+    fn view(&self) -> &Uniform<[[f32; 4]; 4]> {
+        &self.view
+    }
+
+    fn model(&self) -> &Uniform<[[f32; 4]; 4]> {
+        &self.model
+    }
+
+    fn tex(&self) -> &Uniform<TextureBinding<Dim2, Unsigned>> {
+        &self.tex
+    }
+
+    fn tint(&self) -> &Uniform<[f32; 4]> {
+        &self.tint
+    }
+
+    fn uv_min(&self) -> &Uniform<[f32; 2]> {
+        &self.uv_min
+    }
+
+    fn uv_max(&self) -> &Uniform<[f32; 2]> {
+        &self.uv_max
+    }
 }
 
 pub const SPRITE_RENDERER_LOAD_ID: &str = "sprite_renderer";
@@ -97,10 +149,14 @@ pub struct SpriteRendererLoader {
 }
 
 #[derive(Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema_gen", derive(schemars::JsonSchema))]
 pub struct SpriteRendererJSON {
     render_state_path: String,
     tess_path: String,
-    shader_path: String
+    shader_path: String,
+    /// Must match the index type of the SpriteRenderer being loaded.
+    #[serde(default)]
+    tess_type: TessType
 }
 
 impl SpriteRendererLoader {
@@ -110,7 +166,7 @@ impl SpriteRendererLoader {
         }
     }
 
-    pub fn load(&self) -> DrawTask<SpriteRenderer> {
+    pub fn load<I: LoadableTessIndex>(&self) -> DrawTask<SpriteRenderer<I>> {
         let path = self.path.clone();
 
         DrawTask::new(move |(ecs, context)| {
@@ -130,6 +186,17 @@ impl SpriteRendererLoader {
             #[cfg(feature = "trace")]
             debug!("Loaded json from file: {:?}", json.clone());
 
+            if json.tess_type != I::TESS_TYPE {
+                #[cfg(feature = "trace")]
+                error!("Sprite renderer file: ({:?}) has tess type: {:?}, expected: {:?}", path.clone(), json.tess_type, I::TESS_TYPE);
+
+                return Err(TessTypeMismatchError {
+                    path: path.clone(),
+                    expected: I::TESS_TYPE,
+                    actual: json.tess_type
+                }.into())
+            }
+
             let render_state: RenderStateDef = load_deserializable_from_file(&json.render_state_path, RENDER_STATE_LOAD_ID)
                 .map_err(|e| {
                     #[cfg(feature = "trace")]
@@ -145,8 +212,7 @@ impl SpriteRendererLoader {
             #[cfg(feature = "trace")]
             debug!("Loaded Render State: ({:?}) from file: {:?}", render_state.clone(), json.render_state_path.clone());
 
-            let tess = TessLoader::new(json.tess_path.clone())
-                .load()
+            let tess = I::load_tess(&TessLoader::new(json.tess_path.clone()))
                 .execute((ecs.clone(), context.clone()))
                 .map_err(|e| {
                     #[cfg(feature = "trace")]
@@ -179,6 +245,9 @@ impl SpriteRendererLoader {
                 render_state,
                 tess,
                 shader,
+                tess_cache: TessCache::default(),
+                #[cfg(feature = "debug_rendering")]
+                debug_renderer: None
             })
         })
     }
@@ -192,7 +261,10 @@ impl SpriteRendererLoader {
                     SpriteRenderer {
                         render_state,
                         tess,
-                        shader
+                        shader,
+                        tess_cache: TessCache::default(),
+                        #[cfg(feature = "debug_rendering")]
+                        debug_renderer: None
                     }
                 )
             })
@@ -203,7 +275,7 @@ impl SpriteRendererLoader {
 pub enum SpriteRendererLoadError {
     #[error("Failed to deserialize file: {path:?}")]
     DeserializeError {
-        source: LoadError,
+        source: anyhow::Error,
         path: String
     },
 
@@ -220,22 +292,50 @@ pub enum SpriteRendererLoadError {
     ShaderLoadError {
         source: anyhow::Error,
         path: String
+    },
+
+    #[error("Sprite renderer file: {path} has tess type: {actual:?}, expected: {expected:?}")]
+    TessTypeMismatchError {
+        path: String,
+        expected: TessType,
+        actual: TessType
     }
 }
 
-pub struct SpriteRenderer {
+/// What the sprite renderers fetch from the World to draw each sprite.
+pub(crate) type SpriteData<'a> = (
+    ReadStorage<'a, TextureHandle>,
+    ReadStorage<'a, Transform>,
+    ReadStorage<'a, RenderTransform>,
+    ReadStorage<'a, Material>,
+    ReadStorage<'a, Culled>,
+    ReadStorage<'a, ActiveLOD>,
+    ReadStorage<'a, SpriteRegion>,
+    ReadStorage<'a, Pivot>,
+    Write<'a, TextureDict>,
+    CameraData<'a>
+);
+
+/// Draws sprites with `tess`, which has an index buffer when `I` is `u32`.
+pub struct SpriteRenderer<I: LoadableTessIndex = ()> {
     pub render_state: RenderState,
-    pub tess: Tess<(),(),(),Interleaved>,
+    pub tess: Tess<(),I,(),Interleaved>,
     pub shader: Program<(), (), DefaultSpriteShaderUniform>,
+    /// Tesses of entities with an ActiveLOD, filled by the LODSwitchSystem. Entities whose tess
+    /// isn't cached yet are drawn with `tess`.
+    pub tess_cache: TessCache,
+    /// Drawn on top of the sprites when set.
+    #[cfg(feature = "debug_rendering")]
+    pub debug_renderer: Option<DebugRenderer>,
 }
 
-impl ShaderTypes for SpriteRenderer {
+impl<I: LoadableTessIndex> ShaderTypes for SpriteRenderer<I> {
     type Semantics = ();
     type ReturnValue = ();
     type UniformInterface = DefaultSpriteShaderUniform;
 }
 
-impl Renderer for SpriteRenderer {
+impl<I: LoadableTessIndex> Renderer for SpriteRenderer<I> {
     type S = Self;
 
     #[cfg_attr(feature = "trace", instrument)]
@@ -250,86 +350,194 @@ impl Renderer for SpriteRenderer {
         &mut self,
         pipeline: &Pipeline,
         shd_gate: &mut ShadingGate,
-        proj_matrix: &Mat4,
         world: &World,
     ) -> Result<(), SpriteRenderError> {
         let shader = &mut self.shader;
         let tess = &self.tess;
         let render_state = &self.render_state;
+        let tess_cache = &self.tess_cache;
 
-        shd_gate.shade(shader, |mut iface, uni, mut rdr_gate| {
-            #[cfg(feature = "trace")]
-            debug!("Entering shading gate.");
-
-            let (mut textures, transforms, mut texture_dict, mut camera): (WriteStorage<TextureHandle>, ReadStorage<Transform>, Write<TextureDict>, Write<Option<Box<dyn Camera>>>) = world.system_data();
-            #[cfg(feature = "trace")]
-            debug!("Getting all entities with a texture and transform component to draw. Also fetching TextureDict.");
+        let (textures, transforms, render_transforms, materials, culled, active_lods, regions, pivots, mut texture_dict, mut camera): SpriteData = world.system_data();
+        #[cfg(feature = "trace")]
+        debug!("Getting all entities with a texture and transform component to draw. Also fetching TextureDict.");
 
+        let (view, projection) = {
             let camera = camera.as_mut().ok_or(CameraDNE)?;
+            (camera.view(), camera.projection())
+        };
+        let tint = world.try_fetch::<ColorTint>().map(|tint| *tint).unwrap_or_default();
+
+        // The depth test always passes, so background layers have to be drawn first, whichever
+        // shader draws them. The sort is stable, so sprites on the same layer keep their order.
+        // Entities whose material has no compiled program fall back to the default shader.
+        let mut sprites: Vec<_> = (&textures, &transforms, render_transforms.maybe(), materials.maybe(), active_lods.maybe(), regions.maybe(), pivots.maybe(), !&culled).join()
+            .map(|(tex_handle, transform, render_transform, material, active_lod, region, pivot, _)| (
+                material.filter(|material| material.program.is_some()),
+                (tex_handle, render_transform.map_or(transform, |render_transform| &render_transform.0), active_lod, region, pivot)
+            ))
+            .collect();
+        sprites.sort_by(|(_, (_, a, ..)), (_, (_, b, ..))| a.layer().total_cmp(&b.layer()));
+
+        // Consecutive sprites drawn with the same shader share a shading gate.
+        let same_shader = |a: &Option<&Material>, b: &Option<&Material>| a.map(|material| &material.shader_path) == b.map(|material| &material.shader_path);
+        for run in sprites.chunk_by(|(a, _), (b, _)| same_shader(a, b)) {
+            let run_sprites = run.iter().map(|(_, sprite)| *sprite);
+
+            match run[0].0.and_then(|material| material.program.as_ref().map(|program| (&material.shader_path, program))) {
+                None => {
+                    shd_gate.shade(&mut *shader, |mut iface, uni, mut rdr_gate| {
+                        #[cfg(feature = "trace")]
+                        debug!("Entering shading gate.");
 
-            iface.set(&uni.projection, proj_matrix.to_cols_array_2d());
-            iface.set(&uni.view, camera.view().to_cols_array_2d());
-            #[cfg(feature = "trace")]
-            debug!("Setting uniform values for projection and view matrices using ProgramInterface");
-
-            for (tex_handle, transform) in (&mut textures, &transforms).join() {
-                #[cfg(feature = "trace")]
-                debug!("Rendering texture: ({:?}) with transform: {:?}", tex_handle.clone(), transform);
-
-                if let Some(texture) = texture_dict.get_mut(tex_handle) {
+                        render_sprites(pipeline, &mut iface, uni, &mut rdr_gate, render_state, tess, tess_cache, &projection, &view, &tint, run_sprites, &mut texture_dict)
+                    })?;
+                },
+                Some((shader_path, program)) => {
                     #[cfg(feature = "trace")]
-                    debug!("Found texture in dict for given texture handle.");
+                    debug!("Switching to material shader: {:?}", shader_path.clone());
 
-                    let bound_tex = pipeline.bind_texture(texture)
-                        .map_err(|e| {
+                    let mut program = program.write()
+                        .map_err(|_e| {
                             #[cfg(feature = "trace")]
-                            error!("Failed to bind texture to pipeline.");
+                            error!("Failed to acquire write lock for material shader: {:?}", shader_path.clone());
 
-                            FailedToBind {
-                                texture: tex_handle.clone(),
-                                source: e
-                            }
+                            MaterialLockError { shader_path: shader_path.clone() }
                         })?;
 
-                    iface.set(&uni.tex, bound_tex.binding());
-                    let model = transform.to_model();
-                    iface.set(&uni.model, model.to_cols_array_2d());
+                    shd_gate.shade(program.deref_mut(), |mut iface, uni, mut rdr_gate| {
+                        render_sprites(pipeline, &mut iface, uni, &mut rdr_gate, render_state, tess, tess_cache, &projection, &view, &tint, run_sprites, &mut texture_dict)
+                    })?;
+                }
+            }
+        }
+
+        #[cfg(feature = "debug_rendering")]
+        if let Some(debug_renderer) = self.debug_renderer.as_mut() {
+            // The debug renderer fetches these itself.
+            drop((texture_dict, camera));
+
+            debug_renderer.render(pipeline, shd_gate, world)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<I: LoadableTessIndex> RenderPipeline for SpriteRenderer<I> {
+    /// Clears the back buffer and draws every sprite with the camera's view and projection.
+    #[cfg_attr(feature = "trace", instrument(skip(self, ecs, context)))]
+    fn render_frame(&mut self, ecs: &mut World, context: &mut GL33Context) -> anyhow::Result<()> {
+        let back_buffer = context.back_buffer()
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to get back buffer.");
+
+                BackBufferError {
+                    source: e
+                }
+            })?;
+
+        #[cfg(feature = "debug_rendering")]
+        if let Some(debug_renderer) = self.debug_renderer.as_mut() {
+            debug_renderer.prepare(ecs, context)?;
+        }
+
+        context.new_pipeline_gate().pipeline(&back_buffer, &PipelineState::default(), |pipeline, mut shd_gate| {
+            self.render(&pipeline, &mut shd_gate, ecs)
+        }).into_result()?;
+
+        Ok(())
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn render_sprites<'a, U: MaterialUniformInterface, I: LoadableTessIndex>(
+    pipeline: &Pipeline,
+    iface: &mut ProgramInterface,
+    uni: &U,
+    rdr_gate: &mut RenderGate,
+    render_state: &RenderState,
+    tess: &Tess<(),I,(),Interleaved>,
+    tess_cache: &TessCache,
+    proj_matrix: &Mat4,
+    view: &Mat4,
+    tint: &ColorTint,
+    sprites: impl Iterator<Item = (&'a TextureHandle, &'a Transform, Option<&'a ActiveLOD>, Option<&'a SpriteRegion>, Option<&'a Pivot>)>,
+    texture_dict: &mut TextureDict
+) -> Result<(), SpriteRenderError> {
+    iface.set(uni.projection(), proj_matrix.to_cols_array_2d());
+    iface.set(uni.view(), view.to_cols_array_2d());
+    iface.set(uni.tint(), tint.0);
+    #[cfg(feature = "trace")]
+    debug!("Setting uniform values for projection and view matrices using ProgramInterface");
+
+    // `sprites` are in layer order, see SpriteRenderer::render.
+    for (tex_handle, transform, active_lod, region, pivot) in sprites {
+        // LOD tesses are loaded without an index buffer, so they can differ in type from `tess`.
+        let lod_tess = active_lod.and_then(|active_lod| tess_cache.get(&active_lod.0));
+
+        #[cfg(feature = "trace")]
+        debug!("Rendering texture: ({:?}) with transform: {:?}", tex_handle.clone(), transform);
+
+        if let Some(texture) = texture_dict.get_mut(tex_handle) {
+            #[cfg(feature = "trace")]
+            debug!("Found texture in dict for given texture handle.");
+
+            let bound_tex = pipeline.bind_texture(texture)
+                .map_err(|e| {
                     #[cfg(feature = "trace")]
-                    debug!("Successfully bound texture. Setting texture and model matrix for uniform.");
+                    error!("Failed to bind texture to pipeline.");
 
-                    rdr_gate.render(render_state, |mut tess_gate| {
-                        #[cfg(feature = "trace")]
-                        debug!("Entering render gate.");
+                    FailedToBind {
+                        texture: tex_handle.clone(),
+                        source: e
+                    }
+                })?;
 
-                        tess_gate.render(tess)
-                            .map_err(|e| {
-                                #[cfg(feature = "trace")]
-                                error!("Failed to call render on tess gate.");
+            iface.set(uni.tex(), bound_tex.binding());
+            // Uniforms keep their values between sprites, so sprites without a region reset them.
+            let (uv_min, uv_max) = region.map_or(([0.0, 0.0], [1.0, 1.0]), |region| region.uv_bounds(texture.size()));
+            iface.set(uni.uv_min(), uv_min);
+            iface.set(uni.uv_max(), uv_max);
+            let model = transform.to_model(pivot.map(|pivot| pivot.offset));
+            iface.set(uni.model(), model.to_cols_array_2d());
+            #[cfg(feature = "trace")]
+            debug!("Successfully bound texture. Setting texture and model matrix for uniform.");
 
-                                TessRenderError {
-                                    source: e
-                                }
-                            })?;
+            rdr_gate.render(render_state, |mut tess_gate| {
+                #[cfg(feature = "trace")]
+                debug!("Entering render gate.");
 
+                match lod_tess {
+                    Some(lod_tess) => tess_gate.render(lod_tess),
+                    None => tess_gate.render(tess)
+                }
+                    .map_err(|e| {
                         #[cfg(feature = "trace")]
-                        debug!("Successfully called render on tess gate.");
+                        error!("Failed to call render on tess gate.");
 
-                        Ok(())
-                    })
-                        .map_err(|e| {
-                            #[cfg(feature = "trace")]
-                            error!("Failed to call render on render gate.");
+                        TessRenderError {
+                            source: e
+                        }
+                    })?;
 
-                            RenderGateError {
-                                source: Box::new(e)
-                            }
-                        })?;
-                }
-            }
+                #[cfg(feature = "trace")]
+                debug!("Successfully called render on tess gate.");
 
-            Ok(())
-        })
+                Ok(())
+            })
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to call render on render gate.");
+
+                    RenderGateError {
+                        source: Box::new(e)
+                    }
+                })?;
+        }
     }
+
+    Ok(())
 }
 
 #[derive(Error, Debug)]
@@ -356,6 +564,31 @@ pub enum SpriteRenderError {
     },
     #[error("Failed to get camera from System Data.")]
     CameraDNE,
+
+    #[error("Failed to get the back buffer")]
+    BackBufferError {
+        source: FramebufferError
+    },
+
+    #[error("Failed to build the framebuffer sprites are drawn into below full resolution")]
+    ScaledFramebufferError {
+        source: FramebufferError
+    },
+
+    #[error("Failed to copy the scaled sprites to the back buffer")]
+    BlitError {
+        source: BlitError
+    },
+
+    #[error("Failed to acquire write lock for material shader: {shader_path}")]
+    MaterialLockError {
+        shader_path: String
+    },
+
+    #[error("Failed to map the instance buffer of a sprite batch")]
+    InstanceBufferError {
+        source: TessMapError
+    },
 }
 
 impl From<PipelineError> for SpriteRenderError {
@@ -0,0 +1,436 @@
+use std::collections::HashMap;
+
+use luminance_front::{
+    render_state::RenderState,
+    tess::{Tess, Mode, TessError},
+    shader::{Uniform, Program},
+    pipeline::{TextureBinding, Pipeline, PipelineError},
+    texture::Dim2,
+    shading_gate::ShadingGate,
+    pixel::Unsigned,
+    context::GraphicsContext,
+};
+use luminance_derive::{Semantics, Vertex, UniformInterface};
+use luminance_glfw::GL33Context;
+
+use glam::{Mat4, Quat, Vec3};
+use serde::Deserialize;
+use specs::{World, Write, Join, ReadStorage, Read, Entities, Entity};
+use thiserror::Error;
+
+#[cfg(feature = "trace")]
+use tracing::{debug, error, instrument};
+
+use crate::camera::Camera;
+use crate::components::world_text::{WorldText, TextAlignment};
+use crate::components::typewriter::RenderedText;
+use crate::globals::font_atlas_dict::{FontAtlasDict, FontAtlas};
+use crate::globals::texture_dict::TextureDict;
+use crate::globals::accessibility::AccessibilityConfig;
+use crate::graphics::transform::Transform;
+use crate::graphics::shader::ShaderLoader;
+use crate::graphics::render::{Renderer, ShaderTypes, CameraData};
+use crate::graphics::render::deserializations::{RenderStateDef, RENDER_STATE_LOAD_ID};
+use crate::graphics::render::sprite_renderer::{default_sprite_render_state, SpriteRenderError};
+use crate::graphics::render::sprite_renderer::SpriteRenderError::{FailedToBind, TessRenderError, RenderGateError, CameraDNE};
+use crate::graphics::render::text_renderer::WorldTextRendererLoadError::{DeserializeError, ShaderLoadError, ContextWriteError, ShaderProgramBuildError};
+use crate::graphics::render::text_renderer::WorldTextPrepareError::TessBuildError;
+use crate::load::{load_deserializable_from_file, LoadError};
+use crate::loading::DrawTask;
+
+const VS: &str = include_str!("../text-vs.glsl");
+const FS: &str = include_str!("../text-fs.glsl");
+
+pub const WORLD_TEXT_RENDERER_LOAD_ID: &str = "world_text_renderer";
+
+#[derive(Copy, Clone, Debug, Semantics)]
+pub enum TextSemantics {
+    #[sem(name = "position", repr = "[f32; 2]", wrapper = "TextPosition")]
+    Position,
+    #[sem(name = "uv", repr = "[f32; 2]", wrapper = "TextUV")]
+    UV
+}
+
+#[derive(Copy, Clone, Debug, Vertex)]
+#[vertex(sem = "TextSemantics")]
+pub struct TextVertex {
+    pub position: TextPosition,
+    pub uv: TextUV
+}
+
+#[derive(Debug, UniformInterface)]
+pub struct TextShaderUniform {
+    /// PROJECTION matrix in MVP
+    pub(crate) projection: Uniform<[[f32; 4]; 4]>,
+    /// VIEW matrix in MVP
+    pub(crate) view: Uniform<[[f32; 4]; 4]>,
+    /// MODEL matrix in MVP
+    pub(crate) model: Uniform<[[f32; 4]; 4]>,
+    /// Font atlas texture.
+    pub(crate) tex: Uniform<TextureBinding<Dim2, Unsigned>>,
+    /// Color the glyphs are filled with.
+    pub(crate) color: Uniform<[f32; 4]>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema_gen", derive(schemars::JsonSchema))]
+pub struct WorldTextRendererJSON {
+    render_state_path: String,
+    shader_path: String
+}
+
+pub struct WorldTextRendererLoader {
+    pub path: String
+}
+
+impl WorldTextRendererLoader {
+    pub fn new(path: String) -> Self {
+        Self {
+            path
+        }
+    }
+
+    pub fn load(&self) -> DrawTask<WorldTextRenderer> {
+        let path = self.path.clone();
+
+        DrawTask::new(move |(ecs, context)| {
+            #[cfg(feature = "trace")]
+            debug!("Loading World Text Renderer from file: {:?}", path.clone());
+
+            let json: WorldTextRendererJSON = load_deserializable_from_file(&path, WORLD_TEXT_RENDERER_LOAD_ID)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to load deserializable from file: {:?}", path.clone());
+
+                    DeserializeError {
+                        source: e,
+                        path: path.clone()
+                    }
+                })?;
+
+            let render_state: RenderStateDef = load_deserializable_from_file(&json.render_state_path, RENDER_STATE_LOAD_ID)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to deserialize Render State from file: {:?}", json.render_state_path.clone());
+
+                    DeserializeError {
+                        source: e,
+                        path: json.render_state_path.clone()
+                    }
+                })?;
+
+            let shader = ShaderLoader::new(json.shader_path.clone())
+                .load()
+                .execute((ecs, context))
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to load shader from file: {:?}", json.shader_path);
+
+                    ShaderLoadError {
+                        source: e,
+                        path: json.shader_path.clone()
+                    }
+                })?;
+            #[cfg(feature = "trace")]
+            debug!("Loaded shader from file: {:?}", json.shader_path.clone());
+
+            Ok(WorldTextRenderer {
+                render_state: RenderState::from(render_state),
+                shader,
+                cache: HashMap::new()
+            })
+        })
+    }
+
+    /// Uses the sprite render state so glyph coverage is alpha blended.
+    pub fn load_default() -> DrawTask<WorldTextRenderer> {
+        DrawTask::new(|(_ecs, context)| {
+            let mut context = context.write()
+                .map_err(|_e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to acquire write lock for context");
+
+                    ContextWriteError
+                })?;
+
+            let shader = context
+                .new_shader_program()
+                .from_strings(VS, None, None, FS)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to build default text shader program.");
+
+                    ShaderProgramBuildError {
+                        source: e
+                    }
+                })?
+                .ignore_warnings();
+
+            Ok(WorldTextRenderer {
+                render_state: default_sprite_render_state(),
+                shader,
+                cache: HashMap::new()
+            })
+        })
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum WorldTextRendererLoadError {
+    #[error("Failed to deserialize file: {path:?}")]
+    DeserializeError {
+        source: anyhow::Error,
+        path: String
+    },
+
+    #[error("Failed to acquire write lock for context")]
+    ContextWriteError,
+
+    #[error("Failed to load Shader from file: {path}")]
+    ShaderLoadError {
+        source: anyhow::Error,
+        path: String
+    },
+
+    #[error("Failed to build the default text shader program")]
+    ShaderProgramBuildError {
+        source: luminance_front::shader::ProgramError
+    }
+}
+
+/// Draws every entity with a WorldText and Transform component, using its RenderedText instead of
+/// the WorldText's content when it has one. The text is laid out in pixels of the font atlas and
+/// scaled by `WorldText::size` and the AccessibilityConfig's font scale, so the Transform's scale
+/// is ignored.
+pub struct WorldTextRenderer {
+    pub render_state: RenderState,
+    pub shader: Program<TextSemantics, (), TextShaderUniform>,
+    /// Glyph quads of each entity, rebuilt whenever its WorldText changes.
+    cache: HashMap<Entity, (WorldText, Option<Tess<TextVertex>>)>
+}
+
+impl WorldTextRenderer {
+    /// Rebuilds the glyph quads of every WorldText that changed since the last call. Must be called
+    /// before `render` because the Renderer trait has no access to the context.
+    #[cfg_attr(feature = "trace", instrument(skip(self, world, context)))]
+    pub fn prepare(&mut self, world: &World, context: &mut GL33Context) -> Result<(), WorldTextPrepareError> {
+        let (entities, texts, rendered_texts, font_atlas_dict): (Entities, ReadStorage<WorldText>, ReadStorage<RenderedText>, Read<FontAtlasDict>) = world.system_data();
+
+        self.cache.retain(|entity, _| texts.contains(*entity));
+
+        for (entity, text) in (&entities, &texts).join() {
+            let text = &match rendered_texts.get(entity) {
+                Some(rendered) => WorldText { content: rendered.0.clone(), ..text.clone() },
+                None => text.clone()
+            };
+
+            if let Some((cached, _)) = self.cache.get(&entity) {
+                if cached == text {
+                    continue
+                }
+            }
+
+            let vertices = match font_atlas_dict.get(&text.font_handle) {
+                Some(atlas) => layout_text(text, atlas),
+                None => {
+                    #[cfg(feature = "trace")]
+                    debug!("No font atlas found for font: {:?}", text.font_handle.clone());
+
+                    Vec::new()
+                }
+            };
+
+            let tess = if vertices.is_empty() {
+                None
+            } else {
+                Some(
+                    context
+                        .new_tess()
+                        .set_vertices(vertices)
+                        .set_mode(Mode::Triangle)
+                        .build()
+                        .map_err(|e| {
+                            #[cfg(feature = "trace")]
+                            error!("Failed to build tess for text: {:?}", text.content.clone());
+
+                            TessBuildError {
+                                content: text.content.clone(),
+                                source: e
+                            }
+                        })?
+                )
+            };
+
+            #[cfg(feature = "trace")]
+            debug!("Rebuilt glyph quads for text: {:?}", text.content.clone());
+
+            self.cache.insert(entity, (text.clone(), tess));
+        }
+
+        Ok(())
+    }
+}
+
+/// Builds two triangles per visible glyph with the origin on the baseline of the first line.
+pub(crate) fn layout_text(text: &WorldText, atlas: &FontAtlas) -> Vec<TextVertex> {
+    let mut vertices = Vec::new();
+
+    for (line_index, line) in text.content.lines().enumerate() {
+        let width: f32 = line.chars()
+            .filter_map(|character| atlas.glyphs.get(&character))
+            .map(|metrics| metrics.advance)
+            .sum();
+
+        let mut pen_x = match text.alignment {
+            TextAlignment::Left => 0.0,
+            TextAlignment::Center => -width / 2.0,
+            TextAlignment::Right => -width
+        };
+        let pen_y = -(line_index as f32) * atlas.line_height;
+
+        for character in line.chars() {
+            let metrics = match atlas.glyphs.get(&character) {
+                Some(metrics) => metrics,
+                None => continue
+            };
+
+            if metrics.size[0] > 0.0 && metrics.size[1] > 0.0 {
+                let (x0, y0) = (pen_x + metrics.bearing[0], pen_y + metrics.bearing[1]);
+                let (x1, y1) = (x0 + metrics.size[0], y0 + metrics.size[1]);
+                let (u0, v0) = (metrics.uv_min[0], metrics.uv_min[1]);
+                let (u1, v1) = (metrics.uv_max[0], metrics.uv_max[1]);
+
+                let corner = |x: f32, y: f32, u: f32, v: f32| TextVertex {
+                    position: TextPosition::new([x, y]),
+                    uv: TextUV::new([u, v])
+                };
+
+                vertices.extend_from_slice(&[
+                    corner(x0, y0, u0, v0),
+                    corner(x1, y0, u1, v0),
+                    corner(x1, y1, u1, v1),
+                    corner(x0, y0, u0, v0),
+                    corner(x1, y1, u1, v1),
+                    corner(x0, y1, u0, v1)
+                ]);
+            }
+
+            pen_x += metrics.advance;
+        }
+    }
+
+    vertices
+}
+
+#[derive(Error, Debug)]
+pub enum WorldTextPrepareError {
+    #[error("Failed to build glyph tess for text: {content:?}")]
+    TessBuildError {
+        content: String,
+        source: TessError
+    }
+}
+
+impl ShaderTypes for WorldTextRenderer {
+    type Semantics = TextSemantics;
+    type ReturnValue = ();
+    type UniformInterface = TextShaderUniform;
+}
+
+impl Renderer for WorldTextRenderer {
+    type S = Self;
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn load(path: String) -> DrawTask<Self> {
+        let loader = WorldTextRendererLoader::new(path);
+
+        loader.load()
+    }
+
+    #[cfg_attr(feature = "trace", instrument(skip(self, pipeline, shd_gate, world)))]
+    fn render(
+        &mut self,
+        pipeline: &Pipeline,
+        shd_gate: &mut ShadingGate,
+        world: &World,
+    ) -> Result<(), SpriteRenderError> {
+        let shader = &mut self.shader;
+        let cache = &self.cache;
+        let render_state = &self.render_state;
+
+        shd_gate.shade(shader, |mut iface, uni, mut rdr_gate| {
+            #[cfg(feature = "trace")]
+            debug!("Entering shading gate.");
+
+            let (entities, transforms, font_atlas_dict, mut texture_dict, mut camera): (Entities, ReadStorage<Transform>, Read<FontAtlasDict>, Write<TextureDict>, CameraData) = world.system_data();
+            let accessibility: Option<Read<AccessibilityConfig>> = world.system_data();
+            let accessibility = accessibility.map(|config| *config).unwrap_or_default();
+
+            let camera = camera.as_mut().ok_or(CameraDNE)?;
+
+            iface.set(&uni.projection, camera.projection().to_cols_array_2d());
+            iface.set(&uni.view, camera.view().to_cols_array_2d());
+
+            for (entity, transform) in (&entities, &transforms).join() {
+                let (text, tess) = match cache.get(&entity) {
+                    Some((text, Some(tess))) => (text, tess),
+                    _ => continue
+                };
+
+                let atlas = match font_atlas_dict.get(&text.font_handle) {
+                    Some(atlas) => atlas,
+                    None => continue
+                };
+
+                #[cfg(feature = "trace")]
+                debug!("Rendering text: ({:?}) with transform: {:?}", text.content.clone(), transform);
+
+                if let Some(texture) = texture_dict.get_mut(&atlas.texture_handle) {
+                    let bound_tex = pipeline.bind_texture(texture)
+                        .map_err(|e| {
+                            #[cfg(feature = "trace")]
+                            error!("Failed to bind font atlas to pipeline.");
+
+                            FailedToBind {
+                                texture: atlas.texture_handle.clone(),
+                                source: e
+                            }
+                        })?;
+
+                    let scale = text.size * accessibility.font_scale / atlas.font_size;
+                    let model = Mat4::from_scale_rotation_translation(
+                        Vec3::new(scale, scale, 1.0),
+                        transform.rotation_quat(),
+                        transform.translation
+                    );
+
+                    iface.set(&uni.tex, bound_tex.binding());
+                    iface.set(&uni.model, model.to_cols_array_2d());
+                    iface.set(&uni.color, accessibility.text_color(text.color));
+
+                    rdr_gate.render(render_state, |mut tess_gate| {
+                        tess_gate.render(tess)
+                            .map_err(|e| {
+                                #[cfg(feature = "trace")]
+                                error!("Failed to call render on tess gate.");
+
+                                TessRenderError {
+                                    source: e
+                                }
+                            })
+                    })
+                        .map_err(|e| {
+                            #[cfg(feature = "trace")]
+                            error!("Failed to call render on render gate.");
+
+                            RenderGateError {
+                                source: Box::new(e)
+                            }
+                        })?;
+                }
+            }
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,372 @@
+use std::collections::{HashMap, VecDeque};
+
+use luminance_front::context::GraphicsContext;
+use luminance_front::framebuffer::Framebuffer;
+use luminance_front::pipeline::PipelineState;
+use luminance_front::pixel::NormRGBA8UI;
+use luminance_front::scissor::ScissorRegion;
+use luminance_front::texture::{Dim2, Sampler};
+use luminance_glfw::GL33Context;
+use specs::World;
+use glfw::Context as _;
+use thiserror::Error;
+
+#[cfg(feature = "trace")]
+use tracing::{debug, error, instrument};
+
+use crate::graphics::dynamic_resolution::DynamicResolution;
+use crate::graphics::render::Renderer;
+use crate::graphics::render::blit::Blitter;
+use crate::graphics::render::sprite_renderer::{SpriteRenderer, SpriteRenderError};
+use crate::graphics::render::sprite_renderer::SpriteRenderError::{BackBufferError, BlitError, ScaledFramebufferError};
+use crate::graphics::render_graph::RenderGraphError::{CycleError, DuplicateNodeError, MissingDependencyError, NodeRenderError, OverlayError};
+use crate::graphics::tess::LoadableTessIndex;
+
+pub const CLEAR_NODE: &str = "clear";
+pub const SPRITES_NODE: &str = "sprites";
+pub const UI_NODE: &str = "ui";
+pub const POST_PROCESS_NODE: &str = "post_process";
+pub const SWAP_BUFFERS_NODE: &str = "swap_buffers";
+
+/// One pass of a RenderGraph. Closures taking the World and the context are tasks too.
+pub trait RenderNodeTask {
+    fn run(&mut self, ecs: &World, context: &mut GL33Context) -> Result<(), SpriteRenderError>;
+}
+
+impl<F: FnMut(&World, &mut GL33Context) -> Result<(), SpriteRenderError>> RenderNodeTask for F {
+    fn run(&mut self, ecs: &World, context: &mut GL33Context) -> Result<(), SpriteRenderError> {
+        self(ecs, context)
+    }
+}
+
+/// Runs after every node named in `dependencies`.
+pub struct RenderNode {
+    pub name: String,
+    pub dependencies: Vec<String>,
+    pub task: Box<dyn RenderNodeTask>
+}
+
+impl RenderNode {
+    pub fn new(name: impl Into<String>, dependencies: Vec<String>, task: impl RenderNodeTask + 'static) -> Self {
+        Self {
+            name: name.into(),
+            dependencies,
+            task: Box::new(task)
+        }
+    }
+}
+
+impl std::fmt::Debug for RenderNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RenderNode")
+            .field("name", &self.name)
+            .field("dependencies", &self.dependencies)
+            .finish()
+    }
+}
+
+/// Render passes drawn in dependency order each frame, e.g. shadow map, then geometry, then UI,
+/// then a blit. Give it to `SceneStack::set_render_graph` to draw with it instead of with the
+/// scenes' `draw`. Tesses and shaders can't be sent between threads, so the graph is owned by the
+/// SceneStack rather than stored in the World.
+#[derive(Debug, Default)]
+pub struct RenderGraph {
+    nodes: Vec<RenderNode>,
+    /// Indices into `nodes` in the order they run. Cleared whenever the nodes change.
+    order: Option<Vec<usize>>
+}
+
+impl RenderGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The built-in passes: `clear` clears the back buffer, `sprites` draws with `sprite_renderer`
+    /// and `swap_buffers` puts the frame on screen. `ui` and `post_process` draw nothing and are
+    /// there for custom nodes to depend on.
+    ///
+    /// When the World has a DynamicResolution below full scale, `sprites` draws into a framebuffer
+    /// that much smaller than the back buffer and stretches it over the back buffer. Later nodes
+    /// still draw at full resolution.
+    pub fn with_default_nodes<I: LoadableTessIndex>(mut sprite_renderer: SpriteRenderer<I>) -> Self {
+        let mut scaled_target = None;
+
+        let nodes = vec![
+            RenderNode::new(CLEAR_NODE, vec![], clear_back_buffer),
+            RenderNode::new(SPRITES_NODE, vec![CLEAR_NODE.to_string()], move |ecs: &World, context: &mut GL33Context| {
+                let scale = ecs.try_fetch::<DynamicResolution>().map(|resolution| resolution.current_scale);
+
+                match scale {
+                    Some(scale) if scale < 1.0 => draw_sprites_scaled(&mut sprite_renderer, &mut scaled_target, scale, ecs, context),
+                    _ => draw_sprites(&mut sprite_renderer, ecs, context)
+                }
+            }),
+            RenderNode::new(UI_NODE, vec![SPRITES_NODE.to_string()], |_ecs: &World, _context: &mut GL33Context| Ok(())),
+            RenderNode::new(POST_PROCESS_NODE, vec![UI_NODE.to_string()], |_ecs: &World, _context: &mut GL33Context| Ok(())),
+            RenderNode::new(SWAP_BUFFERS_NODE, vec![POST_PROCESS_NODE.to_string()], |_ecs: &World, context: &mut GL33Context| {
+                context.window.swap_buffers();
+                Ok(())
+            })
+        ];
+
+        Self {
+            nodes,
+            order: None
+        }
+    }
+
+    #[cfg_attr(feature = "trace", instrument(skip(self)))]
+    pub fn add_node(&mut self, node: RenderNode) -> Result<(), RenderGraphError> {
+        if self.contains(&node.name) {
+            #[cfg(feature = "trace")]
+            error!("Render graph already has a node named: {:?}", node.name);
+
+            return Err(DuplicateNodeError { name: node.name })
+        }
+
+        self.nodes.push(node);
+        self.order = None;
+
+        Ok(())
+    }
+
+    pub fn remove_node(&mut self, name: &str) -> Option<RenderNode> {
+        let index = self.nodes.iter().position(|node| node.name == name)?;
+        self.order = None;
+
+        Some(self.nodes.remove(index))
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.nodes.iter().any(|node| node.name == name)
+    }
+
+    pub fn node_names(&self) -> impl Iterator<Item = &str> {
+        self.nodes.iter().map(|node| node.name.as_str())
+    }
+
+    /// Sorts the nodes so each runs after its dependencies. Nodes that don't depend on each other
+    /// run in the order they were added. `execute` calls this when the nodes have changed.
+    #[cfg_attr(feature = "trace", instrument(skip(self)))]
+    pub fn build(&mut self) -> Result<(), RenderGraphError> {
+        let indices: HashMap<&str, usize> = self.nodes.iter()
+            .enumerate()
+            .map(|(index, node)| (node.name.as_str(), index))
+            .collect();
+
+        let mut remaining_dependencies = vec![0; self.nodes.len()];
+        let mut dependents = vec![Vec::new(); self.nodes.len()];
+        for (index, node) in self.nodes.iter().enumerate() {
+            for dependency in node.dependencies.iter() {
+                let dependency_index = *indices.get(dependency.as_str())
+                    .ok_or_else(|| {
+                        #[cfg(feature = "trace")]
+                        error!("Render node: {:?} depends on missing node: {:?}", node.name, dependency);
+
+                        MissingDependencyError {
+                            node: node.name.clone(),
+                            dependency: dependency.clone()
+                        }
+                    })?;
+
+                remaining_dependencies[index] += 1;
+                dependents[dependency_index].push(index);
+            }
+        }
+
+        let mut ready: VecDeque<usize> = (0..self.nodes.len())
+            .filter(|index| remaining_dependencies[*index] == 0)
+            .collect();
+        let mut order = Vec::with_capacity(self.nodes.len());
+
+        while let Some(index) = ready.pop_front() {
+            order.push(index);
+
+            for dependent in dependents[index].iter() {
+                remaining_dependencies[*dependent] -= 1;
+                if remaining_dependencies[*dependent] == 0 {
+                    ready.push_back(*dependent);
+                }
+            }
+        }
+
+        if order.len() < self.nodes.len() {
+            let nodes: Vec<String> = (0..self.nodes.len())
+                .filter(|index| remaining_dependencies[*index] > 0)
+                .map(|index| self.nodes[index].name.clone())
+                .collect();
+
+            #[cfg(feature = "trace")]
+            error!("Render graph has a dependency cycle between: {:?}", nodes);
+
+            return Err(CycleError { nodes })
+        }
+
+        #[cfg(feature = "trace")]
+        debug!("Render graph order: {:?}", order.iter().map(|index| self.nodes[*index].name.as_str()).collect::<Vec<_>>());
+
+        self.order = Some(order);
+
+        Ok(())
+    }
+
+    /// Runs every node in dependency order.
+    #[cfg_attr(feature = "trace", instrument(skip(self, ecs, context)))]
+    pub fn execute(&mut self, ecs: &World, context: &mut GL33Context) -> Result<(), RenderGraphError> {
+        self.execute_with_overlay(ecs, context, |_ecs: &World, _context: &mut GL33Context| Ok(()))
+    }
+
+    /// Runs every node in dependency order and draws `overlay` right before the `swap_buffers`
+    /// node, or after the last node if there isn't one. For drawing that isn't a node, like the
+    /// debug overlay.
+    #[cfg_attr(feature = "trace", instrument(skip(self, ecs, context, overlay)))]
+    pub fn execute_with_overlay(&mut self, ecs: &World, context: &mut GL33Context, overlay: impl FnOnce(&World, &mut GL33Context) -> anyhow::Result<()>) -> Result<(), RenderGraphError> {
+        if self.order.is_none() {
+            self.build()?;
+        }
+
+        let mut overlay = Some(overlay);
+        let mut draw_overlay = |ecs: &World, context: &mut GL33Context| match overlay.take() {
+            Some(overlay) => overlay(ecs, context)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to draw the overlay");
+
+                    OverlayError { source: e }
+                }),
+            None => Ok(())
+        };
+
+        if let Some(order) = self.order.as_ref() {
+            for index in order.iter() {
+                let node = &mut self.nodes[*index];
+
+                if node.name == SWAP_BUFFERS_NODE {
+                    draw_overlay(ecs, context)?;
+                }
+
+                node.task.run(ecs, context)
+                    .map_err(|e| {
+                        #[cfg(feature = "trace")]
+                        error!("Render node: {:?} failed", node.name);
+
+                        NodeRenderError {
+                            name: node.name.clone(),
+                            source: e
+                        }
+                    })?;
+            }
+        }
+
+        draw_overlay(ecs, context)
+    }
+}
+
+fn clear_back_buffer(_ecs: &World, context: &mut GL33Context) -> Result<(), SpriteRenderError> {
+    let back_buffer = context.back_buffer()
+        .map_err(|e| BackBufferError { source: e })?;
+
+    context.new_pipeline_gate()
+        .pipeline(&back_buffer, &PipelineState::default(), |_pipeline, _shd_gate| Ok(()))
+        .into_result()
+}
+
+fn draw_sprites<I: LoadableTessIndex>(sprite_renderer: &mut SpriteRenderer<I>, ecs: &World, context: &mut GL33Context) -> Result<(), SpriteRenderError> {
+    let back_buffer = context.back_buffer()
+        .map_err(|e| BackBufferError { source: e })?;
+    let pipeline_state = PipelineState::default()
+        .enable_clear_color(false)
+        .enable_clear_depth(false);
+
+    context.new_pipeline_gate()
+        .pipeline(&back_buffer, &pipeline_state, |pipeline, mut shd_gate| {
+            sprite_renderer.render(&pipeline, &mut shd_gate, ecs)
+        })
+        .into_result()
+}
+
+/// Framebuffer the `sprites` node draws into below full resolution, and the Blitter that copies it
+/// to the back buffer.
+struct ScaledTarget {
+    framebuffer: Framebuffer<Dim2, NormRGBA8UI, ()>,
+    blitter: Blitter
+}
+
+/// Draws the sprites into `scaled_target` at `scale` times the back buffer's size, then stretches
+/// them over the back buffer. The target is rebuilt whenever that size changes.
+fn draw_sprites_scaled<I: LoadableTessIndex>(
+    sprite_renderer: &mut SpriteRenderer<I>,
+    scaled_target: &mut Option<ScaledTarget>,
+    scale: f32,
+    ecs: &World,
+    context: &mut GL33Context
+) -> Result<(), SpriteRenderError> {
+    let [width, height] = context.back_buffer()
+        .map_err(|e| BackBufferError { source: e })?
+        .size();
+    let size = [
+        ((width as f32 * scale).round() as u32).max(1),
+        ((height as f32 * scale).round() as u32).max(1)
+    ];
+
+    let target = match scaled_target {
+        Some(target) if target.framebuffer.size() == size => target,
+        _ => {
+            #[cfg(feature = "trace")]
+            debug!("Building {:?} framebuffer for a render scale of {:?}", size, scale);
+
+            let framebuffer = context.new_framebuffer::<Dim2, NormRGBA8UI, ()>(size, 0, Sampler::default())
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to build {:?} framebuffer for dynamic resolution", size);
+
+                    ScaledFramebufferError { source: e }
+                })?;
+            let blitter = match scaled_target.take() {
+                Some(target) => target.blitter,
+                None => Blitter::new(context).map_err(|e| BlitError { source: e })?
+            };
+
+            scaled_target.insert(ScaledTarget { framebuffer, blitter })
+        }
+    };
+
+    context.new_pipeline_gate()
+        .pipeline(&target.framebuffer, &PipelineState::default(), |pipeline, mut shd_gate| {
+            sprite_renderer.render(&pipeline, &mut shd_gate, ecs)
+        })
+        .into_result()?;
+
+    target.blitter.blit(context, &mut target.framebuffer, ScissorRegion { x: 0, y: 0, width, height })
+        .map_err(|e| BlitError { source: e })
+}
+
+#[derive(Error, Debug)]
+pub enum RenderGraphError {
+    #[error("Render graph already has a node named: {name}")]
+    DuplicateNodeError {
+        name: String
+    },
+
+    #[error("Render node: {node} depends on missing node: {dependency}")]
+    MissingDependencyError {
+        node: String,
+        dependency: String
+    },
+
+    #[error("Render graph has a dependency cycle between: {}", .nodes.join(", "))]
+    CycleError {
+        nodes: Vec<String>
+    },
+
+    #[error("Render node: {name} failed")]
+    NodeRenderError {
+        name: String,
+        source: SpriteRenderError
+    },
+
+    #[error("Failed to draw the overlay on top of the render graph")]
+    OverlayError {
+        source: anyhow::Error
+    }
+}
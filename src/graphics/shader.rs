@@ -4,33 +4,68 @@ use crate::load::{load_deserializable_from_file, LoadError};
 use thiserror::Error;
 
 #[cfg(feature="trace")]
-use tracing::{instrument, error, debug};
-use crate::graphics::shader::ShaderLoadError::{DeserializeError, ContextWriteError, FileReadError, ShaderProgramBuildError};
+use tracing::{instrument, error, debug, warn};
+use crate::graphics::shader::ShaderLoadError::{DeserializeError, ContextWriteError, FileReadError, ShaderProgramBuildError, WorldReadLockError, ShaderCompileWarning, SourcesLockError};
 use luminance::context::GraphicsContext;
-use std::fs::read_to_string;
+use std::fs::{metadata, read_to_string};
 use crate::graphics::render::sprite_renderer::{DefaultSpriteShaderUniform};
 use serde::Deserialize;
 use luminance_front::vertex::Semantics;
 use std::marker::PhantomData;
 use luminance::backend::shader::Shader;
+use luminance_front::shader::BuiltProgram;
+use luminance_glfw::GL33Context;
+use std::collections::HashMap;
+use std::ops::DerefMut;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+#[cfg(feature = "hot_reload")]
+use std::sync::RwLock;
+#[cfg(feature = "hot_reload")]
+use specs::{World, WorldExt};
+#[cfg(feature = "hot_reload")]
+use crate::hot_reload::{self, HotReloadError, HotReloadWatcher};
+#[cfg(feature = "hot_reload")]
+use crate::graphics::shader::ShaderLoadError::{HotReloadFailed, ShaderReloadError};
+#[cfg(all(feature = "hot_reload", feature = "trace"))]
+use crate::load::display_chain;
 
 pub const SHADER_LOAD_ID: &str = "shader";
 
 const VS: &'static str = include_str!("./texture-vs.glsl");
 const FS: &'static str = include_str!("./texture-fs.glsl");
 
+/// When present in the World, shader programs that compile with warnings fail to load with a
+/// ShaderCompileWarning instead of only reporting the warnings.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StrictShaders;
+
 #[derive(Debug, Clone)]
 pub struct ShaderLoader {
     path: String,
+    /// Stages read so far, by path. Shared between clones.
+    sources: Arc<Mutex<HashMap<String, StageSource>>>
+}
+
+#[derive(Debug, Clone)]
+struct StageSource {
+    modified: Option<SystemTime>,
+    source: String
 }
 
 #[derive(Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema_gen", derive(schemars::JsonSchema))]
 pub struct ShaderJSON {
     vertex: String,
     tess_control: Option<String>,
     tess_eval: Option<String>,
     geometry: Option<String>,
     fragment: String,
+    /// Rebuilds programs loaded with `watch_program` when the file or one of its stages changes.
+    /// Needs the `hot_reload` feature.
+    #[serde(default)]
+    #[cfg_attr(not(feature = "hot_reload"), allow(dead_code))]
+    watch: bool
 }
 
 impl ShaderLoader {
@@ -38,7 +73,7 @@ impl ShaderLoader {
     pub fn new(file_path: String) -> Self {
         Self {
             path: file_path,
-            // context: PhantomData,
+            sources: Arc::default()
         }
     }
 
@@ -47,21 +82,11 @@ impl ShaderLoader {
         where Sem: 'static + Semantics,
               Out: 'static,
               Uni: 'static + UniformInterface<luminance_front::Backend> {
-        let path = self.path.clone();
+        let loader = self.clone();
 
-        DrawTask::new(move |(_ecs, context)| {
+        DrawTask::new(move |(ecs, context)| {
             #[cfg(feature = "trace")]
-            debug!("Loading Shader Program from file: {:?}", path.clone());
-
-            let json: ShaderJSON = load_deserializable_from_file(&path, SHADER_LOAD_ID)
-                .map_err(|e| {
-                    #[cfg(feature = "trace")]
-                    error!("Failed to load Shader JSON from file: {:?}", path.clone());
-                    DeserializeError {
-                        source: e,
-                        file_path: path.clone()
-                    }
-                })?;
+            debug!("Loading Shader Program from file: {:?}", loader.path.clone());
 
             let mut context = context.write()
                 .map_err(|_| {
@@ -71,120 +96,169 @@ impl ShaderLoader {
                     ContextWriteError
                 })?;
 
-            let fs = read_to_string(json.fragment.clone())
-                .map_err(|e| {
-                    #[cfg(feature = "trace")]
-                    error!("Failed to read Fragment Shader file: {:?}", json.fragment.clone());
+            let built_program = loader.build(context.deref_mut())?;
 
-                    FileReadError {
-                        source: e,
-                        path: json.fragment.clone()
-                    }
-                })?;
-            #[cfg(feature = "trace")]
-            debug!("Read in Fragment Shader from file: {:?}", json.fragment.clone());
-
-            let ts_c =
-                if let Some(path) = &json.tess_control {
-                    read_to_string(path)
-                        .map_err(|e| {
-                            #[cfg(feature = "trace")]
-                            error!("Failed to read Tess Control Shader file: {:?}", path.clone());
-
-                            FileReadError {
-                                source: e,
-                                path: path.clone()
-                            }
-                        })?
-                } else {
-                    String::new()
-                };
-            #[cfg(feature = "trace")]
-            debug!("Read in Tess Control Shader from file: {:?}", json.tess_control.clone());
-
-            let ts_e =
-                if let Some(path) = &json.tess_eval {
-                    read_to_string(path)
-                        .map_err(|e| {
-                            #[cfg(feature = "trace")]
-                            error!("Failed to read Tess Eval Shader file: {:?}", path.clone());
-
-                            FileReadError {
-                                source: e,
-                                path: path.clone()
-                            }
-                        })?
-                } else {
-                    String::new()
-                };
-            #[cfg(feature = "trace")]
-            debug!("Read in Tess Evaluation Shader from file: {:?}", json.tess_eval.clone());
-
-            let tess_stages =
-                if json.tess_control.is_some() && json.tess_eval.is_some() {
-                    Some(TessellationStages {
-                        control: ts_c.as_str(),
-                        evaluation: ts_e.as_str()
-                    })
-                } else {
-                    None
-                };
-
-            let geometry_shader =
-                if let Some(path) = &json.geometry {
-                    read_to_string(path)
-                        .map_err(|e| {
-                            #[cfg(feature = "trace")]
-                            error!("Failed to read Geometry Shader file: {:?}", json.geometry);
-
-                            FileReadError {
-                                source: e,
-                                path: path.clone()
-                            }
-                        })?
-                } else {
-                    String::new()
-                };
-            let gs =
-                if json.geometry.is_some() {
-                    Some(geometry_shader.as_str())
-                } else {
-                    None
-                };
+            let strict = ecs.read()
+                .map_err(|_| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to acquire read lock for world");
 
-            #[cfg(feature = "trace")]
-            debug!("Read in Geometry Shader from file: {:?}", json.geometry.clone());
+                    WorldReadLockError
+                })?
+                .has_value::<StrictShaders>();
 
-            let vs = read_to_string(json.vertex.clone())
-                .map_err(|e| {
+            let path = &loader.path;
+            for warning in &built_program.warnings {
+                if strict {
                     #[cfg(feature = "trace")]
-                    error!("Failed to read Vertex Shader file: {:?}", json.vertex.clone());
+                    error!("Shader warning in {} with StrictShaders enabled: {}", path, warning);
+
+                    return Err(ShaderCompileWarning {
+                        warning: warning.to_string(),
+                        shader_path: path.clone()
+                    }.into())
+                }
+
+                // Warnings are reported even without the trace feature since they usually point
+                // at real bugs in the shader.
+                #[cfg(feature = "trace")]
+                warn!("Shader warning in {}: {}", path, warning);
+                #[cfg(not(feature = "trace"))]
+                eprintln!("Shader warning in {}: {}", path, warning);
+            }
+
+            Ok(built_program.ignore_warnings())
+        })
+    }
+
+    /// Reads the stages that changed since they were last read and builds the program again with
+    /// the primary window's context. A program with warnings fails with a ShaderReloadError, so a
+    /// working program is only replaced by one that compiles cleanly.
+    #[cfg(feature = "hot_reload")]
+    #[cfg_attr(feature = "trace", instrument(skip(ecs)))]
+    #[allow(clippy::result_large_err)]
+    pub fn reload<Sem, Out, Uni>(&self, ecs: Arc<RwLock<World>>) -> Result<Program<Sem, Out, Uni>, ShaderLoadError>
+        where Sem: 'static + Semantics,
+              Out: 'static,
+              Uni: 'static + UniformInterface<luminance_front::Backend> {
+        let context = hot_reload::primary_context(&ecs)
+            .map_err(|e| HotReloadFailed {
+                path: self.path.clone(),
+                source: e
+            })?;
+
+        let mut context = context.write()
+            .map_err(|_| {
+                #[cfg(feature = "trace")]
+                error!("Failed to write acquire lock for context");
+
+                ContextWriteError
+            })?;
+
+        let built_program = self.build(context.deref_mut())?;
+
+        if !built_program.warnings.is_empty() {
+            let warnings: Vec<String> = built_program.warnings.iter()
+                .map(ToString::to_string)
+                .collect();
 
-                    FileReadError {
-                        source: e,
-                        path: json.vertex.clone()
-                    }
-                })?;
             #[cfg(feature = "trace")]
-            debug!("Read in Vertex Shader from file: {:?}", json.vertex.clone());
+            error!("Reloaded shader: {:?} compiled with warnings: {:?}", self.path, warnings);
 
-            Ok(context.new_shader_program()
-                .from_strings(&vs, tess_stages, gs, &fs)
-                .map_err(|e| {
-                    #[cfg(feature = "trace")]
-                    error!("Failed to create Shader Program from Shader files");
+            return Err(ShaderReloadError {
+                shader_path: self.path.clone(),
+                warnings
+            })
+        }
 
-                    ShaderProgramBuildError {
-                        source: e,
-                        vs: json.vertex.clone(),
-                        ts_c: json.tess_control.clone(),
-                        ts_e: json.tess_eval.clone(),
-                        gs: json.geometry.clone(),
-                        fs: json.fragment.clone()
-                    }
-                })?
-                .ignore_warnings())
-        })
+        Ok(built_program.ignore_warnings())
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn build<Sem, Out, Uni>(&self, context: &mut GL33Context) -> Result<BuiltProgram<Sem, Out, Uni>, ShaderLoadError>
+        where Sem: 'static + Semantics,
+              Out: 'static,
+              Uni: 'static + UniformInterface<luminance_front::Backend> {
+        let json = load_shader_json(&self.path)?;
+
+        let vs = self.read_stage(&json.vertex, "Vertex")?;
+        let ts_c = json.tess_control.as_ref()
+            .map(|path| self.read_stage(path, "Tess Control"))
+            .transpose()?;
+        let ts_e = json.tess_eval.as_ref()
+            .map(|path| self.read_stage(path, "Tess Eval"))
+            .transpose()?;
+        let gs = json.geometry.as_ref()
+            .map(|path| self.read_stage(path, "Geometry"))
+            .transpose()?;
+        let fs = self.read_stage(&json.fragment, "Fragment")?;
+
+        let tess_stages = match (&ts_c, &ts_e) {
+            (Some(control), Some(evaluation)) => Some(TessellationStages {
+                control: control.as_str(),
+                evaluation: evaluation.as_str()
+            }),
+            _ => None
+        };
+
+        context.new_shader_program()
+            .from_strings(&vs, tess_stages, gs.as_deref(), &fs)
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to create Shader Program from Shader files");
+
+                ShaderProgramBuildError {
+                    source: e,
+                    vs: json.vertex.clone(),
+                    ts_c: json.tess_control.clone(),
+                    ts_e: json.tess_eval.clone(),
+                    gs: json.geometry.clone(),
+                    fs: json.fragment.clone()
+                }
+            })
+    }
+
+    /// Source of the shader stage at `path`. The file is only read if it was modified since it was
+    /// last read by this loader or one of its clones.
+    #[allow(clippy::result_large_err)]
+    fn read_stage(&self, path: &str, _stage: &str) -> Result<String, ShaderLoadError> {
+        let modified = metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+
+        let mut sources = self.sources.lock()
+            .map_err(|_| {
+                #[cfg(feature = "trace")]
+                error!("Failed to acquire lock for shader sources");
+
+                SourcesLockError
+            })?;
+
+        if let Some(cached) = sources.get(path) {
+            if modified.is_some() && cached.modified == modified {
+                return Ok(cached.source.clone())
+            }
+        }
+
+        let source = read_to_string(path)
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to read {} Shader file: {:?}", _stage, path);
+
+                FileReadError {
+                    source: e,
+                    path: path.to_string()
+                }
+            })?;
+        #[cfg(feature = "trace")]
+        debug!("Read in {} Shader from file: {:?}", _stage, path);
+
+        sources.insert(path.to_string(), StageSource {
+            modified,
+            source: source.clone()
+        });
+
+        Ok(source)
     }
 
     #[cfg_attr(feature = "trace", instrument)]
@@ -219,17 +293,153 @@ impl ShaderLoader {
     }
 }
 
+/// How many times each watched shader file has changed. A renderer whose program was loaded from
+/// one of these files can rebuild it with ShaderLoader when the generation goes up.
+#[cfg(feature = "hot_reload")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShaderReloads(HashMap<String, u64>);
+
+#[cfg(feature = "hot_reload")]
+impl ShaderReloads {
+    /// 0 until the shader file at `path` first changes.
+    pub fn generation(&self, path: &str) -> u64 {
+        self.0.get(path).copied().unwrap_or_default()
+    }
+}
+
+/// Counts a reload in the World's ShaderReloads whenever the shader file at `path`, or one of the
+/// stages it lists when `watch` is called, changes. Programs live in the renderers that loaded
+/// them, so the renderers rebuild them.
+#[cfg(feature = "hot_reload")]
+#[cfg_attr(feature = "trace", instrument(skip(ecs)))]
+#[allow(clippy::result_large_err)]
+pub fn watch(path: &str, ecs: &Arc<RwLock<World>>) -> Result<(), ShaderLoadError> {
+    let json = load_shader_json(path)?;
+    let shader_path = path.to_string();
+
+    let mut world = ecs.write()
+        .map_err(|_e| {
+            #[cfg(feature = "trace")]
+            error!("Failed to acquire write lock for World");
+
+            HotReloadFailed {
+                path: path.to_string(),
+                source: HotReloadError::WorldWriteLockError
+            }
+        })?;
+    let mut watcher = world.entry::<HotReloadWatcher>()
+        .or_insert_with(HotReloadWatcher::default);
+
+    watch_stages(path, json, &mut watcher, move |ecs| {
+        match ecs.write() {
+            Ok(mut world) => {
+                #[cfg(feature = "trace")]
+                debug!("Shader changed: {:?}", shader_path);
+
+                *world.entry::<ShaderReloads>()
+                    .or_insert_with(ShaderReloads::default)
+                    .0
+                    .entry(shader_path.clone())
+                    .or_default() += 1;
+            },
+            Err(_e) => {
+                #[cfg(feature = "trace")]
+                error!("Failed to acquire write lock for World. Shader reload of: {:?} skipped", shader_path);
+            }
+        }
+    })
+}
+
+/// Rebuilds the program with `ShaderLoader::reload` whenever the shader file at `path`, or one of
+/// the stages it lists, changes and passes it to `replace` to swap in. Does nothing unless the
+/// shader file sets `watch`. A failed reload keeps the program already loaded. Takes the World
+/// itself rather than its lock so it can run from LazyUpdate while entities are loading.
+#[cfg(feature = "hot_reload")]
+#[cfg_attr(feature = "trace", instrument(skip(world, replace)))]
+#[allow(clippy::result_large_err)]
+pub fn watch_program<Sem, Out, Uni, F>(path: &str, world: &mut World, replace: F) -> Result<(), ShaderLoadError>
+    where Sem: 'static + Semantics,
+          Out: 'static,
+          Uni: 'static + UniformInterface<luminance_front::Backend>,
+          F: 'static + Fn(&World, Program<Sem, Out, Uni>) + Send + Sync {
+    let json = load_shader_json(path)?;
+    if !json.watch {
+        return Ok(())
+    }
+
+    let loader = ShaderLoader::new(path.to_string());
+    let replace = Arc::new(replace);
+    let mut watcher = world.entry::<HotReloadWatcher>()
+        .or_insert_with(HotReloadWatcher::default);
+
+    watch_stages(path, json, &mut watcher, move |ecs| {
+        match loader.reload::<Sem, Out, Uni>(ecs.clone()) {
+            Ok(program) => match ecs.read() {
+                Ok(world) => {
+                    #[cfg(feature = "trace")]
+                    debug!("Reloaded shader: {:?}", loader.path);
+
+                    replace(&world, program)
+                },
+                Err(_e) => {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to acquire read lock for World. Reloaded shader: {:?} dropped", loader.path);
+                }
+            },
+            Err(_e) => {
+                #[cfg(feature = "trace")]
+                error!("Failed to reload shader: {:?}. Keeping the loaded program. Error: {}", loader.path, display_chain(&_e));
+            }
+        }
+    })
+}
+
+/// Calls `callback` when the shader file at `path` or one of the stages in `json` changes.
+#[cfg(feature = "hot_reload")]
+#[allow(clippy::result_large_err)]
+fn watch_stages<F>(path: &str, json: ShaderJSON, watcher: &mut HotReloadWatcher, callback: F) -> Result<(), ShaderLoadError>
+    where F: 'static + Fn(Arc<RwLock<World>>) + Clone + Send + Sync {
+    let watched_paths = vec![Some(path.to_string()), Some(json.vertex), json.tess_control, json.tess_eval, json.geometry, Some(json.fragment)];
+
+    for watched_path in watched_paths.into_iter().flatten() {
+        watcher.watch(&watched_path, Box::new(callback.clone()))
+            .map_err(|e| HotReloadFailed {
+                path: watched_path.clone(),
+                source: e
+            })?;
+    }
+
+    Ok(())
+}
+
+#[allow(clippy::result_large_err)]
+fn load_shader_json(path: &str) -> Result<ShaderJSON, ShaderLoadError> {
+    load_deserializable_from_file(path, SHADER_LOAD_ID)
+        .map_err(|e| {
+            #[cfg(feature = "trace")]
+            error!("Failed to load Shader JSON from file: {:?}", path);
+
+            DeserializeError {
+                source: e,
+                file_path: path.to_string()
+            }
+        })
+}
+
 #[derive(Error, Debug)]
 pub enum ShaderLoadError {
     #[error("Failed to load deserializable from file: {file_path}")]
     DeserializeError {
-        source: LoadError,
+        source: anyhow::Error,
         file_path: String
     },
 
     #[error("Failed to get write lock for context")]
     ContextWriteError,
 
+    #[error("Failed to get read lock for world")]
+    WorldReadLockError,
+
     #[error("Failed to read shader program from file: {path}")]
     FileReadError {
         source: std::io::Error,
@@ -244,5 +454,28 @@ pub enum ShaderLoadError {
         ts_e: Option<String>,
         gs: Option<String>,
         fs: String
+    },
+
+    #[error("Shader program: {shader_path} compiled with warning: {warning}")]
+    ShaderCompileWarning {
+        warning: String,
+        shader_path: String
+    },
+
+    #[error("Failed to acquire lock for shader sources")]
+    SourcesLockError,
+
+    #[cfg(feature = "hot_reload")]
+    #[error("Reloaded shader program: {shader_path} compiled with warnings: {warnings:?}")]
+    ShaderReloadError {
+        shader_path: String,
+        warnings: Vec<String>
+    },
+
+    #[cfg(feature = "hot_reload")]
+    #[error("Hot reload of: {path} failed")]
+    HotReloadFailed {
+        path: String,
+        source: HotReloadError
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,11 @@
+in vec2 v_uv;
+out vec4 frag;
+
+uniform usampler2D tex;
+uniform float opacity;
+
+void main() {
+    // Keep the sprite's silhouette but draw it black.
+    float alpha = float(texture(tex, v_uv).a) / 255.;
+    frag = vec4(0., 0., 0., alpha * opacity);
+}
\ No newline at end of file
@@ -6,13 +6,14 @@ use crate::graphics::render::sprite_renderer::SpriteRenderError;
 use luminance_front::tess::{Tess, Mode, TessError, Interleaved};
 use luminance::context::GraphicsContext;
 use thiserror::Error;
-use crate::graphics::tess::TessLoadError::{TessBuildError, DeserializeError, ContextWriteError};
+use crate::graphics::tess::TessLoadError::{TessBuildError, DeserializeError, ContextWriteError, MissingIndicesError};
 use serde::Deserialize;
 use crate::loading::DrawTask;
 use crate::load::{load_deserializable_from_file, LoadError};
-use anyhow::{Error};
-use luminance::tess::TessVertexData;
+use luminance::tess::{TessVertexData, TessIndex};
+use luminance_front::tess::TessBuilder;
 use std::fmt::Debug;
+use std::collections::HashMap;
 
 pub const TESS_LOAD_ID: &str = "tess";
 
@@ -22,6 +23,7 @@ pub struct TessLoader {
 }
 
 #[derive(Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema_gen", derive(schemars::JsonSchema))]
 pub struct TessJSON {
     #[serde(default)]
     mode: Option<ModeDef>,
@@ -35,6 +37,42 @@ pub struct TessJSON {
     attributes: Option<Vec<u32>>,
     #[serde(default)]
     instance_attributes: Option<Vec<u32>>,
+    /// Index buffer used by `build_indexed`, so vertices can be shared between primitives.
+    #[serde(default)]
+    indices: Option<Vec<u32>>,
+}
+
+/// Whether a Tess is drawn with an index buffer.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "schema_gen", derive(schemars::JsonSchema))]
+pub enum TessType {
+    #[default]
+    Unindexed,
+    Indexed
+}
+
+/// Index types a Tess can be loaded with. `()` loads a Tess without an index buffer and `u32` one
+/// with the `indices` of its file.
+pub trait LoadableTessIndex: TessIndex + 'static {
+    const TESS_TYPE: TessType;
+
+    fn load_tess(loader: &TessLoader) -> DrawTask<Tess<(),Self,(),Interleaved>>;
+}
+
+impl LoadableTessIndex for () {
+    const TESS_TYPE: TessType = TessType::Unindexed;
+
+    fn load_tess(loader: &TessLoader) -> DrawTask<Tess<(),(),(),Interleaved>> {
+        loader.load()
+    }
+}
+
+impl LoadableTessIndex for u32 {
+    const TESS_TYPE: TessType = TessType::Indexed;
+
+    fn load_tess(loader: &TessLoader) -> DrawTask<Tess<(),u32,(),Interleaved>> {
+        loader.load_indexed()
+    }
 }
 
 impl TessLoader {
@@ -47,25 +85,44 @@ impl TessLoader {
 
     #[cfg_attr(feature = "trace", instrument)]
     pub fn load(&self) -> DrawTask<Tess<(),(),(),Interleaved>> {
-        let path = self.file_path.clone();
+        let loader = self.clone();
 
         DrawTask::new(move |(_ecs, context)| {
-            #[cfg(feature = "trace")]
-            debug!("Loading Tess from file: {:?}", path.clone());
-
-            let json: TessJSON = load_deserializable_from_file(&path, TESS_LOAD_ID)
-                .map_err(|e| {
+            let mut context = context.write()
+                .map_err(|_| {
                     #[cfg(feature = "trace")]
-                    error!("Failed to load deserializable from file: {:?}", path.clone());
+                    error!("Failed to acquire write lock for context");
 
-                    DeserializeError {
-                        source: e,
-                        file_path: path.clone()
-                    }
+                    ContextWriteError
                 })?;
-            #[cfg(feature = "trace")]
-            debug!("Loaded json from file: {:?}", json.clone());
 
+            Ok(loader.build(&mut context)?)
+        })
+    }
+
+    /// Loads the Tess right away, for callers that already hold the context.
+    #[cfg_attr(feature = "trace", instrument(skip(context)))]
+    pub fn build(&self, context: &mut GL33Context) -> Result<Tess<(),(),(),Interleaved>, TessLoadError> {
+        let json = self.load_json()?;
+
+        Self::new_builder(context, &json)
+            .build()
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to build Tess");
+
+                TessBuildError {
+                    source: e
+                }
+            })
+    }
+
+    /// Like `load`, but the Tess is built with the `indices` in its file.
+    #[cfg_attr(feature = "trace", instrument)]
+    pub fn load_indexed(&self) -> DrawTask<Tess<(),u32,(),Interleaved>> {
+        let loader = self.clone();
+
+        DrawTask::new(move |(_ecs, context)| {
             let mut context = context.write()
                 .map_err(|_| {
                     #[cfg(feature = "trace")]
@@ -74,43 +131,91 @@ impl TessLoader {
                     ContextWriteError
                 })?;
 
-            #[cfg(feature = "trace")]
-            debug!("Tess has interleaved storage.");
+            Ok(loader.build_indexed(&mut context)?)
+        })
+    }
 
-            let mut tess_builder = context.new_tess();
-            #[cfg(feature = "trace")]
-            debug!("Created Tess builder");
+    /// Loads the indexed Tess right away, for callers that already hold the context.
+    #[cfg_attr(feature = "trace", instrument(skip(context)))]
+    pub fn build_indexed(&self, context: &mut GL33Context) -> Result<Tess<(),u32,(),Interleaved>, TessLoadError> {
+        let mut json = self.load_json()?;
 
-            if let Some(mode) = json.mode {
+        let indices = json.indices.take()
+            .ok_or_else(|| {
                 #[cfg(feature = "trace")]
-                debug!("Setting Tess mode: {:?}", mode.clone());
+                error!("Tess file has no indices: {:?}", self.file_path.clone());
 
-                tess_builder = tess_builder.set_mode(Mode::from(mode))
-            }
+                MissingIndicesError {
+                    file_path: self.file_path.clone()
+                }
+            })?;
+        #[cfg(feature = "trace")]
+        debug!("Setting {:?} Tess indices", indices.len());
 
-            if let Some(render_vertex_nb) = json.render_vertices_len {
+        Self::new_builder(context, &json)
+            .set_indices(indices)
+            .build()
+            .map_err(|e| {
                 #[cfg(feature = "trace")]
-                debug!("Setting default number of vertices to render: {:?}", render_vertex_nb);
+                error!("Failed to build indexed Tess");
+
+                TessBuildError {
+                    source: e
+                }
+            })
+    }
+
+    fn load_json(&self) -> Result<TessJSON, TessLoadError> {
+        let path = self.file_path.clone();
 
-                tess_builder = tess_builder.set_render_vertex_nb(render_vertex_nb)
-            }
-            if let Some(render_instance_nb) = json.render_instances_len {
+        #[cfg(feature = "trace")]
+        debug!("Loading Tess from file: {:?}", path.clone());
+
+        let json: TessJSON = load_deserializable_from_file(&path, TESS_LOAD_ID)
+            .map_err(|e| {
                 #[cfg(feature = "trace")]
-                debug!("Setting default number of instances to render: {:?}", render_instance_nb);
+                error!("Failed to load deserializable from file: {:?}", path.clone());
 
-                tess_builder = tess_builder.set_render_instance_nb(render_instance_nb)
-            }
+                DeserializeError {
+                    source: e,
+                    file_path: path.clone()
+                }
+            })?;
+        #[cfg(feature = "trace")]
+        debug!("Loaded json from file: {:?}", json.clone());
 
-            tess_builder.build()
-                .map_err(|e| {
-                    #[cfg(feature = "trace")]
-                    error!("Failed to build Tess");
+        Ok(json)
+    }
 
-                    Error::new(TessBuildError {
-                        source: e
-                    })
-                })
-        })
+    fn new_builder<'a>(context: &'a mut GL33Context, json: &TessJSON) -> TessBuilder<'a, ()> {
+        #[cfg(feature = "trace")]
+        debug!("Tess has interleaved storage.");
+
+        let mut tess_builder = context.new_tess();
+        #[cfg(feature = "trace")]
+        debug!("Created Tess builder");
+
+        if let Some(mode) = json.mode.clone() {
+            #[cfg(feature = "trace")]
+            debug!("Setting Tess mode: {:?}", mode.clone());
+
+            tess_builder = tess_builder.set_mode(Mode::from(mode))
+        }
+
+        if let Some(render_vertex_nb) = json.render_vertices_len {
+            #[cfg(feature = "trace")]
+            debug!("Setting default number of vertices to render: {:?}", render_vertex_nb);
+
+            tess_builder = tess_builder.set_render_vertex_nb(render_vertex_nb)
+        }
+        if let Some(render_instance_nb) = json.render_instances_len {
+            #[cfg(feature = "trace")]
+            debug!("Setting default number of instances to render: {:?}", render_instance_nb);
+
+            tess_builder = tess_builder.set_render_instance_nb(render_instance_nb)
+        }
+
+        tess_builder
     }
 
     #[cfg_attr(feature = "trace", instrument)]
@@ -141,6 +246,37 @@ impl TessLoader {
     }
 }
 
+/// Tesses loaded by file path, so entities can switch between them without reloading. Tess can't
+/// be sent between threads, so the cache is owned by the renderer that draws with it rather than
+/// stored in the World.
+#[derive(Default)]
+pub struct TessCache(pub HashMap<String, Tess<(),(),(),Interleaved>>);
+
+impl TessCache {
+    pub fn get(&self, path: &str) -> Option<&Tess<(),(),(),Interleaved>> {
+        self.0.get(path)
+    }
+
+    /// Loads the Tess at `path` the first time it's asked for.
+    #[cfg_attr(feature = "trace", instrument(skip(self, context)))]
+    pub fn get_or_load(&mut self, path: &str, context: &mut GL33Context) -> Result<&Tess<(),(),(),Interleaved>, TessLoadError> {
+        if !self.0.contains_key(path) {
+            let tess = TessLoader::new(path.to_string()).build(context)?;
+            self.0.insert(path.to_string(), tess);
+        }
+
+        Ok(&self.0[path])
+    }
+}
+
+impl Debug for TessCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("TessCache")
+            .field(&self.0.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum TessLoadError {
     #[error("Failed to build Tesselation")]
@@ -150,15 +286,21 @@ pub enum TessLoadError {
 
     #[error("Failed to load TessJSON from file: {file_path}")]
     DeserializeError {
-        source: LoadError,
+        source: anyhow::Error,
         file_path: String
     },
 
     #[error("Failed to acquire write lock for Context")]
     ContextWriteError,
+
+    #[error("Indexed Tess file has no indices: {file_path}")]
+    MissingIndicesError {
+        file_path: String
+    },
 }
 
 #[derive(Deserialize,Debug,Clone)]
+#[cfg_attr(feature = "schema_gen", derive(schemars::JsonSchema))]
 enum ModeDef {
     Point,
     Line,
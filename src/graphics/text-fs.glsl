@@ -0,0 +1,11 @@
+in vec2 v_uv;
+out vec4 frag;
+
+uniform usampler2D tex;
+uniform vec4 color;
+
+void main() {
+    // The atlas stores glyph coverage in the alpha channel.
+    float coverage = float(texture(tex, v_uv).a) / 255.;
+    frag = vec4(color.rgb, color.a * coverage);
+}
\ No newline at end of file
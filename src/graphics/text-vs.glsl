@@ -0,0 +1,13 @@
+in vec2 position;
+in vec2 uv;
+
+uniform mat4 projection;
+uniform mat4 view;
+uniform mat4 model;
+
+out vec2 v_uv;
+
+void main() {
+  gl_Position = projection * view * model * vec4(position, 0., 1.);
+  v_uv = uv;
+}
\ No newline at end of file
@@ -2,8 +2,9 @@ in vec2 v_uv;
 out vec4 frag;
 
 uniform usampler2D tex;
+uniform vec4 tint;
 
 void main() {
     vec4 color = texture(tex, v_uv);
-    frag = color/255;
+    frag = color/255 * tint;
 }
\ No newline at end of file
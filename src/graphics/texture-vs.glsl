@@ -1,6 +1,9 @@
 uniform mat4 projection;
 uniform mat4 view;
 uniform mat4 model;
+// Part of the texture drawn on the quad. The whole texture unless a SpriteRegion is set.
+uniform vec2 uv_min = vec2(0., 0.);
+uniform vec2 uv_max = vec2(1., 1.);
 
 out vec2 v_uv;
 
@@ -14,5 +17,5 @@ const vec2[4] QUAD_POS = vec2[](
 void main() {
   vec2 p = QUAD_POS[gl_VertexID];
   gl_Position = projection * view * model *  vec4(p, 0., 1.);
-  v_uv = p * .5 + .5; // transform the position of the vertex into UV space
+  v_uv = mix(uv_min, uv_max, p * .5 + .5); // transform the position of the vertex into UV space
 }
\ No newline at end of file
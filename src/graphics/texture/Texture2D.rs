@@ -3,6 +3,7 @@ use luminance_front::texture::{Sampler, Wrap, MinFilter, MagFilter};
 use luminance_front::depth_test::DepthComparison;
 use std::path::PathBuf;
 use crate::loading::DrawTask;
+use crate::graphics::texture::validate_frame_dimensions;
 
 #[derive(Debug, Clone)]
 pub struct Texture2D {
@@ -40,6 +41,8 @@ pub const TEXTURE2D_LOAD_ID: &str = "texture2D";
 
 impl ComponentLoader for Texture2DLoader {
     fn from_json(json: JSONLoad) -> Result<Self> where Self: Sized {
+        Self::validate_schema(&json)?;
+
         let json = load_deserializable_from_json(&json, &TEXTURE_LOAD_ID)
             .map_err(|e| { CanNotDeserialize {json, source: e} })?;
 
@@ -83,6 +86,10 @@ impl ComponentLoader for Texture2DLoader {
                     }
                     )?
                     .decode()?;
+
+                let (full_width, full_height) = dynamic_image.dimensions();
+                validate_frame_dimensions(self.json.dimensions, [full_width, full_height])?;
+
                 let rgb_image = dynamic_image
                     .into_rgba8();
 
@@ -12,29 +12,40 @@ use luminance::depth_test::DepthComparison;
 use luminance_front::pixel::Pixel;
 use luminance_front::texture::{GenMipmaps, MagFilter, MinFilter, Sampler, Texture as LumTex, Wrap};
 use luminance_glfw::GL33Context;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use specs::{Builder, Component, VecStorage, World};
 use specs::storage::UnprotectedStorage;
 use specs::world::LazyBuilder;
 use thiserror::Error;
 #[cfg(feature = "trace")]
 use tracing::{debug, error, instrument};
+#[cfg(feature = "trace")]
+use crate::load::display_chain;
 
 use crate::components::ComponentLoader;
-use crate::globals::texture_dict::TextureDict;
-use crate::graphics::texture::TextureLoaderError::{CanNotDeserialize, ContextMissing, ContextWriteLockError, DecodeError, FileNameDNE, PathNotFile, PathStringConversion, ReaderFailedToOpen, RGB8ConversionFailed, TextureDictDNE, TextureDidNotLoad, WorldReadLockError};
-use crate::load::{JSONLoad, load_deserializable_from_json, LoadError};
+use crate::globals::texture_dict::{TextureDict, TextureDictError, TextureKey};
+use crate::graphics::texture::TextureLoaderError::{CanNotDeserialize, ContextMissing, ContextWriteLockError, DecodeError, FileNameDNE, InvalidDimensions, PathNotFile, PathStringConversion, ReaderFailedToOpen, RGB8ConversionFailed, TextureDictDNE, TextureDidNotLoad, WorldReadLockError, TextureDictLoadError};
+use crate::load::{JSONLoad, load_deserializable_from_json, serialize_to_json_load, LoadError};
 use crate::loading::DrawTask;
 
 #[derive(Debug, Clone)]
 pub struct TextureHandle {
-    pub(crate) handle: String,
+    pub(crate) handle: TextureKey,
 }
 
 impl Component for TextureHandle { type Storage = VecStorage<Self>; }
+crate::register_component!(TextureHandle);
 
 impl TextureHandle {
-    const SAMPLER: Sampler = Sampler {
+    pub fn new(handle: impl Into<TextureKey>) -> Self {
+        Self { handle: handle.into() }
+    }
+
+    pub fn key(&self) -> &TextureKey {
+        &self.handle
+    }
+
+    pub(crate) const SAMPLER: Sampler = Sampler {
         wrap_r: Wrap::ClampToEdge,
         wrap_s: Wrap::ClampToEdge,
         wrap_t: Wrap::ClampToEdge,
@@ -44,14 +55,15 @@ impl TextureHandle {
     };
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema_gen", derive(schemars::JsonSchema))]
 pub struct TextureJSON {
     #[serde(default)]
     pub name: Option<String>,
     pub image_path: String
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TextureLoader {
     pub json: TextureJSON
 }
@@ -61,10 +73,12 @@ pub const TEXTURE_LOAD_ID: &str = "texture";
 impl ComponentLoader for TextureLoader {
     #[cfg_attr(feature = "trace", instrument)]
     fn from_json(json: JSONLoad) -> Result<Self> where Self: Sized {
+        Self::validate_schema(&json)?;
+
         let texture_json: TextureJSON = load_deserializable_from_json(&json, &TEXTURE_LOAD_ID)
             .map_err(|e| {
                 #[cfg(feature = "trace")]
-                error!("Failed to deserialize JSONLoad value: ({:?}) into TextureJSON type", json.clone());
+                error!("Failed to deserialize JSONLoad value: ({:?}) into TextureJSON type. Error: {}", json.clone(), display_chain(&e));
 
                 CanNotDeserialize {
                     json: json.clone(),
@@ -78,6 +92,11 @@ impl ComponentLoader for TextureLoader {
         Ok(Self{ json: texture_json })
     }
 
+    #[cfg(feature = "schema_gen")]
+    fn json_schema() -> serde_json::Value where Self: Sized {
+        crate::schema::validation_schema::<TextureJSON>()
+    }
+
     #[cfg_attr(feature = "trace", instrument(skip(builder, ecs, context)))]
     fn load_component<'a>(&self, builder: LazyBuilder<'a>, ecs: Arc<RwLock<World>>, context: Option<Arc<RwLock<GL33Context>>>) -> Result<LazyBuilder<'a>> {
         if let Some(context) = context {
@@ -129,82 +148,25 @@ impl ComponentLoader for TextureLoader {
             #[cfg(feature = "trace")]
             debug!("Fetched texture store from ECS.");
 
-            let texture_handle = TextureHandle { handle: name.clone() };
-
-            if !texture_dict.contains_key(&texture_handle) {
-                #[cfg(feature = "trace")]
-                debug!("This is a new texture. It needs to be loaded from file and stored in the Texture Store.");
-
-                let dynamic_image = Reader::open(path)
-                    .map_err(|e| {
-                        #[cfg(feature = "trace")]
-                        error!("Failed to open image file at path: {:?}", self.json.image_path.clone());
-
-                        ReaderFailedToOpen {
-                            path: self.json.image_path.clone(),
-                            source: e
-                        }
-                    })?
-                    .decode()
-                    .map_err(|e| {
-                        #[cfg(feature = "trace")]
-                        error!("Failed to decode image at path: {:?}", self.json.image_path.clone());
-
-                        DecodeError {
-                            source: e,
-                            image_path: self.json.image_path.clone()
-                        }
-                    })?;
-
-                let rgb_image = dynamic_image
-                    .into_rgba8();
-
-                #[cfg(feature = "trace")]
-                debug!("Successfully converted image from file into RGBA8 format");
-
-                let rgb_image_rev: Vec<u8> = rgb_image.rows()
-                    // Reverse the contents of each row a.k.a mirror it
-                    // and get rid of the Rev iter layer using flat_map instead of map
-                    .flat_map(|row| {
-                        row.rev()
-                    })
-                    // Reverse all rows a.k.a flip upside down
-                    .rev()
-                    // Flat_map expects an iter as the return value and automatically flattens it
-                    // so we can use it as another way to convert a vec of pixels into the raw bytes
-                    .flat_map(|pixel| {
-                        pixel.0
-                    })
-                    .collect();
-                #[cfg(feature = "trace")]
-                debug!("Flipped and mirrored image so it is drawn properly by renderer.");
+            let texture_handle = TextureHandle::new(name.clone());
 
-                let (x, y) = rgb_image.dimensions();
-                #[cfg(feature = "trace")]
-                debug!("Image size is x: {:?}, y: {:?}", x, y);
-
-                let mut ctx = context.write()
-                    .map_err(|_| {
-                        #[cfg(feature = "trace")]
-                        error!("Failed to acquire write lock for Context");
-
-                        ContextWriteLockError
-                    })?;
+            let mut ctx = context.write()
+                .map_err(|_| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to acquire write lock for Context");
 
-                let texture = LumTex::new_raw(
-                    ctx.deref_mut(),
-                    [x, y],
-                    0,
-                    TextureHandle::SAMPLER,
-                    GenMipmaps::No,
-                    &rgb_image_rev
-                )?;
+                    ContextWriteLockError
+                })?;
 
-                #[cfg(feature = "trace")]
-                debug!("Created texture from raw image bytes. Storing in Texture Store.");
+            texture_dict.load_or_get(&name, &self.json.image_path, ctx.deref_mut())
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to load texture: {:?} into the Texture Store. Error: {}", name.clone(), display_chain(&e));
 
-                texture_dict.insert(&texture_handle, texture);
-            }
+                    TextureDictLoadError {
+                        source: e
+                    }
+                })?;
 
             #[cfg(feature = "trace")]
             debug!("Successfully created Texture. Adding to builder.");
@@ -227,7 +189,7 @@ impl ComponentLoader for TextureLoader {
         self.json = load_deserializable_from_json(&new_value, &TEXTURE_LOAD_ID)
             .map_err(|e| {
                 #[cfg(feature = "trace")]
-                error!("Failed to convert JSONLoad value: ({:?}) into TextureJSON", new_value.clone());
+                error!("Failed to convert JSONLoad value: ({:?}) into TextureJSON. Error: {}", new_value.clone(), display_chain(&e));
 
                 CanNotDeserialize {
                     json: new_value.clone(),
@@ -241,6 +203,15 @@ impl ComponentLoader for TextureLoader {
         Ok(())
     }
 
+    #[cfg_attr(feature = "trace", instrument)]
+    fn get_value(&self) -> Result<JSONLoad, LoadError> {
+        serialize_to_json_load(&self.json, TEXTURE_LOAD_ID)
+    }
+
+    fn clone_boxed(&self) -> Box<dyn ComponentLoader> {
+        Box::new(self.clone())
+    }
+
     #[cfg_attr(feature = "trace", instrument)]
     fn get_component_name(&self) -> String {
         #[cfg(feature = "trace")]
@@ -250,6 +221,25 @@ impl ComponentLoader for TextureLoader {
     }
 }
 
+/// Checks that frames of size `frame` tile a texture of size `texture` exactly. Otherwise the UVs
+/// of the last frame in a row or column straddle the edge of the texture.
+pub fn validate_frame_dimensions(frame: [u32; 2], texture: [u32; 2]) -> Result<(), TextureLoaderError> {
+    let [frame_width, frame_height] = frame;
+    let [full_width, full_height] = texture;
+
+    if frame_width == 0 || frame_height == 0 || full_width % frame_width != 0 || full_height % frame_height != 0 {
+        #[cfg(feature = "trace")]
+        error!("Frame dimensions: {:?} do not evenly divide texture dimensions: {:?}", frame, texture);
+
+        return Err(InvalidDimensions {
+            sprite: frame,
+            texture
+        })
+    }
+
+    Ok(())
+}
+
 #[derive(Error, Debug)]
 pub enum TextureLoaderError {
     #[error("Failed to deserialize json from JSONLoad value={json:?}")]
@@ -298,6 +288,12 @@ pub enum TextureLoaderError {
         image_name: String
     },
 
+    #[error("Sprite dimensions={sprite:?} do not evenly divide texture dimensions={texture:?}")]
+    InvalidDimensions {
+        sprite: [u32; 2],
+        texture: [u32; 2]
+    },
+
     #[error("Failed to acquire read lock for World")]
     WorldReadLockError,
 
@@ -308,5 +304,32 @@ pub enum TextureLoaderError {
     DecodeError {
         source: ImageError,
         image_path: String
+    },
+
+    #[error("Failed to load texture into the TextureDict")]
+    TextureDictLoadError {
+        source: TextureDictError
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_that_tile_the_texture_are_accepted() {
+        assert!(validate_frame_dimensions([25, 50], [100, 100]).is_ok());
+    }
+
+    #[test]
+    fn frames_that_do_not_tile_the_texture_are_rejected() {
+        let result = validate_frame_dimensions([30, 30], [100, 100]);
+
+        assert!(matches!(result, Err(InvalidDimensions { sprite: [30, 30], texture: [100, 100] })));
+    }
+
+    #[test]
+    fn empty_frames_are_rejected() {
+        assert!(matches!(validate_frame_dimensions([0, 10], [100, 100]), Err(InvalidDimensions { .. })));
     }
 }
@@ -1,8 +1,8 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use specs::{Component, VecStorage, World, Builder};
-use glam::{Vec2, Mat4, Quat};
+use glam::{Vec2, Vec3, Mat4, Quat, EulerRot};
 use crate::components::ComponentLoader;
-use crate::load::{JSONLoad, load_deserializable_from_json, LoadError};
+use crate::load::{JSONLoad, load_deserializable_from_json, serialize_to_json_load, LoadError};
 use specs::world::LazyBuilder;
 use std::sync::{Arc, Mutex, RwLock};
 use luminance_glfw::GL33Context;
@@ -13,33 +13,92 @@ use thiserror::Error;
 use tracing::{debug, error, instrument};
 use crate::graphics::transform::TransformLoaderError::{DeserializeError, LoadTypeIDError};
 
+/// `rotation` holds the angles in radians around the x, y and z axes, applied in that order.
+/// 2D games only rotate around z, and use the z of `translation` as the sprite's layer.
+/// Sprites on lower layers are drawn first, so higher layers cover them.
 #[derive(Debug, Clone, Copy)]
 pub struct Transform {
-    pub translation: Vec2,
-    pub scale: Vec2,
-    pub rotation: f32
+    pub translation: Vec3,
+    pub scale: Vec3,
+    pub rotation: Vec3
 }
 
 impl Default for Transform {
     fn default() -> Self {
         Self {
-            translation: Vec2::ZERO,
-            scale: Vec2::ZERO,
-            rotation: 0.0
+            translation: Vec3::ZERO,
+            scale: Vec3::ZERO,
+            rotation: Vec3::ZERO
         }
     }
 }
 
 impl Component for Transform { type Storage = VecStorage<Self>; }
+crate::register_component!(Transform);
 
 impl Transform {
+    /// 2D transform in the z = 0 plane, rotated `rotation` radians around z.
+    pub fn from_2d(translation: Vec2, scale: Vec2, rotation: f32) -> Self {
+        Self {
+            translation: translation.extend(0.0),
+            scale: scale.extend(0.0),
+            rotation: Vec3::new(0.0, 0.0, rotation)
+        }
+    }
+
+    /// Places the transform on `layer`, which must be within the camera's near and far planes.
+    pub fn with_layer(mut self, layer: f32) -> Self {
+        self.translation.z = layer;
+
+        self
+    }
+
+    /// Draw order of the sprite. Lower layers are drawn first.
+    pub fn layer(&self) -> f32 {
+        self.translation.z
+    }
+
+    /// Moves the translation and scale `t` of the way to `other`'s. The rotation is kept, see
+    /// `slerp_rotation`.
+    pub fn lerp(&self, other: &Transform, t: f32) -> Transform {
+        Transform {
+            translation: self.translation.lerp(other.translation, t),
+            scale: self.scale.lerp(other.scale, t),
+            ..*self
+        }
+    }
+
+    /// Turns each rotation angle `t` of the way to `other`'s, the shorter way around the circle.
+    /// Rotations around z alone, as in 2D games, turn at a constant speed.
+    pub fn slerp_rotation(&self, other: &Transform, t: f32) -> Transform {
+        let turn = |from: f32, to: f32| {
+            let difference = to - from;
+
+            from + f32::atan2(difference.sin(), difference.cos()) * t
+        };
+
+        Transform {
+            rotation: Vec3::new(
+                turn(self.rotation.x, other.rotation.x),
+                turn(self.rotation.y, other.rotation.y),
+                turn(self.rotation.z, other.rotation.z)
+            ),
+            ..*self
+        }
+    }
+
+    pub fn rotation_quat(&self) -> Quat {
+        Quat::from_euler(EulerRot::XYZ, self.rotation.x, self.rotation.y, self.rotation.z)
+    }
+
+    /// Model matrix that rotates around `pivot`, an offset from the origin after scaling. `None`
+    /// rotates around the origin.
     #[cfg_attr(feature = "trace", instrument)]
-    pub fn to_model(&self) -> Mat4 {
-        let model = Mat4::from_scale_rotation_translation(
-            self.scale.extend(0.0),
-            Quat::from_rotation_z(self.rotation),
-            self.translation.extend(0.0)
-        );
+    pub fn to_model(&self, pivot: Option<Vec2>) -> Mat4 {
+        let pivot = pivot.unwrap_or(Vec2::ZERO);
+        let model = Mat4::from_rotation_translation(self.rotation_quat(), self.translation)
+            * Mat4::from_translation(-pivot.extend(0.0))
+            * Mat4::from_scale(self.scale);
         #[cfg(feature = "trace")]
         debug!("Created model matrix for entity from transform component: {:?}", model);
 
@@ -47,23 +106,53 @@ impl Transform {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TransformLoader {
     json: TransformJSON
 }
 
 pub const TRANSFORM_LOAD_ID: &str = "transform";
 
-#[derive(Deserialize, Debug, Clone)]
-pub struct TransformJSON {
-    translation: [f32; 2],
-    scale: [f32;2],
-    rotation: f32
+/// V1 files describe a 2D transform. V2 files describe a full 3D one.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema_gen", derive(schemars::JsonSchema))]
+#[serde(untagged)]
+pub enum TransformJSON {
+    V1 {
+        translation: [f32; 2],
+        scale: [f32; 2],
+        rotation: f32,
+        #[serde(default)]
+        layer: f32
+    },
+    V2 {
+        translation: [f32; 3],
+        scale: [f32; 3],
+        rotation: [f32; 3]
+    }
+}
+
+impl From<&TransformJSON> for Transform {
+    fn from(json: &TransformJSON) -> Self {
+        match json {
+            TransformJSON::V1 { translation, scale, rotation, layer } => {
+                Transform::from_2d(Vec2::from(*translation), Vec2::from(*scale), *rotation)
+                    .with_layer(*layer)
+            },
+            TransformJSON::V2 { translation, scale, rotation } => Transform {
+                translation: Vec3::from(*translation),
+                scale: Vec3::from(*scale),
+                rotation: Vec3::from(*rotation)
+            }
+        }
+    }
 }
 
 impl ComponentLoader for TransformLoader {
     #[cfg_attr(feature = "trace", instrument)]
     fn from_json(json: JSONLoad) -> anyhow::Result<Self> where Self: Sized {
+        Self::validate_schema(&json)?;
+
         let transform_json: TransformJSON = load_deserializable_from_json(&json, &TRANSFORM_LOAD_ID)
             .map_err(|e| {
                 #[cfg(feature = "trace")]
@@ -80,13 +169,14 @@ impl ComponentLoader for TransformLoader {
         Ok(Self {json: transform_json})
     }
 
+    #[cfg(feature = "schema_gen")]
+    fn json_schema() -> serde_json::Value where Self: Sized {
+        crate::schema::validation_schema::<TransformJSON>()
+    }
+
     #[cfg_attr(feature = "trace", instrument(skip(builder, _ecs)))]
     fn load_component<'a>(&self, builder: LazyBuilder<'a>, _ecs: Arc<RwLock<World>>, _context: Option<Arc<RwLock<GL33Context>>>) -> anyhow::Result<LazyBuilder<'a>> {
-        let transform = Transform {
-            translation: Vec2::from(self.json.translation),
-            scale: Vec2::from(self.json.scale),
-            rotation: self.json.rotation
-        };
+        let transform = Transform::from(&self.json);
 
         #[cfg(feature = "trace")]
         debug!("Created new transform component: {:?}", transform);
@@ -127,6 +217,15 @@ impl ComponentLoader for TransformLoader {
         }
     }
 
+    #[cfg_attr(feature = "trace", instrument)]
+    fn get_value(&self) -> Result<JSONLoad, LoadError> {
+        serialize_to_json_load(&self.json, TRANSFORM_LOAD_ID)
+    }
+
+    fn clone_boxed(&self) -> Box<dyn ComponentLoader> {
+        Box::new(self.clone())
+    }
+
     #[cfg_attr(feature = "trace", instrument)]
     fn get_component_name(&self) -> String {
         TRANSFORM_LOAD_ID.to_string()
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::sync::mpsc::Receiver;
+
+use glfw::{Window, WindowEvent, WindowHint, WindowMode};
+use luminance_glfw::GL33Context;
+use thiserror::Error;
+
+#[cfg(feature = "trace")]
+use tracing::{debug, error, instrument};
+
+use crate::game_loop::WindowConfig;
+use crate::graphics::window_registry::WindowError::{CreationFailed, DuplicateName, ContextReadLockError, WindowReadLockError};
+
+pub struct GlfwContext {
+    pub window: Window,
+    pub events: Receiver<(f64, WindowEvent)>
+}
+
+unsafe impl Send for GlfwContext {}
+unsafe impl Sync for GlfwContext {}
+
+/// Holds every window opened in addition to the primary one. Each window shares its OpenGL
+/// context with the primary window so textures, shaders and tessellations can be used in any of them.
+pub struct WindowRegistry {
+    primary: Arc<RwLock<GL33Context>>,
+    windows: HashMap<String, Arc<RwLock<GlfwContext>>>
+}
+
+unsafe impl Send for WindowRegistry {}
+unsafe impl Sync for WindowRegistry {}
+
+impl WindowRegistry {
+    pub fn new(primary: Arc<RwLock<GL33Context>>) -> Self {
+        Self {
+            primary,
+            windows: HashMap::new()
+        }
+    }
+
+    /// Context of the window the GameLoop opened.
+    pub fn primary(&self) -> Arc<RwLock<GL33Context>> {
+        self.primary.clone()
+    }
+
+    #[cfg_attr(feature = "trace", instrument(skip(self)))]
+    pub fn create_window(&mut self, name: &str, config: WindowConfig) -> Result<(), WindowError> {
+        if self.windows.contains_key(name) {
+            #[cfg(feature = "trace")]
+            error!("A window named: ({:?}) already exists", name);
+
+            return Err(DuplicateName { name: name.to_string() })
+        }
+
+        let primary = self.primary.read()
+            .map_err(|_e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to acquire read lock for primary context");
+
+                ContextReadLockError
+            })?;
+
+        let mut glfw = primary.window.glfw.clone();
+        glfw.window_hint(WindowHint::Resizable(config.resizable));
+
+        let created = glfw.with_primary_monitor(|_, monitor| {
+            let mode = match monitor {
+                Some(monitor) if config.fullscreen => WindowMode::FullScreen(monitor),
+                _ => WindowMode::Windowed
+            };
+
+            primary.window.create_shared(config.width, config.height, &config.title, mode)
+        });
+
+        // Window hints persist between window creations so put the default back.
+        glfw.window_hint(WindowHint::Resizable(true));
+
+        let (mut window, events) = created
+            .ok_or_else(|| {
+                #[cfg(feature = "trace")]
+                error!("GLFW failed to create window: {:?}", name);
+
+                CreationFailed { name: name.to_string() }
+            })?;
+
+        window.set_all_polling(true);
+
+        #[cfg(feature = "trace")]
+        debug!("Created window: {:?}", name);
+
+        self.windows.insert(name.to_string(), Arc::new(RwLock::new(GlfwContext { window, events })));
+
+        Ok(())
+    }
+
+    #[cfg_attr(feature = "trace", instrument(skip(self)))]
+    pub fn get(&self, name: &str) -> Option<Arc<RwLock<GlfwContext>>> {
+        self.windows.get(name).cloned()
+    }
+
+    /// Dropping the last reference to a window closes it.
+    #[cfg_attr(feature = "trace", instrument(skip(self)))]
+    pub fn remove_window(&mut self, name: &str) -> Option<Arc<RwLock<GlfwContext>>> {
+        self.windows.remove(name)
+    }
+
+    pub fn contains_window(&self, name: &str) -> bool {
+        self.windows.contains_key(name)
+    }
+
+    /// Drains the pending events of every registered window. Events must already have been polled
+    /// through the primary window's `Glfw` instance.
+    #[cfg_attr(feature = "trace", instrument(skip(self)))]
+    pub fn flush_events(&self) -> Result<Vec<(String, WindowEvent)>, WindowError> {
+        let mut events = Vec::new();
+
+        for (name, context) in self.windows.iter() {
+            let context = context.read()
+                .map_err(|_e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to acquire read lock for window: {:?}", name);
+
+                    WindowReadLockError { name: name.clone() }
+                })?;
+
+            events.extend(
+                glfw::flush_messages(&context.events)
+                    .map(|(_time, event)| (name.clone(), event))
+            );
+        }
+
+        Ok(events)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum WindowError {
+    #[error("Failed to create window: {name}")]
+    CreationFailed {
+        name: String
+    },
+
+    #[error("A window named: {name} already exists")]
+    DuplicateName {
+        name: String
+    },
+
+    #[error("Failed to acquire read lock for primary context")]
+    ContextReadLockError,
+
+    #[error("Failed to acquire read lock for window: {name}")]
+    WindowReadLockError {
+        name: String
+    }
+}
@@ -0,0 +1,415 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Debug, Formatter};
+use std::fs::canonicalize;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::sync::mpsc::{channel, Receiver};
+use std::time::Duration;
+
+use luminance_glfw::GL33Context;
+use notify::{DebouncedEvent, RecommendedWatcher, RecursiveMode, Watcher};
+use specs::{World, WorldExt};
+use thiserror::Error;
+
+#[cfg(feature = "trace")]
+use tracing::{debug, error, instrument};
+
+use crate::graphics::window_registry::WindowRegistry;
+use crate::hot_reload::HotReloadError::{WorldWriteLockError, WorldReadLockError, WindowRegistryMissing, WatcherCreationError, WatchError, NoFileName, PathResolveError, EventChannelLockError};
+
+/// How long a file has to go without changing before its callbacks run, so a save that writes
+/// a file in several steps only reloads it once.
+pub const DEBOUNCE_DELAY: Duration = Duration::from_millis(50);
+
+/// Called with the World when a watched file changes. Runs on the draw thread, so it can reach
+/// the OpenGL context through `primary_context`.
+pub type ReloadCallback = Box<dyn Fn(Arc<RwLock<World>>) + Send + Sync>;
+
+struct WatchedFile {
+    /// Absolute path the file system events for this file use.
+    resolved_path: PathBuf,
+    callbacks: Vec<ReloadCallback>
+}
+
+/// OS file watcher and the channel it sends its events through.
+struct Notifier {
+    watcher: RecommendedWatcher,
+    /// Only read by `poll`. The Mutex makes the watcher `Sync`, so it can be kept in the World.
+    events: Mutex<Receiver<DebouncedEvent>>
+}
+
+/// Runs callbacks when the files they watch are modified. The GameLoop keeps one in the World
+/// and polls it once a frame. The directories of watched files are watched through the `notify`
+/// crate, so files replaced by an editor's save are still noticed, and `poll` only drains the
+/// events that arrived since the last frame.
+#[derive(Default)]
+pub struct HotReloadWatcher {
+    watched: HashMap<String, WatchedFile>,
+    directories: HashSet<PathBuf>,
+    /// Created by the first `watch`.
+    notifier: Option<Notifier>
+}
+
+impl Debug for HotReloadWatcher {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HotReloadWatcher")
+            .field("watched", &self.watched.keys().collect::<Vec<_>>())
+            .field("directories", &self.directories)
+            .finish()
+    }
+}
+
+impl HotReloadWatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Calls `callback` every time the file at `path` changes from now on. A path can have
+    /// several callbacks, which run in the order they were added. Fails if the directory holding
+    /// `path` can't be watched.
+    #[cfg_attr(feature = "trace", instrument(skip(self, callback)))]
+    pub fn watch(&mut self, path: &str, callback: ReloadCallback) -> Result<(), HotReloadError> {
+        if let Some(file) = self.watched.get_mut(path) {
+            file.callbacks.push(callback);
+
+            return Ok(())
+        }
+
+        let resolved_path = resolve(path)?;
+        self.watch_directory(path, &resolved_path)?;
+
+        self.watched.insert(path.to_string(), WatchedFile {
+            resolved_path,
+            callbacks: vec![callback]
+        });
+
+        Ok(())
+    }
+
+    /// Stops watching `path`. Returns whether it was watched.
+    pub fn unwatch(&mut self, path: &str) -> bool {
+        let file = match self.watched.remove(path) {
+            Some(file) => file,
+            None => return false
+        };
+
+        let directory = file.resolved_path.parent().map(Path::to_path_buf).unwrap_or_default();
+        let directory_in_use = self.watched.values()
+            .any(|other| other.resolved_path.parent() == Some(directory.as_path()));
+
+        if !directory_in_use && self.directories.remove(&directory) {
+            if let Some(notifier) = self.notifier.as_mut() {
+                // The directory is already gone if this fails, which is all unwatching needs.
+                let _ = notifier.watcher.unwatch(&directory);
+            }
+        }
+
+        true
+    }
+
+    pub fn is_watching(&self, path: &str) -> bool {
+        self.watched.contains_key(path)
+    }
+
+    /// Runs the callbacks of every file written, created or moved into place since the last
+    /// poll. A file that was removed is skipped until it is written again.
+    #[cfg_attr(feature = "trace", instrument(skip(self, ecs)))]
+    pub fn poll(&mut self, ecs: Arc<RwLock<World>>) -> Result<(), HotReloadError> {
+        let notifier = match self.notifier.as_ref() {
+            Some(notifier) => notifier,
+            None => return Ok(())
+        };
+
+        let changed: HashSet<PathBuf> = notifier.events
+            .lock()
+            .map_err(|_e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to acquire lock for the file event channel");
+
+                EventChannelLockError
+            })?
+            .try_iter()
+            .filter_map(|event| match event {
+                DebouncedEvent::Create(path)
+                | DebouncedEvent::Write(path)
+                | DebouncedEvent::Rename(_, path) => Some(path),
+                DebouncedEvent::Error(_e, _path) => {
+                    #[cfg(feature = "trace")]
+                    error!("File watcher error for path: {:?}. Error: {:?}", _path, _e);
+
+                    None
+                },
+                _ => None
+            })
+            .collect();
+
+        for (_path, file) in self.watched.iter().filter(|(_, file)| changed.contains(&file.resolved_path)) {
+            #[cfg(feature = "trace")]
+            debug!("Watched file changed: {:?}. Running {:?} reload callbacks", _path, file.callbacks.len());
+
+            for callback in &file.callbacks {
+                callback(ecs.clone());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Moves the callbacks of `other` into this watcher.
+    fn merge(&mut self, other: HotReloadWatcher) -> Result<(), HotReloadError> {
+        for (path, file) in other.watched {
+            match self.watched.get_mut(&path) {
+                Some(existing) => existing.callbacks.extend(file.callbacks),
+                None => {
+                    self.watch_directory(&path, &file.resolved_path)?;
+                    self.watched.insert(path, file);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Watches the directory holding `resolved_path`, creating the Notifier if needed.
+    fn watch_directory(&mut self, path: &str, resolved_path: &Path) -> Result<(), HotReloadError> {
+        let directory = resolved_path.parent().map(Path::to_path_buf).unwrap_or_default();
+        if self.directories.contains(&directory) {
+            return Ok(())
+        }
+
+        let notifier = match self.notifier.as_mut() {
+            Some(notifier) => notifier,
+            None => self.notifier.insert(Notifier::new()?)
+        };
+
+        notifier.watcher.watch(&directory, RecursiveMode::NonRecursive)
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to watch directory: {:?} for file: {:?}", directory, path);
+
+                WatchError {
+                    path: path.to_string(),
+                    source: e
+                }
+            })?;
+
+        self.directories.insert(directory);
+
+        Ok(())
+    }
+}
+
+impl Notifier {
+    fn new() -> Result<Self, HotReloadError> {
+        let (sender, receiver) = channel();
+        let watcher = notify::watcher(sender, DEBOUNCE_DELAY)
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to create the file watcher");
+
+                WatcherCreationError {
+                    source: e
+                }
+            })?;
+
+        Ok(Self {
+            watcher,
+            events: Mutex::new(receiver)
+        })
+    }
+}
+
+/// Absolute path of the file at `path`, which is what the file events hold. Only the directory
+/// has to exist, so a file can be watched before it is written.
+fn resolve(path: &str) -> Result<PathBuf, HotReloadError> {
+    let path_buf = Path::new(path);
+    let directory = match path_buf.parent() {
+        Some(directory) if !directory.as_os_str().is_empty() => directory,
+        _ => Path::new(".")
+    };
+
+    let file_name = path_buf.file_name()
+        .ok_or_else(|| {
+            #[cfg(feature = "trace")]
+            error!("Watched path: {:?} has no file name", path);
+
+            NoFileName {
+                path: path.to_string()
+            }
+        })?;
+
+    canonicalize(directory)
+        .map(|directory| directory.join(file_name))
+        .map_err(|e| {
+            #[cfg(feature = "trace")]
+            error!("Failed to resolve the directory of watched path: {:?}", path);
+
+            PathResolveError {
+                path: path.to_string(),
+                source: e
+            }
+        })
+}
+
+/// Adds `callback` to the World's HotReloadWatcher, creating the watcher if needed.
+#[cfg_attr(feature = "trace", instrument(skip(ecs, callback)))]
+pub fn watch(path: &str, ecs: &Arc<RwLock<World>>, callback: ReloadCallback) -> Result<(), HotReloadError> {
+    ecs.write()
+        .map_err(|_e| {
+            #[cfg(feature = "trace")]
+            error!("Failed to acquire write lock for World");
+
+            WorldWriteLockError
+        })?
+        .entry::<HotReloadWatcher>()
+        .or_insert_with(HotReloadWatcher::default)
+        .watch(path, callback)
+}
+
+/// Polls the World's HotReloadWatcher. The watcher is taken out of the World while its callbacks
+/// run so they can lock the World themselves. Anything they watch is kept.
+#[cfg_attr(feature = "trace", instrument(skip(ecs)))]
+pub fn poll(ecs: &Arc<RwLock<World>>) -> Result<(), HotReloadError> {
+    let watcher = ecs.write()
+        .map_err(|_e| {
+            #[cfg(feature = "trace")]
+            error!("Failed to acquire write lock for World");
+
+            WorldWriteLockError
+        })?
+        .remove::<HotReloadWatcher>();
+
+    let mut watcher = match watcher {
+        Some(watcher) => watcher,
+        None => return Ok(())
+    };
+
+    let polled = watcher.poll(ecs.clone());
+
+    let mut world = ecs.write()
+        .map_err(|_e| {
+            #[cfg(feature = "trace")]
+            error!("Failed to acquire write lock for World");
+
+            WorldWriteLockError
+        })?;
+
+    let merged = match world.remove::<HotReloadWatcher>() {
+        Some(added) => watcher.merge(added),
+        None => Ok(())
+    };
+    world.insert(watcher);
+
+    polled.and(merged)
+}
+
+/// OpenGL context of the primary window, for callbacks that upload to the GPU.
+pub fn primary_context(ecs: &Arc<RwLock<World>>) -> Result<Arc<RwLock<GL33Context>>, HotReloadError> {
+    let world = ecs.read()
+        .map_err(|_e| {
+            #[cfg(feature = "trace")]
+            error!("Failed to acquire read lock for World");
+
+            WorldReadLockError
+        })?;
+
+    let primary = world.try_fetch::<WindowRegistry>()
+        .map(|registry| registry.primary());
+
+    match primary {
+        Some(primary) => Ok(primary),
+        None => {
+            #[cfg(feature = "trace")]
+            error!("World has no WindowRegistry to get the primary context from");
+
+            Err(WindowRegistryMissing)
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum HotReloadError {
+    #[error("Failed to acquire write lock for World")]
+    WorldWriteLockError,
+
+    #[error("Failed to acquire read lock for World")]
+    WorldReadLockError,
+
+    #[error("World has no WindowRegistry to get the primary context from")]
+    WindowRegistryMissing,
+
+    #[error("Failed to create the file watcher")]
+    WatcherCreationError {
+        source: notify::Error
+    },
+
+    #[error("Failed to watch file: {path}")]
+    WatchError {
+        path: String,
+        source: notify::Error
+    },
+
+    #[error("Watched path: {path} has no file name")]
+    NoFileName {
+        path: String
+    },
+
+    #[error("Failed to resolve the directory of watched path: {path}")]
+    PathResolveError {
+        path: String,
+        source: std::io::Error
+    },
+
+    #[error("Failed to acquire lock for the file event channel")]
+    EventChannelLockError
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{create_dir_all, remove_dir_all, write};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread::sleep;
+    use std::time::Instant;
+
+    use super::*;
+
+    /// Polls `watcher` until `reloads` reaches `expected` or a second passes.
+    fn poll_until(watcher: &mut HotReloadWatcher, reloads: &AtomicUsize, expected: usize) {
+        let ecs = Arc::new(RwLock::new(World::new()));
+        let start = Instant::now();
+
+        while reloads.load(Ordering::SeqCst) < expected && start.elapsed() < Duration::from_secs(1) {
+            watcher.poll(ecs.clone()).unwrap();
+            sleep(DEBOUNCE_DELAY);
+        }
+    }
+
+    #[test]
+    fn poll_runs_the_callbacks_of_written_files_only() {
+        let dir = std::env::temp_dir().join(format!("game_engine_hot_reload_{}", std::process::id()));
+        create_dir_all(&dir).unwrap();
+        let watched = dir.join("watched.json").to_string_lossy().to_string();
+        let other = dir.join("other.json").to_string_lossy().to_string();
+        write(&watched, "{}").unwrap();
+
+        let reloads = Arc::new(AtomicUsize::new(0));
+        let counter = reloads.clone();
+        let mut watcher = HotReloadWatcher::new();
+        watcher.watch(&watched, Box::new(move |_ecs| { counter.fetch_add(1, Ordering::SeqCst); })).unwrap();
+
+        write(&other, "{}").unwrap();
+        poll_until(&mut watcher, &reloads, 1);
+        assert_eq!(reloads.load(Ordering::SeqCst), 0);
+
+        write(&watched, "{\"changed\": true}").unwrap();
+        poll_until(&mut watcher, &reloads, 1);
+        assert_eq!(reloads.load(Ordering::SeqCst), 1);
+
+        assert!(watcher.unwatch(&watched));
+        write(&watched, "{}").unwrap();
+        poll_until(&mut watcher, &reloads, 2);
+        assert_eq!(reloads.load(Ordering::SeqCst), 1);
+
+        let _ = remove_dir_all(&dir);
+    }
+}
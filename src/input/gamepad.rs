@@ -0,0 +1,242 @@
+use std::collections::HashMap;
+#[cfg(feature = "gamepad_input")]
+use std::collections::HashSet;
+#[cfg(feature = "gamepad_input")]
+use std::fmt::{Debug, Formatter};
+#[cfg(feature = "gamepad_input")]
+use std::sync::{Arc, Mutex};
+
+use glfw::GamepadButton;
+#[cfg(feature = "gamepad_input")]
+use glfw::WindowEvent;
+#[cfg(feature = "gamepad_input")]
+use gilrs::{Axis, Button, EventType, Gilrs};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "gamepad_input")]
+use thiserror::Error;
+
+#[cfg(feature = "gamepad_input")]
+use crate::input::Input;
+#[cfg(feature = "gamepad_input")]
+use crate::input::gamepad::GamepadError::GilrsInitError;
+
+#[cfg(all(feature = "trace", feature = "gamepad_input"))]
+use tracing::{debug, error, instrument};
+
+/// Button layouts that UI prompts are drawn for.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ControllerType {
+    Xbox,
+    PlayStation,
+    Nintendo
+}
+
+/// Type of the controller the player is using, or `None` when no gamepad is connected. The game
+/// sets it when it detects a controller.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConnectedGamepad(pub Option<ControllerType>);
+
+/// Gamepad button each action is bound to, keyed by action name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GamepadActionMap(pub HashMap<String, GamepadButton>);
+
+impl GamepadActionMap {
+    pub fn get(&self, action_name: &str) -> Option<GamepadButton> {
+        self.0.get(action_name).copied()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub(crate) enum GamepadButtonDef {
+    A,
+    B,
+    X,
+    Y,
+    LeftBumper,
+    RightBumper,
+    Back,
+    Start,
+    Guide,
+    LeftThumb,
+    RightThumb,
+    DpadUp,
+    DpadRight,
+    DpadDown,
+    DpadLeft
+}
+
+impl From<GamepadButtonDef> for GamepadButton {
+    fn from(b: GamepadButtonDef) -> Self {
+        match b {
+            GamepadButtonDef::A => GamepadButton::ButtonA,
+            GamepadButtonDef::B => GamepadButton::ButtonB,
+            GamepadButtonDef::X => GamepadButton::ButtonX,
+            GamepadButtonDef::Y => GamepadButton::ButtonY,
+            GamepadButtonDef::LeftBumper => GamepadButton::ButtonLeftBumper,
+            GamepadButtonDef::RightBumper => GamepadButton::ButtonRightBumper,
+            GamepadButtonDef::Back => GamepadButton::ButtonBack,
+            GamepadButtonDef::Start => GamepadButton::ButtonStart,
+            GamepadButtonDef::Guide => GamepadButton::ButtonGuide,
+            GamepadButtonDef::LeftThumb => GamepadButton::ButtonLeftThumb,
+            GamepadButtonDef::RightThumb => GamepadButton::ButtonRightThumb,
+            GamepadButtonDef::DpadUp => GamepadButton::ButtonDpadUp,
+            GamepadButtonDef::DpadRight => GamepadButton::ButtonDpadRight,
+            GamepadButtonDef::DpadDown => GamepadButton::ButtonDpadDown,
+            GamepadButtonDef::DpadLeft => GamepadButton::ButtonDpadLeft
+        }
+    }
+}
+
+/// Buttons and sticks of every connected controller, read through gilrs since GLFW doesn't send
+/// controller events. Controllers are polled on every `update` and `poll`. Buttons and axes from
+/// all controllers are combined.
+#[cfg(feature = "gamepad_input")]
+#[derive(Clone)]
+pub struct Gamepad {
+    /// `None` when gilrs doesn't support the platform.
+    gilrs: Option<Arc<Mutex<GilrsHandle>>>,
+    axes: HashMap<Axis, f32>,
+    pressed_buttons: HashSet<Button>,
+    released_buttons: HashSet<Button>,
+    held_buttons: HashSet<Button>
+}
+
+#[cfg(feature = "gamepad_input")]
+struct GilrsHandle(Gilrs);
+
+// gilrs keeps platform handles that are only used while the Gamepad's Mutex is held.
+#[cfg(feature = "gamepad_input")]
+unsafe impl Send for GilrsHandle {}
+
+#[cfg(feature = "gamepad_input")]
+impl Debug for Gamepad {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Gamepad")
+            .field("axes", &self.axes)
+            .field("pressed_buttons", &self.pressed_buttons)
+            .field("released_buttons", &self.released_buttons)
+            .field("held_buttons", &self.held_buttons)
+            .finish()
+    }
+}
+
+#[cfg(feature = "gamepad_input")]
+impl Gamepad {
+    /// Fails if gilrs can't read controllers on this platform. `Input::new` returns a Gamepad
+    /// that never sees any input instead.
+    #[cfg_attr(feature = "trace", instrument)]
+    pub fn try_new() -> Result<Self, GamepadError> {
+        let gilrs = Gilrs::new()
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to initialize gilrs: {:?}", e);
+
+                GilrsInitError { source: Box::new(e) }
+            })?;
+
+        Ok(Self::with_gilrs(Some(gilrs)))
+    }
+
+    fn with_gilrs(gilrs: Option<Gilrs>) -> Self {
+        Self {
+            gilrs: gilrs.map(|gilrs| Arc::new(Mutex::new(GilrsHandle(gilrs)))),
+            axes: HashMap::new(),
+            pressed_buttons: HashSet::new(),
+            released_buttons: HashSet::new(),
+            held_buttons: HashSet::new()
+        }
+    }
+
+    /// Last value reported for `axis`, from -1.0 to 1.0. 0.0 until the axis first moves.
+    pub fn get_axis(&self, axis: Axis) -> f32 {
+        self.axes.get(&axis).copied().unwrap_or_default()
+    }
+
+    pub fn get_pressed_buttons(&self) -> &HashSet<Button> {
+        &self.pressed_buttons
+    }
+
+    pub fn get_released_buttons(&self) -> &HashSet<Button> {
+        &self.released_buttons
+    }
+
+    /// Buttons pressed in an earlier frame that haven't been released yet.
+    pub fn get_held_buttons(&self) -> &HashSet<Button> {
+        &self.held_buttons
+    }
+
+    /// Applies every controller event gilrs has received since the last poll.
+    #[cfg_attr(feature = "trace", instrument(skip(self)))]
+    pub fn poll_events(&mut self) {
+        let gilrs = match &self.gilrs {
+            Some(gilrs) => gilrs.clone(),
+            None => return
+        };
+
+        let mut gilrs = match gilrs.lock() {
+            Ok(gilrs) => gilrs,
+            Err(_e) => {
+                #[cfg(feature = "trace")]
+                error!("Failed to acquire lock for gilrs");
+
+                return
+            }
+        };
+
+        while let Some(event) = gilrs.0.next_event() {
+            #[cfg(feature = "trace")]
+            debug!("Gamepad event: {:?}", event);
+
+            match event.event {
+                EventType::ButtonPressed(button, _) => {
+                    self.pressed_buttons.insert(button);
+                },
+                EventType::ButtonReleased(button, _) => {
+                    self.released_buttons.insert(button);
+                    self.held_buttons.remove(&button);
+                },
+                EventType::AxisChanged(axis, value, _) => {
+                    self.axes.insert(axis, value);
+                },
+                EventType::Disconnected => {
+                    self.axes.clear();
+                    self.held_buttons.clear();
+                },
+                _ => ()
+            }
+        }
+    }
+}
+
+#[cfg(feature = "gamepad_input")]
+impl Input for Gamepad {
+    #[cfg_attr(feature = "trace", instrument)]
+    fn new() -> Self {
+        match Self::try_new() {
+            Ok(gamepad) => gamepad,
+            Err(_e) => Self::with_gilrs(None)
+        }
+    }
+
+    /// Window events never come from controllers, so this only polls gilrs.
+    fn update(&mut self, _event: WindowEvent) {
+        self.poll_events();
+    }
+
+    fn poll(&mut self) {
+        self.poll_events();
+    }
+
+    fn clear(&mut self) {
+        let released = &self.released_buttons;
+        self.held_buttons.extend(self.pressed_buttons.drain().filter(|button| !released.contains(button)));
+        self.released_buttons.clear();
+    }
+}
+
+#[cfg(feature = "gamepad_input")]
+#[derive(Error, Debug)]
+pub enum GamepadError {
+    #[error("Failed to initialize gilrs")]
+    GilrsInitError { source: Box<gilrs::Error> }
+}
@@ -1,6 +1,9 @@
 pub mod mouse;
 pub mod keyboard;
 pub mod multi_input;
+pub mod gamepad;
+#[cfg(feature = "touch_input")]
+pub mod touch;
 
 use glfw::{WindowEvent};
 
@@ -9,5 +12,9 @@ pub trait Input: Send + Sync {
 
     fn update(&mut self, event: WindowEvent);
 
+    /// Called once a frame, before the frame's window events, for devices that GLFW doesn't
+    /// send events for.
+    fn poll(&mut self) {}
+
     fn clear(&mut self);
 }
@@ -3,6 +3,8 @@ use crate::input::keyboard::{KeyBoard, Key};
 use std::collections::{HashMap, HashSet};
 use crate::input::Input;
 use glfw::WindowEvent;
+#[cfg(feature = "gamepad_input")]
+use crate::input::gamepad::Gamepad;
 
 #[cfg(feature = "trace")]
 use tracing::{debug, error, warn, instrument};
@@ -12,7 +14,10 @@ pub struct MultiInput {
     mouse: Mouse,
     keyboard: KeyBoard,
     pub held_buttons: HashMap<Button, CursorPosition>,
-    pub held_keys: HashSet<Key>
+    pub held_keys: HashSet<Key>,
+    /// `None` when gilrs can't read controllers on this platform.
+    #[cfg(feature = "gamepad_input")]
+    pub gamepad: Option<Gamepad>
 }
 
 impl MultiInput {
@@ -65,6 +70,11 @@ impl MultiInput {
     pub fn get_released_keys(&self) -> &HashSet<Key> {
         self.keyboard.get_released_keys()
     }
+
+    #[cfg(feature = "gamepad_input")]
+    pub fn get_gamepad(&self) -> Option<&Gamepad> {
+        self.gamepad.as_ref()
+    }
 }
 
 impl Input for MultiInput {
@@ -74,7 +84,9 @@ impl Input for MultiInput {
             mouse: Mouse::new(),
             keyboard: KeyBoard::new(),
             held_buttons: HashMap::new(),
-            held_keys: HashSet::new()
+            held_keys: HashSet::new(),
+            #[cfg(feature = "gamepad_input")]
+            gamepad: Gamepad::try_new().ok()
         };
 
         #[cfg(feature = "trace")]
@@ -130,6 +142,18 @@ impl Input for MultiInput {
         self.held_keys = self.held_keys.difference(self.keyboard.get_released_keys()).copied().collect();
         #[cfg(feature = "trace")]
         debug!("Updated held keys.");
+
+        #[cfg(feature = "gamepad_input")]
+        if let Some(gamepad) = &mut self.gamepad {
+            gamepad.poll_events();
+        }
+    }
+
+    #[cfg(feature = "gamepad_input")]
+    fn poll(&mut self) {
+        if let Some(gamepad) = &mut self.gamepad {
+            gamepad.poll_events();
+        }
     }
 
     #[cfg_attr(feature = "trace", instrument)]
@@ -142,6 +166,10 @@ impl Input for MultiInput {
 
         self.mouse.clear();
         self.keyboard.clear();
+        #[cfg(feature = "gamepad_input")]
+        if let Some(gamepad) = &mut self.gamepad {
+            gamepad.clear();
+        }
         #[cfg(feature = "trace")]
         debug!("Cleared mouse and keyboard.");
     }
@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+
+use glam::Vec2;
+use glfw::WindowEvent;
+
+use crate::input::Input;
+use crate::input::mouse::Mouse;
+use crate::input::keyboard::KeyBoard;
+
+#[cfg(feature = "trace")]
+use tracing::{debug, instrument};
+
+/// Touches that travel less than this distance before ending are taps. Anything further is a swipe.
+pub const TAP_MAX_DISTANCE: f32 = 10.0;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum TouchPhase {
+    Started,
+    Moved,
+    Ended,
+    Cancelled
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct TouchPoint {
+    pub position: Vec2,
+    pub phase: TouchPhase,
+    pub pressure: f32
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum GestureKind {
+    Tap,
+    /// Distance travelled from where the touch started.
+    Swipe(Vec2),
+    /// Ratio between the current and previous distance of the two touches.
+    Pinch(f32)
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Gesture {
+    pub kind: GestureKind,
+    pub position: Vec2
+}
+
+/// GLFW does not emit touch events so `Input::update` ignores every event it is given.
+/// Platforms that do receive touches forward them through `TouchInput::touch`.
+#[derive(Debug, Clone)]
+pub struct TouchInput {
+    active_touches: HashMap<u64, TouchPoint>,
+    touch_origins: HashMap<u64, Vec2>,
+    pinch_distance: Option<f32>,
+    gestures: Vec<Gesture>
+}
+
+impl TouchInput {
+    #[cfg_attr(feature = "trace", instrument)]
+    pub fn get_active_touches(&self) -> &HashMap<u64, TouchPoint> {
+        &self.active_touches
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    pub fn get_gestures(&self) -> &Vec<Gesture> {
+        &self.gestures
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    pub fn touch(&mut self, id: u64, position: Vec2, phase: TouchPhase, pressure: f32) {
+        #[cfg(feature = "trace")]
+        debug!("Touch: {:?} at {:?} with phase {:?}", id, position, phase);
+
+        if phase == TouchPhase::Started {
+            self.touch_origins.insert(id, position);
+        }
+
+        self.active_touches.insert(id, TouchPoint { position, phase, pressure });
+
+        match phase {
+            TouchPhase::Moved => self.detect_pinch(),
+            TouchPhase::Ended => {
+                let origin = self.touch_origins.get(&id).copied().unwrap_or(position);
+                let distance = position - origin;
+
+                let kind = if distance.length() < TAP_MAX_DISTANCE {
+                    GestureKind::Tap
+                } else {
+                    GestureKind::Swipe(distance)
+                };
+
+                #[cfg(feature = "trace")]
+                debug!("Touch: {:?} ended. Detected gesture: {:?}", id, kind);
+
+                self.gestures.push(Gesture { kind, position });
+            },
+            TouchPhase::Started | TouchPhase::Cancelled => ()
+        }
+    }
+
+    fn detect_pinch(&mut self) {
+        let positions: Vec<Vec2> = self.active_touches
+            .values()
+            .filter(|point| point.phase == TouchPhase::Started || point.phase == TouchPhase::Moved)
+            .map(|point| point.position)
+            .collect();
+
+        if positions.len() != 2 {
+            self.pinch_distance = None;
+            return
+        }
+
+        let distance = positions[0].distance(positions[1]);
+
+        if let Some(previous) = self.pinch_distance {
+            if previous > 0.0 {
+                self.gestures.push(Gesture {
+                    kind: GestureKind::Pinch(distance / previous),
+                    position: (positions[0] + positions[1]) / 2.0
+                });
+            }
+        }
+
+        self.pinch_distance = Some(distance);
+    }
+}
+
+impl Input for TouchInput {
+    #[cfg_attr(feature = "trace", instrument)]
+    fn new() -> Self {
+        Self {
+            active_touches: HashMap::new(),
+            touch_origins: HashMap::new(),
+            pinch_distance: None,
+            gestures: Vec::new()
+        }
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn update(&mut self, _event: WindowEvent) {
+        /* GLFW has no touch events */
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn clear(&mut self) {
+        let finished: Vec<u64> = self.active_touches
+            .iter()
+            .filter(|(_, point)| point.phase == TouchPhase::Ended || point.phase == TouchPhase::Cancelled)
+            .map(|(id, _)| *id)
+            .collect();
+
+        for id in finished {
+            self.active_touches.remove(&id);
+            self.touch_origins.remove(&id);
+        }
+
+        self.gestures.clear();
+        #[cfg(feature = "trace")]
+        debug!("Removed finished touches and cleared gestures.");
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MultiTouch {
+    mouse: Mouse,
+    keyboard: KeyBoard,
+    touch: TouchInput
+}
+
+impl MultiTouch {
+    pub fn get_mouse(&self) -> &Mouse {
+        &self.mouse
+    }
+
+    pub fn get_keyboard(&self) -> &KeyBoard {
+        &self.keyboard
+    }
+
+    pub fn get_touch(&self) -> &TouchInput {
+        &self.touch
+    }
+
+    pub fn get_touch_mut(&mut self) -> &mut TouchInput {
+        &mut self.touch
+    }
+}
+
+impl Input for MultiTouch {
+    #[cfg_attr(feature = "trace", instrument)]
+    fn new() -> Self {
+        Self {
+            mouse: Mouse::new(),
+            keyboard: KeyBoard::new(),
+            touch: TouchInput::new()
+        }
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn update(&mut self, event: WindowEvent) {
+        match event {
+            WindowEvent::Key(..)
+            | WindowEvent::Char(_)
+            | WindowEvent::CharModifiers(..) => self.keyboard.update(event),
+            _ => self.mouse.update(event)
+        }
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    fn clear(&mut self) {
+        self.mouse.clear();
+        self.keyboard.clear();
+        self.touch.clear();
+    }
+}
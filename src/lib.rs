@@ -1,4 +1,5 @@
 #![allow(unused_imports)]
+pub use inventory;
 #[macro_use]
 pub mod load;
 pub mod entities;
@@ -14,3 +15,17 @@ pub mod log;
 pub mod graphics;
 pub mod loading;
 pub mod camera;
+pub mod networking;
+pub mod events;
+#[cfg(feature = "lua_scripting")]
+pub mod scripting;
+#[cfg(any(test, feature = "test_harness"))]
+pub mod testing;
+#[cfg(test)]
+pub(crate) mod test_util;
+#[cfg(feature = "schema_gen")]
+pub mod schema;
+#[cfg(feature = "validate")]
+pub mod schema_validation;
+#[cfg(feature = "hot_reload")]
+pub mod hot_reload;
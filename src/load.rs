@@ -1,11 +1,13 @@
-use serde_json::{Value, from_str, from_value};
-use serde::Deserialize;
+use serde_json::{Value, Map, from_str, from_value, to_value};
+use serde::{Deserialize, Serialize};
 
 use std::fs::read_to_string;
+use std::collections::HashSet;
 use std::error::Error;
 use std::sync::{RwLock, Arc};
 
 use thiserror::Error;
+use anyhow::Context;
 
 #[cfg(feature="trace")]
 use tracing::{instrument, trace, debug, error};
@@ -13,7 +15,7 @@ use tracing::{instrument, trace, debug, error};
 use specs::{World, Entity};
 
 use crate::entities::{EntityLoader};
-use crate::load::LoadError::{JSONLoadConversionError, ValueConversionError, ReadError, LoadIDError, DeserializationError, ExecutionError};
+use crate::load::LoadError::{JSONLoadConversionError, ValueConversionError, ReadError, LoadIDError, DeserializationError, ExecutionError, SerializationError, CircularIncludeError, IncludeDepthError};
 use crate::components::ComponentMux;
 use std::fmt::Debug;
 use crate::loading::{Task, DrawTask};
@@ -24,6 +26,11 @@ pub const JSON_FILE: &str = ".json";
 
 pub const ENTITY_VEC_LOAD_ID: &str = "entity_vec";
 
+/// A JSON object whose only key is `$include` is replaced by the `actual_value` of the file at the
+/// path it holds, e.g. `{"$include": "assets/JSON/stats/goblin.json"}`.
+pub const INCLUDE_KEY: &str = "$include";
+pub const DEFAULT_MAX_INCLUDE_DEPTH: usize = 32;
+
 #[macro_export]
 macro_rules! map_err_return {
     ( $e:expr, $err:expr ) => {
@@ -40,19 +47,138 @@ pub struct JSONLoad {
     pub actual_value: Value
 }
 
+/// The files `load_json` is in the middle of loading while it follows `$include`s.
+#[derive(Debug, Clone)]
+pub struct LoadContext {
+    pub visited_paths: HashSet<String>,
+    /// Includes nested deeper than this fail, even when they aren't circular.
+    pub max_depth: usize,
+    /// `visited_paths` in the order they were included.
+    chain: Vec<String>
+}
+
+impl Default for LoadContext {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_INCLUDE_DEPTH)
+    }
+}
+
+impl LoadContext {
+    pub fn new(max_depth: usize) -> Self {
+        Self {
+            visited_paths: HashSet::new(),
+            max_depth,
+            chain: Vec::new()
+        }
+    }
+
+    fn enter(&mut self, file_path: &str) -> Result<(), LoadError> {
+        if self.visited_paths.contains(file_path) {
+            let mut chain = self.chain.clone();
+            chain.push(file_path.to_string());
+
+            #[cfg(feature = "trace")]
+            error!("Circular include of: {:?}. Include chain: {:?}", file_path, chain.clone());
+
+            return Err(CircularIncludeError {
+                path: file_path.to_string(),
+                chain
+            })
+        }
+
+        if self.chain.len() >= self.max_depth {
+            #[cfg(feature = "trace")]
+            error!("Including: {:?} goes past the max include depth: {:?}", file_path, self.max_depth);
+
+            return Err(IncludeDepthError {
+                path: file_path.to_string(),
+                max_depth: self.max_depth
+            })
+        }
+
+        self.visited_paths.insert(file_path.to_string());
+        self.chain.push(file_path.to_string());
+
+        Ok(())
+    }
+
+    fn leave(&mut self, file_path: &str) {
+        self.visited_paths.remove(file_path);
+        self.chain.pop();
+    }
+}
+
+#[cfg_attr(feature="trace", instrument)]
+pub fn load_json(file_path: &str) -> anyhow::Result<JSONLoad> {
+    load_json_with_context(file_path, &mut LoadContext::default())
+}
+
+/// `load_json` for a file included from the files already in `context`. Errors keep their
+/// LoadError as the root cause, with the file being read and any includes leading to it as
+/// context, so `{:#}` prints the whole chain.
 #[cfg_attr(feature="trace", instrument)]
-pub fn load_json(file_path: &str) -> Result<JSONLoad, LoadError> {
+pub fn load_json_with_context(file_path: &str, context: &mut LoadContext) -> anyhow::Result<JSONLoad> {
     #[cfg(feature="trace")]
     trace!("ENTER: load_json");
 
+    context.enter(file_path)?;
+    let json_value = read_json_value(file_path)
+        .and_then(|json_value| resolve_includes(json_value, context));
+    context.leave(file_path);
+    let json_value = json_value?;
+
+    let load_json = from_value(json_value.clone())
+        .map_err(|e| {
+            #[cfg(feature = "trace")]
+            error!("Error occurred while converting serde_json Value into JSONLoad object");
+
+            anyhow::Error::new(JSONLoadConversionError {
+                value: json_value,
+                source: e
+            }).context(format!("reading load_type_id and actual_value from '{}'", file_path))
+        });
+
+    #[cfg(feature="trace")]
+    debug!("EXIT: load_json. value: {:?}", load_json);
+
+    return load_json;
+}
+
+/// Replaces every `$include` object in `value` with the value of the file it names.
+fn resolve_includes(value: Value, context: &mut LoadContext) -> anyhow::Result<Value> {
+    match value {
+        Value::Object(map) => {
+            if let (1, Some(Value::String(include_path))) = (map.len(), map.get(INCLUDE_KEY)) {
+                #[cfg(feature = "trace")]
+                debug!("Including file: {:?}", include_path);
+
+                return Ok(load_json_with_context(include_path, context)
+                    .with_context(|| format!("including '{}'", include_path))?
+                    .actual_value)
+            }
+
+            map.into_iter()
+                .map(|(key, value)| Ok((key, resolve_includes(value, context)?)))
+                .collect::<anyhow::Result<Map<String, Value>>>()
+                .map(Value::Object)
+        },
+        Value::Array(values) => values.into_iter()
+            .map(|value| resolve_includes(value, context))
+            .collect::<anyhow::Result<Vec<Value>>>()
+            .map(Value::Array),
+        value => Ok(value)
+    }
+}
+
+fn read_json_value(file_path: &str) -> anyhow::Result<Value> {
     let json_string = read_to_string(file_path)
         .map_err(|e| {
             #[cfg(feature = "trace")]
             error!("Something went wrong while reading in json from file: {:?}", file_path.clone());
 
-            ReadError {
+            anyhow::Error::new(ReadError {
                 path: file_path.to_string(), source: e
-            }
+            }).context(format!("reading asset file at '{}'", file_path))
         })?;
 
     #[cfg(feature="trace")]
@@ -63,34 +189,20 @@ pub fn load_json(file_path: &str) -> Result<JSONLoad, LoadError> {
             #[cfg(feature = "trace")]
             error!("Error converting json string: ({:?}) into serde_json Value.", json_string.clone());
 
-            ValueConversionError {
+            anyhow::Error::new(ValueConversionError {
                 string_value: json_string.clone(),
                 source: e
-            }
+            }).context(format!("parsing JSON in '{}'", file_path))
         })?;
 
     #[cfg(feature = "trace")]
     debug!("JSON string: ({:?}) from file translated into serde_json value: {:?}", json_string.clone(), json_value.clone());
 
-    let load_json = from_value(json_value.clone())
-        .map_err(|e| {
-            #[cfg(feature = "trace")]
-            error!("Error occurred while converting serde_json Value into JSONLoad object");
-
-            JSONLoadConversionError {
-                value: json_value,
-                source: e
-            }
-        });
-
-    #[cfg(feature="trace")]
-    debug!("EXIT: load_json. value: {:?}", load_json);
-
-    return load_json;
+    Ok(json_value)
 }
 
 #[cfg_attr(feature="trace", instrument(skip(ecs, context)))]
-pub fn create_entity_vec<T: 'static + ComponentMux>(entity_paths: &Vec<String>, ecs: Arc<RwLock<World>>, context: Arc<RwLock<GL33Context>>) -> Result<Vec<Entity>, LoadError> {
+pub fn create_entity_vec<T: 'static + ComponentMux>(entity_paths: &Vec<String>, ecs: Arc<RwLock<World>>, context: Arc<RwLock<GL33Context>>) -> anyhow::Result<Vec<Entity>> {
     let mut entity_vec = Vec::new();
 
     for entity_path in entity_paths {
@@ -104,9 +216,9 @@ pub fn create_entity_vec<T: 'static + ComponentMux>(entity_paths: &Vec<String>,
                 #[cfg(feature = "trace")]
                 debug!("A failure occurred during execution of the entity task");
 
-                ExecutionError {
+                anyhow::Error::new(ExecutionError {
                     source: e
-                }
+                }).context(format!("loading entity '{}'", entity_path))
             })?;
         #[cfg(feature = "trace")]
         debug!("Entity loaded");
@@ -120,7 +232,7 @@ pub fn create_entity_vec<T: 'static + ComponentMux>(entity_paths: &Vec<String>,
 }
 
 #[cfg_attr(feature="trace", instrument)]
-pub fn load_deserializable_from_file<T: for<'de> Deserialize<'de> + Debug>(file_path: &str, load_id: &str) -> Result<T, LoadError> {
+pub fn load_deserializable_from_file<T: for<'de> Deserialize<'de> + Debug>(file_path: &str, load_id: &str) -> anyhow::Result<T> {
     let json_value = load_json(file_path)
         .map_err(|e| {
             #[cfg(feature = "trace")]
@@ -136,24 +248,24 @@ pub fn load_deserializable_from_file<T: for<'de> Deserialize<'de> + Debug>(file_
         #[cfg(feature = "trace")]
         error!("Type ID: ({:?}) of loaded object does not match given type ID: {:?}", json_value.load_type_id.clone(), load_id.clone());
 
-        return Err( LoadIDError {
+        return Err(anyhow::Error::new(LoadIDError {
                 actual: json_value.load_type_id,
                 expected: load_id.to_string(),
-            })
+            }).context(format!("checking load ID of '{}'", file_path)))
     }
 
     #[cfg(feature="trace")]
     debug!("Load ID: ({:?}) matched given file ID: {:?}", json_value.load_type_id.clone(), load_id.clone());
 
-    let deserialized_value: Result<T, LoadError> = from_value(json_value.actual_value.clone())
+    let deserialized_value: anyhow::Result<T> = from_value(json_value.actual_value.clone())
         .map_err(|e| {
             #[cfg(feature = "trace")]
             error!("Failed to convert generic JSONLoad object: ({:?}) into specific type", json_value.clone());
 
-            DeserializationError {
+            anyhow::Error::new(DeserializationError {
                 value: json_value.actual_value,
                 source: e
-            }
+            }).context(format!("deserializing '{}' as {}", file_path, load_id))
         });
 
     return deserialized_value
@@ -185,6 +297,40 @@ pub fn load_deserializable_from_json<T: for<'de> Deserialize<'de>>(json: &JSONLo
     }
 }
 
+/// Inverse of `load_deserializable_from_json`.
+#[cfg_attr(feature="trace", instrument(skip(value)))]
+pub fn serialize_to_json_load<T: Serialize>(value: &T, load_id: &str) -> Result<JSONLoad, LoadError> {
+    let actual_value = to_value(value)
+        .map_err(|e| {
+            #[cfg(feature = "trace")]
+            error!("Failed to convert value into JSONLoad object with load_id: {:?}", load_id);
+
+            SerializationError {
+                load_id: load_id.to_string(),
+                source: e
+            }
+        })?;
+
+    Ok(JSONLoad {
+        load_type_id: load_id.to_string(),
+        actual_value
+    })
+}
+
+/// Formats `err` followed by every error in its `source` chain, one per line as
+/// `Caused by: {}`, so the root cause isn't lost when the error is logged.
+pub fn display_chain(err: &dyn Error) -> String {
+    let mut message = err.to_string();
+
+    let mut source = err.source();
+    while let Some(cause) = source {
+        message.push_str(&format!("\nCaused by: {}", cause));
+        source = cause.source();
+    }
+
+    message
+}
+
 #[derive(Debug, Error)]
 pub enum LoadError {
     #[error("Error loading file at path: {path}")]
@@ -215,6 +361,47 @@ pub enum LoadError {
     #[error("Failed to execute task")]
     ExecutionError {
         source: anyhow::Error
+    },
+    #[error("Error serializing value with load ID: {load_id}")]
+    SerializationError {
+        load_id: String,
+        source: serde_json::error::Error
+    },
+    #[error("Error applying JSON patch")]
+    PatchError {
+        source: json_patch::PatchError
+    },
+    #[error("Error converting value: {value} into JSON patch")]
+    PatchConversionError {
+        value: Value,
+        source: serde_json::error::Error
+    },
+    #[error("Error setting patched value of component: {component}")]
+    PatchedValueError {
+        component: String,
+        source: anyhow::Error
+    },
+    #[error("File: {path} includes itself. Include chain: {}", .chain.join(" -> "))]
+    CircularIncludeError {
+        path: String,
+        chain: Vec<String>
+    },
+    #[error("Including file: {path} goes past the max include depth of {max_depth}")]
+    IncludeDepthError {
+        path: String,
+        max_depth: usize
+    },
+    #[error("Field: {field} does not match the loader's schema: {message}")]
+    SchemaValidationError {
+        field: String,
+        message: String
+    }
+}
+
+impl LoadError {
+    /// This error and all of its causes. See `display_chain`.
+    pub fn chain(&self) -> String {
+        display_chain(self)
     }
 }
 
@@ -230,4 +417,66 @@ pub enum LoadActionError {
         file_path: String,
         source: LoadError
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::test_util::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn circular_include_is_reported_with_its_chain() {
+        let dir = TempDir::new("circular_include");
+        let (a, b, c) = (dir.path("a.json"), dir.path("b.json"), dir.path("c.json"));
+        dir.write_load("a.json", "test", json!({INCLUDE_KEY: b}));
+        dir.write_load("b.json", "test", json!({INCLUDE_KEY: c}));
+        dir.write_load("c.json", "test", json!({INCLUDE_KEY: a}));
+
+        let error = load_json(&a).unwrap_err();
+
+        match error.root_cause().downcast_ref::<LoadError>() {
+            Some(CircularIncludeError { path, chain }) => {
+                assert_eq!(path, &a);
+                assert_eq!(chain, &vec![a.clone(), b, c, a]);
+            },
+            other => panic!("Expected CircularIncludeError, got: {:?}", other)
+        }
+    }
+
+    #[test]
+    fn includes_past_the_max_depth_fail() {
+        let dir = TempDir::new("include_depth");
+        let (b, c) = (dir.path("b.json"), dir.path("c.json"));
+        let a = dir.write_load("a.json", "test", json!({INCLUDE_KEY: b}));
+        dir.write_load("b.json", "test", json!({INCLUDE_KEY: c}));
+        dir.write_load("c.json", "test", json!({"hp": 10}));
+
+        let error = load_json_with_context(&a, &mut LoadContext::new(2)).unwrap_err();
+
+        assert!(matches!(error.root_cause().downcast_ref::<LoadError>(), Some(IncludeDepthError { path, max_depth: 2 }) if path == &c));
+        assert_eq!(load_json_with_context(&a, &mut LoadContext::new(3)).unwrap().actual_value, json!({"hp": 10}));
+    }
+
+    #[test]
+    fn a_file_can_be_included_twice_without_being_circular() {
+        let dir = TempDir::new("repeated_include");
+        let b = dir.write_load("b.json", "test", json!({"hp": 10}));
+        let a = dir.write_load("a.json", "test", json!([{INCLUDE_KEY: b}, {INCLUDE_KEY: b}]));
+
+        assert_eq!(load_json(&a).unwrap().actual_value, json!([{"hp": 10}, {"hp": 10}]));
+    }
+
+    #[test]
+    fn enter_rejects_a_path_already_in_the_chain() {
+        let mut context = LoadContext::default();
+        context.enter("a.json").unwrap();
+        context.enter("b.json").unwrap();
+
+        assert!(matches!(context.enter("a.json"), Err(CircularIncludeError { .. })));
+
+        context.leave("b.json");
+        assert!(context.enter("b.json").is_ok());
+    }
+}
@@ -0,0 +1,112 @@
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+#[cfg(feature = "trace")]
+use tracing::{warn, instrument};
+
+/// Manifest `cargo xtask validate-assets` starts from unless it is given another.
+pub const DEFAULT_ASSET_ROOT: &str = "assets/JSON/scene_stack.json";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    pub source_json: PathBuf,
+    pub missing_path: PathBuf
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ERROR: {} references missing file: {}", self.source_json.display(), self.missing_path.display())
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Follows every path in the JSON files reachable from `root` and returns the ones that don't
+/// exist. Paths are relative to the current directory, like the paths the engine loads at
+/// runtime, so run it from the game's directory. Keys named `path` or ending in `_path` hold a
+/// path, and keys ending in `_paths` hold a list of them. Objects marked `"required": false` are
+/// skipped, since the engine loads without them. Referenced JSON files are checked in turn.
+#[cfg_attr(feature = "trace", instrument)]
+pub fn validate_asset_tree(root: &str) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+    let mut visited = HashSet::new();
+    let mut pending = vec![PathBuf::from(root)];
+
+    if !Path::new(root).is_file() {
+        errors.push(ValidationError {
+            source_json: PathBuf::from("asset root"),
+            missing_path: PathBuf::from(root)
+        });
+
+        return errors
+    }
+
+    while let Some(json_path) = pending.pop() {
+        if !visited.insert(json_path.clone()) {
+            continue
+        }
+
+        let value = match read_to_string(&json_path).map(|text| serde_json::from_str::<Value>(&text)) {
+            Ok(Ok(value)) => value,
+            _ => {
+                #[cfg(feature = "trace")]
+                warn!("Skipping unreadable asset file: {:?}", json_path);
+
+                continue
+            }
+        };
+
+        let mut referenced = Vec::new();
+        collect_paths(&value, &mut referenced);
+
+        for path in referenced {
+            let path = PathBuf::from(path);
+
+            if !path.is_file() {
+                errors.push(ValidationError {
+                    source_json: json_path.clone(),
+                    missing_path: path
+                });
+            } else if path.extension() == Some("json".as_ref()) {
+                pending.push(path);
+            }
+        }
+    }
+
+    errors
+}
+
+fn collect_paths(value: &Value, paths: &mut Vec<String>) {
+    match value {
+        Value::Object(map) if map.get("required") == Some(&Value::Bool(false)) => (),
+        Value::Object(map) => {
+            for (key, value) in map {
+                let is_path = key == "path" || key.ends_with("_path");
+                let is_path_list = key.ends_with("_paths");
+
+                match value {
+                    Value::String(path) if is_path => paths.push(path.clone()),
+                    Value::Array(list) if is_path_list => {
+                        for entry in list {
+                            match entry {
+                                Value::String(path) => paths.push(path.clone()),
+                                _ => collect_paths(entry, paths)
+                            }
+                        }
+                    },
+                    _ => collect_paths(value, paths)
+                }
+            }
+        },
+        Value::Array(values) => {
+            for value in values {
+                collect_paths(value, paths);
+            }
+        },
+        _ => ()
+    }
+}
@@ -0,0 +1,163 @@
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+use serde::Deserialize;
+use thiserror::Error;
+
+#[cfg(feature = "trace")]
+use tracing::{debug, error, instrument};
+
+use crate::load::{load_json, load_deserializable_from_file, JSON_FILE};
+use crate::loading::manifest::ManifestError::{FileMissing, InvalidLoadId, DuplicateHandle, ManifestLoadError, AssetLoadError};
+
+pub const MANIFEST_LOAD_ID: &str = "asset_manifest";
+/// Manifest `GameLoop::run` validates with the `validate_manifest` feature unless
+/// `GameLoop::asset_manifest` sets another.
+pub const DEFAULT_ASSET_MANIFEST: &str = "assets/JSON/asset_manifest.json";
+
+/// Every asset the game loads, so missing or mislabeled files are found before anything is
+/// loaded. Written as:
+///
+/// `{"load_type_id": "asset_manifest", "actual_value": {"assets": {"entity": ["assets/JSON/player.json"], "image": ["assets/images/player.png"]}}}`
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AssetManifest {
+    /// Asset paths grouped by load type ID. JSON files must have the load type ID of their group.
+    /// Other files only need to exist.
+    pub assets: BTreeMap<String, Vec<String>>
+}
+
+impl AssetManifest {
+    /// Loads the manifest at `manifest_path` and checks every asset in it. All problems are
+    /// returned, not just the first.
+    #[cfg_attr(feature = "trace", instrument)]
+    pub fn validate(manifest_path: &str) -> Result<(), Vec<ManifestError>> {
+        let manifest = ManifestLoader::new(manifest_path.to_string())
+            .load()
+            .map_err(|e| vec![e])?;
+
+        let errors = manifest.check();
+
+        #[cfg(feature = "trace")]
+        debug!("Asset manifest: {:?} has {:?} errors", manifest_path, errors.len());
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Checks that each asset exists and has the load type ID of its group, and that no two
+    /// assets of a load type share a handle. Assets are looked up by their file name without the
+    /// extension, like the names in the TextureDict, so those have to be unique.
+    pub fn check(&self) -> Vec<ManifestError> {
+        let mut errors = Vec::new();
+
+        for (load_type_id, paths) in self.assets.iter() {
+            let mut handles = HashSet::new();
+
+            for path in paths {
+                if !Path::new(path).is_file() {
+                    #[cfg(feature = "trace")]
+                    error!("Asset: {:?} in the manifest does not exist", path);
+
+                    errors.push(FileMissing { path: path.clone() });
+                    continue
+                }
+
+                if let Some(handle) = Path::new(path).file_stem().and_then(|stem| stem.to_str()) {
+                    if !handles.insert(handle.to_string()) {
+                        #[cfg(feature = "trace")]
+                        error!("More than one {:?} asset has the handle: {:?}", load_type_id, handle);
+
+                        errors.push(DuplicateHandle { handle: handle.to_string() });
+                    }
+                }
+
+                if !path.ends_with(JSON_FILE) {
+                    continue
+                }
+
+                match load_json(path) {
+                    Ok(json) if json.load_type_id != *load_type_id => {
+                        #[cfg(feature = "trace")]
+                        error!("Asset: {:?} has load type ID: {:?}, but is listed under: {:?}", path, json.load_type_id, load_type_id);
+
+                        errors.push(InvalidLoadId {
+                            path: path.clone(),
+                            expected: load_type_id.clone(),
+                            actual: json.load_type_id
+                        });
+                    },
+                    Ok(_) => {},
+                    Err(e) => {
+                        #[cfg(feature = "trace")]
+                        error!("Failed to load asset: {:?} listed in the manifest", path);
+
+                        errors.push(AssetLoadError {
+                            path: path.clone(),
+                            source: e
+                        });
+                    }
+                }
+            }
+        }
+
+        errors
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ManifestLoader {
+    manifest_file: String
+}
+
+impl ManifestLoader {
+    pub fn new(file_path: String) -> Self {
+        Self {
+            manifest_file: file_path
+        }
+    }
+
+    #[cfg_attr(feature = "trace", instrument)]
+    pub fn load(&self) -> Result<AssetManifest, ManifestError> {
+        load_deserializable_from_file(&self.manifest_file, MANIFEST_LOAD_ID)
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to load asset manifest from: {:?}", self.manifest_file);
+
+                ManifestLoadError {
+                    path: self.manifest_file.clone(),
+                    source: e
+                }
+            })
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ManifestError {
+    #[error("Asset: {path} listed in the manifest does not exist")]
+    FileMissing {
+        path: String
+    },
+    #[error("Asset: {path} has load type ID: {actual}, but is listed under: {expected}")]
+    InvalidLoadId {
+        path: String,
+        expected: String,
+        actual: String
+    },
+    #[error("More than one asset of the same load type has the handle: {handle}")]
+    DuplicateHandle {
+        handle: String
+    },
+    #[error("Failed to load the asset manifest from: {path}")]
+    ManifestLoadError {
+        path: String,
+        source: anyhow::Error
+    },
+    #[error("Failed to load asset: {path} listed in the manifest")]
+    AssetLoadError {
+        path: String,
+        source: anyhow::Error
+    }
+}
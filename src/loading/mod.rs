@@ -1,20 +1,34 @@
 use anyhow::Result;
 use specs::World;
+use specs::rayon::ThreadPool;
 use luminance_glfw::GL33Context;
 use crossbeam_epoch::Atomic;
 use std::sync::{Arc, Mutex, RwLock};
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+use thiserror::Error;
 
 #[cfg(feature = "trace")]
 use tracing::{warn, debug, error, instrument};
 
+pub mod timing;
+pub mod manifest;
+#[cfg(feature = "validate_assets")]
+pub mod asset_tree;
+#[cfg(feature = "trace_recording")]
+pub mod trace_recorder;
+
+use crate::loading::TaskError::{TimeoutError, PanicError};
+
 pub struct Task<Ret,Args> {
-    function: Box<dyn FnOnce(Args) -> Result<Ret>>
+    function: Box<dyn FnOnce(Args) -> Result<Ret> + Send>
 }
 
 impl<Ret: 'static, Args: 'static> Task<Ret,Args> {
 
     #[cfg_attr(feature = "trace", instrument(skip(f)))]
-    pub fn new(f: impl FnOnce(Args) -> Result<Ret> + 'static) -> Self {
+    pub fn new(f: impl FnOnce(Args) -> Result<Ret> + Send + 'static) -> Self {
         Self { function: Box::new(f) }
     }
 
@@ -42,7 +56,7 @@ impl<Ret: 'static, Args: 'static> Task<Ret,Args> {
 
     #[cfg_attr(feature = "trace", instrument(skip(self, other, map)))]
     pub fn join<OtherRet: 'static,NewRet>
-    (self, other: Task<OtherRet,Args>, map: impl FnOnce((Ret,OtherRet)) -> NewRet + 'static) -> Task<NewRet,Args>
+    (self, other: Task<OtherRet,Args>, map: impl FnOnce((Ret,OtherRet)) -> NewRet + Send + 'static) -> Task<NewRet,Args>
         where Args: Clone {
         Task {
             function: Box::new(|args: Args| {
@@ -54,7 +68,7 @@ impl<Ret: 'static, Args: 'static> Task<Ret,Args> {
     }
 
     #[cfg_attr(feature = "trace", instrument(skip(self, other)))]
-    pub fn map<NewRet>(self, other: impl FnOnce(Ret,Args) -> Result<NewRet> + 'static) -> Task<NewRet,Args>
+    pub fn map<NewRet>(self, other: impl FnOnce(Ret,Args) -> Result<NewRet> + Send + 'static) -> Task<NewRet,Args>
         where Args: Clone {
         Task {
             function: Box::new(|args: Args| {
@@ -64,12 +78,238 @@ impl<Ret: 'static, Args: 'static> Task<Ret,Args> {
         }
     }
 
+    /// Runs `next` with this task's result once it succeeds, for loads that depend on an earlier
+    /// one, such as spawning enemies from a loaded tilemap's size. Unlike `serialize`, `next` is a
+    /// closure instead of a Task taking a tuple.
+    #[cfg_attr(feature = "trace", instrument(skip(self, next)))]
+    pub fn and_then<NewRet: 'static>(self, next: impl FnOnce(Ret, Args) -> Result<NewRet> + Send + 'static) -> Task<NewRet,Args>
+        where Args: Clone {
+        Task {
+            function: Box::new(|args: Args| {
+                let a = (self.function)(args.clone())?;
+                next(a, args)
+            })
+        }
+    }
+
+    /// Builds the next Task from this task's result and runs it with the same args.
+    #[cfg_attr(feature = "trace", instrument(skip(self, f)))]
+    pub fn flat_map<NewRet: 'static>(self, f: impl FnOnce(Ret) -> Task<NewRet,Args> + Send + 'static) -> Task<NewRet,Args>
+        where Args: Clone {
+        Task {
+            function: Box::new(|args: Args| {
+                let a = (self.function)(args.clone())?;
+                f(a).execute(args)
+            })
+        }
+    }
+
+    /// Like `new`, but `f` runs on its own thread and the Task fails with `TaskError::TimeoutError`
+    /// if it hasn't finished after `max_duration`, for loads that can hang such as network assets.
+    /// A thread can't be stopped, so a timed out `f` keeps running in the background and its
+    /// result is dropped. Needs `Send` args, so it can't be used for DrawTasks.
+    #[cfg_attr(feature = "trace", instrument(skip(f)))]
+    pub fn with_timeout(max_duration: Duration, f: impl FnOnce(Args) -> Result<Ret> + Send + 'static) -> Self
+        where Args: Send, Ret: Send {
+        Self::new(move |args: Args| {
+            let (sender, receiver) = channel();
+
+            thread::spawn(move || {
+                // The receiver is gone if the Task already timed out.
+                let _ = sender.send(f(args));
+            });
+
+            match receiver.recv_timeout(max_duration) {
+                Ok(result) => result,
+                Err(RecvTimeoutError::Timeout) => {
+                    #[cfg(feature = "trace")]
+                    error!("Task timed out after: {:?}", max_duration);
+
+                    Err(TimeoutError { timeout: max_duration }.into())
+                },
+                Err(RecvTimeoutError::Disconnected) => {
+                    #[cfg(feature = "trace")]
+                    error!("Task panicked before returning a result");
+
+                    Err(PanicError.into())
+                }
+            }
+        })
+    }
+
+    /// Runs this task and `other` at the same time on rayon's global thread pool, which
+    /// `GameLoop::num_threads` sizes, each with a clone of the args. Both get the same
+    /// `Arc<RwLock<World>>` in a GenTask, so they should only read-lock the World. Use
+    /// `ParallelUnsafe` for tasks that need to write to it. Combine the two results with `map`.
+    #[cfg_attr(feature = "trace", instrument(skip(self, other)))]
+    pub fn parallel<B: Send + 'static>(self, other: Task<B, Args>) -> Task<(Ret, B), Args>
+        where Args: Clone + Send, Ret: Send {
+        Task::new(move |args: Args| {
+            let other_args = args.clone();
+            let (a, b) = specs::rayon::join(move || self.execute(args), move || other.execute(other_args));
+
+            Ok((a?, b?))
+        })
+    }
+
     #[cfg_attr(feature = "trace", instrument(skip(self, args)))]
     pub fn execute(self, args: Args) -> Result<Ret> {
         (self.function)(args)
     }
 }
 
+/// A Task that write-locks the World, so it can't run alongside other tasks. Its `parallel` runs
+/// the two tasks one after the other instead.
+pub struct ParallelUnsafe<Ret, Args>(pub Task<Ret, Args>);
+
+impl<Ret: 'static, Args: Clone + 'static> ParallelUnsafe<Ret, Args> {
+    #[cfg_attr(feature = "trace", instrument(skip(self, other)))]
+    pub fn parallel<B: 'static>(self, other: Task<B, Args>) -> Task<(Ret, B), Args> {
+        self.0.join(other, |results| results)
+    }
+}
+
 pub type GenTask<T> = Task<T, Arc<RwLock<World>>>;
 
-pub type DrawTask<T> = Task<T, (Arc<RwLock<World>>, Arc<RwLock<GL33Context>>)>;
\ No newline at end of file
+pub type DrawTask<T> = Task<T, (Arc<RwLock<World>>, Arc<RwLock<GL33Context>>)>;
+
+/// Thread pool in the World for work done in the background while the game runs, such as
+/// `SceneTransition::PRELOAD`. The GameLoop inserts one with `GameLoop::num_threads` threads.
+pub type Pool = Arc<ThreadPool>;
+
+#[derive(Error, Debug)]
+pub enum TaskError {
+    #[error("Task timed out after {timeout:?}")]
+    TimeoutError {
+        timeout: Duration
+    },
+
+    #[error("Task panicked before returning a result")]
+    PanicError
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use specs::{Builder, Component, VecStorage, WorldExt};
+    use specs::rayon::{ThreadPool, ThreadPoolBuilder};
+
+    use super::*;
+
+    const STEP_DURATION: Duration = Duration::from_millis(100);
+
+    #[derive(Debug, PartialEq)]
+    struct Size(u32);
+
+    impl Component for Size { type Storage = VecStorage<Self>; }
+
+    fn world() -> Arc<RwLock<World>> {
+        let mut world = World::new();
+        world.register::<Size>();
+
+        Arc::new(RwLock::new(world))
+    }
+
+    /// First step of a two-step load: creates an entity the second step reads back.
+    fn create_entity() -> GenTask<specs::Entity> {
+        GenTask::new(|ecs: Arc<RwLock<World>>| {
+            let mut world = ecs.write().unwrap();
+
+            Ok(world.create_entity().with(Size(16)).build())
+        })
+    }
+
+    #[test]
+    fn and_then_passes_the_result_to_the_next_step() {
+        let task = create_entity().and_then(|entity, ecs| {
+            let world = ecs.read().unwrap();
+            let sizes = world.read_storage::<Size>();
+
+            Ok(sizes.get(entity).map(|size| size.0))
+        });
+
+        assert_eq!(task.execute(world()).unwrap(), Some(16));
+    }
+
+    #[test]
+    fn flat_map_runs_the_built_task_with_the_same_args() {
+        let task = create_entity().flat_map(|entity| {
+            GenTask::new(move |ecs: Arc<RwLock<World>>| {
+                let world = ecs.read().unwrap();
+                let sizes = world.read_storage::<Size>();
+
+                Ok(sizes.get(entity).map(|size| size.0))
+            })
+        });
+
+        assert_eq!(task.execute(world()).unwrap(), Some(16));
+    }
+
+    #[test]
+    fn and_then_skips_the_next_step_when_the_first_fails() {
+        let task = GenTask::new(|_ecs: Arc<RwLock<World>>| -> Result<u32> { Err(anyhow::anyhow!("Failed to load the tilemap")) })
+            .and_then(|_, _| -> Result<u32> { panic!("The second step ran after the first failed") });
+
+        assert!(task.execute(world()).is_err());
+    }
+
+    fn pool() -> ThreadPool {
+        ThreadPoolBuilder::new().num_threads(2).build().unwrap()
+    }
+
+    fn slow_read(value: u32) -> GenTask<u32> {
+        GenTask::new(move |ecs: Arc<RwLock<World>>| {
+            let _world = ecs.read().unwrap();
+            thread::sleep(STEP_DURATION);
+
+            Ok(value)
+        })
+    }
+
+    #[test]
+    fn parallel_returns_both_outputs() {
+        let task = slow_read(1).parallel(slow_read(2));
+
+        assert_eq!(pool().install(|| task.execute(world())).unwrap(), (1, 2));
+    }
+
+    #[test]
+    fn parallel_is_faster_than_sequential() {
+        let start = Instant::now();
+        pool().install(|| slow_read(1).parallel(slow_read(2)).execute(world())).unwrap();
+
+        assert!(start.elapsed() < STEP_DURATION * 2);
+    }
+
+    #[test]
+    fn parallel_fails_when_either_task_fails() {
+        let task = slow_read(1).parallel(GenTask::new(|_ecs: Arc<RwLock<World>>| -> Result<u32> { Err(anyhow::anyhow!("Failed to load the tilemap")) }));
+
+        assert!(pool().install(|| task.execute(world())).is_err());
+    }
+
+    #[test]
+    fn parallel_unsafe_runs_the_tasks_one_after_the_other() {
+        let start = Instant::now();
+        let results = ParallelUnsafe(slow_read(1)).parallel(slow_read(2)).execute(world()).unwrap();
+
+        assert_eq!(results, (1, 2));
+        assert!(start.elapsed() >= STEP_DURATION * 2);
+    }
+
+    #[test]
+    fn parallel_runs_each_half_on_its_own_rayon_thread() {
+        let thread_index = |_ecs: Arc<RwLock<World>>| {
+            thread::sleep(STEP_DURATION);
+
+            Ok(specs::rayon::current_thread_index())
+        };
+        let task = GenTask::new(thread_index).parallel(GenTask::new(thread_index));
+
+        let (a, b) = pool().install(|| task.execute(world())).unwrap();
+
+        assert!(a.is_some() && b.is_some());
+        assert_ne!(a, b);
+    }
+}
@@ -0,0 +1,95 @@
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use specs::{World, WorldExt};
+use thiserror::Error;
+
+#[cfg(feature = "trace")]
+use tracing::{warn, debug, error};
+
+use crate::loading::timing::LoadTimingError::WorldWriteLockError;
+
+/// Loads that take longer than `warn_threshold` are logged as warnings when tracing is enabled.
+#[derive(Debug, Clone, Copy)]
+pub struct LoadTimingConfig {
+    pub warn_threshold: Duration
+}
+
+impl Default for LoadTimingConfig {
+    fn default() -> Self {
+        Self {
+            warn_threshold: Duration::from_millis(500)
+        }
+    }
+}
+
+/// How long the most recent scene and entity loads took.
+#[derive(Debug, Clone, Default)]
+pub struct LoadTiming {
+    pub last_scene_load_ms: u64,
+    pub last_entity_load_ms: u64,
+    pub slowest_entity_path: String,
+    pub slowest_entity_load_ms: u64
+}
+
+/// Stores a scene's load time in the World's LoadTiming.
+pub fn record_scene_load(ecs: &Arc<RwLock<World>>, scene_path: &str, elapsed: Duration) -> Result<(), LoadTimingError> {
+    let mut world = write_world(ecs)?;
+
+    warn_if_slow(&world, "scene", scene_path, elapsed);
+
+    world.entry::<LoadTiming>()
+        .or_insert_with(LoadTiming::default)
+        .last_scene_load_ms = elapsed.as_millis() as u64;
+
+    Ok(())
+}
+
+/// Stores an entity's load time in the World's LoadTiming and keeps track of the slowest entity file.
+pub fn record_entity_load(ecs: &Arc<RwLock<World>>, entity_path: &str, elapsed: Duration) -> Result<(), LoadTimingError> {
+    let mut world = write_world(ecs)?;
+
+    warn_if_slow(&world, "entity", entity_path, elapsed);
+
+    let elapsed_ms = elapsed.as_millis() as u64;
+    let mut timing = world.entry::<LoadTiming>().or_insert_with(LoadTiming::default);
+
+    timing.last_entity_load_ms = elapsed_ms;
+    if elapsed_ms >= timing.slowest_entity_load_ms {
+        timing.slowest_entity_load_ms = elapsed_ms;
+        timing.slowest_entity_path = entity_path.to_string();
+    }
+
+    Ok(())
+}
+
+#[allow(unused_variables)]
+fn warn_if_slow(world: &World, kind: &str, name: &str, elapsed: Duration) {
+    #[cfg(feature = "trace")]
+    {
+        let threshold = world.try_fetch::<LoadTimingConfig>()
+            .map_or(LoadTimingConfig::default().warn_threshold, |config| config.warn_threshold);
+
+        if elapsed > threshold {
+            warn!("Loading {} {:?} took {:?}, longer than the threshold of {:?}", kind, name, elapsed, threshold);
+        } else {
+            debug!("Loaded {} {:?} in {:?}", kind, name, elapsed);
+        }
+    }
+}
+
+fn write_world(ecs: &Arc<RwLock<World>>) -> Result<std::sync::RwLockWriteGuard<'_, World>, LoadTimingError> {
+    ecs.write()
+        .map_err(|_e| {
+            #[cfg(feature = "trace")]
+            error!("Failed to acquire write lock for World");
+
+            WorldWriteLockError
+        })
+}
+
+#[derive(Error, Debug)]
+pub enum LoadTimingError {
+    #[error("Failed to acquire write lock for World")]
+    WorldWriteLockError
+}
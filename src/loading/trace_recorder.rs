@@ -0,0 +1,391 @@
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Seek, SeekFrom, Write};
+use std::sync::{Arc, RwLock};
+
+use serde::{Deserialize, Serialize};
+use serde::de::DeserializeOwned;
+use specs::{Builder, Component, Entity, Join, RunNow, World, WorldExt};
+use thiserror::Error;
+
+#[cfg(feature = "trace")]
+use tracing::{debug, error, instrument};
+
+use crate::graphics::transform::Transform;
+use crate::loading::trace_recorder::TraceError::{FileCreateError, FileOpenError, FileWriteError, EncodeError, DecodeError, ComponentDecodeError, UnknownComponentError, RestoreError, WorldWriteLockError};
+
+/// Frames kept by a TraceRecorder unless another limit is given. Ten seconds at 60 fps.
+pub const DEFAULT_MAX_TRACE_FRAMES: usize = 600;
+
+/// A component that is written to trace files. `Snapshot` is what gets stored, so it must round
+/// trip through bincode.
+pub trait SnapshotableComponent: Component + Sized {
+    type Snapshot: Serialize + DeserializeOwned;
+
+    fn snapshot(&self) -> Self::Snapshot;
+    fn restore(snapshot: Self::Snapshot) -> Self;
+}
+
+impl SnapshotableComponent for Transform {
+    type Snapshot = [[f32; 3]; 3];
+
+    fn snapshot(&self) -> Self::Snapshot {
+        [self.translation.into(), self.scale.into(), self.rotation.into()]
+    }
+
+    fn restore(snapshot: Self::Snapshot) -> Self {
+        let [translation, scale, rotation] = snapshot;
+
+        Self {
+            translation: translation.into(),
+            scale: scale.into(),
+            rotation: rotation.into()
+        }
+    }
+}
+
+/// One component of one entity. `entity` is the entity's id when the frame was recorded.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ComponentRecord {
+    pub component: String,
+    pub entity: u32,
+    pub data: Vec<u8>
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FrameRecord {
+    pub frame: u64,
+    pub components: Vec<ComponentRecord>
+}
+
+type CaptureFn = fn(&World, &str, &mut Vec<ComponentRecord>) -> Result<(), TraceError>;
+type RestoreFn = fn(&mut World, Entity, &str, &[u8]) -> Result<(), TraceError>;
+
+/// The components written to trace files, by the name they are stored under. Transform is
+/// registered by default. Insert it into the World to record more components.
+#[derive(Clone)]
+pub struct TraceRegistry {
+    components: HashMap<String, (CaptureFn, RestoreFn)>
+}
+
+impl Default for TraceRegistry {
+    fn default() -> Self {
+        let mut registry = Self { components: HashMap::new() };
+        registry.register::<Transform>("Transform");
+
+        registry
+    }
+}
+
+impl TraceRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register<C: SnapshotableComponent>(&mut self, name: &str) where C::Storage: Default {
+        self.components.insert(name.to_string(), (capture::<C>, restore::<C>));
+    }
+
+    fn capture(&self, ecs: &World) -> Result<Vec<ComponentRecord>, TraceError> {
+        let mut records = Vec::new();
+
+        for (name, (capture_fn, _)) in self.components.iter() {
+            capture_fn(ecs, name, &mut records)?;
+        }
+
+        Ok(records)
+    }
+}
+
+fn capture<C: SnapshotableComponent>(ecs: &World, name: &str, records: &mut Vec<ComponentRecord>) -> Result<(), TraceError> {
+    let entities = ecs.entities();
+    let storage = ecs.read_storage::<C>();
+
+    for (entity, component) in (&entities, &storage).join() {
+        let data = bincode::serialize(&component.snapshot())
+            .map_err(|e| EncodeError {
+                component: name.to_string(),
+                source: e
+            })?;
+
+        records.push(ComponentRecord {
+            component: name.to_string(),
+            entity: entity.id(),
+            data
+        });
+    }
+
+    Ok(())
+}
+
+fn restore<C: SnapshotableComponent>(ecs: &mut World, entity: Entity, name: &str, data: &[u8]) -> Result<(), TraceError> where C::Storage: Default {
+    let snapshot: C::Snapshot = bincode::deserialize(data)
+        .map_err(|e| ComponentDecodeError {
+            component: name.to_string(),
+            source: e
+        })?;
+
+    ecs.register::<C>();
+    ecs.write_storage::<C>().insert(entity, C::restore(snapshot))
+        .map_err(|e| RestoreError {
+            component: name.to_string(),
+            source: e
+        })?;
+
+    Ok(())
+}
+
+/// Keeps the last `max_frames` frames recorded by the TraceRecordSystem and writes them to its
+/// file on `flush`, and again when it is dropped. Each flush replaces the file's contents, so its
+/// size stays bounded.
+#[derive(Debug)]
+pub struct TraceRecorder {
+    file: BufWriter<File>,
+    frame_count: u64,
+    frames: VecDeque<FrameRecord>,
+    max_frames: usize
+}
+
+impl TraceRecorder {
+    #[cfg_attr(feature = "trace", instrument)]
+    pub fn new(path: &str) -> Result<Self, TraceError> {
+        Self::with_max_frames(path, DEFAULT_MAX_TRACE_FRAMES)
+    }
+
+    /// A `max_frames` of zero is treated as one.
+    #[cfg_attr(feature = "trace", instrument)]
+    pub fn with_max_frames(path: &str, max_frames: usize) -> Result<Self, TraceError> {
+        let file = File::create(path)
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to create trace file: {:?}", path);
+
+                FileCreateError {
+                    path: path.to_string(),
+                    source: e
+                }
+            })?;
+
+        Ok(Self {
+            file: BufWriter::new(file),
+            frame_count: 0,
+            frames: VecDeque::new(),
+            max_frames: max_frames.max(1)
+        })
+    }
+
+    /// Frames recorded so far, including those dropped from the buffer.
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    pub fn record(&mut self, components: Vec<ComponentRecord>) {
+        self.frames.push_back(FrameRecord {
+            frame: self.frame_count,
+            components
+        });
+        self.frame_count += 1;
+
+        while self.frames.len() > self.max_frames {
+            self.frames.pop_front();
+        }
+    }
+
+    /// Writes the buffered frames to the file and syncs it to disk.
+    #[cfg_attr(feature = "trace", instrument(skip(self)))]
+    pub fn flush(&mut self) -> Result<(), TraceError> {
+        self.file.seek(SeekFrom::Start(0))
+            .and_then(|_| self.file.get_ref().set_len(0))
+            .map_err(|e| FileWriteError { source: e })?;
+
+        bincode::serialize_into(&mut self.file, &self.frames)
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to encode trace frames");
+
+                EncodeError {
+                    component: "FrameRecord".to_string(),
+                    source: e
+                }
+            })?;
+
+        self.file.flush()
+            .and_then(|_| self.file.get_ref().sync_all())
+            .map_err(|e| FileWriteError { source: e })?;
+
+        #[cfg(feature = "trace")]
+        debug!("Flushed {:?} trace frames", self.frames.len());
+
+        Ok(())
+    }
+}
+
+impl Drop for TraceRecorder {
+    fn drop(&mut self) {
+        if let Err(_e) = self.flush() {
+            #[cfg(feature = "trace")]
+            error!("Failed to flush trace frames on drop: {:?}", _e);
+        }
+    }
+}
+
+/// Flushes the World's TraceRecorder, if it has one. Meant for when the game is already exiting
+/// on an error, so a failed flush is only logged.
+#[cfg_attr(feature = "trace", instrument(skip(world)))]
+pub fn flush_trace_recorder(world: &World) {
+    if let Some(mut recorder) = world.try_fetch_mut::<TraceRecorder>() {
+        if let Err(_e) = recorder.flush() {
+            #[cfg(feature = "trace")]
+            error!("Failed to flush trace frames: {:?}", _e);
+        }
+    }
+}
+
+/// Records every registered component into the World's TraceRecorder. Does nothing when there is
+/// no TraceRecorder. Uses the World's TraceRegistry, or the default one. It reads any number of
+/// component storages, so it is run with `RunNow::run_now` rather than from a Dispatcher.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TraceRecordSystem;
+
+impl<'a> RunNow<'a> for TraceRecordSystem {
+    #[cfg_attr(feature = "trace", instrument(skip(self, world)))]
+    fn run_now(&mut self, world: &'a World) {
+        let mut recorder = match world.try_fetch_mut::<TraceRecorder>() {
+            Some(recorder) => recorder,
+            None => return
+        };
+
+        let records = match world.try_fetch::<TraceRegistry>() {
+            Some(registry) => registry.capture(world),
+            None => TraceRegistry::default().capture(world)
+        };
+
+        match records {
+            Ok(records) => recorder.record(records),
+            Err(_e) => {
+                #[cfg(feature = "trace")]
+                error!("Failed to record frame: {:?}. Error: {:?}", recorder.frame_count(), _e);
+            }
+        }
+    }
+
+    fn setup(&mut self, _world: &mut World) {}
+}
+
+/// Reads the frames written by a TraceRecorder, oldest first.
+#[cfg_attr(feature = "trace", instrument)]
+pub fn read_trace(path: &str) -> Result<Vec<FrameRecord>, TraceError> {
+    let file = File::open(path)
+        .map_err(|e| {
+            #[cfg(feature = "trace")]
+            error!("Failed to open trace file: {:?}", path);
+
+            FileOpenError {
+                path: path.to_string(),
+                source: e
+            }
+        })?;
+
+    bincode::deserialize_from(BufReader::new(file))
+        .map_err(|e| {
+            #[cfg(feature = "trace")]
+            error!("Failed to decode trace file: {:?}", path);
+
+            DecodeError {
+                path: path.to_string(),
+                source: e
+            }
+        })
+}
+
+/// Applies every frame of the trace at `path` to the World in order, leaving it in the state of
+/// the last recorded frame. Each recorded entity is created as a new entity the first time it
+/// appears. Use `read_trace` to step through the frames instead.
+#[cfg_attr(feature = "trace", instrument(skip(ecs)))]
+pub fn replay_trace(path: &str, ecs: Arc<RwLock<World>>) -> Result<(), TraceError> {
+    let frames = read_trace(path)?;
+
+    let mut world = ecs.write()
+        .map_err(|_e| {
+            #[cfg(feature = "trace")]
+            error!("Failed to acquire write lock for World");
+
+            WorldWriteLockError
+        })?;
+
+    let registry = world.try_fetch::<TraceRegistry>()
+        .map(|registry| TraceRegistry::clone(&registry))
+        .unwrap_or_default();
+    let mut entities: HashMap<u32, Entity> = HashMap::new();
+
+    for frame in frames {
+        for record in frame.components {
+            let (_, restore_fn) = registry.components.get(&record.component)
+                .ok_or_else(|| UnknownComponentError { component: record.component.clone() })?;
+
+            let entity = *entities.entry(record.entity)
+                .or_insert_with(|| world.create_entity().build());
+
+            restore_fn(&mut world, entity, &record.component, &record.data)?;
+        }
+
+        #[cfg(feature = "trace")]
+        debug!("Replayed frame: {:?}", frame.frame);
+    }
+
+    world.maintain();
+
+    Ok(())
+}
+
+#[derive(Error, Debug)]
+pub enum TraceError {
+    #[error("Failed to create trace file: {path}")]
+    FileCreateError {
+        path: String,
+        source: std::io::Error
+    },
+
+    #[error("Failed to open trace file: {path}")]
+    FileOpenError {
+        path: String,
+        source: std::io::Error
+    },
+
+    #[error("Failed to write trace file")]
+    FileWriteError {
+        source: std::io::Error
+    },
+
+    #[error("Failed to encode {component} for the trace")]
+    EncodeError {
+        component: String,
+        source: bincode::Error
+    },
+
+    #[error("Failed to decode trace file: {path}")]
+    DecodeError {
+        path: String,
+        source: bincode::Error
+    },
+
+    #[error("Failed to decode recorded {component}")]
+    ComponentDecodeError {
+        component: String,
+        source: bincode::Error
+    },
+
+    #[error("No component named: {component} is registered for tracing")]
+    UnknownComponentError {
+        component: String
+    },
+
+    #[error("Failed to restore recorded {component}")]
+    RestoreError {
+        component: String,
+        source: specs::error::Error
+    },
+
+    #[error("Failed to acquire write lock for World")]
+    WorldWriteLockError
+}
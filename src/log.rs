@@ -2,11 +2,20 @@ use tracing_bunyan_formatter::{BunyanFormattingLayer, JsonStorageLayer};
 use tracing_log::LogTracer;
 use tracing_subscriber::{Registry, EnvFilter};
 use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+use tracing_subscriber::util::{SubscriberInitExt, TryInitError};
 use tracing_appender::non_blocking;
 
+use std::fs::OpenOptions;
+use std::io::{sink, stdout};
+use std::path::PathBuf;
+use std::sync::Arc;
+
 use thiserror::Error;
 use anyhow::Result;
 use crate::log::LogError::{InitLogTracerError, SetGlobalDefaultError};
+use crate::log::LogInitError::{LogFileOpenError, SubscriberInitError};
 
 pub fn init_logger() -> Result<()>{
     // LogTracer::init().map_err(|e| {
@@ -43,4 +52,85 @@ pub enum LogError {
     SetGlobalDefaultError {
         source: tracing::subscriber::SetGlobalDefaultError
     }
-}
\ No newline at end of file
+}
+
+/// Where trace output is written.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LogOutput {
+    Stdout,
+    /// Appends to the file, creating it if needed.
+    File(PathBuf),
+    Discard
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LogFormat {
+    Pretty,
+    Compact,
+    Json
+}
+
+/// Replaces `init_logger` when the default bunyan log file isn't wanted.
+#[derive(Debug, Clone)]
+pub struct LogConfig {
+    pub level: LevelFilter,
+    pub output: LogOutput,
+    pub format: LogFormat
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            level: LevelFilter::INFO,
+            output: LogOutput::Stdout,
+            format: LogFormat::Compact
+        }
+    }
+}
+
+impl LogConfig {
+    /// Installs the global subscriber. Fails if one has already been installed.
+    pub fn init(&self) -> Result<(), LogInitError> {
+        let writer = match &self.output {
+            LogOutput::Stdout => BoxMakeWriter::new(stdout),
+            LogOutput::File(path) => {
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .map_err(|e| {
+                        LogFileOpenError {
+                            path: path.clone(),
+                            source: e
+                        }
+                    })?;
+
+                BoxMakeWriter::new(Arc::new(file))
+            },
+            LogOutput::Discard => BoxMakeWriter::new(sink)
+        };
+
+        let builder = tracing_subscriber::fmt()
+            .with_max_level(self.level)
+            .with_writer(writer);
+
+        match self.format {
+            LogFormat::Pretty => builder.pretty().finish().try_init(),
+            LogFormat::Compact => builder.compact().finish().try_init(),
+            LogFormat::Json => builder.json().finish().try_init()
+        }.map_err(|e| SubscriberInitError { source: e })
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum LogInitError {
+    #[error("Failed to open log file: {path:?}")]
+    LogFileOpenError {
+        path: PathBuf,
+        source: std::io::Error
+    },
+    #[error("Error setting global default subscriber for tracing")]
+    SubscriberInitError {
+        source: TryInitError
+    }
+}
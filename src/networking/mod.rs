@@ -0,0 +1,170 @@
+#[cfg(feature = "trace")]
+use tracing::{debug, error, instrument};
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use specs::{Component, Join, World, WorldExt};
+use specs::world::Index;
+use thiserror::Error;
+
+use crate::networking::NetworkError::{WorldReadLockError, WorldWriteLockError, RegistryDNE, SerializeError, DeserializeError, UnknownComponent};
+
+/// A component whose values are sent between peers. Register it with the NetworkRegistry.
+pub trait Networked: Component + Serialize + DeserializeOwned {}
+
+/// Hook for a networking layer to read the world state out of an ECS and write it back in.
+pub trait NetworkSync {
+    fn serialize_state(ecs: Arc<RwLock<World>>) -> Result<Vec<u8>, NetworkError>;
+    fn apply_state(ecs: Arc<RwLock<World>>, data: &[u8]) -> Result<(), NetworkError>;
+}
+
+type SerializeFn = fn(&World) -> Result<Value, NetworkError>;
+type ApplyFn = fn(&World, Value) -> Result<(), NetworkError>;
+
+/// Maps the name a component is sent under to the functions that serialize and apply it.
+/// Insert it into the World before calling `NetworkSync` on it.
+#[derive(Default)]
+pub struct NetworkRegistry {
+    components: HashMap<String, (SerializeFn, ApplyFn)>
+}
+
+impl NetworkRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register<C: Networked>(&mut self, name: &str) {
+        self.components.insert(name.to_string(), (serialize_component::<C>, apply_component::<C>));
+    }
+}
+
+/// State is sent as JSON of the form `{ component_name: [[entity_id, value], ...] }`. Entities are
+/// matched by ID, so both sides must have created them in the same order. Values for entities that
+/// are not alive are ignored.
+impl NetworkSync for NetworkRegistry {
+    #[cfg_attr(feature = "trace", instrument(skip(ecs)))]
+    fn serialize_state(ecs: Arc<RwLock<World>>) -> Result<Vec<u8>, NetworkError> {
+        let world = ecs.read()
+            .map_err(|_e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to acquire read lock for World");
+
+                WorldReadLockError
+            })?;
+
+        let registry = world.try_fetch::<NetworkRegistry>().ok_or(RegistryDNE)?;
+
+        let mut state = Map::new();
+        for (name, (serialize, _apply)) in registry.components.iter() {
+            state.insert(name.clone(), serialize(&world)?);
+        }
+
+        #[cfg(feature = "trace")]
+        debug!("Serialized {:?} networked components", state.len());
+
+        serde_json::to_vec(&state)
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to serialize network state");
+
+                SerializeError { source: e }
+            })
+    }
+
+    #[cfg_attr(feature = "trace", instrument(skip(ecs, data)))]
+    fn apply_state(ecs: Arc<RwLock<World>>, data: &[u8]) -> Result<(), NetworkError> {
+        let state: Map<String, Value> = serde_json::from_slice(data)
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to deserialize network state");
+
+                DeserializeError { source: e }
+            })?;
+
+        let world = ecs.write()
+            .map_err(|_e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to acquire write lock for World");
+
+                WorldWriteLockError
+            })?;
+
+        let registry = world.try_fetch::<NetworkRegistry>().ok_or(RegistryDNE)?;
+
+        for (name, values) in state {
+            let (_serialize, apply) = registry.components.get(&name)
+                .ok_or_else(|| {
+                    #[cfg(feature = "trace")]
+                    error!("Received unregistered component: {:?}", name.clone());
+
+                    UnknownComponent { name: name.clone() }
+                })?;
+
+            apply(&world, values)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn serialize_component<C: Networked>(world: &World) -> Result<Value, NetworkError> {
+    let entities = world.entities();
+    let storage = world.read_storage::<C>();
+
+    let values: Vec<(Index, &C)> = (&entities, &storage).join()
+        .map(|(entity, component)| (entity.id(), component))
+        .collect();
+
+    serde_json::to_value(values)
+        .map_err(|e| SerializeError { source: e })
+}
+
+fn apply_component<C: Networked>(world: &World, values: Value) -> Result<(), NetworkError> {
+    let values: Vec<(Index, C)> = serde_json::from_value(values)
+        .map_err(|e| DeserializeError { source: e })?;
+
+    let entities = world.entities();
+    let mut storage = world.write_storage::<C>();
+
+    for (id, component) in values {
+        let entity = entities.entity(id);
+
+        if entities.is_alive(entity) {
+            // Only fails for dead entities, which were just filtered out.
+            let _ = storage.insert(entity, component);
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Error, Debug)]
+pub enum NetworkError {
+    #[error("Failed to acquire read lock for World")]
+    WorldReadLockError,
+
+    #[error("Failed to acquire write lock for World")]
+    WorldWriteLockError,
+
+    #[error("No NetworkRegistry has been inserted into the World")]
+    RegistryDNE,
+
+    #[error("Failed to serialize network state")]
+    SerializeError {
+        source: serde_json::Error
+    },
+
+    #[error("Failed to deserialize network state")]
+    DeserializeError {
+        source: serde_json::Error
+    },
+
+    #[error("Received state for unregistered component: {name}")]
+    UnknownComponent {
+        name: String
+    }
+}
@@ -0,0 +1,273 @@
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::time::Duration;
+
+use anyhow::Result;
+use glam::{Vec2, Vec3};
+use luminance_glfw::GL33Context;
+use serde::Deserialize;
+use specs::{Entity, World, WorldExt};
+use thiserror::Error;
+
+#[cfg(feature = "trace")]
+use tracing::{debug, error, instrument};
+
+use crate::camera::Camera;
+use crate::components::ComponentMux;
+use crate::events::EventQueue;
+use crate::input::Input;
+use crate::load::{create_entity_vec, JSONLoad, load_deserializable_from_json, LoadError};
+use crate::loading::DrawTask;
+use crate::scenes::{Scene, SceneLoader, SceneLoaderJSON};
+use crate::scenes::scene_stack::SceneTransition;
+use crate::systems::clear_events::register_event;
+use crate::scenes::cutscene::CutsceneLoaderError::{DeserializeError, StepsDeserializeError, EntityLoadError};
+
+pub const CUTSCENE_LOAD_ID: &str = "cutscene";
+
+/// Sounds, dialogue and entities belong to the game, so the CutsceneScene only announces the steps
+/// that use them, through an `EventQueue<CutsceneStep>`. The game drains the queue after updating
+/// the scene stack and carries them out. The scene registers the event itself.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CutsceneStep {
+    /// Slides the active camera so it is centered on `target` over `duration` seconds. The camera
+    /// keeps its distance from the scene.
+    MoveCameraTo { target: Vec2, duration: f32 },
+    Wait { seconds: f32 },
+    PlaySound { name: String },
+    ShowDialogue { entity_name: String },
+    /// `prefab` is the path of the entity file to spawn.
+    SpawnEntity { prefab: String, position: Vec2 },
+    DespawnEntity { name: String }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub enum CutsceneStepJSON {
+    MoveCameraTo { target: [f32; 2], duration: f32 },
+    Wait { seconds: f32 },
+    PlaySound { name: String },
+    ShowDialogue { entity_name: String },
+    SpawnEntity { prefab: String, position: [f32; 2] },
+    DespawnEntity { name: String }
+}
+
+impl From<CutsceneStepJSON> for CutsceneStep {
+    fn from(json: CutsceneStepJSON) -> Self {
+        match json {
+            CutsceneStepJSON::MoveCameraTo { target, duration } => CutsceneStep::MoveCameraTo { target: Vec2::from(target), duration },
+            CutsceneStepJSON::Wait { seconds } => CutsceneStep::Wait { seconds },
+            CutsceneStepJSON::PlaySound { name } => CutsceneStep::PlaySound { name },
+            CutsceneStepJSON::ShowDialogue { entity_name } => CutsceneStep::ShowDialogue { entity_name },
+            CutsceneStepJSON::SpawnEntity { prefab, position } => CutsceneStep::SpawnEntity { prefab, position: Vec2::from(position) },
+            CutsceneStepJSON::DespawnEntity { name } => CutsceneStep::DespawnEntity { name }
+        }
+    }
+}
+
+/// Plays its steps in order, one after the other, then pops itself off the scene stack.
+#[derive(Debug)]
+pub struct CutsceneScene<T: Input + Debug> {
+    name: String,
+    steps: Vec<CutsceneStep>,
+    current_step: usize,
+    step_time: f32,
+    camera_start: Option<Vec3>,
+    entities: Vec<Entity>,
+    input: PhantomData<T>
+}
+
+impl<T: Input + Debug> CutsceneScene<T> {
+    pub fn new(name: String, steps: Vec<CutsceneStep>, entities: Vec<Entity>) -> Self {
+        Self {
+            name,
+            steps,
+            current_step: 0,
+            step_time: 0.0,
+            camera_start: None,
+            entities,
+            input: PhantomData
+        }
+    }
+
+    /// Entities loaded from the scene file's `entity_paths`.
+    pub fn entities(&self) -> &[Entity] {
+        &self.entities
+    }
+
+    /// Runs the current step for `step_time` seconds and returns whether it is complete.
+    fn run_step(&mut self, ecs: &mut World) -> bool {
+        match self.steps[self.current_step].clone() {
+            CutsceneStep::MoveCameraTo { target, duration } => {
+                let mut camera = ecs.try_fetch_mut::<Option<Box<dyn Camera>>>();
+
+                let camera = match camera.as_deref_mut().and_then(Option::as_mut) {
+                    Some(camera) => camera,
+                    None => {
+                        #[cfg(feature = "trace")]
+                        debug!("No active camera. Skipping camera move to: {:?}", target);
+
+                        return true
+                    }
+                };
+
+                let start = *self.camera_start.get_or_insert_with(|| camera.position());
+                let end = target.extend(start.z);
+                let t = if duration > 0.0 { (self.step_time / duration).min(1.0) } else { 1.0 };
+
+                let position = start.lerp(end, t);
+                let offset = position - camera.position();
+
+                camera.set_position(position);
+                camera.set_target(camera.target() + offset);
+
+                t >= 1.0
+            },
+            CutsceneStep::Wait { seconds } => self.step_time >= seconds,
+            step => {
+                register_event::<CutsceneStep>(ecs);
+                ecs.fetch_mut::<EventQueue<CutsceneStep>>().push(step);
+
+                true
+            }
+        }
+    }
+}
+
+impl<T: Input + Debug> Scene<T> for CutsceneScene<T> {
+    #[cfg_attr(feature = "trace", instrument(skip(ecs)))]
+    fn update(&mut self, ecs: &mut World, delta: Duration) -> Result<SceneTransition<T>> {
+        if self.current_step >= self.steps.len() {
+            return Ok(SceneTransition::NONE)
+        }
+
+        self.step_time += delta.as_secs_f32();
+
+        // Steps that complete immediately don't hold up the ones after them.
+        while self.current_step < self.steps.len() && self.run_step(ecs) {
+            #[cfg(feature = "trace")]
+            debug!("Finished cutscene step: {:?}", self.steps[self.current_step]);
+
+            self.current_step += 1;
+            self.step_time = 0.0;
+            self.camera_start = None;
+        }
+
+        if self.current_step >= self.steps.len() {
+            #[cfg(feature = "trace")]
+            debug!("Cutscene: {:?} finished", self.name.clone());
+
+            Ok(SceneTransition::POP(1))
+        } else {
+            Ok(SceneTransition::NONE)
+        }
+    }
+
+    fn draw(&mut self, _ecs: &mut World, _context: &mut GL33Context) -> Result<()> {
+        Ok(())
+    }
+
+    fn interact(&mut self, _ecs: &mut World, _input: &T) -> Result<()> {
+        Ok(())
+    }
+
+    fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn is_finished(&self, _ecs: &mut World) -> Result<bool> {
+        Ok(self.current_step >= self.steps.len())
+    }
+}
+
+/// Loads a CutsceneScene from a scene file whose `scene_values` is the array of steps. Entities in
+/// `entity_paths` are loaded with the ComponentMux `M`. Register `CutsceneLoader::from_json` with the
+/// SceneRegistry under `CUTSCENE_LOAD_ID`.
+pub struct CutsceneLoader<T: Input + Debug, M: ComponentMux> {
+    entity_paths: Vec<String>,
+    steps: Vec<CutsceneStep>,
+    phantom: PhantomData<(T, M)>
+}
+
+impl<T: Input + Debug, M: ComponentMux> Debug for CutsceneLoader<T, M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CutsceneLoader")
+            .field("entity_paths", &self.entity_paths)
+            .field("steps", &self.steps)
+            .finish()
+    }
+}
+
+impl<T: Input + Debug, M: ComponentMux> CutsceneLoader<T, M> {
+    #[cfg_attr(feature = "trace", instrument)]
+    pub fn from_json(json: JSONLoad) -> Result<Self> {
+        let scene_json: SceneLoaderJSON = load_deserializable_from_json(&json, CUTSCENE_LOAD_ID)
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to convert JSONLoad object: ({:?}) into SceneLoaderJSON value", json.clone());
+
+                DeserializeError {
+                    source: e,
+                    json: json.clone()
+                }
+            })?;
+
+        let steps: Vec<CutsceneStepJSON> = serde_json::from_value(scene_json.scene_values.clone())
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to convert scene values: ({:?}) into cutscene steps", scene_json.scene_values.clone());
+
+                StepsDeserializeError {
+                    source: e,
+                    json: json.clone()
+                }
+            })?;
+
+        Ok(Self {
+            entity_paths: scene_json.entity_paths,
+            steps: steps.into_iter().map(CutsceneStep::from).collect(),
+            phantom: PhantomData
+        })
+    }
+}
+
+impl<T: 'static + Input + Debug, M: 'static + ComponentMux> SceneLoader<T> for CutsceneLoader<T, M> {
+    #[cfg_attr(feature = "trace", instrument)]
+    fn load_scene(&self) -> DrawTask<Box<dyn Scene<T>>> {
+        let entity_paths = self.entity_paths.clone();
+        let steps = self.steps.clone();
+
+        DrawTask::new(move |(ecs, context)| {
+            let entities = create_entity_vec::<M>(&entity_paths, ecs, context)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to load cutscene entities: {:?}", entity_paths.clone());
+
+                    EntityLoadError { source: e }
+                })?;
+
+            let scene: Box<dyn Scene<T>> = Box::new(CutsceneScene::<T>::new(CUTSCENE_LOAD_ID.to_string(), steps, entities));
+
+            Ok(scene)
+        })
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum CutsceneLoaderError {
+    #[error("Failed to convert JSONLoad object: ({json:?}) to SceneLoaderJSON value")]
+    DeserializeError {
+        source: LoadError,
+        json: JSONLoad
+    },
+
+    #[error("Failed to convert scene values of: ({json:?}) to cutscene steps")]
+    StepsDeserializeError {
+        source: serde_json::Error,
+        json: JSONLoad
+    },
+
+    #[error("Failed to load cutscene entities")]
+    EntityLoadError {
+        source: anyhow::Error
+    }
+}
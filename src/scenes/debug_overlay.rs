@@ -0,0 +1,337 @@
+use std::fmt::{Debug, Formatter};
+use std::time::Duration;
+
+use glam::{Mat4, Quat, Vec3};
+use glfw::Key;
+use luminance_front::{
+    context::GraphicsContext,
+    pipeline::PipelineState,
+    render_state::RenderState,
+    shader::Program,
+    tess::{Mode, Tess},
+};
+use luminance_glfw::GL33Context;
+use specs::{Join, World, WorldExt};
+use thiserror::Error;
+
+#[cfg(feature = "trace")]
+use tracing::{debug, error, instrument};
+
+use crate::components::world_text::{TextAlignment, WorldText};
+use crate::events::EventQueue;
+use crate::globals::font_atlas_dict::FontAtlasDict;
+use crate::globals::texture_dict::TextureDict;
+use crate::graphics::dynamic_resolution::DynamicResolution;
+use crate::graphics::render::sprite_renderer::{default_sprite_render_state, SpriteRenderError};
+use crate::graphics::render::sprite_renderer::SpriteRenderError::{FailedToBind, TessRenderError, RenderGateError};
+use crate::graphics::render::text_renderer::{layout_text, TextSemantics, TextShaderUniform, TextVertex};
+use crate::loading::timing::LoadTiming;
+use crate::scenes::debug_overlay::DebugOverlayError::{BackBufferError, ShaderProgramBuildError, TessBuildError, RenderError};
+
+const TEXT_VS: &str = include_str!("../graphics/text-vs.glsl");
+const TEXT_FS: &str = include_str!("../graphics/text-fs.glsl");
+
+const TEXT_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+/// Distance in pixels between the text and the top left corner of the window.
+const MARGIN: f32 = 8.0;
+/// How often the text is rewritten, so the numbers can be read.
+const REFRESH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Key that opens and closes the DebugOverlay. F3 unless the World holds another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DebugOverlayKey(pub Key);
+
+impl Default for DebugOverlayKey {
+    fn default() -> Self {
+        Self(Key::F3)
+    }
+}
+
+/// Sent through an `EventQueue<DebugOverlayToggled>` by the GameLoop when the DebugOverlayKey is
+/// pressed. The Game opens or closes its DebugOverlay when it sees one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DebugOverlayToggled;
+
+/// Font the DebugOverlay is written with. The overlay isn't opened until the World holds one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DebugOverlayFont {
+    pub name: String,
+    pub size: f32
+}
+
+/// Whether the DebugOverlayKey was pressed this frame.
+pub fn toggle_requested(ecs: &World) -> bool {
+    matches!(ecs.try_fetch::<EventQueue<DebugOverlayToggled>>(), Some(events) if !events.is_empty())
+}
+
+/// Writes the frame rate, entity count, TextureDict usage and load times in the top left corner of
+/// the window. The text is laid out with a font from the FontAtlasDict, so nothing is drawn until
+/// `font_name` is loaded into it.
+///
+/// The overlay isn't a scene on the SceneStack. The Game updates it after the scenes and draws it
+/// on top of them, so the game keeps running while it is open and the numbers are the game's own.
+/// It isn't drawn over a render graph that swaps the buffers itself.
+pub struct DebugOverlay {
+    font_name: String,
+    font_size: f32,
+    text: String,
+    /// Frames and time since the text was last rewritten.
+    frames: u32,
+    elapsed: Duration,
+    /// Set when the glyph quads have to be rebuilt from `text`.
+    dirty: bool,
+    render_state: RenderState,
+    /// Built on the first draw, when there is a context.
+    shader: Option<Program<TextSemantics, (), TextShaderUniform>>,
+    tess: Option<Tess<TextVertex>>
+}
+
+impl Debug for DebugOverlay {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DebugOverlay")
+            .field("font_name", &self.font_name)
+            .field("font_size", &self.font_size)
+            .field("text", &self.text)
+            .finish()
+    }
+}
+
+impl DebugOverlay {
+    pub fn new(font_name: String, font_size: f32) -> Self {
+        Self {
+            font_name,
+            font_size,
+            text: String::new(),
+            frames: 0,
+            elapsed: Duration::default(),
+            dirty: false,
+            render_state: default_sprite_render_state(),
+            shader: None,
+            tess: None
+        }
+    }
+
+    /// Rewrites the text every `REFRESH_INTERVAL` with the average frame time since the last rewrite.
+    pub fn update(&mut self, ecs: &World, delta: Duration) {
+        self.frames += 1;
+        self.elapsed += delta;
+
+        if self.elapsed >= REFRESH_INTERVAL || self.text.is_empty() {
+            self.text = overlay_text(ecs, self.elapsed / self.frames);
+            self.frames = 0;
+            self.elapsed = Duration::default();
+            self.dirty = true;
+        }
+    }
+
+    /// Draws the text over whatever is in the back buffer.
+    #[cfg_attr(feature = "trace", instrument(skip(ecs, context)))]
+    pub fn draw(&mut self, ecs: &World, context: &mut GL33Context) -> Result<(), DebugOverlayError> {
+        self.prepare(ecs, context)?;
+
+        let (shader, tess) = match (self.shader.as_mut(), self.tess.as_ref()) {
+            (Some(shader), Some(tess)) => (shader, tess),
+            _ => return Ok(())
+        };
+
+        let font_atlas_dict = ecs.fetch::<FontAtlasDict>();
+        let atlas = match font_atlas_dict.get(&self.font_name) {
+            Some(atlas) => atlas,
+            None => return Ok(())
+        };
+        let mut texture_dict = ecs.fetch_mut::<TextureDict>();
+        let texture = match texture_dict.get_mut(&atlas.texture_handle) {
+            Some(texture) => texture,
+            None => return Ok(())
+        };
+
+        let back_buffer = context.back_buffer()
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to get back buffer.");
+
+                BackBufferError {
+                    source: e
+                }
+            })?;
+        let [width, height] = back_buffer.size();
+        let (width, height) = (width as f32, height as f32);
+
+        // Pixel coordinates with the origin in the bottom left corner of the window.
+        let projection = Mat4::orthographic_rh_gl(0.0, width, 0.0, height, -1.0, 1.0);
+        let scale = self.font_size / atlas.font_size;
+        let baseline = Vec3::new(MARGIN, height - MARGIN - self.font_size, 0.0);
+        let model = Mat4::from_scale_rotation_translation(Vec3::new(scale, scale, 1.0), Quat::IDENTITY, baseline);
+        let render_state = &self.render_state;
+        let texture_handle = atlas.texture_handle.clone();
+
+        // The scenes beneath have already been drawn, so the back buffer isn't cleared.
+        context.new_pipeline_gate().pipeline(&back_buffer, &PipelineState::default().enable_clear_color(false), |pipeline, mut shd_gate| {
+            let bound_tex = pipeline.bind_texture(texture)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to bind font atlas to pipeline.");
+
+                    FailedToBind {
+                        texture: texture_handle,
+                        source: e
+                    }
+                })?;
+
+            shd_gate.shade(shader, |mut iface, uni, mut rdr_gate| {
+                iface.set(&uni.projection, projection.to_cols_array_2d());
+                iface.set(&uni.view, Mat4::IDENTITY.to_cols_array_2d());
+                iface.set(&uni.model, model.to_cols_array_2d());
+                iface.set(&uni.tex, bound_tex.binding());
+                iface.set(&uni.color, TEXT_COLOR);
+
+                rdr_gate.render(render_state, |mut tess_gate| {
+                    tess_gate.render(tess)
+                        .map_err(|e| {
+                            #[cfg(feature = "trace")]
+                            error!("Failed to call render on tess gate.");
+
+                            TessRenderError {
+                                source: e
+                            }
+                        })
+                })
+                    .map_err(|e| {
+                        #[cfg(feature = "trace")]
+                        error!("Failed to call render on render gate.");
+
+                        RenderGateError {
+                            source: Box::new(e)
+                        }
+                    })
+            })
+        }).into_result()
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to draw the debug overlay.");
+
+                RenderError {
+                    source: e
+                }
+            })?;
+
+        Ok(())
+    }
+
+    /// Builds the shader on the first call and the glyph quads whenever the text changed.
+    fn prepare(&mut self, ecs: &World, context: &mut GL33Context) -> Result<(), DebugOverlayError> {
+        if self.shader.is_none() {
+            let shader = context
+                .new_shader_program()
+                .from_strings(TEXT_VS, None, None, TEXT_FS)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to build debug overlay shader program.");
+
+                    ShaderProgramBuildError {
+                        source: e
+                    }
+                })?
+                .ignore_warnings();
+
+            self.shader = Some(shader);
+        }
+
+        if !self.dirty {
+            return Ok(())
+        }
+
+        let font_atlas_dict = ecs.try_fetch::<FontAtlasDict>();
+        let atlas = match font_atlas_dict.as_ref().and_then(|font_atlas_dict| font_atlas_dict.get(&self.font_name)) {
+            Some(atlas) => atlas,
+            None => {
+                #[cfg(feature = "trace")]
+                debug!("No font atlas found for the debug overlay. Font: {:?}", self.font_name.clone());
+
+                return Ok(())
+            }
+        };
+
+        let text = WorldText {
+            content: self.text.clone(),
+            font_handle: self.font_name.clone(),
+            size: self.font_size,
+            color: TEXT_COLOR,
+            alignment: TextAlignment::Left
+        };
+
+        let vertices = layout_text(&text, atlas);
+        self.tess = if vertices.is_empty() {
+            None
+        } else {
+            Some(
+                context
+                    .new_tess()
+                    .set_vertices(vertices)
+                    .set_mode(Mode::Triangle)
+                    .build()
+                    .map_err(|e| {
+                        #[cfg(feature = "trace")]
+                        error!("Failed to build tess for the debug overlay.");
+
+                        TessBuildError {
+                            source: e
+                        }
+                    })?
+            )
+        };
+        self.dirty = false;
+
+        Ok(())
+    }
+}
+
+/// Lines of the overlay for a frame time of `frame_time`.
+fn overlay_text(ecs: &World, frame_time: Duration) -> String {
+    let frame_ms = frame_time.as_secs_f32() * 1000.0;
+    let fps = if frame_ms > 0.0 { 1000.0 / frame_ms } else { 0.0 };
+
+    let mut lines = vec![
+        format!("FPS: {:.0} ({:.1} ms)", fps, frame_ms),
+        format!("Entities: {}", ecs.entities().join().count())
+    ];
+
+    if let Some(texture_dict) = ecs.try_fetch::<TextureDict>() {
+        let stats = texture_dict.stats();
+        lines.push(format!("Textures: {} (hits: {}, misses: {})", texture_dict.keys().count(), stats.cache_hits, stats.cache_misses));
+    }
+
+    if let Some(timing) = ecs.try_fetch::<LoadTiming>() {
+        lines.push(format!("Last scene load: {} ms", timing.last_scene_load_ms));
+        lines.push(format!("Slowest entity load: {} ms ({})", timing.slowest_entity_load_ms, timing.slowest_entity_path));
+    }
+
+    if let Some(resolution) = ecs.try_fetch::<DynamicResolution>() {
+        lines.push(format!("Resolution scale: {:.2}", resolution.current_scale));
+    }
+
+    lines.join("\n")
+}
+
+#[derive(Error, Debug)]
+pub enum DebugOverlayError {
+    #[error("Failed to build the debug overlay shader program")]
+    ShaderProgramBuildError {
+        source: luminance_front::shader::ProgramError
+    },
+
+    #[error("Failed to build the debug overlay text tess")]
+    TessBuildError {
+        source: luminance_front::tess::TessError
+    },
+
+    #[error("Failed to get the back buffer")]
+    BackBufferError {
+        source: luminance_front::framebuffer::FramebufferError
+    },
+
+    #[error("Failed to draw the debug overlay text")]
+    RenderError {
+        source: SpriteRenderError
+    }
+}
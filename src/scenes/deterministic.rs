@@ -0,0 +1,34 @@
+use specs::{Entities, Entity, Join, World, WorldExt};
+
+#[cfg(feature = "trace")]
+use tracing::{debug, instrument};
+
+/// Inserting this resource into the World makes the SceneStack refresh its list of entities, sorted by
+/// `Entity::id`, before every update. Scenes that need a reproducible order iterate `entities()` and
+/// read components through `Storage::get` instead of calling `join()`.
+#[derive(Debug, Default, Clone)]
+pub struct DeterministicUpdate {
+    entities: Vec<Entity>
+}
+
+impl DeterministicUpdate {
+    pub fn entities(&self) -> &[Entity] {
+        &self.entities
+    }
+}
+
+/// Refreshes the sorted entity list if the World contains a DeterministicUpdate resource.
+#[cfg_attr(feature = "trace", instrument(skip(ecs)))]
+pub fn sort_entities(ecs: &mut World) {
+    if !ecs.has_value::<DeterministicUpdate>() {
+        return
+    }
+
+    let mut sorted: Vec<Entity> = (&ecs.system_data::<Entities>()).join().collect();
+    sorted.sort_by_key(|entity| entity.id());
+
+    #[cfg(feature = "trace")]
+    debug!("Sorted {:?} entities for deterministic update", sorted.len());
+
+    ecs.fetch_mut::<DeterministicUpdate>().entities = sorted;
+}
@@ -0,0 +1,118 @@
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::time::Duration;
+
+use anyhow::Result;
+use luminance_glfw::GL33Context;
+use specs::{World, WorldExt};
+
+#[cfg(feature = "trace")]
+use tracing::{debug, instrument};
+
+use crate::components::register_all_components;
+use crate::input::Input;
+use crate::scenes::{DrawMode, Scene, draw_scene};
+use crate::scenes::scene_stack::SceneTransition;
+
+/// Which World a scene's update, interact and draw calls receive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SceneIsolationMode {
+    /// The World shared by every scene on the stack.
+    #[default]
+    Shared,
+    /// A World owned by the scene, so its systems can't see other scenes' entities.
+    Isolated
+}
+
+/// Runs `S` against a World of its own instead of the shared one. The World starts with every
+/// component submitted through `register_component!` registered and no resources, so the scene
+/// inserts whatever its systems and renderers fetch, such as the TextureDict and camera.
+///
+/// Scene transitions are still applied to the shared World by the SceneStack.
+pub struct IsolatedScene<T: Input + Debug, S: Scene<T>> {
+    scene: S,
+    // Scene::is_finished passes a mutable World from behind a shared reference.
+    world: RefCell<World>,
+    input: PhantomData<T>
+}
+
+impl<T: Input + Debug, S: Scene<T>> Debug for IsolatedScene<T, S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IsolatedScene")
+            .field("scene", &self.scene)
+            .finish()
+    }
+}
+
+impl<T: Input + Debug, S: Scene<T>> IsolatedScene<T, S> {
+    #[cfg_attr(feature = "trace", instrument(skip(scene)))]
+    pub fn new(scene: S) -> Self {
+        let mut world = World::new();
+        register_all_components(&mut world);
+
+        #[cfg(feature = "trace")]
+        debug!("Created isolated World for scene: {:?}", scene.get_name());
+
+        Self::with_world(scene, world)
+    }
+
+    /// Uses `world` as the scene's World, for example one already filled with its entities.
+    pub fn with_world(scene: S, world: World) -> Self {
+        Self {
+            scene,
+            world: RefCell::new(world),
+            input: PhantomData
+        }
+    }
+
+    pub fn world_mut(&mut self) -> &mut World {
+        self.world.get_mut()
+    }
+}
+
+impl<T: Input + Debug, S: Scene<T>> Scene<T> for IsolatedScene<T, S> {
+    fn update(&mut self, _ecs: &mut World, delta: Duration) -> Result<SceneTransition<T>> {
+        let world = self.world.get_mut();
+        let transition = self.scene.update(world, delta)?;
+        world.maintain();
+
+        Ok(transition)
+    }
+
+    fn draw(&mut self, _ecs: &mut World, context: &mut GL33Context) -> Result<()> {
+        draw_scene(&mut self.scene, self.world.get_mut(), context)
+    }
+
+    fn interact(&mut self, _ecs: &mut World, input: &T) -> Result<()> {
+        self.scene.interact(self.world.get_mut(), input)
+    }
+
+    fn get_name(&self) -> String {
+        self.scene.get_name()
+    }
+
+    fn is_finished(&self, _ecs: &mut World) -> Result<bool> {
+        self.scene.is_finished(&mut self.world.borrow_mut())
+    }
+
+    fn draw_mode(&self) -> DrawMode {
+        self.scene.draw_mode()
+    }
+
+    fn isolation_mode(&self) -> SceneIsolationMode {
+        SceneIsolationMode::Isolated
+    }
+
+    fn on_exit(&mut self, _ecs: &mut World) -> Result<()> {
+        self.scene.on_exit(self.world.get_mut())
+    }
+
+    fn pause(&mut self, _ecs: &mut World) -> Result<()> {
+        self.scene.pause(self.world.get_mut())
+    }
+
+    fn resume(&mut self, _ecs: &mut World) -> Result<()> {
+        self.scene.resume(self.world.get_mut())
+    }
+}
@@ -5,6 +5,7 @@ use crate::scenes::scene_stack::SceneTransition;
 use specs::{World};
 
 use std::fmt::Debug;
+use std::time::Duration;
 use std::sync::{Arc, RwLock};
 
 use anyhow::Result;
@@ -13,26 +14,96 @@ use serde::Deserialize;
 use serde_json::Value;
 use crate::input::Input;
 use crate::loading::DrawTask;
+use crate::scenes::isolation::SceneIsolationMode;
+use crate::graphics::render::RenderPipeline;
+use crate::graphics::render_graph::RenderGraph;
 use luminance_glfw::GL33Context;
 
 pub mod scene_stack;
+pub mod registry;
+pub mod cutscene;
+pub mod pause;
+pub mod isolation;
+pub mod transition;
+#[cfg(feature = "deterministic")]
+pub mod deterministic;
+#[cfg(feature = "debug_overlay")]
+pub mod debug_overlay;
 
 pub const SCENES_DIR: &str = "scenes/";
 pub const SCENE_LOADER_FILE_ID: &str = "scene_loader";
 
 #[derive(Deserialize, Debug)]
+#[cfg_attr(feature = "schema_gen", derive(schemars::JsonSchema))]
 pub struct SceneLoaderJSON {
     pub entity_paths: Vec<String>,
     pub scene_values: Value
 }
 
+/// How a scene is drawn relative to the scenes beneath it on the SceneStack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrawMode {
+    /// Only this scene and the scenes above it are drawn.
+    Exclusive,
+    /// The scenes beneath this one are drawn first so this scene can overlay them.
+    Transparent,
+}
+
 pub trait Scene<T: Input + Debug>: Debug {
     // Instance Methods
-    fn update(&mut self, ecs: &mut World) -> Result<SceneTransition<T>>;
+    /// `delta` is the time since the previous update, capped at the GameLoop's `max_delta`.
+    fn update(&mut self, ecs: &mut World, delta: Duration) -> Result<SceneTransition<T>>;
     fn draw(&mut self, ecs: &mut World, context: &mut GL33Context) -> Result<()>;
     fn interact(&mut self, ecs: &mut World, input: &T) -> Result<()>;
     fn get_name(&self) -> String;
     fn is_finished(&self, ecs: &mut World) -> Result<bool>;
+
+    fn draw_mode(&self) -> DrawMode {
+        DrawMode::Exclusive
+    }
+
+    /// Wrap a scene in an `IsolatedScene` to give it a World of its own.
+    fn isolation_mode(&self) -> SceneIsolationMode {
+        SceneIsolationMode::Shared
+    }
+
+    /// The scene's own rendering pipeline, usually built in its `SceneLoader::load_scene`. When
+    /// there is one, the SceneStack draws the scene with it instead of calling `draw`.
+    fn get_renderer(&mut self) -> Option<&mut dyn RenderPipeline> {
+        None
+    }
+
+    /// Called right before the SceneStack drops the scene after a POP, REPLACE, CLEAR or UNPAUSE,
+    /// so it can clean up its entities and resources.
+    fn on_exit(&mut self, _ecs: &mut World) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called when an overlay pushed with PUSH_PAUSE covers the scene, during the update that
+    /// pushed it.
+    fn pause(&mut self, _ecs: &mut World) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called when a POP or UNPAUSE leaves the scene on top of the stack again.
+    fn resume(&mut self, _ecs: &mut World) -> Result<()> {
+        Ok(())
+    }
+
+    /// Adds the scene's own passes to the SceneStack's render graph, e.g. a minimap node that
+    /// depends on `sprites`. Called for every scene in the stack each time the stack changes, after
+    /// the nodes added last time have been removed.
+    fn extend_render_graph(&mut self, _render_graph: &mut RenderGraph) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Draws `scene` with its renderer from `Scene::get_renderer`, or with `Scene::draw` if it has none.
+pub fn draw_scene<T: Input + Debug, S: Scene<T> + ?Sized>(scene: &mut S, ecs: &mut World, context: &mut GL33Context) -> Result<()> {
+    match scene.get_renderer() {
+        Some(renderer) => renderer.render_frame(ecs, context),
+        None => scene.draw(ecs, context)
+    }
 }
 
 pub trait SceneLoader<T: Input + Debug>: Debug {
@@ -0,0 +1,20 @@
+use specs::World;
+
+/// While set, the SceneStack only updates the pause menu pushed by `SceneTransition::PAUSE` and
+/// any scenes pushed on top of it. The scenes beneath are still drawn and the World is still
+/// maintained every frame, so the frozen game shows behind the menu.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PauseState(pub bool);
+
+/// Whether music should stop while the game is paused. Without this resource audio keeps playing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PauseAudioWithGame(pub bool);
+
+pub fn is_paused(ecs: &World) -> bool {
+    matches!(ecs.try_fetch::<PauseState>().as_deref(), Some(PauseState(true)))
+}
+
+/// The PlaylistSystem stops advancing the Playlist while this is true.
+pub fn is_audio_paused(ecs: &World) -> bool {
+    is_paused(ecs) && matches!(ecs.try_fetch::<PauseAudioWithGame>().as_deref(), Some(PauseAudioWithGame(true)))
+}
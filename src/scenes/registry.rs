@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+
+use anyhow::Result;
+use thiserror::Error;
+
+#[cfg(feature="trace")]
+use tracing::{instrument, error, debug};
+
+use crate::input::Input;
+use crate::load::JSONLoad;
+use crate::scenes::SceneLoader;
+use crate::scenes::registry::SceneRegistryError::{UnknownSceneType, SceneFactoryError};
+
+type SceneFactory<T> = Box<dyn Fn(JSONLoad) -> Result<Box<dyn SceneLoader<T>>> + Send + Sync>;
+
+/// Maps the `load_type_id` of a scene file to the function that turns the file into a SceneLoader.
+/// `Game` fills it through `GameWrapper::register_scenes` and inserts it into the World as an
+/// `Arc<SceneRegistry<T>>` before `GameWrapper::load` runs.
+pub struct SceneRegistry<T: Input + Debug> {
+    factories: HashMap<String, SceneFactory<T>>
+}
+
+impl<T: Input + Debug> Default for SceneRegistry<T> {
+    fn default() -> Self {
+        Self {
+            factories: HashMap::new()
+        }
+    }
+}
+
+impl<T: Input + Debug> Debug for SceneRegistry<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SceneRegistry")
+            .field("type_ids", &self.factories.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl<T: 'static + Input + Debug> SceneRegistry<T> {
+    /// Replaces any factory already registered for `type_id`.
+    pub fn register<L: SceneLoader<T> + 'static>(&mut self, type_id: &str, factory: impl Fn(JSONLoad) -> Result<L> + Send + Sync + 'static) {
+        #[cfg(feature = "trace")]
+        debug!("Registering scene type: {:?}", type_id);
+
+        self.factories.insert(
+            type_id.to_string(),
+            Box::new(move |json| Ok(Box::new(factory(json)?) as Box<dyn SceneLoader<T>>))
+        );
+    }
+
+    #[cfg_attr(feature="trace", instrument(skip(self)))]
+    pub fn create(&self, json: JSONLoad) -> Result<Box<dyn SceneLoader<T>>, SceneRegistryError> {
+        let factory = self.factories.get(&json.load_type_id)
+            .ok_or_else(|| {
+                #[cfg(feature = "trace")]
+                error!("No scene type registered for: {:?}", json.load_type_id.clone());
+
+                UnknownSceneType { type_id: json.load_type_id.clone() }
+            })?;
+
+        factory(json.clone())
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Scene factory failed for JSON value: {:?}", json.clone());
+
+                SceneFactoryError {
+                    type_id: json.load_type_id.clone(),
+                    source: e
+                }
+            })
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum SceneRegistryError {
+    #[error("No scene type registered for load type ID: {type_id}")]
+    UnknownSceneType {
+        type_id: String
+    },
+
+    #[error("Scene factory for: {type_id} failed")]
+    SceneFactoryError {
+        type_id: String,
+        source: anyhow::Error
+    }
+}
@@ -1,12 +1,14 @@
-use crate::scenes::{Scene, SceneLoader};
-use crate::scenes::scene_stack::SceneStackError::{SceneStackEmptyError, SceneStackPopError, SceneStackSwapError, SceneStackReplaceError, SceneStackClearError, SceneStackUpdateError, SceneStackDrawError, SceneStackInteractError, SceneStackIsFinishedError, SceneStackDeserializationError, SceneStackFactoryError};
+use crate::scenes::{Scene, SceneLoader, DrawMode, draw_scene};
+use crate::scenes::scene_stack::SceneStackError::{SceneStackEmptyError, SceneStackPopError, SceneStackSwapError, SceneStackReplaceError, SceneStackClearError, SceneStackUpdateError, SceneStackDrawError, SceneStackInteractError, SceneStackIsFinishedError, SceneStackDeserializationError, SceneStackFactoryError, OnExitError, SceneStackPauseError, SceneStackResumeError, ExtendRenderGraphError, SceneRenderGraphError, SceneOverlayDrawError, ScenePreloadError};
 use crate::load::{load_json, JSONLoad, LoadError, load_deserializable_from_file};
 
 use specs::World;
 
 use std::marker::PhantomData;
 use std::sync::{Arc, RwLock};
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
 use std::cmp::{min, max};
+use std::ops::Range;
 use std::fmt::Debug;
 
 use serde_json::{Value};
@@ -17,28 +19,132 @@ use anyhow::Result;
 
 #[cfg(feature="trace")]
 use tracing::{instrument, trace, error, debug};
+#[cfg(feature="trace")]
+use crate::load::display_chain;
 
 use crate::input::Input;
-use crate::loading::DrawTask;
+use crate::globals::accessibility::AccessibilityConfig;
+#[cfg(feature = "deterministic")]
+use crate::scenes::deterministic::sort_entities;
+use crate::loading::{DrawTask, Pool, TaskError};
 use luminance_glfw::GL33Context;
-use crate::scenes::scene_stack::SceneStackLoaderError::{JSONDeserializeFromFileError, JSONLoadFromFileError, SceneFactoryError, SceneLoadError};
+use crate::loading::timing::{record_scene_load, LoadTimingError};
+use crate::scenes::registry::SceneRegistry;
+use crate::scenes::pause::{PauseState, is_paused};
+use crate::game_loop::FullscreenRequest;
+use crate::graphics::color_tint::ColorTint;
+use crate::graphics::render_graph::{RenderGraph, RenderGraphError};
+use crate::scenes::transition::{TransitionState, TransitionPhase};
+use crate::scenes::scene_stack::SceneStackLoaderError::{JSONDeserializeFromFileError, JSONLoadFromFileError, SceneFactoryError, SceneLoadError, SceneLoadTimeoutError, SceneLoadTimingError, MultipleLoadErrors, ScenePreparePanicked};
+use std::time::{Duration, Instant};
+use specs::rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use crate::globals::texture_dict::{self, TextureDict, SceneTextureScope};
 
 pub const SCENE_STACK_FILE_ID: &str = "scene_stack";
+/// Name preloaded scenes are given in load errors and timings, which are otherwise named by file.
+const PRELOADED_SCENE: &str = "preloaded scene";
 
 #[derive(Debug)]
 pub enum SceneTransition<T: Input + Debug> {
     POP(usize),
     PUSH(Box<dyn Scene<T>>),
+    /// Fades the current top scene out, pushes the scene and fades it in, taking the given time in
+    /// total. No scene is updated during the fade.
+    #[allow(non_camel_case_types)]
+    PUSH_WITH_FADE(Box<dyn Scene<T>>, Duration),
     SWAP(usize, usize),
     REPLACE(usize, Box<dyn Scene<T>>),
     CLEAR,
+    /// Sets `AccessibilityConfig::font_scale`, inserting the config if the World does not have one yet.
+    #[allow(non_camel_case_types)]
+    SET_FONT_SCALE(f32),
+    /// Pushes the scene as an overlay and pauses the scene beneath it until the overlay is removed.
+    /// Unlike PAUSE, the World's PauseState is left alone. Only the top scene is updated and sent
+    /// input, and the paused scene is drawn beneath the overlay whatever the overlay's DrawMode.
+    #[allow(non_camel_case_types)]
+    PUSH_PAUSE(Box<dyn Scene<T>>),
+    /// Sets `PauseState(true)` and pushes the given pause menu. A PAUSE while a pause menu is open
+    /// pushes another menu on top of it, and each UNPAUSE closes one.
+    PAUSE(Box<dyn Scene<T>>),
+    /// Pops the topmost pause menu along with any scenes pushed on top of it, and sets
+    /// `PauseState(false)` once no pause menu is left. Does nothing when no pause menu from PAUSE
+    /// is open.
+    UNPAUSE,
+    /// Switches the primary window to fullscreen (`true`) or windowed (`false`) through the
+    /// World's FullscreenRequest, which the GameLoop applies after the update.
+    #[allow(non_camel_case_types)]
+    SET_FULLSCREEN(bool),
+    /// Loads a scene in the background while the current scene keeps running.
+    /// `SceneLoader::load_scene` is called on the World's Pool. The DrawTask it returns uses the
+    /// OpenGL context, so it runs on the draw thread after an update. The scene is pushed by
+    /// COMMIT_PRELOAD. Replaces a preloaded scene that was never committed.
+    PRELOAD(Box<dyn SceneLoader<T> + Send>),
+    /// Pushes the scene from PRELOAD, or pushes it as soon as it has loaded if it hasn't yet.
+    #[allow(non_camel_case_types)]
+    COMMIT_PRELOAD,
     NONE,
 }
 
-#[derive(Debug, Clone)]
+/// Scene from `SceneTransition::PRELOAD`, waiting to be loaded or pushed.
+#[derive(Debug)]
+pub struct PendingScene<T: Input + Debug> {
+    state: PreloadState<T>,
+    /// Whether COMMIT_PRELOAD came before the scene finished loading.
+    commit_requested: bool
+}
+
+#[derive(Debug)]
+enum PreloadState<T: Input + Debug> {
+    /// The loader is creating the scene's DrawTask on the World's Pool.
+    Preparing(Receiver<DrawTask<Box<dyn Scene<T>>>>),
+    Loaded(Box<dyn Scene<T>>, SceneTextureScope),
+    /// Kept until COMMIT_PRELOAD or `SceneStack::take_preload_error` reports it.
+    Failed(SceneStackError)
+}
+
+impl<T: Input + Debug> PendingScene<T> {
+    /// Starts `scene_loader` on `pool`, or on rayon's global pool when the World has no Pool.
+    pub fn new(scene_loader: Box<dyn SceneLoader<T> + Send>, pool: Option<&Pool>) -> Self where T: 'static {
+        let (sender, receiver) = channel();
+        let prepare = move || {
+            // The receiver is gone if another PRELOAD replaced this one.
+            let _ = sender.send(scene_loader.load_scene());
+        };
+
+        match pool {
+            Some(pool) => pool.spawn(prepare),
+            None => specs::rayon::spawn(prepare)
+        }
+
+        Self {
+            state: PreloadState::Preparing(receiver),
+            commit_requested: false
+        }
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        matches!(self.state, PreloadState::Loaded(..))
+    }
+
+    pub fn is_failed(&self) -> bool {
+        matches!(self.state, PreloadState::Failed(_))
+    }
+}
+
+type SceneFactory<T> = Arc<dyn Fn(JSONLoad) -> Result<Box<dyn SceneLoader<T>>> + Send + Sync>;
+
+#[derive(Clone)]
 pub struct SceneStackLoader<T: Input + Debug> {
     scene_stack_file: String,
-    scene_factory: fn(JSONLoad) -> Result<Box<dyn SceneLoader<T>>>
+    scene_factory: SceneFactory<T>
+}
+
+impl<T: Input + Debug> Debug for SceneStackLoader<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SceneStackLoader")
+            .field("scene_stack_file", &self.scene_stack_file)
+            .finish()
+    }
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -51,23 +157,32 @@ impl<T: 'static + Input + Debug> SceneStackLoader<T> {
     pub fn new(file_path: String, scene_factory: fn(JSONLoad) -> Result<Box<dyn SceneLoader<T>>>) -> Self {
         let new = Self {
             scene_stack_file: file_path,
-            scene_factory
+            scene_factory: Arc::new(scene_factory)
         };
 
         return new
     }
 
+    /// Creates each scene's loader with the SceneLoader registered for the scene file's load type ID.
+    #[cfg_attr(feature="trace", instrument(skip(registry)))]
+    pub fn from_registry(file_path: String, registry: Arc<SceneRegistry<T>>) -> Self {
+        Self {
+            scene_stack_file: file_path,
+            scene_factory: Arc::new(move |json| Ok(registry.create(json)?))
+        }
+    }
+
     #[cfg_attr(feature="trace", instrument(skip(self)))]
     pub fn load(&self) -> DrawTask<SceneStack<T>> {
         // Attempts to not bring self into closure.
         let path = self.scene_stack_file.clone();
-        let scene_factory = self.scene_factory;
+        let scene_factory = self.scene_factory.clone();
 
         let task = DrawTask::new(move |(ecs, context)| {
             let scene_stack_json: SceneStackLoaderJSON = load_deserializable_from_file(&path, SCENE_STACK_FILE_ID)
                 .map_err(|e| {
                     #[cfg(feature = "trace")]
-                    error!("Failed to deserialize JSON file: ({:?}) into Scene Stack JSON object", path.clone());
+                    error!("Failed to deserialize JSON file: ({:?}) into Scene Stack JSON object. Error: {}", path.clone(), display_chain(&*e));
 
                     JSONDeserializeFromFileError {
                         source: e,
@@ -76,83 +191,464 @@ impl<T: 'static + Input + Debug> SceneStackLoader<T> {
                 })?;
 
             let mut scene_vec = Vec::new();
+            let mut texture_scopes = Vec::new();
             #[cfg(feature = "trace")]
             debug!("SceneStack json deserialized: ({:?}). Loading scenes", scene_stack_json.clone());
 
-            for scene_path in scene_stack_json.scene_paths {
-                #[cfg(feature = "trace")]
-                debug!("Loading Scene: {:?}", scene_path);
-
-                let scene_value = load_json(&scene_path)
-                    .map_err(|e| {
+            // Scene files are read and parsed in parallel. Building the scenes uses the OpenGL
+            // context, so that stays on the draw thread. Their entities are created through
+            // LazyUpdate and added when the World is next maintained.
+            let scene_values: Vec<_> = scene_stack_json.scene_paths
+                .par_iter()
+                .map(|scene_path| read_scene_json(scene_path))
+                .collect();
+
+            let mut errors = Vec::new();
+
+            // Every scene is attempted so all of the broken scene files are reported at once.
+            for (index, (scene_path, scene_value)) in scene_stack_json.scene_paths.iter().zip(scene_values).enumerate() {
+                let scene = scene_value
+                    .and_then(|scene_value| load_scene(scene_path, scene_value, &scene_factory, &ecs, &context));
+
+                match scene {
+                    Ok((scene, texture_scope)) => {
+                        scene_vec.push(scene);
+                        texture_scopes.push(texture_scope);
+                    },
+                    Err(e) => {
                         #[cfg(feature = "trace")]
-                        debug!("Failed to create JSONLoad object from scene file: {:?}", scene_path);
+                        error!("Failed to load scene: {:?} at index: {:?}. Error: {}", scene_path, index, display_chain(&e));
 
-                        JSONLoadFromFileError {
-                            source: e,
-                            path: scene_path.clone()
-                        }
-                    })?;
+                        errors.push((index, e))
+                    }
+                }
+            }
 
-                let scene_loader = (scene_factory)(scene_value.clone())
-                    .map_err(|e| {
-                        #[cfg(feature = "trace")]
-                        error!("An error occurred while passing the JSON value: ({:?}) for a scene to the scene_factory", scene_value);
+            if errors.len() == 1 {
+                return Err(errors.remove(0).1.into())
+            } else if !errors.is_empty() {
+                return Err(MultipleLoadErrors { errors }.into())
+            }
 
-                        SceneFactoryError {
-                            source: e,
-                            scene_json: scene_value.clone()
-                        }
-                    })?;
+            #[cfg(feature = "trace")]
+            debug!("Returning SceneStack from Task");
 
-                let scene = scene_loader.load_scene()
-                    .execute((ecs.clone(), context.clone()))
-                    .map_err(|e| {
-                        #[cfg(feature = "trace")]
-                        error!("An error occurred while loading the scene: ({:?})", e);
+            let mut scene_stack = SceneStack::new(scene_vec);
+            for (index, texture_scope) in texture_scopes.into_iter().enumerate() {
+                scene_stack.track_textures(index, texture_scope);
+            }
 
-                        SceneLoadError {
-                            source: e
-                        }
-                    })?;
+            Ok(scene_stack)
+        });
 
+        return task;
+    }
+}
 
-                #[cfg(feature = "trace")]
-                debug!("Scene loaded: {:?}", scene.get_name());
+/// Reads the scene file at `scene_path`. Safe to call from any thread.
+fn read_scene_json(scene_path: &str) -> Result<JSONLoad, SceneStackLoaderError> {
+    #[cfg(feature = "trace")]
+    debug!("Reading Scene: {:?}", scene_path);
+
+    load_json(scene_path)
+        .map_err(|e| {
+            #[cfg(feature = "trace")]
+            debug!("Failed to create JSONLoad object from scene file: {:?}", scene_path);
 
-                scene_vec.push(scene);
+            JSONLoadFromFileError {
+                source: e,
+                path: scene_path.to_string()
             }
+        })
+}
 
+/// Loads the scene read from `scene_path` with the loader the factory picks for it. The scope
+/// holds the textures the scene added to the TextureDict.
+fn load_scene<T: 'static + Input + Debug>(
+    scene_path: &str,
+    scene_value: JSONLoad,
+    scene_factory: &SceneFactory<T>,
+    ecs: &Arc<RwLock<World>>,
+    context: &Arc<RwLock<GL33Context>>
+) -> Result<(Box<dyn Scene<T>>, SceneTextureScope), SceneStackLoaderError> {
+    #[cfg(feature = "trace")]
+    debug!("Loading Scene: {:?}", scene_path);
+
+    let scene_loader = (scene_factory)(scene_value.clone())
+        .map_err(|e| {
             #[cfg(feature = "trace")]
-            debug!("Returning SceneStack from Task");
+            error!("An error occurred while passing the JSON value: ({:?}) for a scene to the scene_factory. Error: {}", scene_value, display_chain(&*e));
 
-            Ok(SceneStack {
-                stack: scene_vec,
-                phantom_input: PhantomData::default()
-            })
-        });
+            SceneFactoryError {
+                source: e,
+                scene_json: scene_value.clone()
+            }
+        })?;
 
-        return task;
+    run_scene_task(scene_path, scene_loader.load_scene(), ecs, context)
+}
+
+/// Runs the DrawTask from `SceneLoader::load_scene`. `scene_path` names the scene in errors and
+/// load timings. The scope holds the textures the scene added to the TextureDict.
+fn run_scene_task<T: 'static + Input + Debug>(
+    scene_path: &str,
+    task: DrawTask<Box<dyn Scene<T>>>,
+    ecs: &Arc<RwLock<World>>,
+    context: &Arc<RwLock<GL33Context>>
+) -> Result<(Box<dyn Scene<T>>, SceneTextureScope), SceneStackLoaderError> {
+    let load_start = Instant::now();
+    texture_dict::begin_scope(ecs);
+    let scene = task.execute((ecs.clone(), context.clone()));
+    // Dropped on failure, which releases the textures the scene got to.
+    let texture_scope = texture_dict::end_scope(ecs);
+
+    let scene = scene
+        .map_err(|e| {
+            #[cfg(feature = "trace")]
+            error!("An error occurred while loading the scene. Error: {}", display_chain(&*e));
+
+            let timeout = e.chain()
+                .find_map(|cause| match cause.downcast_ref::<TaskError>() {
+                    Some(TaskError::TimeoutError { timeout }) => Some(*timeout),
+                    _ => None
+                });
+
+            match timeout {
+                Some(timeout) => SceneLoadTimeoutError {
+                    scene_path: scene_path.to_string(),
+                    timeout,
+                    source: e
+                },
+                None => SceneLoadError {
+                    source: e
+                }
+            }
+        })?;
+
+    record_scene_load(ecs, scene_path, load_start.elapsed())
+        .map_err(|e| SceneLoadTimingError { source: e })?;
+
+    #[cfg(feature = "trace")]
+    debug!("Scene loaded: {:?}. Textures used: {:?}", scene.get_name(), texture_scope.handles());
+
+    Ok((scene, texture_scope))
+}
+
+/// How a scene came to be in the SceneStack. The role moves with the scene through SWAP and is
+/// dropped with it by REPLACE.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SceneRole {
+    Scene,
+    /// Pushed by `SceneTransition::PUSH_PAUSE`. The scene beneath it is paused.
+    PauseOverlay,
+    /// Pushed by `SceneTransition::PAUSE`. The game stays paused while one is in the stack.
+    PauseMenu
+}
+
+/// A scene in the SceneStack with its role and the textures it loaded. The textures are released
+/// when the StackedScene is dropped, once the scene leaves the stack.
+#[derive(Debug)]
+pub struct StackedScene<T: Input + Debug> {
+    pub scene: Box<dyn Scene<T>>,
+    role: SceneRole,
+    texture_scope: Option<SceneTextureScope>
+}
+
+impl<T: Input + Debug> StackedScene<T> {
+    pub fn new(scene: Box<dyn Scene<T>>) -> Self {
+        Self::with_role(scene, SceneRole::Scene)
+    }
+
+    fn with_role(scene: Box<dyn Scene<T>>, role: SceneRole) -> Self {
+        Self {
+            scene,
+            role,
+            texture_scope: None
+        }
+    }
+
+    pub fn role(&self) -> SceneRole {
+        self.role
     }
 }
 
 #[derive(Debug)]
 pub struct SceneStack<T: Input + Debug> {
-    pub stack: Vec<Box<dyn Scene<T>>>,
+    pub stack: Vec<StackedScene<T>>,
+    /// Fade started by `SceneTransition::PUSH_WITH_FADE`.
+    transition: Option<TransitionState>,
+    /// Scene pushed once the fade out finishes.
+    incoming_scene: Option<Box<dyn Scene<T>>>,
+    /// Draws the frame instead of the scenes' `draw` when set.
+    render_graph: Option<RenderGraph>,
+    /// Nodes the scenes added to the render graph, removed again whenever the stack changes.
+    scene_node_names: Vec<String>,
+    /// Scene from `SceneTransition::PRELOAD` that hasn't been pushed yet.
+    preload: Option<PendingScene<T>>,
     phantom_input: PhantomData<T>
 }
 
 impl<T: Input + Debug> SceneStack<T> {
+    #[cfg_attr(feature="trace", instrument)]
+    pub fn new(stack: Vec<Box<dyn Scene<T>>>) -> Self {
+        Self {
+            stack: stack.into_iter().map(StackedScene::new).collect(),
+            transition: None,
+            incoming_scene: None,
+            render_graph: None,
+            scene_node_names: Vec::new(),
+            preload: None,
+            phantom_input: PhantomData
+        }
+    }
+
+    /// Whether the scene from `SceneTransition::PRELOAD` has finished loading.
+    pub fn preload_complete(&self) -> bool {
+        self.preload.as_ref().is_some_and(PendingScene::is_loaded)
+    }
+
+    /// Why the scene from `SceneTransition::PRELOAD` failed to load, if it did.
+    pub fn preload_error(&self) -> Option<&SceneStackError> {
+        match self.preload.as_ref().map(|pending| &pending.state) {
+            Some(PreloadState::Failed(e)) => Some(e),
+            _ => None
+        }
+    }
+
+    /// Drops the failed scene from `SceneTransition::PRELOAD` and returns why it failed.
+    pub fn take_preload_error(&mut self) -> Option<SceneStackError> {
+        if !self.preload.as_ref().is_some_and(PendingScene::is_failed) {
+            return None
+        }
+
+        match self.preload.take().map(|pending| pending.state) {
+            Some(PreloadState::Failed(e)) => Some(e),
+            _ => None
+        }
+    }
+
+    /// Runs the DrawTask of the scene from `SceneTransition::PRELOAD` once its loader has created
+    /// it, and pushes the scene if COMMIT_PRELOAD already asked for it. The GameLoop calls this
+    /// after each update, while the World is unlocked. Scenes create their GPU resources as they
+    /// load, so this part runs on the draw thread. Entities the scene creates are added the next
+    /// time the World is maintained, which can be before the scene is pushed.
+    ///
+    /// A scene that fails to load is kept as its error, which `preload_error` returns and
+    /// COMMIT_PRELOAD fails the update with. If COMMIT_PRELOAD already came, the error is returned
+    /// here instead.
+    #[cfg_attr(feature="trace", instrument(skip(self, ecs, context)))]
+    pub fn load_preload(&mut self, ecs: Arc<RwLock<World>>, context: Arc<RwLock<GL33Context>>) -> Result<(), SceneStackError>
+        where T: 'static {
+        let pending = match self.preload.as_mut() {
+            Some(pending) => pending,
+            None => return Ok(())
+        };
+
+        let task = match &pending.state {
+            PreloadState::Preparing(receiver) => match receiver.try_recv() {
+                Ok(task) => Ok(task),
+                Err(TryRecvError::Empty) => return Ok(()),
+                Err(TryRecvError::Disconnected) => Err(ScenePreparePanicked)
+            },
+            _ => return Ok(())
+        };
+
+        let scene = task.and_then(|task| run_scene_task(PRELOADED_SCENE, task, &ecs, &context));
+
+        pending.state = match scene {
+            Ok((scene, texture_scope)) => {
+                #[cfg(feature = "trace")]
+                debug!("Preloaded scene: {}", scene.get_name());
+
+                PreloadState::Loaded(scene, texture_scope)
+            },
+            Err(e) => {
+                #[cfg(feature = "trace")]
+                error!("Failed to preload scene. Error: {}", display_chain(&e));
+
+                PreloadState::Failed(ScenePreloadError {
+                    source: e
+                })
+            }
+        };
+
+        if pending.commit_requested {
+            if let Some(e) = self.take_preload_error() {
+                return Err(e)
+            }
+
+            self.push_preload();
+            self.refresh_render_graph()?;
+        }
+
+        Ok(())
+    }
+
+    fn push_preload(&mut self) {
+        if let Some(PendingScene { state: PreloadState::Loaded(scene, texture_scope), .. }) = self.preload.take() {
+            #[cfg(feature="trace")]
+            debug!("Pushed preloaded scene: {}", scene.get_name());
+
+            self.stack.push(StackedScene::new(scene));
+            self.track_textures(self.stack.len() - 1, texture_scope);
+        }
+    }
+
+    /// Releases the textures in `texture_scope` when the scene at `index` leaves the stack. With
+    /// no scene at `index`, the textures are kept.
+    pub fn track_textures(&mut self, index: usize, texture_scope: SceneTextureScope) {
+        match self.stack.get_mut(index) {
+            Some(stacked) => stacked.texture_scope = Some(texture_scope),
+            None => texture_scope.keep()
+        }
+    }
+
+    /// Removes the textures of the scenes that left the stack from the TextureDict. Their scopes
+    /// were dropped with them.
+    fn release_textures(&mut self, ecs: &mut World) {
+        if let Some(mut texture_dict) = ecs.try_fetch_mut::<TextureDict>() {
+            texture_dict.remove_released();
+        }
+    }
+
+    /// Drops every scene, including any pause menu or fade in progress, and pushes `scene`, whose
+    /// textures are in `texture_scope`. Textures only the dropped scenes used are removed the next
+    /// time `update` changes the stack.
+    pub fn reset_to(&mut self, scene: Box<dyn Scene<T>>, texture_scope: SceneTextureScope) -> Result<(), SceneStackError> {
+        self.stack.clear();
+        self.transition = None;
+        self.incoming_scene = None;
+        self.stack.push(StackedScene::new(scene));
+        self.track_textures(0, texture_scope);
+
+        self.refresh_render_graph()
+    }
+
+    /// Draws with `render_graph` from now on. Each scene in the stack gets to add its own nodes
+    /// through `Scene::extend_render_graph`, again every time the stack changes.
+    #[cfg_attr(feature="trace", instrument(skip(self, render_graph)))]
+    pub fn set_render_graph(&mut self, render_graph: RenderGraph) -> Result<(), SceneStackError> {
+        self.render_graph = Some(render_graph);
+        self.scene_node_names.clear();
+
+        self.refresh_render_graph()
+    }
+
+    /// Whether a pause menu pushed with `SceneTransition::PAUSE` is in the stack.
+    pub fn is_pause_menu_open(&self) -> bool {
+        self.stack.iter().any(|stacked| stacked.role == SceneRole::PauseMenu)
+    }
+
+    /// Whether the scene at `index` is covered by an overlay pushed with `SceneTransition::PUSH_PAUSE`.
+    pub fn is_scene_paused(&self, index: usize) -> bool {
+        matches!(self.stack.get(index + 1), Some(stacked) if stacked.role == SceneRole::PauseOverlay)
+    }
+
+    /// Goes back to drawing with the scenes' `draw`, returning the graph without the scenes' nodes.
+    pub fn take_render_graph(&mut self) -> Option<RenderGraph> {
+        let mut render_graph = self.render_graph.take()?;
+        for name in self.scene_node_names.drain(..) {
+            render_graph.remove_node(&name);
+        }
+
+        Some(render_graph)
+    }
+
+    pub fn render_graph(&self) -> Option<&RenderGraph> {
+        self.render_graph.as_ref()
+    }
+
+    /// Replaces the scenes' nodes with the ones the scenes in the stack add now, bottom scene first.
+    fn refresh_render_graph(&mut self) -> Result<(), SceneStackError> {
+        let render_graph = match self.render_graph.as_mut() {
+            Some(render_graph) => render_graph,
+            None => return Ok(())
+        };
+
+        for name in self.scene_node_names.drain(..) {
+            render_graph.remove_node(&name);
+        }
+
+        for StackedScene { scene, .. } in self.stack.iter_mut() {
+            let existing: Vec<String> = render_graph.node_names()
+                .map(String::from)
+                .collect();
+
+            scene.extend_render_graph(render_graph)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("An error occurred while calling extend_render_graph on scene: {:?}. Error: {}", scene.get_name(), display_chain(&*e));
+
+                    ExtendRenderGraphError {
+                        scene_name: scene.get_name(),
+                        source: e
+                    }
+                })?;
+
+            self.scene_node_names.extend(render_graph.node_names()
+                .filter(|name| !existing.iter().any(|existing| existing == name))
+                .map(String::from));
+        }
+
+        #[cfg(feature="trace")]
+        debug!("Scenes added render nodes: {:?}", self.scene_node_names);
+
+        Ok(())
+    }
+
     #[cfg_attr(feature="trace", instrument(skip(self, ecs)))]
-    pub fn update(&mut self, ecs: &mut World) -> Result<(), SceneStackError> {
-        return if let Some(scene) = self.stack.last_mut() {
+    pub fn update(&mut self, ecs: &mut World, delta: Duration) -> Result<(), SceneStackError>
+        where T: 'static {
+        if let Some(transition) = self.transition.as_mut() {
+            transition.tick(delta);
+
+            if transition.is_phase_done() {
+                match transition.phase {
+                    TransitionPhase::FadeOut => {
+                        if let Some(scene) = self.incoming_scene.take() {
+                            #[cfg(feature="trace")]
+                            debug!("Fade out finished. Pushed new scene: {}", scene.get_name());
+
+                            self.stack.push(StackedScene::new(scene));
+                        }
+
+                        transition.fade_in();
+                        self.refresh_render_graph()?;
+                    },
+                    TransitionPhase::FadeIn => {
+                        #[cfg(feature="trace")]
+                        debug!("Fade in finished");
+
+                        self.transition = None;
+                    }
+                }
+            }
+
+            #[cfg(feature="trace")]
+            trace!("Scene transition in progress. Skipping update");
+
+            return Ok(())
+        }
+
+        let pause_menu_open = self.is_pause_menu_open();
+        if is_paused(ecs) && !pause_menu_open {
+            #[cfg(feature="trace")]
+            trace!("Game is paused. Skipping update");
+
+            return Ok(())
+        }
+
+        return if let Some(StackedScene { scene, .. }) = self.stack.last_mut() {
             #[cfg(feature="trace")]
             debug!("Calling update on {}", scene.get_name());
 
-            let transition = scene.update(ecs)
+            #[cfg(feature = "deterministic")]
+            sort_entities(ecs);
+
+            let transition = scene.update(ecs, delta)
                 .map_err(|e| {
                     #[cfg(feature = "trace")]
-                    error!("An occurred while calling update on scene: {:?}", scene.get_name());
+                    error!("An error occurred while calling update on scene: {:?}. Error: {}", scene.get_name(), display_chain(&*e));
 
                     SceneStackUpdateError {
                         scene_name: scene.get_name(),
@@ -163,33 +659,69 @@ impl<T: Input + Debug> SceneStack<T> {
             #[cfg(feature="trace")]
             trace!("Scene returned: {:?}", transition);
 
+            let stack_changed = matches!(transition,
+                SceneTransition::POP(_)
+                | SceneTransition::PUSH(_)
+                | SceneTransition::PUSH_PAUSE(_)
+                | SceneTransition::SWAP(..)
+                | SceneTransition::REPLACE(..)
+                | SceneTransition::CLEAR
+                | SceneTransition::PAUSE(_)
+                | SceneTransition::UNPAUSE
+                | SceneTransition::COMMIT_PRELOAD);
+
             match transition {
                 SceneTransition::POP(quantity) => {
                     for _i in 0..quantity {
-                        let _scene = self.stack
-                            .pop()
+                        let num_scenes = self.stack.len();
+                        let StackedScene { scene: popped_scene, .. } = self.stack
+                            .last_mut()
                             .ok_or_else(
                                 || {
                                     #[cfg(feature="trace")]
-                                    error!("Attempted to pop: {} scenes. More scenes than available: ({}). Failed on iteration: {}", quantity, self.stack.len(), _i);
+                                    error!("Attempted to pop: {} scenes. More scenes than available: ({}). Failed on iteration: {}", quantity, num_scenes, _i);
 
                                     SceneStackPopError {
-                                        num_scenes: self.stack.len(),
+                                        num_scenes,
                                         pop_amount: quantity
                                     }
                                 }
                             )?;
+                        exit_scene(popped_scene.as_mut(), ecs)?;
+
                         #[cfg(feature="trace")]
-                        debug!("Popped scene: {}", _scene.get_name());
+                        debug!("Popped scene: {}", popped_scene.get_name());
+
+                        self.stack.pop();
                     }
                     #[cfg(feature="trace")]
-                    debug!("{} scenes were popped", quantity)
+                    debug!("{} scenes were popped", quantity);
+
+                    if quantity > 0 {
+                        if let Some(StackedScene { scene, .. }) = self.stack.last_mut() {
+                            resume_scene(scene.as_mut(), ecs)?;
+                        }
+                    }
                 },
                 SceneTransition::PUSH(new_scene) => {
                     #[cfg(feature="trace")]
                     debug!("Pushed new scene: {}", new_scene.get_name());
 
-                    self.stack.push(new_scene);
+                    self.stack.push(StackedScene::new(new_scene));
+                },
+                SceneTransition::PUSH_PAUSE(overlay) => {
+                    #[cfg(feature="trace")]
+                    debug!("Paused: {} and pushed overlay: {}", scene.get_name(), overlay.get_name());
+
+                    pause_scene(scene.as_mut(), ecs)?;
+                    self.stack.push(StackedScene::with_role(overlay, SceneRole::PauseOverlay));
+                },
+                SceneTransition::PUSH_WITH_FADE(new_scene, duration) => {
+                    #[cfg(feature="trace")]
+                    debug!("Fading to new scene: {} over {:?}", new_scene.get_name(), duration);
+
+                    self.incoming_scene = Some(new_scene);
+                    self.transition = Some(TransitionState::new(duration));
                 },
                 SceneTransition::SWAP(scene_1, scene_2) => {
                     if scene_1 == scene_2 {
@@ -215,12 +747,12 @@ impl<T: Input + Debug> SceneStack<T> {
                         let max = max(scene_1, scene_2);
                         let min = min(scene_1, scene_2);
                         let max_scene = self.stack.remove(max);
-                        let _max_name = max_scene.get_name();
+                        let _max_name = max_scene.scene.get_name();
 
                         self.stack.insert(min, max_scene);
 
                         let min_scene = self.stack.remove(min + 1);
-                        let _min_name = min_scene.get_name();
+                        let _min_name = min_scene.scene.get_name();
                         self.stack.insert(max, min_scene);
 
                         #[cfg(feature="trace")]
@@ -235,18 +767,19 @@ impl<T: Input + Debug> SceneStack<T> {
                             })
                     } else {
                         let _new_scene_name = new_scene.get_name();
-                        self.stack.insert(index, new_scene);
-                        let _deleted_scene = self.stack.remove(index + 1);
+                        self.stack.insert(index, StackedScene::new(new_scene));
+                        let StackedScene { scene: mut deleted_scene, .. } = self.stack.remove(index + 1);
+                        exit_scene(deleted_scene.as_mut(), ecs)?;
 
                         #[cfg(feature="trace")]
-                        debug!("Replaced: {:#?} with {:#?}", _deleted_scene.get_name(), _new_scene_name);
+                        debug!("Replaced: {:#?} with {:#?}", deleted_scene.get_name(), _new_scene_name);
                     }
                 },
                 SceneTransition::CLEAR => {
                     let stack_height = self.stack.len();
                     // Only call pop length - 1 times so one scene is left.
                     for i in 0..stack_height - 1 {
-                       let _deleted_scene = self.stack
+                       let StackedScene { scene: mut deleted_scene, .. } = self.stack
                            .pop()
                            .ok_or_else(
                                || {
@@ -260,9 +793,10 @@ impl<T: Input + Debug> SceneStack<T> {
                                    }
                                }
                            )?;
+                       exit_scene(deleted_scene.as_mut(), ecs)?;
 
                        #[cfg(feature="trace")]
-                       debug!("Clearing stack... Deleted: {} ({}/{})", _deleted_scene.get_name(), i + 1, stack_height - 1);
+                       debug!("Clearing stack... Deleted: {} ({}/{})", deleted_scene.get_name(), i + 1, stack_height - 1);
                     }
                     let _remaining_scene = self.stack
                         .first()
@@ -271,7 +805,87 @@ impl<T: Input + Debug> SceneStack<T> {
                         )?;
 
                     #[cfg(feature="trace")]
-                    debug!("Cleared full scene stack except for bottom scene: {}", _remaining_scene.get_name())
+                    debug!("Cleared full scene stack except for bottom scene: {}", _remaining_scene.scene.get_name())
+                },
+                SceneTransition::SET_FONT_SCALE(font_scale) => {
+                    ecs.entry::<AccessibilityConfig>()
+                        .or_insert_with(AccessibilityConfig::default)
+                        .font_scale = font_scale;
+
+                    #[cfg(feature="trace")]
+                    debug!("Set font scale to: {}", font_scale)
+                },
+                SceneTransition::PAUSE(pause_menu) => {
+                    ecs.entry::<PauseState>()
+                        .or_insert_with(PauseState::default)
+                        .0 = true;
+
+                    #[cfg(feature="trace")]
+                    debug!("Paused game and pushed pause menu: {}", pause_menu.get_name());
+
+                    self.stack.push(StackedScene::with_role(pause_menu, SceneRole::PauseMenu));
+                },
+                SceneTransition::UNPAUSE => {
+                    let index = match self.stack.iter().rposition(|stacked| stacked.role == SceneRole::PauseMenu) {
+                        Some(index) => index,
+                        None => {
+                            #[cfg(feature="trace")]
+                            error!("UNPAUSE returned without a pause menu from PAUSE. Nothing was popped");
+
+                            return Ok(())
+                        }
+                    };
+
+                    while self.stack.len() > index {
+                        if let Some(StackedScene { scene: mut popped_scene, .. }) = self.stack.pop() {
+                            exit_scene(popped_scene.as_mut(), ecs)?;
+
+                            #[cfg(feature="trace")]
+                            debug!("Popped scene: {}", popped_scene.get_name());
+                        }
+                    }
+
+                    if let Some(StackedScene { scene, .. }) = self.stack.last_mut() {
+                        resume_scene(scene.as_mut(), ecs)?;
+                    }
+
+                    #[cfg(feature="trace")]
+                    debug!("Closed pause menu. {} scenes remain", self.stack.len())
+                },
+                SceneTransition::PRELOAD(scene_loader) => {
+                    #[cfg(feature="trace")]
+                    debug!("Preloading scene with: {:?}", scene_loader);
+
+                    let pool = ecs.try_fetch::<Pool>().map(|pool| Pool::clone(&pool));
+                    self.preload = Some(PendingScene::new(scene_loader, pool.as_ref()));
+                },
+                SceneTransition::COMMIT_PRELOAD => {
+                    match self.preload.as_mut() {
+                        Some(pending) if pending.is_loaded() => self.push_preload(),
+                        Some(pending) if pending.is_failed() => {
+                            if let Some(e) = self.take_preload_error() {
+                                return Err(e)
+                            }
+                        },
+                        Some(pending) => {
+                            #[cfg(feature="trace")]
+                            debug!("Preloaded scene is still loading. It will be pushed once loaded");
+
+                            pending.commit_requested = true;
+                        },
+                        None => {
+                            #[cfg(feature="trace")]
+                            error!("COMMIT_PRELOAD returned without a preloaded scene");
+                        }
+                    }
+                },
+                SceneTransition::SET_FULLSCREEN(fullscreen) => {
+                    ecs.entry::<FullscreenRequest>()
+                        .or_insert_with(FullscreenRequest::default)
+                        .0 = Some(fullscreen);
+
+                    #[cfg(feature="trace")]
+                    debug!("Requested fullscreen: {}", fullscreen)
                 },
                 SceneTransition::NONE => {
                     #[cfg(feature="trace")]
@@ -279,6 +893,22 @@ impl<T: Input + Debug> SceneStack<T> {
                 }
             };
 
+            if stack_changed {
+                // The game is unpaused once the last pause menu is closed, whether by UNPAUSE,
+                // POP, REPLACE or CLEAR.
+                if pause_menu_open && !self.is_pause_menu_open() {
+                    ecs.entry::<PauseState>()
+                        .or_insert_with(PauseState::default)
+                        .0 = false;
+
+                    #[cfg(feature="trace")]
+                    debug!("Last pause menu was removed. Unpaused game");
+                }
+
+                self.release_textures(ecs);
+                self.refresh_render_graph()?;
+            }
+
             anyhow::Result::Ok(())
         } else {
             #[cfg(feature="trace")]
@@ -288,39 +918,108 @@ impl<T: Input + Debug> SceneStack<T> {
         }
     }
 
+    /// Finds the scenes that need to be drawn. Starting at the top of the stack, scenes are included
+    /// until the first `DrawMode::Exclusive` scene, which is included as the bottom of the range.
+    /// Overlays pushed with PUSH_PAUSE count as transparent so the paused scene is drawn too.
+    #[cfg_attr(feature="trace", instrument(skip(self)))]
+    pub fn compute_draw_range(&self) -> Result<Range<usize>, SceneStackError> {
+        if self.stack.is_empty() {
+            #[cfg(feature="trace")]
+            error!("SceneStack was empty");
+
+            return Err( SceneStackEmptyError {})
+        }
+
+        let start = self.stack
+            .iter()
+            .rposition(|stacked| stacked.scene.draw_mode() == DrawMode::Exclusive && stacked.role != SceneRole::PauseOverlay)
+            .unwrap_or(0);
+
+        #[cfg(feature="trace")]
+        debug!("Drawing scenes in range: {}..{}", start, self.stack.len());
+
+        Ok(start..self.stack.len())
+    }
+
     #[cfg_attr(feature="trace", instrument(skip(self, ecs, context)))]
     pub fn draw(&mut self, ecs: &mut World, context: &mut GL33Context) -> Result<(), SceneStackError> {
-        return if let Some(scene) = self.stack.last_mut() {
-            scene.draw(ecs, context)
-                .map_err( |e| {
-                    #[cfg(feature = "trace")]
-                    error!("An error occurred while calling Scene::draw. Error: ({:?}). Scene: {:?}", e, scene.get_name());
+        self.draw_with_overlay(ecs, context, |_ecs: &World, _context: &mut GL33Context| Ok(()))
+    }
 
-                    SceneStackDrawError {
-                        scene_name: scene.get_name(),
-                        source: e
-                    }
-                })?;
+    /// Draws the scenes, then `overlay` on top of them. A render graph draws the overlay right
+    /// before its `swap_buffers` node, so it still ends up on screen.
+    #[cfg_attr(feature="trace", instrument(skip(self, ecs, context, overlay)))]
+    pub fn draw_with_overlay(&mut self, ecs: &mut World, context: &mut GL33Context, overlay: impl FnOnce(&World, &mut GL33Context) -> anyhow::Result<()>) -> Result<(), SceneStackError> {
+        if let Some(transition) = self.transition {
+            self.draw_transition(ecs, context, transition.alpha())?;
+        } else if let Some(render_graph) = self.render_graph.as_mut() {
+            return render_graph.execute_with_overlay(ecs, context, overlay)
+                .map_err(|e| SceneRenderGraphError { source: e })
+        } else {
+            let range = self.compute_draw_range()?;
 
-            #[cfg(feature="trace")]
-            debug!("Called draw on {}", scene.get_name());
+            for StackedScene { scene, .. } in self.stack[range].iter_mut() {
+                draw_scene(scene.as_mut(), ecs, context)
+                    .map_err( |e| {
+                        #[cfg(feature = "trace")]
+                        error!("An error occurred while calling Scene::draw. Error: {}. Scene: {:?}", display_chain(&*e), scene.get_name());
 
-            Result::Ok(())
-        } else {
-            #[cfg(feature="trace")]
-            error!("SceneStack was empty");
+                        SceneStackDrawError {
+                            scene_name: scene.get_name(),
+                            source: e
+                        }
+                    })?;
 
-            Err( SceneStackEmptyError {})
+                #[cfg(feature="trace")]
+                debug!("Called draw on {}", scene.get_name());
+            }
         }
+
+        overlay(ecs, context)
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("An error occurred while drawing the overlay. Error: {}", display_chain(&*e));
+
+                SceneOverlayDrawError { source: e }
+            })
+    }
+
+    /// Draws only the top scene, with the ColorTint's alpha scaled by `alpha`. The render graph
+    /// isn't used during a fade.
+    fn draw_transition(&mut self, ecs: &mut World, context: &mut GL33Context, alpha: f32) -> Result<(), SceneStackError> {
+        let scene = &mut self.stack.last_mut().ok_or(SceneStackEmptyError {})?.scene;
+
+        let tint = *ecs.entry::<ColorTint>().or_insert_with(ColorTint::default);
+        let mut faded = tint;
+        faded.0[3] *= alpha;
+        ecs.insert(faded);
+
+        let drawn = draw_scene(scene.as_mut(), ecs, context);
+        ecs.insert(tint);
+
+        drawn.map_err(|e| {
+            #[cfg(feature = "trace")]
+            error!("An error occurred while calling Scene::draw. Error: {}. Scene: {:?}", display_chain(&*e), scene.get_name());
+
+            SceneStackDrawError {
+                scene_name: scene.get_name(),
+                source: e
+            }
+        })?;
+
+        #[cfg(feature="trace")]
+        debug!("Drew {} with alpha: {}", scene.get_name(), alpha);
+
+        Ok(())
     }
 
     #[cfg_attr(feature="trace", instrument(skip(self, ecs)))]
     pub fn interact(&mut self, ecs: &mut World, input: &T) -> Result<(), SceneStackError> {
-        return if let Some(scene) = self.stack.last_mut() {
+        return if let Some(StackedScene { scene, .. }) = self.stack.last_mut() {
             scene.interact(ecs, input)
                 .map_err(|e| {
                     #[cfg(feature = "trace")]
-                    error!("An error occurred while calling Scene::interact. Error: ({:?}). Scene: {:?}", e, scene.get_name());
+                    error!("An error occurred while calling Scene::interact. Error: {}. Scene: {:?}", display_chain(&*e), scene.get_name());
 
                     SceneStackInteractError {
                         scene_name: scene.get_name(),
@@ -342,11 +1041,11 @@ impl<T: Input + Debug> SceneStack<T> {
 
     #[cfg_attr(feature="trace", instrument(skip(self, ecs)))]
     pub fn is_finished(&self, ecs: &mut World) -> Result<bool, SceneStackError> {
-        return if let Some(scene) = self.stack.last() {
+        return if let Some(StackedScene { scene, .. }) = self.stack.last() {
             let should_finish = scene.is_finished(ecs)
                 .map_err(|e| {
                     #[cfg(feature = "trace")]
-                    error!("An error occurred while calling Scene::is_finished. Error: ({:?}). Scene: {:?}", e, scene.get_name());
+                    error!("An error occurred while calling Scene::is_finished. Error: {}. Scene: {:?}", display_chain(&*e), scene.get_name());
 
                     SceneStackIsFinishedError {
                         scene_name: scene.get_name(),
@@ -371,12 +1070,12 @@ impl<T: Input + Debug> SceneStack<T> {
 pub enum SceneStackLoaderError {
     #[error("Failed to deserialize Scene Stack JSON from file at {path:?}")]
     JSONDeserializeFromFileError {
-        source: LoadError,
+        source: anyhow::Error,
         path: String
     },
     #[error("Failed to create LoadJSON object from file at {path:?}")]
     JSONLoadFromFileError {
-        source: LoadError,
+        source: anyhow::Error,
         path: String
     },
     #[error("The scene factory failed to detect this scene JSON value: {scene_json:?}")]
@@ -387,7 +1086,81 @@ pub enum SceneStackLoaderError {
     #[error("Failed to load scene")]
     SceneLoadError {
         source: anyhow::Error
+    },
+    #[error("Scene: {scene_path} took longer than {timeout:?} to load")]
+    SceneLoadTimeoutError {
+        scene_path: String,
+        timeout: Duration,
+        source: anyhow::Error
+    },
+    #[error("Failed to record how long the scene took to load")]
+    SceneLoadTimingError {
+        source: LoadTimingError
+    },
+    #[error("{}", format_load_errors(.errors))]
+    MultipleLoadErrors {
+        /// Index of each failed scene in the stack file's `scene_paths`, with its error.
+        errors: Vec<(usize, SceneStackLoaderError)>
+    },
+    #[error("The preloaded scene's loader panicked before returning its DrawTask")]
+    ScenePreparePanicked
+}
+
+/// Calls `on_exit` on a scene that is about to be removed from the stack.
+fn exit_scene<T: Input + Debug>(scene: &mut dyn Scene<T>, ecs: &mut World) -> Result<(), SceneStackError> {
+    scene.on_exit(ecs)
+        .map_err(|e| {
+            #[cfg(feature = "trace")]
+            error!("An error occurred while calling on_exit on scene: {:?}. Error: {}", scene.get_name(), display_chain(&*e));
+
+            OnExitError {
+                scene_name: scene.get_name(),
+                source: e
+            }
+        })
+}
+
+fn pause_scene<T: Input + Debug>(scene: &mut dyn Scene<T>, ecs: &mut World) -> Result<(), SceneStackError> {
+    scene.pause(ecs)
+        .map_err(|e| {
+            #[cfg(feature = "trace")]
+            error!("An error occurred while calling pause on scene: {:?}. Error: {}", scene.get_name(), display_chain(&*e));
+
+            SceneStackPauseError {
+                scene_name: scene.get_name(),
+                source: e
+            }
+        })
+}
+
+fn resume_scene<T: Input + Debug>(scene: &mut dyn Scene<T>, ecs: &mut World) -> Result<(), SceneStackError> {
+    scene.resume(ecs)
+        .map_err(|e| {
+            #[cfg(feature = "trace")]
+            error!("An error occurred while calling resume on scene: {:?}. Error: {}", scene.get_name(), display_chain(&*e));
+
+            SceneStackResumeError {
+                scene_name: scene.get_name(),
+                source: e
+            }
+        })
+}
+
+/// One line per failed scene, each with the full chain of causes.
+fn format_load_errors(errors: &[(usize, SceneStackLoaderError)]) -> String {
+    let mut message = format!("Failed to load {} scenes:", errors.len());
+
+    for (index, error) in errors {
+        message.push_str(&format!("\n  scene {}: {}", index, error));
+
+        let mut source = std::error::Error::source(error);
+        while let Some(cause) = source {
+            message.push_str(&format!(": {}", cause));
+            source = cause.source();
+        }
     }
+
+    message
 }
 
 #[derive(Error, Debug)]
@@ -450,5 +1223,188 @@ pub enum SceneStackError {
     SceneStackIsFinishedError {
         scene_name: String,
         source: anyhow::Error
+    },
+    #[error("Error during call to {scene_name}.on_exit()")]
+    OnExitError {
+        scene_name: String,
+        source: anyhow::Error
+    },
+    #[error("Error during call to {scene_name}.pause()")]
+    SceneStackPauseError {
+        scene_name: String,
+        source: anyhow::Error
+    },
+    #[error("Error during call to {scene_name}.resume()")]
+    SceneStackResumeError {
+        scene_name: String,
+        source: anyhow::Error
+    },
+    #[error("Error during call to {scene_name}.extend_render_graph()")]
+    ExtendRenderGraphError {
+        scene_name: String,
+        source: anyhow::Error
+    },
+    #[error("Error drawing the frame with the render graph")]
+    SceneRenderGraphError {
+        source: RenderGraphError
+    },
+    #[error("Error drawing the overlay on top of the scenes")]
+    SceneOverlayDrawError {
+        source: anyhow::Error
+    },
+    #[error("Error preloading scene")]
+    ScenePreloadError {
+        source: SceneStackLoaderError
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::keyboard::KeyBoard;
+    use specs::WorldExt;
+
+    /// Returns its transitions from its updates, one per update, then NONE.
+    #[derive(Debug)]
+    struct ScriptedScene {
+        name: &'static str,
+        transitions: Vec<SceneTransition<KeyBoard>>
+    }
+
+    impl Scene<KeyBoard> for ScriptedScene {
+        fn update(&mut self, _ecs: &mut World, _delta: Duration) -> Result<SceneTransition<KeyBoard>> {
+            Ok(self.transitions.pop().unwrap_or(SceneTransition::NONE))
+        }
+
+        fn draw(&mut self, _ecs: &mut World, _context: &mut GL33Context) -> Result<()> {
+            Ok(())
+        }
+
+        fn interact(&mut self, _ecs: &mut World, _input: &KeyBoard) -> Result<()> {
+            Ok(())
+        }
+
+        fn get_name(&self) -> String {
+            self.name.to_string()
+        }
+
+        fn is_finished(&self, _ecs: &mut World) -> Result<bool> {
+            Ok(false)
+        }
+    }
+
+    fn scene(name: &'static str, transitions: Vec<SceneTransition<KeyBoard>>) -> Box<dyn Scene<KeyBoard>> {
+        Box::new(ScriptedScene {
+            name,
+            transitions: transitions.into_iter().rev().collect()
+        })
+    }
+
+    fn update(scene_stack: &mut SceneStack<KeyBoard>, ecs: &mut World) {
+        scene_stack.update(ecs, Duration::from_millis(16)).unwrap();
+    }
+
+    fn names(scene_stack: &SceneStack<KeyBoard>) -> Vec<String> {
+        scene_stack.stack.iter()
+            .map(|stacked| stacked.scene.get_name())
+            .collect()
+    }
+
+    #[test]
+    fn swap_moves_the_overlay_role_with_the_overlay() {
+        let mut ecs = World::new();
+        let inventory = scene("inventory", vec![SceneTransition::SWAP(0, 1)]);
+        let mut scene_stack = SceneStack::new(vec![scene("game", vec![SceneTransition::PUSH_PAUSE(inventory)])]);
+
+        update(&mut scene_stack, &mut ecs);
+        assert!(scene_stack.is_scene_paused(0));
+        assert_eq!(scene_stack.compute_draw_range().unwrap(), 0..2);
+
+        update(&mut scene_stack, &mut ecs);
+        assert_eq!(names(&scene_stack), vec!["inventory", "game"]);
+        assert_eq!(scene_stack.stack[0].role(), SceneRole::PauseOverlay);
+        assert_eq!(scene_stack.stack[1].role(), SceneRole::Scene);
+        assert!(!scene_stack.is_scene_paused(0));
+        assert_eq!(scene_stack.compute_draw_range().unwrap(), 1..2);
+    }
+
+    #[test]
+    fn replacing_the_overlay_drops_its_role() {
+        let mut ecs = World::new();
+        let inventory = scene("inventory", vec![SceneTransition::REPLACE(1, scene("map", vec![]))]);
+        let mut scene_stack = SceneStack::new(vec![scene("game", vec![SceneTransition::PUSH_PAUSE(inventory)])]);
+
+        update(&mut scene_stack, &mut ecs);
+        update(&mut scene_stack, &mut ecs);
+
+        assert_eq!(names(&scene_stack), vec!["game", "map"]);
+        assert!(!scene_stack.is_scene_paused(0));
+        assert_eq!(scene_stack.compute_draw_range().unwrap(), 1..2);
+    }
+
+    #[test]
+    fn scene_replacing_the_paused_scene_stays_under_the_overlay() {
+        let mut ecs = World::new();
+        let inventory = scene("inventory", vec![SceneTransition::REPLACE(0, scene("level_2", vec![]))]);
+        let mut scene_stack = SceneStack::new(vec![scene("level_1", vec![SceneTransition::PUSH_PAUSE(inventory)])]);
+
+        update(&mut scene_stack, &mut ecs);
+        update(&mut scene_stack, &mut ecs);
+
+        assert_eq!(names(&scene_stack), vec!["level_2", "inventory"]);
+        assert!(scene_stack.is_scene_paused(0));
+        assert_eq!(scene_stack.compute_draw_range().unwrap(), 0..2);
+    }
+
+    #[test]
+    fn unpause_after_swap_closes_the_moved_pause_menu() {
+        let mut ecs = World::new();
+        let menu = scene("menu", vec![SceneTransition::SWAP(1, 2)]);
+        let hud = scene("hud", vec![SceneTransition::PAUSE(menu), SceneTransition::UNPAUSE]);
+        let mut scene_stack = SceneStack::new(vec![scene("level", vec![SceneTransition::PUSH(hud)])]);
+
+        update(&mut scene_stack, &mut ecs);
+        update(&mut scene_stack, &mut ecs);
+        update(&mut scene_stack, &mut ecs);
+        assert_eq!(names(&scene_stack), vec!["level", "menu", "hud"]);
+        assert!(is_paused(&ecs));
+
+        update(&mut scene_stack, &mut ecs);
+        assert_eq!(names(&scene_stack), vec!["level"]);
+        assert!(!is_paused(&ecs));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn nested_pause_menus_are_closed_one_unpause_at_a_time() {
+        let mut ecs = World::new();
+        let options = scene("options", vec![SceneTransition::UNPAUSE]);
+        let menu = scene("menu", vec![SceneTransition::PAUSE(options), SceneTransition::UNPAUSE]);
+        let mut scene_stack = SceneStack::new(vec![scene("game", vec![SceneTransition::PAUSE(menu)])]);
+
+        update(&mut scene_stack, &mut ecs);
+        update(&mut scene_stack, &mut ecs);
+        assert_eq!(names(&scene_stack), vec!["game", "menu", "options"]);
+
+        update(&mut scene_stack, &mut ecs);
+        assert_eq!(names(&scene_stack), vec!["game", "menu"]);
+        assert!(is_paused(&ecs));
+
+        update(&mut scene_stack, &mut ecs);
+        assert_eq!(names(&scene_stack), vec!["game"]);
+        assert!(!is_paused(&ecs));
+    }
+
+    #[test]
+    fn replacing_the_pause_menu_unpauses_the_game() {
+        let mut ecs = World::new();
+        let menu = scene("menu", vec![SceneTransition::REPLACE(1, scene("shop", vec![]))]);
+        let mut scene_stack = SceneStack::new(vec![scene("game", vec![SceneTransition::PAUSE(menu)])]);
+
+        update(&mut scene_stack, &mut ecs);
+        assert!(is_paused(&ecs));
+
+        update(&mut scene_stack, &mut ecs);
+        assert_eq!(names(&scene_stack), vec!["game", "shop"]);
+        assert!(!scene_stack.is_pause_menu_open());
+        assert!(!is_paused(&ecs));
+    }
+}
@@ -0,0 +1,57 @@
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransitionPhase {
+    /// The old top scene fades out.
+    FadeOut,
+    /// The new scene has been pushed and fades in.
+    FadeIn
+}
+
+/// Progress of a `SceneTransition::PUSH_WITH_FADE`. Each phase lasts `duration`, which is half of
+/// the duration given to the transition.
+#[derive(Debug, Clone, Copy)]
+pub struct TransitionState {
+    pub phase: TransitionPhase,
+    pub elapsed: Duration,
+    pub duration: Duration
+}
+
+impl TransitionState {
+    pub fn new(total_duration: Duration) -> Self {
+        Self {
+            phase: TransitionPhase::FadeOut,
+            elapsed: Duration::ZERO,
+            duration: total_duration / 2
+        }
+    }
+
+    /// Adds the time since the last update to `elapsed`.
+    pub fn tick(&mut self, delta: Duration) {
+        self.elapsed += delta;
+    }
+
+    pub fn is_phase_done(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+
+    /// Starts the FadeIn phase.
+    pub fn fade_in(&mut self) {
+        self.phase = TransitionPhase::FadeIn;
+        self.elapsed = Duration::ZERO;
+    }
+
+    /// Alpha the top scene is drawn with.
+    pub fn alpha(&self) -> f32 {
+        let t = if self.duration.is_zero() {
+            1.0
+        } else {
+            (self.elapsed.as_secs_f32() / self.duration.as_secs_f32()).min(1.0)
+        };
+
+        match self.phase {
+            TransitionPhase::FadeOut => 1.0 - t,
+            TransitionPhase::FadeIn => t
+        }
+    }
+}
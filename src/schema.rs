@@ -0,0 +1,121 @@
+use schemars::JsonSchema;
+use schemars::gen::SchemaSettings;
+use serde_json::Value;
+
+use crate::camera::orthographic_camera::{OrthographicCameraJSON, ORTHOGRAPHIC_CAMERA_LOAD_ID};
+use crate::camera::perspective_camera::{PerspectiveCameraJSON, PERSPECTIVE_CAMERA_LOAD_ID};
+use crate::components::animated_sprite::{AnimatedSpriteJSON, ANIMATED_SPRITE_LOAD_ID};
+use crate::components::cursor_icon::{CursorIconJSON, CURSOR_ICON_LOAD_ID};
+use crate::components::fog_of_war::{FogOfWarJSON, FOG_OF_WAR_LOAD_ID};
+use crate::components::gamepad_prompt::{GamepadPromptJSON, GAMEPAD_PROMPT_LOAD_ID};
+use crate::components::grid_mover::{GridMoverJSON, GRID_MOVER_LOAD_ID};
+use crate::components::lod::{LevelOfDetailJSON, LEVEL_OF_DETAIL_LOAD_ID};
+use crate::components::material::{MaterialJSON, MATERIAL_LOAD_ID};
+use crate::components::named_entity::{NamedEntityJSON, NAMED_ENTITY_LOAD_ID};
+use crate::components::outline::{OutlineJSON, OUTLINE_LOAD_ID};
+use crate::components::persistent_id::{PersistentIdJSON, PERSISTENT_ID_LOAD_ID};
+use crate::components::pivot::{PivotJSON, PIVOT_LOAD_ID};
+use crate::components::spatial_audio::{SpatialAudioJSON, SPATIAL_AUDIO_LOAD_ID};
+use crate::components::shadow::{ShadowJSON, SHADOW_LOAD_ID};
+use crate::components::sprite_region::{SpriteRegion, SPRITE_REGION_LOAD_ID};
+use crate::components::typewriter::{TypewriterEffectJSON, TYPEWRITER_EFFECT_LOAD_ID};
+use crate::components::world_text::{WorldTextJSON, WORLD_TEXT_LOAD_ID};
+use crate::graphics::render::fog_renderer::{FogRendererJSON, FOG_RENDERER_LOAD_ID};
+use crate::graphics::render::outline_renderer::{OutlineRendererJSON, OUTLINE_RENDERER_LOAD_ID};
+use crate::graphics::render::shadow_renderer::{ShadowRendererJSON, SHADOW_RENDERER_LOAD_ID};
+use crate::graphics::render::sprite_renderer::{SpriteRendererJSON, SPRITE_RENDERER_LOAD_ID};
+use crate::graphics::render::text_renderer::{WorldTextRendererJSON, WORLD_TEXT_RENDERER_LOAD_ID};
+use crate::graphics::shader::{ShaderJSON, SHADER_LOAD_ID};
+use crate::graphics::tess::{TessJSON, TESS_LOAD_ID};
+use crate::graphics::texture::{TextureJSON, TEXTURE_LOAD_ID};
+use crate::graphics::transform::{TransformJSON, TRANSFORM_LOAD_ID};
+use crate::scenes::{SceneLoaderJSON, SCENE_LOADER_FILE_ID};
+
+/// Where `cargo xtask generate-schemas` writes the schemas, as `<type_name>.schema.json`.
+pub const SCHEMA_DIR: &str = "assets/JSON/schemas/";
+
+/// JSON Schema for `T`, titled `type_name`. Every field that can be left out has a `default`,
+/// which is `null` when serde has no default value to show.
+pub fn generate_schema<T: JsonSchema>(type_name: &str) -> Value {
+    let schema = SchemaSettings::draft07()
+        .into_generator()
+        .into_root_schema_for::<T>();
+
+    let mut schema = serde_json::to_value(schema).unwrap_or(Value::Null);
+    if let Value::Object(object) = &mut schema {
+        object.insert("title".to_string(), Value::String(type_name.to_string()));
+    }
+    add_missing_defaults(&mut schema);
+
+    schema
+}
+
+/// Schema a ComponentLoader checks its JSON against, from the JsonSchema derive of the type it
+/// deserializes. Referenced types are inlined, since `validate_json` doesn't follow `$ref`.
+pub fn validation_schema<T: JsonSchema>() -> Value {
+    let mut settings = SchemaSettings::draft07();
+    settings.inline_subschemas = true;
+
+    serde_json::to_value(settings.into_generator().into_root_schema_for::<T>()).unwrap_or(Value::Bool(true))
+}
+
+/// Schemas of every loader file, keyed by the file's load ID.
+pub fn all_schemas() -> Vec<(&'static str, Value)> {
+    vec![
+        (SPATIAL_AUDIO_LOAD_ID, generate_schema::<SpatialAudioJSON>(SPATIAL_AUDIO_LOAD_ID)),
+        (ANIMATED_SPRITE_LOAD_ID, generate_schema::<AnimatedSpriteJSON>(ANIMATED_SPRITE_LOAD_ID)),
+        (PERSISTENT_ID_LOAD_ID, generate_schema::<PersistentIdJSON>(PERSISTENT_ID_LOAD_ID)),
+        (GAMEPAD_PROMPT_LOAD_ID, generate_schema::<GamepadPromptJSON>(GAMEPAD_PROMPT_LOAD_ID)),
+        (NAMED_ENTITY_LOAD_ID, generate_schema::<NamedEntityJSON>(NAMED_ENTITY_LOAD_ID)),
+        (LEVEL_OF_DETAIL_LOAD_ID, generate_schema::<LevelOfDetailJSON>(LEVEL_OF_DETAIL_LOAD_ID)),
+        (OUTLINE_LOAD_ID, generate_schema::<OutlineJSON>(OUTLINE_LOAD_ID)),
+        (MATERIAL_LOAD_ID, generate_schema::<MaterialJSON>(MATERIAL_LOAD_ID)),
+        (GRID_MOVER_LOAD_ID, generate_schema::<GridMoverJSON>(GRID_MOVER_LOAD_ID)),
+        (CURSOR_ICON_LOAD_ID, generate_schema::<CursorIconJSON>(CURSOR_ICON_LOAD_ID)),
+        (SHADOW_LOAD_ID, generate_schema::<ShadowJSON>(SHADOW_LOAD_ID)),
+        (FOG_OF_WAR_LOAD_ID, generate_schema::<FogOfWarJSON>(FOG_OF_WAR_LOAD_ID)),
+        (WORLD_TEXT_LOAD_ID, generate_schema::<WorldTextJSON>(WORLD_TEXT_LOAD_ID)),
+        (PIVOT_LOAD_ID, generate_schema::<PivotJSON>(PIVOT_LOAD_ID)),
+        (TYPEWRITER_EFFECT_LOAD_ID, generate_schema::<TypewriterEffectJSON>(TYPEWRITER_EFFECT_LOAD_ID)),
+        (SPRITE_REGION_LOAD_ID, generate_schema::<SpriteRegion>(SPRITE_REGION_LOAD_ID)),
+        (SCENE_LOADER_FILE_ID, generate_schema::<SceneLoaderJSON>(SCENE_LOADER_FILE_ID)),
+        (TESS_LOAD_ID, generate_schema::<TessJSON>(TESS_LOAD_ID)),
+        (TRANSFORM_LOAD_ID, generate_schema::<TransformJSON>(TRANSFORM_LOAD_ID)),
+        (SHADER_LOAD_ID, generate_schema::<ShaderJSON>(SHADER_LOAD_ID)),
+        (TEXTURE_LOAD_ID, generate_schema::<TextureJSON>(TEXTURE_LOAD_ID)),
+        (SPRITE_RENDERER_LOAD_ID, generate_schema::<SpriteRendererJSON>(SPRITE_RENDERER_LOAD_ID)),
+        (OUTLINE_RENDERER_LOAD_ID, generate_schema::<OutlineRendererJSON>(OUTLINE_RENDERER_LOAD_ID)),
+        (FOG_RENDERER_LOAD_ID, generate_schema::<FogRendererJSON>(FOG_RENDERER_LOAD_ID)),
+        (WORLD_TEXT_RENDERER_LOAD_ID, generate_schema::<WorldTextRendererJSON>(WORLD_TEXT_RENDERER_LOAD_ID)),
+        (SHADOW_RENDERER_LOAD_ID, generate_schema::<ShadowRendererJSON>(SHADOW_RENDERER_LOAD_ID)),
+        (ORTHOGRAPHIC_CAMERA_LOAD_ID, generate_schema::<OrthographicCameraJSON>(ORTHOGRAPHIC_CAMERA_LOAD_ID)),
+        (PERSPECTIVE_CAMERA_LOAD_ID, generate_schema::<PerspectiveCameraJSON>(PERSPECTIVE_CAMERA_LOAD_ID)),
+    ]
+}
+
+/// schemars only writes a `default` for types that implement Serialize, so optional properties
+/// of the other types get `null`.
+fn add_missing_defaults(schema: &mut Value) {
+    match schema {
+        Value::Object(object) => {
+            let required: Vec<String> = object.get("required")
+                .and_then(Value::as_array)
+                .map(|required| required.iter().filter_map(Value::as_str).map(String::from).collect())
+                .unwrap_or_default();
+
+            if let Some(Value::Object(properties)) = object.get_mut("properties") {
+                for (name, property) in properties.iter_mut() {
+                    if let Value::Object(property) = property {
+                        if !required.contains(name) {
+                            property.entry("default").or_insert(Value::Null);
+                        }
+                    }
+                }
+            }
+
+            object.values_mut().for_each(add_missing_defaults);
+        },
+        Value::Array(array) => array.iter_mut().for_each(add_missing_defaults),
+        _ => {}
+    }
+}
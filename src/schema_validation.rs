@@ -0,0 +1,273 @@
+#[cfg(feature = "trace")]
+use tracing::{error, instrument};
+
+use serde_json::{Map, Value};
+
+use crate::load::LoadError;
+use crate::load::LoadError::SchemaValidationError;
+
+/// Name used for the field when the whole value fails validation.
+pub const ROOT_FIELD: &str = "(root)";
+
+/// Checks `value` against a JSON Schema. Supports boolean schemas and the `type`, `enum`,
+/// `const`, `minimum`, `maximum`, `minLength`, `maxLength`, `properties`, `required`,
+/// `additionalProperties`, `items`, `minItems`, `maxItems` and `anyOf` keywords. Other keywords
+/// are ignored. The error names the first field that failed, e.g. `offset[1]`.
+#[cfg_attr(feature = "trace", instrument(skip(value, schema)))]
+pub fn validate_json(value: &Value, schema: &Value) -> Result<(), LoadError> {
+    validate_at(value, schema, ROOT_FIELD)
+        .map_err(|(field, message)| {
+            #[cfg(feature = "trace")]
+            error!("Field: {:?} failed schema validation: {}", field, message);
+
+            SchemaValidationError {
+                field,
+                message
+            }
+        })
+}
+
+fn validate_at(value: &Value, schema: &Value, field: &str) -> Result<(), (String, String)> {
+    let schema = match schema {
+        Value::Bool(true) => return Ok(()),
+        Value::Bool(false) => return Err((field.to_string(), "no value is allowed here".to_string())),
+        Value::Object(schema) => schema,
+        _ => return Ok(())
+    };
+
+    let fail = |message: String| Err((field.to_string(), message));
+
+    if let Some(types) = schema.get("type") {
+        let allowed: Vec<&str> = match types {
+            Value::String(name) => vec![name.as_str()],
+            Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new()
+        };
+
+        if !allowed.is_empty() && !allowed.iter().any(|name| is_type(value, name)) {
+            return fail(format!("expected {}, found {}", allowed.join(" or "), type_name(value)))
+        }
+    }
+
+    if let Some(Value::Array(options)) = schema.get("enum") {
+        if !options.contains(value) {
+            return fail(format!("expected one of {}, found {}", Value::Array(options.clone()), value))
+        }
+    }
+
+    if let Some(expected) = schema.get("const") {
+        if expected != value {
+            return fail(format!("expected {}, found {}", expected, value))
+        }
+    }
+
+    if let Value::Number(number) = value {
+        let number = number.as_f64().unwrap_or_default();
+
+        if let Some(minimum) = schema.get("minimum").and_then(Value::as_f64) {
+            if number < minimum {
+                return fail(format!("{} is less than the minimum of {}", number, minimum))
+            }
+        }
+
+        if let Some(maximum) = schema.get("maximum").and_then(Value::as_f64) {
+            if number > maximum {
+                return fail(format!("{} is greater than the maximum of {}", number, maximum))
+            }
+        }
+    }
+
+    if let Value::String(string) = value {
+        let length = string.chars().count() as u64;
+
+        if let Some(min_length) = schema.get("minLength").and_then(Value::as_u64) {
+            if length < min_length {
+                return fail(format!("must be at least {} characters long", min_length))
+            }
+        }
+
+        if let Some(max_length) = schema.get("maxLength").and_then(Value::as_u64) {
+            if length > max_length {
+                return fail(format!("must be at most {} characters long", max_length))
+            }
+        }
+    }
+
+    if let Value::Object(object) = value {
+        validate_object(object, schema, field)?;
+    }
+
+    if let Value::Array(items) = value {
+        if let Some(min_items) = schema.get("minItems").and_then(Value::as_u64) {
+            if (items.len() as u64) < min_items {
+                return fail(format!("expected at least {} items, found {}", min_items, items.len()))
+            }
+        }
+
+        if let Some(max_items) = schema.get("maxItems").and_then(Value::as_u64) {
+            if (items.len() as u64) > max_items {
+                return fail(format!("expected at most {} items, found {}", max_items, items.len()))
+            }
+        }
+
+        if let Some(item_schema) = schema.get("items") {
+            for (index, item) in items.iter().enumerate() {
+                validate_at(item, item_schema, &format!("{}[{}]", field, index))?;
+            }
+        }
+    }
+
+    if let Some(Value::Array(options)) = schema.get("anyOf") {
+        let mut first_error = None;
+
+        for option in options {
+            match validate_at(value, option, field) {
+                Ok(()) => return Ok(()),
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+
+        if let Some(e) = first_error {
+            return Err(e)
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_object(object: &Map<String, Value>, schema: &Map<String, Value>, field: &str) -> Result<(), (String, String)> {
+    if let Some(Value::Array(required)) = schema.get("required") {
+        for name in required.iter().filter_map(Value::as_str) {
+            if !object.contains_key(name) {
+                return Err((child_field(field, name), "required field is missing".to_string()))
+            }
+        }
+    }
+
+    let properties = schema.get("properties").and_then(Value::as_object);
+
+    for (name, property) in object {
+        let child = child_field(field, name);
+
+        match properties.and_then(|properties| properties.get(name)) {
+            Some(property_schema) => validate_at(property, property_schema, &child)?,
+            None => match schema.get("additionalProperties") {
+                Some(Value::Bool(false)) => return Err((child, "unknown field".to_string())),
+                Some(additional) => validate_at(property, additional, &child)?,
+                None => {}
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn child_field(parent: &str, name: &str) -> String {
+    if parent == ROOT_FIELD {
+        name.to_string()
+    } else {
+        format!("{}.{}", parent, name)
+    }
+}
+
+fn is_type(value: &Value, name: &str) -> bool {
+    match name {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        _ => true
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn failed_field(value: Value, schema: Value) -> String {
+        match validate_json(&value, &schema) {
+            Err(SchemaValidationError { field, .. }) => field,
+            other => panic!("Expected a schema validation error, found: {:?}", other)
+        }
+    }
+
+    fn pivot_schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "offset": {
+                    "type": "array",
+                    "items": { "type": "number" },
+                    "minItems": 2,
+                    "maxItems": 2
+                }
+            },
+            "required": ["offset"]
+        })
+    }
+
+    #[test]
+    fn type_accepts_any_listed_type() {
+        let schema = json!({ "type": ["integer", "null"] });
+
+        assert!(validate_json(&json!(3), &schema).is_ok());
+        assert!(validate_json(&Value::Null, &schema).is_ok());
+        assert_eq!(failed_field(json!(1.5), schema), ROOT_FIELD);
+    }
+
+    #[test]
+    fn missing_required_field_is_named() {
+        assert_eq!(failed_field(json!({}), pivot_schema()), "offset");
+    }
+
+    #[test]
+    fn items_name_the_failing_index() {
+        assert!(validate_json(&json!({ "offset": [0.5, 1.0] }), &pivot_schema()).is_ok());
+        assert_eq!(failed_field(json!({ "offset": [0.5, "up"] }), pivot_schema()), "offset[1]");
+        assert_eq!(failed_field(json!({ "offset": [0.5] }), pivot_schema()), "offset");
+    }
+
+    #[test]
+    fn nested_fields_are_joined_with_dots() {
+        let schema = json!({
+            "type": "object",
+            "properties": { "levels": { "type": "array", "items": pivot_schema() } }
+        });
+
+        assert_eq!(failed_field(json!({ "levels": [{ "offset": [0, 0] }, { "offset": [0, true] }] }), schema), "levels[1].offset[1]");
+    }
+
+    #[test]
+    fn any_of_passes_if_one_option_does() {
+        let schema = json!({ "anyOf": [{ "type": "string" }, { "type": "number", "minimum": 0 }] });
+
+        assert!(validate_json(&json!("name"), &schema).is_ok());
+        assert!(validate_json(&json!(2), &schema).is_ok());
+        assert!(validate_json(&json!(-2), &schema).is_err());
+    }
+
+    #[test]
+    fn unknown_fields_fail_without_additional_properties() {
+        let mut schema = pivot_schema();
+        schema["additionalProperties"] = Value::Bool(false);
+
+        assert_eq!(failed_field(json!({ "offset": [0, 0], "ofset": [0, 0] }), schema), "ofset");
+    }
+}
@@ -0,0 +1,333 @@
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::fs::read_to_string;
+use std::marker::PhantomData;
+use std::time::Duration;
+
+use anyhow::Result;
+use luminance_glfw::GL33Context;
+use mlua::{Function, Lua, MultiValue, ToLua, Value};
+use serde::Deserialize;
+use specs::{Component, Entity, World, WorldExt};
+use thiserror::Error;
+
+#[cfg(feature = "trace")]
+use tracing::{debug, error, instrument};
+
+use crate::graphics::transform::Transform;
+use crate::input::Input;
+use crate::load::{JSONLoad, load_deserializable_from_json, LoadError};
+use crate::loading::DrawTask;
+use crate::scenes::{Scene, SceneLoader};
+use crate::scenes::scene_stack::SceneTransition;
+use crate::scripting::LuaSceneError::{DeserializeError, ScriptReadError, ScriptLoadError, ScriptCallError, InvalidReturnValue};
+
+pub const LUA_SCENE_LOAD_ID: &str = "lua_scene";
+
+/// A component that scripts can read through `ecs.get_component`.
+pub trait ScriptComponent: Component {
+    fn to_lua<'lua>(&self, lua: &'lua Lua) -> mlua::Result<Value<'lua>>;
+}
+
+/// Converts the game's Input into the value passed to the script's `interact` function.
+pub trait ScriptInput {
+    fn to_lua<'lua>(&self, lua: &'lua Lua) -> mlua::Result<Value<'lua>>;
+}
+
+impl ScriptComponent for Transform {
+    fn to_lua<'lua>(&self, lua: &'lua Lua) -> mlua::Result<Value<'lua>> {
+        let table = lua.create_table()?;
+        table.set("translation", vec![self.translation.x, self.translation.y, self.translation.z])?;
+        table.set("scale", vec![self.scale.x, self.scale.y, self.scale.z])?;
+        table.set("rotation", vec![self.rotation.x, self.rotation.y, self.rotation.z])?;
+
+        Ok(Value::Table(table))
+    }
+}
+
+type GetComponentFn = for<'lua> fn(&'lua Lua, &World, Entity) -> mlua::Result<Value<'lua>>;
+
+/// Maps the names scripts use for components to the functions that read them. Transform is
+/// registered by default. Insert it into the World to expose more components.
+pub struct ScriptRegistry {
+    components: HashMap<String, GetComponentFn>
+}
+
+impl Default for ScriptRegistry {
+    fn default() -> Self {
+        let mut registry = Self { components: HashMap::new() };
+        registry.register::<Transform>("Transform");
+
+        registry
+    }
+}
+
+impl ScriptRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register<C: ScriptComponent>(&mut self, name: &str) {
+        self.components.insert(name.to_string(), get_component::<C>);
+    }
+}
+
+/// Returns nil if the entity does not have the component.
+fn get_component<'lua, C: ScriptComponent>(lua: &'lua Lua, world: &World, entity: Entity) -> mlua::Result<Value<'lua>> {
+    match world.read_storage::<C>().get(entity) {
+        Some(component) => component.to_lua(lua),
+        None => Ok(Value::Nil)
+    }
+}
+
+/// A scene whose logic lives in a Lua script. The script defines any of the global functions
+/// `update(dt)`, `draw()`, `interact(input)` and `is_finished()`. Missing functions do nothing.
+///
+/// While a function runs, the global `ecs` gives read access to the World:
+/// `ecs.get_component(entity_id, "Transform")` returns a table of the component's values, or nil.
+/// Component names come from the ScriptRegistry in the World. Scripts can't change the scene
+/// stack, so `update` always returns `SceneTransition::NONE`.
+pub struct LuaScene<T: Input + Debug + ScriptInput> {
+    name: String,
+    lua: Lua,
+    input: PhantomData<T>
+}
+
+impl<T: Input + Debug + ScriptInput> Debug for LuaScene<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LuaScene")
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+impl<T: Input + Debug + ScriptInput> LuaScene<T> {
+    /// Runs the script once so its functions are defined.
+    #[cfg_attr(feature = "trace", instrument(skip(source)))]
+    pub fn from_source(name: &str, source: &str) -> Result<Self, LuaSceneError> {
+        let lua = Lua::new();
+
+        lua.load(source)
+            .set_name(name)
+            .and_then(|chunk| chunk.exec())
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to run Lua script: {:?}", name);
+
+                ScriptLoadError {
+                    source: e,
+                    path: name.to_string()
+                }
+            })?;
+
+        Ok(Self {
+            name: name.to_string(),
+            lua,
+            input: PhantomData
+        })
+    }
+
+    /// Calls the script's global function `function` with `ecs` set, or returns nil if the script
+    /// doesn't define it.
+    fn call<'lua, R: 'static>(&'lua self, ecs: &World, function: &str, args: MultiValue<'lua>, ret: impl FnOnce(Value) -> R) -> Result<R, LuaSceneError> {
+        let lua = &self.lua;
+
+        lua.scope(|scope| {
+            let globals = lua.globals();
+
+            let script_fn: Option<Function> = globals.get(function)?;
+            let script_fn = match script_fn {
+                Some(script_fn) => script_fn,
+                None => return Ok(ret(Value::Nil))
+            };
+
+            let get_component = scope.create_function(|lua, (id, name): (u32, String)| {
+                let registry = ecs.try_fetch::<ScriptRegistry>();
+                let default_registry;
+                let registry = match &registry {
+                    Some(registry) => &**registry,
+                    None => {
+                        default_registry = ScriptRegistry::default();
+                        &default_registry
+                    }
+                };
+
+                let entity = ecs.entities().entity(id);
+                if !ecs.is_alive(entity) {
+                    return Ok(Value::Nil)
+                }
+
+                match registry.components.get(&name) {
+                    Some(get) => get(lua, ecs, entity),
+                    None => Err(mlua::Error::RuntimeError(format!("No script component registered for: {}", name)))
+                }
+            })?;
+
+            let ecs_table = lua.create_table()?;
+            ecs_table.set("get_component", get_component)?;
+            globals.set("ecs", ecs_table)?;
+
+            let value: Value = script_fn.call(args)?;
+            let result = ret(value);
+
+            globals.set("ecs", Value::Nil)?;
+
+            Ok(result)
+        })
+        .map_err(|e| {
+            #[cfg(feature = "trace")]
+            error!("Lua function: {:?} failed in script: {:?}", function, self.name.clone());
+
+            ScriptCallError {
+                source: e,
+                function: function.to_string(),
+                path: self.name.clone()
+            }
+        })
+    }
+}
+
+impl<T: Input + Debug + ScriptInput> Scene<T> for LuaScene<T> {
+    #[cfg_attr(feature = "trace", instrument(skip(ecs)))]
+    fn update(&mut self, ecs: &mut World, delta: Duration) -> Result<SceneTransition<T>> {
+        let args = delta.as_secs_f32().to_lua(&self.lua).map(|dt| MultiValue::from_vec(vec![dt]))?;
+        self.call(ecs, "update", args, |_| ())?;
+
+        Ok(SceneTransition::NONE)
+    }
+
+    #[cfg_attr(feature = "trace", instrument(skip(ecs, _context)))]
+    fn draw(&mut self, ecs: &mut World, _context: &mut GL33Context) -> Result<()> {
+        self.call(ecs, "draw", MultiValue::new(), |_| ())?;
+
+        Ok(())
+    }
+
+    #[cfg_attr(feature = "trace", instrument(skip(ecs)))]
+    fn interact(&mut self, ecs: &mut World, input: &T) -> Result<()> {
+        let args = ScriptInput::to_lua(input, &self.lua).map(|input| MultiValue::from_vec(vec![input]))?;
+        self.call(ecs, "interact", args, |_| ())?;
+
+        Ok(())
+    }
+
+    fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    #[cfg_attr(feature = "trace", instrument(skip(ecs)))]
+    fn is_finished(&self, ecs: &mut World) -> Result<bool> {
+        let finished = self.call(ecs, "is_finished", MultiValue::new(), |value| match value {
+            Value::Nil => Ok(false),
+            Value::Boolean(finished) => Ok(finished),
+            other => Err(other.type_name())
+        })?;
+
+        finished.map_err(|type_name| {
+            #[cfg(feature = "trace")]
+            error!("is_finished returned a {:?} in script: {:?}", type_name, self.name.clone());
+
+            InvalidReturnValue {
+                function: "is_finished".to_string(),
+                type_name: type_name.to_string(),
+                path: self.name.clone()
+            }.into()
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct LuaSceneJSON {
+    script_path: String
+}
+
+/// Loads a LuaScene from a scene file of the form `{ "script_path": "scenes/menu.lua" }`.
+/// Register `LuaSceneLoader::from_json` with the SceneRegistry under `LUA_SCENE_LOAD_ID`.
+#[derive(Debug, Clone)]
+pub struct LuaSceneLoader<T: Input + Debug + ScriptInput> {
+    json: LuaSceneJSON,
+    input: PhantomData<T>
+}
+
+impl<T: Input + Debug + ScriptInput> LuaSceneLoader<T> {
+    #[cfg_attr(feature = "trace", instrument)]
+    pub fn from_json(json: JSONLoad) -> Result<Self> {
+        let scene_json: LuaSceneJSON = load_deserializable_from_json(&json, LUA_SCENE_LOAD_ID)
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Failed to convert JSONLoad object: ({:?}) into LuaSceneJSON value", json.clone());
+
+                DeserializeError {
+                    source: e,
+                    json: json.clone()
+                }
+            })?;
+
+        Ok(Self {
+            json: scene_json,
+            input: PhantomData
+        })
+    }
+}
+
+impl<T: 'static + Input + Debug + ScriptInput> SceneLoader<T> for LuaSceneLoader<T> {
+    #[cfg_attr(feature = "trace", instrument)]
+    fn load_scene(&self) -> DrawTask<Box<dyn Scene<T>>> {
+        let path = self.json.script_path.clone();
+
+        DrawTask::new(move |(_ecs, _context)| {
+            #[cfg(feature = "trace")]
+            debug!("Loading Lua scene from script: {:?}", path.clone());
+
+            let source = read_to_string(&path)
+                .map_err(|e| {
+                    #[cfg(feature = "trace")]
+                    error!("Failed to read Lua script: {:?}", path.clone());
+
+                    ScriptReadError {
+                        source: e,
+                        path: path.clone()
+                    }
+                })?;
+
+            let scene: Box<dyn Scene<T>> = Box::new(LuaScene::<T>::from_source(&path, &source)?);
+
+            Ok(scene)
+        })
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum LuaSceneError {
+    #[error("Failed to convert JSONLoad object: ({json:?}) to LuaSceneJSON value")]
+    DeserializeError {
+        source: LoadError,
+        json: JSONLoad
+    },
+
+    #[error("Failed to read Lua script: {path}")]
+    ScriptReadError {
+        source: std::io::Error,
+        path: String
+    },
+
+    #[error("Failed to run Lua script: {path}")]
+    ScriptLoadError {
+        source: mlua::Error,
+        path: String
+    },
+
+    #[error("Lua function: {function} failed in script: {path}")]
+    ScriptCallError {
+        source: mlua::Error,
+        function: String,
+        path: String
+    },
+
+    #[error("Lua function: {function} returned a {type_name} in script: {path}")]
+    InvalidReturnValue {
+        function: String,
+        type_name: String,
+        path: String
+    }
+}
@@ -1,34 +1,71 @@
-use specs::{System, WriteStorage, Join};
-use crate::components::drawables::Drawable;
+use specs::{Entities, Entity, Join, System, Write, WriteStorage};
 
-pub struct AnimateSprites;
+#[cfg(feature = "trace")]
+use tracing::{debug, instrument};
+
+use crate::components::animated_sprite::AnimatedSprite;
+use crate::components::sprite_region::SpriteRegion;
+use crate::events::EventQueue;
+
+/// Sent through an `EventQueue<AnimationComplete>` when an animation that doesn't loop has shown
+/// its last frame. Register it with `systems::clear_events::register_event` before running
+/// AnimateSprites.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnimationComplete {
+    pub entity: Entity
+}
+
+/// Advances every AnimatedSprite by one tick. Once a frame has been shown for `frame_pause` ticks
+/// the SpriteRegion moves to the next column. After `end_frame` it goes back to `start_frame`
+/// when `looping` is set, and otherwise stays on `end_frame` and sends AnimationComplete.
+#[derive(Debug, Clone, Copy)]
+pub struct AnimateSprites {
+    pub looping: bool
+}
 
 impl<'a> System<'a> for AnimateSprites {
     type SystemData = (
-        WriteStorage<'a, Drawable>
+        Entities<'a>,
+        WriteStorage<'a, AnimatedSprite>,
+        WriteStorage<'a, SpriteRegion>,
+        Write<'a, EventQueue<AnimationComplete>>
     );
 
+    #[cfg_attr(feature = "trace", instrument(skip(self, data)))]
     fn run(&mut self, data: Self::SystemData) {
-        let mut drawables = data;
+        let (entities, mut animations, mut regions, mut completed) = data;
+
+        for (entity, animation, region) in (&entities, &mut animations, &mut regions).join() {
+            if animation.finished || region.width == 0 {
+                continue
+            }
 
-        for mut drawable in (&mut drawables).join() {
-            if let Some(sprites) = &mut drawable.animated_sprites {
-                for sprite in sprites {
-                    let total_frames = (sprite.end_frame - sprite.start_frame + 1) * sprite.frame_pause;
+            let frame = AnimatedSprite::frame(region);
+
+            // Regions that start outside the animation jump to its first frame.
+            if frame < animation.start_frame || frame > animation.end_frame {
+                animation.restart(region);
+                continue
+            }
+
+            animation.frame_pause_counter += 1;
+            if animation.frame_pause_counter < animation.frame_pause {
+                continue
+            }
 
-                    if sprite.frame_pause_counter == total_frames {
-                        sprite.frame_pause_counter = 0;
-                        let height_difference = (sprite.end_frame - sprite.start_frame) * sprite.sprite.source.height;
-                        sprite.sprite.source.y = sprite.sprite.source.y - height_difference;
-                    }
+            animation.frame_pause_counter = 0;
 
-                    if sprite.frame_pause_counter != 0 && sprite.frame_pause_counter % sprite.frame_pause == 0 {
-                        sprite.sprite.source.y += sprite.sprite.source.height;
-                    }
+            if frame < animation.end_frame {
+                AnimatedSprite::set_frame(region, frame + 1);
+            } else if self.looping {
+                AnimatedSprite::set_frame(region, animation.start_frame);
+            } else {
+                #[cfg(feature = "trace")]
+                debug!("Animation of entity: {:?} is complete", entity);
 
-                    sprite.frame_pause_counter += 1;
-                }
+                animation.finished = true;
+                completed.push(AnimationComplete { entity });
             }
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,72 @@
+use std::any::TypeId;
+use std::marker::PhantomData;
+
+use specs::{RunNow, System, World, WorldExt, Write};
+
+#[cfg(feature = "trace")]
+use tracing::{debug, instrument};
+
+use crate::events::EventQueue;
+
+/// Empties the `EventQueue<E>` so each frame only sees the events sent during it.
+#[derive(Debug)]
+pub struct ClearEvents<E>(PhantomData<E>);
+
+impl<E> Default for ClearEvents<E> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<'a, E: 'static + Send + Sync> System<'a> for ClearEvents<E> {
+    type SystemData = Write<'a, EventQueue<E>>;
+
+    fn run(&mut self, mut queue: Self::SystemData) {
+        queue.clear();
+    }
+}
+
+type ClearFn = fn(&World);
+
+/// ClearEvents systems of every event type added with `register_event`. The GameLoop runs them
+/// at the start of every frame.
+#[derive(Debug, Default)]
+pub struct EventClearers(Vec<(TypeId, ClearFn)>);
+
+impl EventClearers {
+    #[cfg_attr(feature = "trace", instrument(skip(ecs)))]
+    pub fn run(ecs: &World) {
+        let clearers = match ecs.try_fetch::<EventClearers>() {
+            Some(clearers) => clearers.0.clone(),
+            None => return
+        };
+
+        #[cfg(feature = "trace")]
+        debug!("Clearing {:?} event queues", clearers.len());
+
+        for (_, clear) in clearers {
+            clear(ecs);
+        }
+    }
+}
+
+fn clear_events<E: 'static + Send + Sync>(ecs: &World) {
+    ClearEvents::<E>::default().run_now(ecs);
+}
+
+/// Inserts an empty `EventQueue<E>` and clears it at the start of every frame. Call it from
+/// `GameWrapper::register_components` once for each event type the game sends. Registering a
+/// type again does nothing.
+#[cfg_attr(feature = "trace", instrument(skip(ecs)))]
+pub fn register_event<E: 'static + Send + Sync>(ecs: &mut World) {
+    ecs.entry::<EventQueue<E>>()
+        .or_insert_with(EventQueue::default);
+
+    let mut clearers = ecs.entry::<EventClearers>()
+        .or_insert_with(EventClearers::default);
+
+    let type_id = TypeId::of::<E>();
+    if !clearers.0.iter().any(|(registered, _)| *registered == type_id) {
+        clearers.0.push((type_id, clear_events::<E>));
+    }
+}
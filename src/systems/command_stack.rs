@@ -0,0 +1,307 @@
+use std::ops::DerefMut;
+use std::sync::{Arc, Mutex, RwLock};
+
+use glam::Vec2;
+use specs::{Builder, Entity, World, WorldExt};
+use thiserror::Error;
+
+#[cfg(feature = "trace")]
+use tracing::{debug, error, instrument};
+
+use crate::graphics::texture::TextureHandle;
+use crate::graphics::transform::Transform;
+use crate::systems::command_stack::CommandError::{WorldWriteLockError, CommandLockError, EntityDNE, EntityNotSpawned, DeleteError};
+
+/// An editor action that can be reverted. Commands lock the World themselves, so the CommandStack
+/// they are run through must not be borrowed from the World at the same time. Take it out with
+/// `World::remove` and insert it back afterwards.
+pub trait Command: Send + Sync {
+    fn execute(&self, ecs: Arc<RwLock<World>>) -> Result<(), CommandError>;
+    fn undo(&self, ecs: Arc<RwLock<World>>) -> Result<(), CommandError>;
+}
+
+pub struct CommandStack {
+    history: Vec<Box<dyn Command>>,
+    redo_stack: Vec<Box<dyn Command>>,
+    max_depth: usize
+}
+
+impl Default for CommandStack {
+    fn default() -> Self {
+        Self::new(100)
+    }
+}
+
+impl CommandStack {
+    pub fn new(max_depth: usize) -> Self {
+        Self {
+            history: Vec::new(),
+            redo_stack: Vec::new(),
+            max_depth
+        }
+    }
+
+    /// Executes the command and records it. Clears anything that could be redone.
+    #[cfg_attr(feature = "trace", instrument(skip(self, command, ecs)))]
+    pub fn push_and_execute(&mut self, command: Box<dyn Command>, ecs: Arc<RwLock<World>>) -> Result<(), CommandError> {
+        command.execute(ecs)?;
+
+        self.redo_stack.clear();
+        self.history.push(command);
+
+        if self.history.len() > self.max_depth {
+            self.history.remove(0);
+        }
+
+        Ok(())
+    }
+
+    /// Returns false if there was nothing to undo.
+    #[cfg_attr(feature = "trace", instrument(skip(self, ecs)))]
+    pub fn undo(&mut self, ecs: Arc<RwLock<World>>) -> Result<bool, CommandError> {
+        let command = match self.history.pop() {
+            Some(command) => command,
+            None => return Ok(false)
+        };
+
+        if let Err(e) = command.undo(ecs) {
+            self.history.push(command);
+            return Err(e)
+        }
+
+        self.redo_stack.push(command);
+
+        #[cfg(feature = "trace")]
+        debug!("Undid command. {:?} commands left in history", self.history.len());
+
+        Ok(true)
+    }
+
+    /// Returns false if there was nothing to redo.
+    #[cfg_attr(feature = "trace", instrument(skip(self, ecs)))]
+    pub fn redo(&mut self, ecs: Arc<RwLock<World>>) -> Result<bool, CommandError> {
+        let command = match self.redo_stack.pop() {
+            Some(command) => command,
+            None => return Ok(false)
+        };
+
+        if let Err(e) = command.execute(ecs) {
+            self.redo_stack.push(command);
+            return Err(e)
+        }
+
+        self.history.push(command);
+
+        #[cfg(feature = "trace")]
+        debug!("Redid command. {:?} commands left to redo", self.redo_stack.len());
+
+        Ok(true)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.history.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+}
+
+/// The components of an entity that are restored when a despawn is undone.
+#[derive(Debug, Clone, Default)]
+pub struct EntitySnapshot {
+    pub transform: Option<Transform>,
+    pub texture: Option<TextureHandle>
+}
+
+impl EntitySnapshot {
+    pub fn capture(ecs: &World, entity: Entity) -> Self {
+        Self {
+            transform: ecs.read_storage::<Transform>().get(entity).copied(),
+            texture: ecs.read_storage::<TextureHandle>().get(entity).cloned()
+        }
+    }
+
+    fn spawn(&self, ecs: &mut World) -> Entity {
+        let mut builder = ecs.create_entity();
+
+        if let Some(transform) = self.transform {
+            builder = builder.with(transform);
+        }
+
+        if let Some(texture) = &self.texture {
+            builder = builder.with(texture.clone());
+        }
+
+        builder.build()
+    }
+}
+
+/// Spawns an entity with a Transform at `position`. Undoing deletes it again.
+pub struct SpawnEntityCommand {
+    pub position: Vec2,
+    spawned: Mutex<Option<Entity>>
+}
+
+impl SpawnEntityCommand {
+    pub fn new(position: Vec2) -> Self {
+        Self {
+            position,
+            spawned: Mutex::new(None)
+        }
+    }
+}
+
+impl Command for SpawnEntityCommand {
+    #[cfg_attr(feature = "trace", instrument(skip(self, ecs)))]
+    fn execute(&self, ecs: Arc<RwLock<World>>) -> Result<(), CommandError> {
+        let mut world = write_world(&ecs)?;
+
+        let entity = world.create_entity()
+            .with(Transform::from_2d(self.position, Vec2::ONE, 0.0))
+            .build();
+
+        *lock(&self.spawned)? = Some(entity);
+
+        #[cfg(feature = "trace")]
+        debug!("Spawned entity: {:?} at {:?}", entity, self.position);
+
+        Ok(())
+    }
+
+    #[cfg_attr(feature = "trace", instrument(skip(self, ecs)))]
+    fn undo(&self, ecs: Arc<RwLock<World>>) -> Result<(), CommandError> {
+        let entity = lock(&self.spawned)?.take().ok_or(EntityNotSpawned)?;
+
+        delete(write_world(&ecs)?.deref_mut(), entity)
+    }
+}
+
+/// Deletes an entity. Undoing spawns a new entity with the components in the snapshot.
+pub struct DespawnEntityCommand {
+    entity: Mutex<Entity>,
+    pub snapshot: EntitySnapshot
+}
+
+impl DespawnEntityCommand {
+    pub fn new(entity: Entity, snapshot: EntitySnapshot) -> Self {
+        Self {
+            entity: Mutex::new(entity),
+            snapshot
+        }
+    }
+}
+
+impl Command for DespawnEntityCommand {
+    #[cfg_attr(feature = "trace", instrument(skip(self, ecs)))]
+    fn execute(&self, ecs: Arc<RwLock<World>>) -> Result<(), CommandError> {
+        delete(write_world(&ecs)?.deref_mut(), *lock(&self.entity)?)
+    }
+
+    #[cfg_attr(feature = "trace", instrument(skip(self, ecs)))]
+    fn undo(&self, ecs: Arc<RwLock<World>>) -> Result<(), CommandError> {
+        // The restored entity gets a new ID so redoing has to delete that one instead.
+        let entity = self.snapshot.spawn(write_world(&ecs)?.deref_mut());
+        *lock(&self.entity)? = entity;
+
+        #[cfg(feature = "trace")]
+        debug!("Restored despawned entity as: {:?}", entity);
+
+        Ok(())
+    }
+}
+
+/// Moves an entity's Transform between two positions.
+pub struct MoveEntityCommand {
+    pub entity: Entity,
+    pub from: Vec2,
+    pub to: Vec2
+}
+
+impl MoveEntityCommand {
+    fn move_to(&self, ecs: Arc<RwLock<World>>, position: Vec2) -> Result<(), CommandError> {
+        let world = write_world(&ecs)?;
+        let mut transforms = world.write_storage::<Transform>();
+
+        let transform = transforms.get_mut(self.entity)
+            .ok_or_else(|| {
+                #[cfg(feature = "trace")]
+                error!("Entity: {:?} has no Transform to move", self.entity);
+
+                EntityDNE { entity: self.entity }
+            })?;
+
+        transform.translation = position.extend(transform.translation.z);
+
+        Ok(())
+    }
+}
+
+impl Command for MoveEntityCommand {
+    #[cfg_attr(feature = "trace", instrument(skip(self, ecs)))]
+    fn execute(&self, ecs: Arc<RwLock<World>>) -> Result<(), CommandError> {
+        self.move_to(ecs, self.to)
+    }
+
+    #[cfg_attr(feature = "trace", instrument(skip(self, ecs)))]
+    fn undo(&self, ecs: Arc<RwLock<World>>) -> Result<(), CommandError> {
+        self.move_to(ecs, self.from)
+    }
+}
+
+fn write_world(ecs: &Arc<RwLock<World>>) -> Result<std::sync::RwLockWriteGuard<'_, World>, CommandError> {
+    ecs.write()
+        .map_err(|_e| {
+            #[cfg(feature = "trace")]
+            error!("Failed to acquire write lock for World");
+
+            WorldWriteLockError
+        })
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> Result<std::sync::MutexGuard<'_, T>, CommandError> {
+    mutex.lock()
+        .map_err(|_e| {
+            #[cfg(feature = "trace")]
+            error!("Failed to acquire lock for command state");
+
+            CommandLockError
+        })
+}
+
+fn delete(world: &mut World, entity: Entity) -> Result<(), CommandError> {
+    world.delete_entity(entity)
+        .map_err(|e| {
+            #[cfg(feature = "trace")]
+            error!("Failed to delete entity: {:?}", entity);
+
+            DeleteError { entity, source: e }
+        })?;
+
+    world.maintain();
+
+    Ok(())
+}
+
+#[derive(Error, Debug)]
+pub enum CommandError {
+    #[error("Failed to acquire write lock for World")]
+    WorldWriteLockError,
+
+    #[error("Failed to acquire lock for command state")]
+    CommandLockError,
+
+    #[error("Entity: {entity:?} does not have the components the command needs")]
+    EntityDNE {
+        entity: Entity
+    },
+
+    #[error("Spawn command was undone before it was executed")]
+    EntityNotSpawned,
+
+    #[error("Failed to delete entity: {entity:?}")]
+    DeleteError {
+        entity: Entity,
+        source: specs::error::WrongGeneration
+    }
+}
@@ -0,0 +1,92 @@
+use specs::{RunNow, World};
+
+#[cfg(feature = "trace")]
+use tracing::{instrument, trace};
+
+type Condition = Box<dyn Fn(&World) -> bool + Send + Sync>;
+
+/// Runs the wrapped system only on every `run_every`th call and only while `condition` holds.
+/// The condition needs the whole World, so the wrapper is run with `RunNow::run_now` rather than
+/// from a Dispatcher.
+pub struct ConditionalSystem<S> {
+    inner: S,
+    condition: Condition,
+    run_every: u32,
+    frame_counter: u32
+}
+
+impl<S> ConditionalSystem<S> {
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// Per-frame values such as `delta_time` are set on the wrapped system.
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+}
+
+impl<'a, S: RunNow<'a>> RunNow<'a> for ConditionalSystem<S> {
+    #[cfg_attr(feature = "trace", instrument(skip(self, world)))]
+    fn run_now(&mut self, world: &'a World) {
+        let due = self.frame_counter == 0;
+        self.frame_counter = (self.frame_counter + 1) % self.run_every;
+
+        if due && (self.condition)(world) {
+            self.inner.run_now(world);
+        } else {
+            #[cfg(feature = "trace")]
+            trace!("Skipped conditional system. Due this frame: {:?}", due);
+        }
+    }
+
+    fn setup(&mut self, world: &mut World) {
+        self.inner.setup(world);
+    }
+}
+
+/// ```ignore
+/// let pathfinding = ConditionalSystemBuilder::new(PathfindingSystem)
+///     .every_n_frames(4)
+///     .when(|world| !is_paused(world))
+///     .build();
+/// ```
+pub struct ConditionalSystemBuilder<S> {
+    inner: S,
+    condition: Condition,
+    run_every: u32
+}
+
+impl<S> ConditionalSystemBuilder<S> {
+    /// Without further calls the system runs every frame.
+    pub fn new(system: S) -> Self {
+        Self {
+            inner: system,
+            condition: Box::new(|_| true),
+            run_every: 1
+        }
+    }
+
+    /// The first call runs the system, then it skips `n - 1` calls between runs. Zero is treated as one.
+    pub fn every_n_frames(mut self, n: u32) -> Self {
+        self.run_every = n.max(1);
+
+        self
+    }
+
+    /// Replaces any previous condition.
+    pub fn when(mut self, condition: impl Fn(&World) -> bool + Send + Sync + 'static) -> Self {
+        self.condition = Box::new(condition);
+
+        self
+    }
+
+    pub fn build(self) -> ConditionalSystem<S> {
+        ConditionalSystem {
+            inner: self.inner,
+            condition: self.condition,
+            run_every: self.run_every,
+            frame_counter: 0
+        }
+    }
+}
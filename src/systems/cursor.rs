@@ -0,0 +1,50 @@
+use std::sync::{Arc, RwLock};
+
+use luminance_glfw::GL33Context;
+use specs::{Join, World, WorldExt};
+
+#[cfg(feature = "trace")]
+use tracing::{debug, instrument};
+
+use crate::components::cursor_icon::{CursorIcon, CursorType};
+use crate::components::focusable::Focusable;
+use crate::graphics::{restore_default_cursor, set_cursor, CursorError};
+
+/// Shows the CursorIcon of the focused entity, or the default cursor when no focused entity has
+/// one. The window is only changed when the cursor does. Changing the cursor needs the context, so
+/// this runs in the scene's draw instead of as a specs System.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CursorChangeSystem {
+    current: Option<CursorType>
+}
+
+impl CursorChangeSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[cfg_attr(feature = "trace", instrument(skip(self, ecs, context)))]
+    pub fn run(&mut self, ecs: &World, context: &Arc<RwLock<GL33Context>>) -> Result<(), CursorError> {
+        let focusables = ecs.read_storage::<Focusable>();
+        let icons = ecs.read_storage::<CursorIcon>();
+
+        let cursor = (&focusables, &icons).join()
+            .find(|(focusable, _)| focusable.is_focused)
+            .map(|(_, icon)| icon.0);
+
+        if cursor == self.current {
+            return Ok(())
+        }
+
+        #[cfg(feature = "trace")]
+        debug!("Changing cursor from: {:?} to: {:?}", self.current, cursor);
+
+        match cursor {
+            Some(cursor_type) => set_cursor(context, cursor_type)?,
+            None => restore_default_cursor(context)?
+        }
+        self.current = cursor;
+
+        Ok(())
+    }
+}
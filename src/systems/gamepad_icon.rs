@@ -0,0 +1,60 @@
+use specs::{Entities, Join, Read, ReadStorage, System, WriteStorage};
+
+#[cfg(feature = "trace")]
+use tracing::{debug, instrument};
+
+use crate::components::gamepad_prompt::GamepadPrompt;
+use crate::globals::gamepad_icon_dict::GamepadIconDict;
+use crate::graphics::texture::TextureHandle;
+use crate::input::gamepad::{ConnectedGamepad, GamepadActionMap};
+
+/// Points the TextureHandle of every GamepadPrompt at the icon for its action's button on the
+/// ConnectedGamepad. Prompts are left alone while no gamepad is connected or when the action or
+/// button has no icon. The icons must already be in the TextureDict.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GamepadIconSystem;
+
+impl<'a> System<'a> for GamepadIconSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, GamepadPrompt>,
+        WriteStorage<'a, TextureHandle>,
+        Read<'a, ConnectedGamepad>,
+        Read<'a, GamepadActionMap>,
+        Read<'a, GamepadIconDict>
+    );
+
+    #[cfg_attr(feature = "trace", instrument(skip(self, data)))]
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, prompts, mut textures, connected_gamepad, action_map, icon_dict) = data;
+
+        let controller_type = match connected_gamepad.0 {
+            Some(controller_type) => controller_type,
+            None => return
+        };
+
+        for (entity, prompt) in (&entities, &prompts).join() {
+            let icon = action_map.get(&prompt.action_name)
+                .and_then(|button| icon_dict.get(controller_type, button));
+
+            if let Some(icon) = icon {
+                match textures.get_mut(entity) {
+                    Some(texture) if texture.handle.as_str() == icon.as_str() => {},
+                    Some(texture) => {
+                        #[cfg(feature = "trace")]
+                        debug!("Changing prompt icon of entity: {:?} to: {:?}", entity, icon);
+
+                        texture.handle = icon.clone().into();
+                    },
+                    None => {
+                        #[cfg(feature = "trace")]
+                        debug!("Adding prompt icon: {:?} to entity: {:?}", icon, entity);
+
+                        // Insertion only fails for dead entities, which join doesn't return.
+                        let _ = textures.insert(entity, TextureHandle::new(icon.clone()));
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+
+use luminance_glfw::GL33Context;
+use specs::{Entities, Entity, Join, Read, ReadStorage, System, World, WorldExt, WriteStorage};
+
+#[cfg(feature = "trace")]
+use tracing::{debug, instrument};
+
+use crate::camera::Camera;
+use crate::components::lod::{ActiveLOD, LevelOfDetail};
+use crate::graphics::tess::{TessCache, TessLoadError};
+use crate::graphics::transform::Transform;
+
+/// Sets each entity's ActiveLOD to the level of its LevelOfDetail that matches its distance from
+/// the camera on the x and y axes.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LODSystem;
+
+impl<'a> System<'a> for LODSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, LevelOfDetail>,
+        ReadStorage<'a, Transform>,
+        WriteStorage<'a, ActiveLOD>,
+        Read<'a, Option<Box<dyn Camera>>>
+    );
+
+    #[cfg_attr(feature = "trace", instrument(skip(self, data)))]
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, lods, transforms, mut active_lods, camera) = data;
+
+        let camera_position = match camera.as_ref() {
+            Some(camera) => camera.position().truncate(),
+            None => return
+        };
+
+        for (entity, lod, transform) in (&entities, &lods, &transforms).join() {
+            let distance = (transform.translation.truncate() - camera_position).length();
+
+            let tess_path = match lod.select(distance) {
+                Some(tess_path) => tess_path,
+                None => continue
+            };
+
+            if !matches!(active_lods.get(entity), Some(active) if active.0 == tess_path) {
+                // Inserting only fails for dead entities and every joined entity is alive.
+                let _ = active_lods.insert(entity, ActiveLOD(tess_path.to_string()));
+            }
+        }
+    }
+}
+
+/// Loads the Tess of every ActiveLOD that changed since the last run into the renderer's
+/// TessCache, so the SpriteRenderer can draw with it. Loading needs the context, so this runs in
+/// the scene's draw instead of as a specs System. Run it after the LODSystem.
+#[derive(Debug, Clone, Default)]
+pub struct LODSwitchSystem {
+    seen: HashMap<Entity, String>
+}
+
+impl LODSwitchSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[cfg_attr(feature = "trace", instrument(skip(self, ecs, context, cache)))]
+    pub fn run(&mut self, ecs: &World, context: &mut GL33Context, cache: &mut TessCache) -> Result<(), TessLoadError> {
+        let entities = ecs.entities();
+        let active_lods = ecs.read_storage::<ActiveLOD>();
+
+        self.seen.retain(|entity, _| entities.is_alive(*entity));
+
+        for (entity, active_lod) in (&entities, &active_lods).join() {
+            if self.seen.get(&entity) == Some(&active_lod.0) {
+                continue
+            }
+
+            #[cfg(feature = "trace")]
+            debug!("Entity: {:?} switched to level of detail: {:?}", entity, active_lod.0.clone());
+
+            cache.get_or_load(&active_lod.0, context)?;
+            self.seen.insert(entity, active_lod.0.clone());
+        }
+
+        Ok(())
+    }
+}
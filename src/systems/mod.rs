@@ -1,3 +1,11 @@
 // pub mod draw_basic;
-// pub mod animate_sprites;
-// pub mod play_default_sounds;
\ No newline at end of file
+pub mod animate_sprites;
+// pub mod play_default_sounds;
+pub mod command_stack;
+pub mod visibility;
+pub mod conditional;
+pub mod lod;
+pub mod name_registry;
+pub mod cursor;
+pub mod gamepad_icon;
+pub mod clear_events;
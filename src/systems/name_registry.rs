@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+
+use specs::{Entities, Entity, Join, Read, ReadStorage, System, Write};
+
+#[cfg(feature = "trace")]
+use tracing::{error, instrument};
+
+use crate::components::named_entity::NamedEntity;
+use crate::globals::name_registry::{NameRegistry, NameRegistryConfig};
+
+/// Keeps the NameRegistry in step with every NamedEntity, using the strictness from the World's
+/// NameRegistryConfig. Names of deleted entities, or entities whose NamedEntity was removed, are
+/// unregistered. In strict mode a duplicate name is logged and the entity stays unregistered until
+/// its NamedEntity changes.
+#[derive(Debug, Clone, Default)]
+pub struct NameRegistrySystem {
+    seen: HashMap<Entity, String>
+}
+
+impl NameRegistrySystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<'a> System<'a> for NameRegistrySystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, NamedEntity>,
+        Read<'a, NameRegistryConfig>,
+        Write<'a, NameRegistry>
+    );
+
+    #[cfg_attr(feature = "trace", instrument(skip(self, data)))]
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, names, config, mut registry) = data;
+
+        registry.strict_names = config.strict_names;
+
+        self.seen.retain(|entity, name| {
+            let current = if entities.is_alive(*entity) { names.get(*entity) } else { None };
+
+            if matches!(current, Some(current) if current.0 == *name) {
+                true
+            } else {
+                registry.unregister(name, *entity);
+                false
+            }
+        });
+
+        for (entity, name) in (&entities, &names).join() {
+            if self.seen.contains_key(&entity) {
+                continue
+            }
+
+            if let Err(_e) = registry.register(&name.0, entity) {
+                #[cfg(feature = "trace")]
+                error!("Failed to register entity: {:?}. Error: {:?}", entity, _e);
+            }
+
+            self.seen.insert(entity, name.0.clone());
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use glam::{Mat4, Vec2, Vec4};
+use specs::{Component, NullStorage, System, Entities, ReadStorage, WriteStorage, Write, Join};
+
+#[cfg(feature = "trace")]
+use tracing::{debug, instrument};
+
+use crate::camera::Camera;
+use crate::graphics::transform::Transform;
+
+/// Marks an entity that is outside of the camera's view. The SpriteRenderer skips culled entities.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Culled;
+
+impl Component for Culled { type Storage = NullStorage<Self>; }
+crate::register_component!(Culled);
+
+/// Culls every entity whose Transform translation is more than `margin` world units outside of the
+/// area seen through the camera's projection and view. Run it before the SpriteRenderer. Only
+/// meant for orthographic projections.
+#[derive(Debug, Clone, Copy)]
+pub struct VisibilityCullingSystem {
+    /// Keeps sprites that are partly on screen from being culled. Should be at least half the size
+    /// of the largest sprite.
+    pub margin: f32
+}
+
+impl VisibilityCullingSystem {
+    /// Corners of the view in world space, found by unprojecting the corners of clip space.
+    fn visible_bounds(&self, projection: Mat4, view: Mat4) -> (Vec2, Vec2) {
+        let inverse = (projection * view).inverse();
+
+        let mut min = Vec2::splat(f32::MAX);
+        let mut max = Vec2::splat(f32::MIN);
+
+        for corner in [Vec4::new(-1.0, -1.0, 0.0, 1.0), Vec4::new(1.0, -1.0, 0.0, 1.0), Vec4::new(1.0, 1.0, 0.0, 1.0), Vec4::new(-1.0, 1.0, 0.0, 1.0)] {
+            let world = inverse * corner;
+            let world = Vec2::new(world.x, world.y) / world.w;
+
+            min = min.min(world);
+            max = max.max(world);
+        }
+
+        (min - Vec2::splat(self.margin), max + Vec2::splat(self.margin))
+    }
+}
+
+impl<'a> System<'a> for VisibilityCullingSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, Transform>,
+        WriteStorage<'a, Culled>,
+        Write<'a, Option<Box<dyn Camera>>>
+    );
+
+    #[cfg_attr(feature = "trace", instrument(skip(self, data)))]
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, transforms, mut culled, mut camera) = data;
+
+        let (projection, view) = match camera.as_mut() {
+            Some(camera) => (camera.projection(), camera.view()),
+            None => return
+        };
+
+        let (min, max) = self.visible_bounds(projection, view);
+        #[cfg(feature = "trace")]
+        debug!("Culling entities outside of: {:?} to {:?}", min, max);
+
+        for (entity, transform) in (&entities, &transforms).join() {
+            let position = transform.translation.truncate();
+            let visible = position.cmpge(min).all() && position.cmple(max).all();
+
+            // Inserting and removing only fail for dead entities and every joined entity is alive.
+            if visible {
+                culled.remove(entity);
+            } else if !culled.contains(entity) {
+                let _ = culled.insert(entity, Culled);
+            }
+        }
+    }
+}
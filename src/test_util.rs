@@ -0,0 +1,47 @@
+use std::fs::{create_dir_all, remove_dir_all, write};
+use std::path::PathBuf;
+
+use serde_json::{json, Value};
+
+/// A directory under the system temp dir that is removed when dropped.
+pub(crate) struct TempDir(PathBuf);
+
+impl TempDir {
+    pub(crate) fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("game_engine_{}_{}", name, std::process::id()));
+        create_dir_all(&dir).unwrap();
+
+        Self(dir)
+    }
+
+    /// Path of the directory itself.
+    pub(crate) fn root(&self) -> String {
+        self.0.to_string_lossy().to_string()
+    }
+
+    /// Path of `name` in the directory.
+    pub(crate) fn path(&self, name: &str) -> String {
+        self.0.join(name).to_string_lossy().to_string()
+    }
+
+    /// Writes `contents` to `name` and returns its path.
+    pub(crate) fn write(&self, name: &str, contents: &str) -> String {
+        let path = self.path(name);
+        write(&path, contents).unwrap();
+
+        path
+    }
+
+    /// Writes a JSONLoad with `load_type_id` and `actual_value` to `name` and returns its path.
+    pub(crate) fn write_load(&self, name: &str, load_type_id: &str, actual_value: Value) -> String {
+        let load = json!({"load_type_id": load_type_id, "actual_value": actual_value});
+
+        self.write(name, &load.to_string())
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = remove_dir_all(&self.0);
+    }
+}
@@ -0,0 +1,145 @@
+#[cfg(feature = "trace")]
+use tracing::{debug, error, instrument};
+
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::time::Duration;
+
+use glfw::WindowEvent;
+use specs::{Component, Entity, World, WorldExt};
+use thiserror::Error;
+
+use crate::input::Input;
+use crate::scenes::Scene;
+use crate::scenes::scene_stack::SceneTransition;
+use crate::testing::integration::IntegrationError::{SceneInteractError, SceneUpdateError};
+
+/// Feeds an Input the events queued for each frame, in place of the window's event receiver.
+pub struct MockInput<T: Input> {
+    input: T,
+    frames: VecDeque<Vec<WindowEvent>>
+}
+
+impl<T: Input> Default for MockInput<T> {
+    fn default() -> Self {
+        Self {
+            input: T::new(),
+            frames: VecDeque::new()
+        }
+    }
+}
+
+impl<T: Input> MockInput<T> {
+    /// `frames[i]` holds the events received before frame `i` is updated.
+    pub fn new(frames: Vec<Vec<WindowEvent>>) -> Self {
+        Self {
+            input: T::new(),
+            frames: frames.into()
+        }
+    }
+
+    /// Queues `events` after every frame already queued.
+    pub fn push_frame(&mut self, events: Vec<WindowEvent>) {
+        self.frames.push_back(events);
+    }
+
+    /// Clears the previous frame's input and applies the next frame's events. Frames past the
+    /// end of the queue receive no events.
+    pub fn next_frame(&mut self) -> &T {
+        self.input.clear();
+
+        for event in self.frames.pop_front().unwrap_or_default() {
+            self.input.update(event);
+        }
+
+        &self.input
+    }
+}
+
+/// Time step each frame's update is given, 60 frames per second.
+pub const MOCK_FRAME_DELTA: Duration = Duration::from_nanos(16_666_667);
+
+pub struct IntegrationResult<T: Input + Debug> {
+    /// The World after the last frame.
+    pub world: World,
+    pub frames_run: u32,
+    /// The last transition other than `NONE` returned by the scene, if any. The harness has no
+    /// SceneStack, so transitions are recorded but not applied.
+    pub last_transition: Option<SceneTransition<T>>
+}
+
+/// Runs the interact/update cycle of `scene` for `frames` frames without a window. Stops early
+/// if the scene reports that it is finished. Draw is skipped because it needs a GL context. Every
+/// update is given `MOCK_FRAME_DELTA`.
+#[cfg_attr(feature = "trace", instrument(skip(scene, ecs, input)))]
+pub fn run_scene_for_frames<T: Input + Debug, S: Scene<T>>(mut scene: S, mut ecs: World, mut input: MockInput<T>, frames: u32) -> Result<IntegrationResult<T>, IntegrationError> {
+    let mut last_transition = None;
+    let mut frames_run = 0;
+
+    while frames_run < frames {
+        scene.interact(&mut ecs, input.next_frame())
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Scene: {:?} failed to interact on frame: {:?}", scene.get_name(), frames_run);
+
+                SceneInteractError {
+                    frame: frames_run,
+                    source: e
+                }
+            })?;
+
+        let transition = scene.update(&mut ecs, MOCK_FRAME_DELTA)
+            .map_err(|e| {
+                #[cfg(feature = "trace")]
+                error!("Scene: {:?} failed to update on frame: {:?}", scene.get_name(), frames_run);
+
+                SceneUpdateError {
+                    frame: frames_run,
+                    source: e
+                }
+            })?;
+
+        if !matches!(transition, SceneTransition::NONE) {
+            last_transition = Some(transition);
+        }
+
+        ecs.maintain();
+        frames_run += 1;
+
+        if scene.is_finished(&mut ecs).unwrap_or(false) {
+            #[cfg(feature = "trace")]
+            debug!("Scene: {:?} finished after {:?} frames", scene.get_name(), frames_run);
+
+            break
+        }
+    }
+
+    Ok(IntegrationResult {
+        world: ecs,
+        frames_run,
+        last_transition
+    })
+}
+
+/// Panics if `entity` does not have a `C` component.
+pub fn assert_entity_has_component<C: Component>(world: &World, entity: Entity) {
+    assert!(
+        world.read_storage::<C>().contains(entity),
+        "Entity: {:?} does not have component: {}", entity, std::any::type_name::<C>()
+    );
+}
+
+#[derive(Error, Debug)]
+pub enum IntegrationError {
+    #[error("Scene failed to interact on frame: {frame}")]
+    SceneInteractError {
+        frame: u32,
+        source: anyhow::Error
+    },
+
+    #[error("Scene failed to update on frame: {frame}")]
+    SceneUpdateError {
+        frame: u32,
+        source: anyhow::Error
+    }
+}
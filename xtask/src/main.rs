@@ -0,0 +1,71 @@
+use std::env;
+use std::fs::{create_dir_all, write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+
+use game_engine::schema::{all_schemas, SCHEMA_DIR};
+use game_engine::loading::asset_tree::{validate_asset_tree, DEFAULT_ASSET_ROOT};
+
+const USAGE: &str = "Usage: cargo xtask <task>
+
+Tasks:
+    generate-schemas            Write a JSON Schema for every loader file to assets/JSON/schemas/
+    validate-assets [root]      Check that every file referenced from the root JSON file exists.
+                                Paths are relative to the current directory. The root defaults to
+                                assets/JSON/scene_stack.json";
+
+fn main() -> Result<()> {
+    match env::args().nth(1).as_deref() {
+        Some("generate-schemas") => generate_schemas(),
+        Some("validate-assets") => validate_assets(env::args().nth(2).as_deref().unwrap_or(DEFAULT_ASSET_ROOT)),
+        Some(task) => bail!("Unknown task: {}\n\n{}", task, USAGE),
+        None => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+    }
+}
+
+/// Writes the schemas under the workspace root, so it works from any directory.
+fn generate_schemas() -> Result<()> {
+    let schema_dir = workspace_root().join(SCHEMA_DIR);
+    create_dir_all(&schema_dir)
+        .with_context(|| format!("Failed to create schema directory: {}", schema_dir.display()))?;
+
+    for (type_name, schema) in all_schemas() {
+        let path = schema_dir.join(format!("{}.schema.json", type_name));
+        let contents = serde_json::to_string_pretty(&schema)
+            .with_context(|| format!("Failed to serialize schema for: {}", type_name))?;
+
+        write(&path, contents + "\n")
+            .with_context(|| format!("Failed to write schema: {}", path.display()))?;
+
+        println!("Wrote {}", path.display());
+    }
+
+    Ok(())
+}
+
+/// Prints every missing file and exits with code 1 if there are any.
+fn validate_assets(root: &str) -> Result<()> {
+    let errors = validate_asset_tree(root);
+
+    if errors.is_empty() {
+        println!("Every asset referenced from {} exists", root);
+        return Ok(())
+    }
+
+    for error in errors.iter() {
+        eprintln!("{}", error);
+    }
+
+    std::process::exit(1);
+}
+
+fn workspace_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default()
+}